@@ -0,0 +1,128 @@
+//! The About box text, which the Win32 front end keeps as constants in `main.rs`.
+//!
+//! The dialog template still holds the 1993 shareware wording; the front end replaces it
+//! when the dialog opens. Changing a line here and rerunning the tool rewrites the
+//! matching constant, whatever it held before.
+
+/// Constant names and the text each should hold.
+pub const ABOUT_TEXT: [(&str, &str); 7] = [
+    ("ABOUT_TITLE", "Stop the Bus"),
+    (
+        "ABOUT_COPYRIGHT",
+        "Copyright (c) Martin Davidson - 1993-2025",
+    ),
+    ("ABOUT_VERSION", "Version 2.0.0"),
+    ("ABOUT_RELEASE", "Modernization build: 2025-09-22"),
+    ("LICENSE_NAME", "Rust modernization build."),
+    ("LICENSE_COMPANY", "Maintained by the community."),
+    ("LICENSE_ADDRESS", ""),
+];
+
+/// rustfmt's line width; longer declarations put the string on its own line.
+const MAX_WIDTH: usize = 100;
+
+/// `source` with every [`ABOUT_TEXT`] constant set to its text. Each must be declared
+/// once, as `const NAME: &str = "...";` starting a line.
+pub fn rewrite(source: &str) -> Result<String, String> {
+    let mut text = source.to_string();
+
+    for (name, value) in ABOUT_TEXT {
+        let prefix = format!("const {}: &str =", name);
+        let starts: Vec<usize> = text
+            .match_indices(&prefix)
+            .map(|(index, _)| index)
+            .filter(|&index| index == 0 || text[..index].ends_with('\n'))
+            .collect();
+        let start = match starts.as_slice() {
+            [start] => *start,
+            [] => return Err(format!("no '{}' declaration", prefix)),
+            _ => return Err(format!("'{}' is declared more than once", prefix)),
+        };
+
+        let end = start
+            + prefix.len()
+            + literal_end(&text[start + prefix.len()..])
+                .ok_or_else(|| format!("{} is not a string literal", name))?;
+        text.replace_range(start..end, &declaration(name, value));
+    }
+
+    Ok(text)
+}
+
+/// The length of ` "..." ;` up to and including the semicolon.
+fn literal_end(rest: &str) -> Option<usize> {
+    let open = rest.find(|c: char| !c.is_whitespace())?;
+    if !rest[open..].starts_with('"') {
+        return None;
+    }
+
+    let mut escaped = false;
+    for (index, c) in rest[open + 1..].char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => {
+                let after = open + 1 + index + 1;
+                let semicolon = rest[after..].find(|c: char| !c.is_whitespace())?;
+                return rest[after + semicolon..]
+                    .starts_with(';')
+                    .then_some(after + semicolon + 1);
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn declaration(name: &str, value: &str) -> String {
+    let one_line = format!("const {}: &str = {:?};", name, value);
+    if one_line.len() <= MAX_WIDTH {
+        one_line
+    } else {
+        format!("const {}: &str =\n    {:?};", name, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHAREWARE: &str = "fn main() {}\n\n\
+        const ABOUT_TITLE: &str = \"Stop the Bus\";\n\
+        const ABOUT_COPYRIGHT: &str = \"Copyright (c) Martin Davidson - 1993\";\n\
+        const ABOUT_VERSION: &str = \"Version 1.0\";\n\
+        const ABOUT_RELEASE: &str = \"Released: 09/05/1994\";\n\
+        const LICENSE_NAME: &str = \"This program is ShareWare.\";\n\
+        const LICENSE_COMPANY: &str =\n    \
+            \"If you like it then send 5 pounds (UK Sterling) or $10 (US dollars) to:\";\n\
+        const LICENSE_ADDRESS: &str =\n    \
+            \"Martin G Davidson\\r\\nHertford College\\r\\nOxford\\r\\nOX1 3BW\";\n";
+
+    #[test]
+    fn rewrites_every_constant_whatever_it_held() {
+        let text = rewrite(SHAREWARE).unwrap();
+
+        assert!(text.starts_with("fn main() {}\n\nconst ABOUT_TITLE: &str = \"Stop the Bus\";\n"));
+        assert!(text.contains("const ABOUT_RELEASE: &str = \"Modernization build: 2025-09-22\";\n"));
+        assert!(text.contains("const LICENSE_COMPANY: &str = \"Maintained by the community.\";\n"));
+        assert!(text.ends_with("const LICENSE_ADDRESS: &str = \"\";\n"));
+        assert_eq!(rewrite(&text).unwrap(), text);
+    }
+
+    #[test]
+    fn reports_missing_and_odd_declarations() {
+        assert_eq!(
+            rewrite("const ABOUT_TITLE: &str = TITLE;\n").unwrap_err(),
+            "ABOUT_TITLE is not a string literal"
+        );
+        assert_eq!(
+            rewrite(&SHAREWARE.replace("LICENSE_NAME", "NAME")).unwrap_err(),
+            "no 'const LICENSE_NAME: &str =' declaration"
+        );
+        assert_eq!(literal_end(" \"a\\\"b\" ;\n"), Some(9));
+        assert_eq!(
+            declaration("LICENSE_COMPANY", &"x".repeat(80)),
+            format!("const LICENSE_COMPANY: &str =\n    \"{}\";", "x".repeat(80))
+        );
+    }
+}
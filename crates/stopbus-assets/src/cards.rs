@@ -0,0 +1,330 @@
+//! Card bitmaps rewritten as Windows 3 BMP files that `rc.exe` embeds unchanged.
+//!
+//! `STOPBUS.RES` stores the cards with OS/2 core headers: three-byte palette entries and
+//! rows padded to 16 bits. The resource compiler pads 1-bpp images to 32 bits with extra
+//! palette data, which shifts the pixels, so those are widened to 4 bpp with a 16-entry
+//! palette. Every row is padded to 32 bits and the header becomes a 40-byte
+//! `BITMAPINFOHEADER`.
+
+const CORE_HEADER_SIZE: u32 = 12;
+const INFO_HEADER_SIZE: u32 = 40;
+const FILE_HEADER_SIZE: u32 = 14;
+
+/// A `.bmp` file holding the resource's pixels in Windows 3 form.
+pub fn normalise(dib: &[u8]) -> Result<Vec<u8>, String> {
+    let header_size = u32_at(dib, 0)?;
+    let bitmap = if header_size == CORE_HEADER_SIZE {
+        from_core(dib)?
+    } else if header_size >= INFO_HEADER_SIZE {
+        from_info(dib, header_size)?
+    } else {
+        return Err(format!("unknown header size {}", header_size));
+    };
+
+    Ok(bitmap.into_file())
+}
+
+/// Everything the new header needs, with the palette already in `RGBQUAD` form.
+struct Converted {
+    width: i32,
+    height: i32,
+    planes: u16,
+    bit_count: u16,
+    compression: u32,
+    /// The stored image size; only kept for compressed bitmaps.
+    image_size: Option<u32>,
+    colors_used: u32,
+    palette: Vec<u8>,
+    pixels: Vec<u8>,
+}
+
+impl Converted {
+    fn into_file(self) -> Vec<u8> {
+        let image_size = self.image_size.unwrap_or(self.pixels.len() as u32);
+        let mut dib = Vec::with_capacity(INFO_HEADER_SIZE as usize + self.pixels.len());
+        dib.extend(INFO_HEADER_SIZE.to_le_bytes());
+        dib.extend(self.width.to_le_bytes());
+        dib.extend(self.height.to_le_bytes());
+        dib.extend(self.planes.to_le_bytes());
+        dib.extend(self.bit_count.to_le_bytes());
+        dib.extend(self.compression.to_le_bytes());
+        dib.extend(image_size.to_le_bytes());
+        dib.extend([0; 8]);
+        dib.extend(self.colors_used.to_le_bytes());
+        dib.extend([0; 4]);
+        dib.extend(&self.palette);
+        let bits_offset = FILE_HEADER_SIZE + dib.len() as u32;
+        dib.extend(&self.pixels);
+
+        let mut file = Vec::with_capacity(FILE_HEADER_SIZE as usize + dib.len());
+        file.extend(b"BM");
+        file.extend((FILE_HEADER_SIZE + dib.len() as u32).to_le_bytes());
+        file.extend([0; 4]);
+        file.extend(bits_offset.to_le_bytes());
+        file.extend(dib);
+        file
+    }
+}
+
+fn from_core(dib: &[u8]) -> Result<Converted, String> {
+    let width = usize::from(u16_at(dib, 4)?);
+    let height = usize::from(u16_at(dib, 6)?);
+    let planes = u16_at(dib, 8)?;
+    let bit_count = u16_at(dib, 10)?;
+
+    let entries = if bit_count <= 8 { 1 << bit_count } else { 0 };
+    let palette_end = CORE_HEADER_SIZE as usize + entries * 3;
+    let palette = dib
+        .get(CORE_HEADER_SIZE as usize..palette_end)
+        .ok_or("palette runs past the end of the data")?;
+    let pixels = &dib[palette_end..];
+
+    let significant = (width * usize::from(bit_count)).div_ceil(8);
+    let mut stride = (width * usize::from(bit_count)).div_ceil(16) * 2;
+    // Some writers padded rows further; trust the data when it divides evenly.
+    if height > 0 && stride * height != pixels.len() && pixels.len().is_multiple_of(height) {
+        stride = pixels.len() / height;
+    }
+    if stride * height != pixels.len() {
+        return Err(format!(
+            "{} bytes of pixels do not make {} rows",
+            pixels.len(),
+            height
+        ));
+    }
+
+    let colors: Vec<[u8; 3]> = palette
+        .chunks_exact(3)
+        .map(|entry| [entry[0], entry[1], entry[2]])
+        .collect();
+    let (bit_count, colors_used, palette, pixels) = if bit_count == 1 {
+        (
+            4,
+            16,
+            four_bit_palette(&colors),
+            widen_to_four_bits(width, height, pixels, stride, significant),
+        )
+    } else {
+        (
+            bit_count,
+            if bit_count <= 8 {
+                colors.len() as u32
+            } else {
+                0
+            },
+            colors
+                .iter()
+                .flat_map(|&[blue, green, red]| [blue, green, red, 0])
+                .collect(),
+            repad_rows(width, height, pixels, stride, significant, bit_count),
+        )
+    };
+
+    Ok(Converted {
+        width: width as i32,
+        height: height as i32,
+        planes,
+        bit_count,
+        compression: 0,
+        image_size: None,
+        colors_used,
+        palette,
+        pixels,
+    })
+}
+
+fn from_info(dib: &[u8], header_size: u32) -> Result<Converted, String> {
+    let width = u32_at(dib, 4)? as i32;
+    let height = u32_at(dib, 8)? as i32;
+    let planes = u16_at(dib, 12)?;
+    let bit_count = u16_at(dib, 14)?;
+    let compression = u32_at(dib, 16)?;
+    let image_size = u32_at(dib, 20)?;
+    let mut entries = u32_at(dib, 32)? as usize;
+    if entries == 0 && bit_count <= 8 {
+        entries = 1 << bit_count;
+    }
+
+    let palette_start = header_size as usize;
+    let palette_end = palette_start + entries * 4;
+    let palette = dib
+        .get(palette_start..palette_end)
+        .ok_or("palette runs past the end of the data")?;
+    let pixels = &dib[palette_end..];
+
+    if bit_count == 1 {
+        let width = width.unsigned_abs() as usize;
+        let height = height.unsigned_abs() as usize;
+        let colors: Vec<[u8; 3]> = palette
+            .chunks_exact(4)
+            .map(|entry| [entry[0], entry[1], entry[2]])
+            .collect();
+        let stride = width.div_ceil(32) * 4;
+        if pixels.len() < stride * height {
+            return Err("pixel data is cut short".to_string());
+        }
+
+        return Ok(Converted {
+            width: width as i32,
+            height: height as i32,
+            planes,
+            bit_count: 4,
+            compression,
+            image_size: (compression != 0).then_some(image_size),
+            colors_used: 16,
+            palette: four_bit_palette(&colors),
+            pixels: widen_to_four_bits(width, height, pixels, stride, width.div_ceil(8)),
+        });
+    }
+
+    Ok(Converted {
+        width,
+        height,
+        planes,
+        bit_count,
+        compression,
+        image_size: (compression != 0).then_some(image_size),
+        colors_used: if bit_count <= 8 { entries as u32 } else { 0 },
+        palette: palette.to_vec(),
+        pixels: pixels.to_vec(),
+    })
+}
+
+/// The first two colours, then the second repeated to fill 16 entries. An image with no
+/// palette gets black and white.
+fn four_bit_palette(colors: &[[u8; 3]]) -> Vec<u8> {
+    let (first, second) = match colors {
+        [] => ([0, 0, 0], [0xFF, 0xFF, 0xFF]),
+        [only] => (*only, *only),
+        [first, second, ..] => (*first, *second),
+    };
+
+    let mut palette = Vec::with_capacity(16 * 4);
+    palette.extend(first);
+    palette.push(0);
+    for _ in 1..16 {
+        palette.extend(second);
+        palette.push(0);
+    }
+    palette
+}
+
+/// Spreads each 1-bpp row into 4-bpp nibbles, repeating the last pixel of an odd width.
+fn widen_to_four_bits(
+    width: usize,
+    height: usize,
+    pixels: &[u8],
+    stride: usize,
+    significant: usize,
+) -> Vec<u8> {
+    let new_stride = (width * 4).div_ceil(32) * 4;
+    let mut out = Vec::with_capacity(new_stride * height);
+
+    for row in pixels.chunks(stride).take(height) {
+        let values: Vec<u8> = row[..significant.min(row.len())]
+            .iter()
+            .flat_map(|&byte| (0..8).rev().map(move |bit| (byte >> bit) & 1))
+            .take(width)
+            .collect();
+        let start = out.len();
+        for pair in values.chunks(2) {
+            let high = pair[0];
+            let low = pair.get(1).copied().unwrap_or(high);
+            out.push(high << 4 | low);
+        }
+        out.resize(start + new_stride, 0);
+    }
+    out
+}
+
+/// Copies the meaningful bytes of each row and pads them to 32 bits.
+fn repad_rows(
+    width: usize,
+    height: usize,
+    pixels: &[u8],
+    stride: usize,
+    significant: usize,
+    bit_count: u16,
+) -> Vec<u8> {
+    let new_stride = (width * usize::from(bit_count)).div_ceil(32) * 4;
+    let mut out = Vec::with_capacity(new_stride * height);
+
+    for row in pixels.chunks(stride).take(height) {
+        let start = out.len();
+        out.extend(&row[..significant.min(row.len())]);
+        out.resize(start + new_stride, 0);
+    }
+    out
+}
+
+fn u16_at(data: &[u8], offset: usize) -> Result<u16, String> {
+    data.get(offset..offset + 2)
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+        .ok_or_else(|| "bitmap header is cut short".to_string())
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Result<u32, String> {
+    data.get(offset..offset + 4)
+        .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .ok_or_else(|| "bitmap header is cut short".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 3x2 1-bpp core bitmap: rows of 2 bytes, a red and a white palette entry.
+    fn core_one_bit() -> Vec<u8> {
+        let mut dib = Vec::new();
+        dib.extend(12u32.to_le_bytes());
+        dib.extend(3u16.to_le_bytes());
+        dib.extend(2u16.to_le_bytes());
+        dib.extend(1u16.to_le_bytes());
+        dib.extend(1u16.to_le_bytes());
+        dib.extend([0, 0, 0xFF, 0xFF, 0xFF, 0xFF]);
+        dib.extend([0b1010_0000, 0, 0b0110_0000, 0]);
+        dib
+    }
+
+    #[test]
+    fn widens_one_bit_core_bitmaps() {
+        let file = normalise(&core_one_bit()).unwrap();
+
+        // 14 + 40 + 16 * 4 palette bytes, then two rows of 4 bytes.
+        assert_eq!(file.len(), 14 + 40 + 64 + 8);
+        assert_eq!(u32_at(&file, 10).unwrap(), 14 + 40 + 64);
+        assert_eq!(u16_at(&file, 14 + 14).unwrap(), 4);
+        assert_eq!(u32_at(&file, 14 + 32).unwrap(), 16);
+        assert_eq!(&file[54..62], [0, 0, 0xFF, 0, 0xFF, 0xFF, 0xFF, 0]);
+        assert_eq!(&file[54 + 60..54 + 64], [0xFF, 0xFF, 0xFF, 0]);
+        assert_eq!(&file[118..], [0x10, 0x11, 0, 0, 0x01, 0x11, 0, 0]);
+    }
+
+    #[test]
+    fn repads_wider_core_bitmaps_and_reports_bad_ones() {
+        // 3x1 at 4 bpp: two significant bytes, stored in a 2-byte row.
+        let mut dib = Vec::new();
+        dib.extend(12u32.to_le_bytes());
+        dib.extend([3, 0, 1, 0, 1, 0, 4, 0]);
+        dib.extend([7; 48]);
+        dib.extend([0x12, 0x30]);
+
+        let file = normalise(&dib).unwrap();
+        assert_eq!(u32_at(&file, 14 + 32).unwrap(), 16);
+        assert_eq!(&file[54..58], [7, 7, 7, 0]);
+        assert_eq!(&file[file.len() - 4..], [0x12, 0x30, 0, 0]);
+
+        assert_eq!(
+            normalise(&dib[..20]).unwrap_err(),
+            "palette runs past the end of the data"
+        );
+        assert_eq!(
+            normalise(&[1, 0]).unwrap_err(),
+            "bitmap header is cut short"
+        );
+        assert_eq!(
+            normalise(&24u32.to_le_bytes()).unwrap_err(),
+            "unknown header size 24"
+        );
+    }
+}
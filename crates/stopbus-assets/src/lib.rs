@@ -0,0 +1,232 @@
+//! Rebuilds the Win32 front end's generated assets from `STOPBUS.RES`.
+//!
+//! Every card bitmap is written twice: as stored, with its file header restored, under
+//! `assets/cards-os2`, and normalised for the resource compiler under
+//! `crates/stopbus-ui/resources/cards`. The resource script listing them is regenerated
+//! and the About text constants in the front end are rewritten. The WinHelp sources are
+//! converted to Markdown and HTML pages under `docs/help`. The output depends only on the
+//! inputs, so running the tool on an up-to-date tree changes nothing.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use stopbus_help::{HelpError, HelpFile};
+use stopbus_res::extract::bitmap_file;
+use stopbus_res::{ResError, ResFile, ResId, RT_BITMAP};
+
+pub mod about;
+pub mod cards;
+pub mod script;
+
+/// Inputs and outputs, relative to the repository root.
+pub const RES_PATH: &str = "STOPBUS.RES";
+pub const OS2_CARDS_DIR: &str = "assets/cards-os2";
+pub const UI_RESOURCES_DIR: &str = "crates/stopbus-ui/resources";
+pub const SCRIPT_FILE: &str = "stopbus.rc";
+pub const UI_MAIN: &str = "crates/stopbus-ui/src/main.rs";
+pub const HELP_OUTPUT_DIR: &str = "docs/help";
+
+/// A file the tool produces and the bytes it should hold.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Output {
+    /// Relative to the repository root.
+    pub path: PathBuf,
+    pub data: Vec<u8>,
+}
+
+/// Everything the tool writes for the repository at `root`, in a fixed order.
+pub fn generate(root: &Path) -> Result<Vec<Output>, AssetError> {
+    let resources = ResFile::read(&root.join(RES_PATH)).map_err(AssetError::Res)?;
+    let ui_resources = Path::new(UI_RESOURCES_DIR);
+
+    let mut outputs = Vec::new();
+    let mut ids = Vec::new();
+    for resource in resources.of_kind(RT_BITMAP) {
+        let ResId::Number(id) = resource.name else {
+            continue;
+        };
+        let name = script::card_file_name(id);
+
+        outputs.push(Output {
+            path: Path::new(OS2_CARDS_DIR).join(&name),
+            data: bitmap_file(resource).map_err(AssetError::Res)?,
+        });
+        outputs.push(Output {
+            path: ui_resources.join(script::CARDS_DIR).join(&name),
+            data: cards::normalise(&resource.data)
+                .map_err(|reason| AssetError::Bitmap { id, reason })?,
+        });
+        ids.push(id);
+    }
+
+    outputs.push(Output {
+        path: ui_resources.join(SCRIPT_FILE),
+        data: script::resource_script(&ids).into_bytes(),
+    });
+
+    let main_path = root.join(UI_MAIN);
+    let main = fs::read_to_string(&main_path).map_err(|err| AssetError::Io {
+        path: main_path.clone(),
+        err,
+    })?;
+    outputs.push(Output {
+        path: PathBuf::from(UI_MAIN),
+        data: about::rewrite(&main)
+            .map_err(|reason| AssetError::About { reason })?
+            .into_bytes(),
+    });
+
+    let help = HelpFile::load(root).map_err(AssetError::Help)?;
+    let pictures = help.pictures(root).map_err(AssetError::Help)?;
+    for (path, data) in stopbus_help::export::files(&help, &pictures) {
+        outputs.push(Output {
+            path: Path::new(HELP_OUTPUT_DIR).join(path),
+            data,
+        });
+    }
+
+    Ok(outputs)
+}
+
+/// The outputs whose file under `root` is missing or holds other bytes.
+pub fn stale<'a>(root: &Path, outputs: &'a [Output]) -> Vec<&'a Output> {
+    outputs
+        .iter()
+        .filter(|output| fs::read(root.join(&output.path)).ok().as_ref() != Some(&output.data))
+        .collect()
+}
+
+/// Writes the stale outputs, returning how many there were.
+pub fn write(root: &Path, outputs: &[Output]) -> Result<usize, AssetError> {
+    let changed = stale(root, outputs);
+
+    for output in &changed {
+        let path = root.join(&output.path);
+        let io_error = |err| AssetError::Io {
+            path: path.clone(),
+            err,
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(io_error)?;
+        }
+        fs::write(&path, &output.data).map_err(io_error)?;
+    }
+
+    Ok(changed.len())
+}
+
+#[derive(Debug)]
+pub enum AssetError {
+    Res(ResError),
+    Io { path: PathBuf, err: io::Error },
+    Bitmap { id: u16, reason: String },
+    About { reason: String },
+    Help(HelpError),
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetError::Res(err) => write!(f, "{}: {}", RES_PATH, err),
+            AssetError::Io { path, err } => write!(f, "{}: {}", path.display(), err),
+            AssetError::Bitmap { id, reason } => write!(f, "bitmap {}: {}", id, reason),
+            AssetError::About { reason } => write!(f, "{}: {}", UI_MAIN, reason),
+            AssetError::Help(err) => write!(f, "help: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for AssetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AssetError::Res(err) => Some(err),
+            AssetError::Io { err, .. } => Some(err),
+            AssetError::Help(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo_root() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../..")
+    }
+
+    /// The golden test: the committed files are exactly what the tool writes.
+    #[test]
+    fn committed_assets_match_the_generator() {
+        let root = repo_root();
+        let outputs = generate(&root).unwrap();
+
+        // Two copies of each card, the script and main.rs, then a Markdown and an HTML
+        // page for each help topic, six pictures and the context map.
+        assert_eq!(outputs.len(), 54 * 2 + 2 + 22 * 2 + 6 + 1);
+        let stale: Vec<String> = stale(&root, &outputs)
+            .iter()
+            .map(|output| output.path.display().to_string())
+            .collect();
+        assert!(
+            stale.is_empty(),
+            "out of date, run `cargo run -p stopbus-assets`: {:?}",
+            stale
+        );
+    }
+
+    #[test]
+    fn output_is_reproducible_and_normalised() {
+        let first = generate(&repo_root()).unwrap();
+        assert_eq!(generate(&repo_root()).unwrap(), first);
+
+        for output in first
+            .iter()
+            .filter(|output| output.path.starts_with(UI_RESOURCES_DIR))
+        {
+            if output
+                .path
+                .extension()
+                .is_some_and(|extension| extension == "bmp")
+            {
+                // A 40-byte header, and 4 bpp at most: no 1-bpp image is left.
+                assert_eq!(cards_header(&output.data), (40, 4), "{:?}", output.path);
+            }
+        }
+    }
+
+    fn cards_header(file: &[u8]) -> (u32, u16) {
+        (
+            u32::from_le_bytes([file[14], file[15], file[16], file[17]]),
+            u16::from_le_bytes([file[28], file[29]]),
+        )
+    }
+
+    #[test]
+    fn write_only_touches_stale_files() {
+        let dir = std::env::temp_dir().join(format!("stopbus-assets-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let outputs = vec![
+            Output {
+                path: PathBuf::from("a/one.txt"),
+                data: b"one".to_vec(),
+            },
+            Output {
+                path: PathBuf::from("two.txt"),
+                data: b"two".to_vec(),
+            },
+        ];
+        assert_eq!(write(&dir, &outputs).unwrap(), 2);
+        assert_eq!(fs::read(dir.join("a/one.txt")).unwrap(), b"one");
+        assert_eq!(write(&dir, &outputs).unwrap(), 0);
+
+        fs::write(dir.join("two.txt"), "changed").unwrap();
+        assert_eq!(stale(&dir, &outputs), [&outputs[1]]);
+        assert_eq!(write(&dir, &outputs).unwrap(), 1);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,80 @@
+//! Regenerates the card bitmaps, resource script and About text from `STOPBUS.RES`, and
+//! the help pages from the WinHelp sources, or checks that the committed copies are
+//! current.
+//!
+//! Usage: stopbus-assets [generate|check] [ROOT]
+//!
+//! ROOT is the repository root and defaults to the current directory.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use stopbus_assets::{generate, stale, write};
+
+const USAGE: &str = "usage: stopbus-assets [generate|check] [ROOT]";
+
+enum Command {
+    Generate(PathBuf),
+    Check(PathBuf),
+}
+
+fn parse_args() -> Result<Command, String> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match args.as_slice() {
+        [] | ["generate"] => Ok(Command::Generate(".".into())),
+        ["generate", root] => Ok(Command::Generate(root.into())),
+        ["check"] => Ok(Command::Check(".".into())),
+        ["check", root] => Ok(Command::Check(root.into())),
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn run(command: Command) -> Result<(), String> {
+    match command {
+        Command::Generate(root) => {
+            let outputs = generate(&root).map_err(|err| err.to_string())?;
+            let written = write(&root, &outputs).map_err(|err| err.to_string())?;
+            println!(
+                "{} of {} files were out of date and have been rewritten",
+                written,
+                outputs.len()
+            );
+        }
+        Command::Check(root) => {
+            let outputs = generate(&root).map_err(|err| err.to_string())?;
+            let stale = stale(&root, &outputs);
+            for output in &stale {
+                println!("out of date: {}", output.path.display());
+            }
+            if !stale.is_empty() {
+                return Err(format!(
+                    "{} files need `stopbus-assets generate`",
+                    stale.len()
+                ));
+            }
+            println!("all {} files are current", outputs.len());
+        }
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let command = match parse_args() {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("stopbus-assets: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! `resources/stopbus.rc`, the script the Win32 build compiles into the executable.
+//!
+//! The bitmap list is generated from the card resources; the icons, dialogs and version
+//! block after it are kept here as written.
+
+/// Folder of the normalised card bitmaps, relative to the script.
+pub const CARDS_DIR: &str = "cards";
+
+const HEADER: &str = r#"// Generated by stopbus-assets; change crates/stopbus-assets/src/script.rs instead.
+#include <windows.h>
+
+#define ICON_1 100
+#define APP_ICON 102
+
+"#;
+
+const FIXED_SECTIONS: &str = r#"// Icons
+ICON_1 ICON "..\\..\\..\\MD.ICO"
+APP_ICON ICON "..\\..\\..\\assets\\original-icons\\icon_001.ico"
+
+// Options dialog
+OPTIONS DIALOGEX 20, 20, 170, 168
+STYLE DS_MODALFRAME | WS_POPUP | WS_CAPTION | WS_SYSMENU
+CAPTION "Options"
+FONT 9, "MS Shell Dlg"
+BEGIN
+    CONTROL "Show cheat &cards", 500, "BUTTON", BS_AUTOCHECKBOX | WS_TABSTOP | WS_VISIBLE | WS_CHILD, 12, 12, 140, 10
+    CONTROL "Show cheat &deck", 501, "BUTTON", BS_AUTOCHECKBOX | WS_TABSTOP | WS_VISIBLE | WS_CHILD, 12, 28, 140, 10
+    CONTROL "Show cheat &scores", 502, "BUTTON", BS_AUTOCHECKBOX | WS_TABSTOP | WS_VISIBLE | WS_CHILD, 12, 44, 140, 10
+    CONTROL "&Read the table", 504, "BUTTON", BS_AUTOCHECKBOX | WS_TABSTOP | WS_VISIBLE | WS_CHILD, 12, 60, 140, 10
+    CONTROL "&Pop up game messages", 505, "BUTTON", BS_AUTOCHECKBOX | WS_TABSTOP | WS_VISIBLE | WS_CHILD, 12, 76, 140, 10
+    CONTROL "Show &alerts over the table", 506, "BUTTON", BS_AUTOCHECKBOX | WS_TABSTOP | WS_VISIBLE | WS_CHILD, 12, 92, 140, 10
+    CONTROL "&Save on exit", 503, "BUTTON", BS_AUTOCHECKBOX | WS_TABSTOP | WS_VISIBLE | WS_CHILD, 12, 108, 140, 10
+    DEFPUSHBUTTON "OK", IDOK, 50, 138, 50, 14, WS_TABSTOP
+    PUSHBUTTON "Cancel", IDCANCEL, 110, 138, 50, 14, WS_TABSTOP
+END
+
+// About dialog
+ABOUTBOX DIALOGEX 30, 30, 200, 150
+STYLE DS_MODALFRAME | WS_POPUP | WS_CAPTION | WS_SYSMENU
+CAPTION "About Stop the Bus"
+FONT 9, "MS Shell Dlg"
+BEGIN
+    ICON ICON_1, -1, 10, 10, 16, 16
+    LTEXT "Stop the Bus", 550, 36, 12, 120, 10
+    LTEXT "Copyright (c) Martin Davidson - 1993", 551, 36, 26, 150, 10
+    LTEXT "Version 2.0.0", 554, 36, 40, 120, 10
+    LTEXT "Released: 09/05/1993", 555, 36, 52, 120, 10
+    CTEXT "Modernization build information:", -1, 10, 72, 180, 10
+    CTEXT "", 552, 10, 84, 180, 10
+    CTEXT "", 553, 10, 96, 180, 10
+    LTEXT "", 556, 36, 112, 150, 28
+    DEFPUSHBUTTON "OK", IDOK, 75, 130, 50, 14, WS_TABSTOP
+END
+VS_VERSION_INFO VERSIONINFO
+ FILEVERSION 2,0,0,0
+ PRODUCTVERSION 2,0,0,0
+ FILEFLAGSMASK 0x3FL
+ FILEFLAGS 0x0L
+ FILEOS 0x00040004L
+ FILETYPE 0x00000001L
+ FILESUBTYPE 0x00000000L
+{
+ BLOCK "StringFileInfo"
+ {
+  BLOCK "040904B0"
+  {
+   VALUE "CompanyName", "Maintained by the community"
+   VALUE "FileDescription", "Stop the Bus game modernization"
+   VALUE "FileVersion", "2.0.0"
+   VALUE "InternalName", "stopbus.exe"
+   VALUE "LegalCopyright", "Copyright (c) 1993-2025"
+   VALUE "OriginalFilename", "stopbus.exe"
+   VALUE "ProductName", "Stop the Bus"
+   VALUE "ProductVersion", "2.0.0"
+  }
+ }
+ BLOCK "VarFileInfo"
+ {
+  VALUE "Translation", 0x0409, 1200
+ }
+}
+"#;
+
+pub fn card_file_name(id: u16) -> String {
+    format!("card{:02}.bmp", id)
+}
+
+/// The script for these bitmap IDs: the 52 faces, then the back and the cross.
+pub fn resource_script(bitmap_ids: &[u16]) -> String {
+    let mut ids = bitmap_ids.to_vec();
+    ids.sort_unstable();
+    let (faces, extras): (Vec<u16>, Vec<u16>) = ids.into_iter().partition(|&id| id <= 52);
+
+    let mut script = String::from(HEADER);
+    script.push_str("// Card face bitmaps (1-52)\n");
+    for id in faces {
+        script.push_str(&bitmap_line(id));
+    }
+    script.push_str("\n// Card back and empty-slot overlay\n");
+    for id in extras {
+        script.push_str(&bitmap_line(id));
+    }
+    script.push('\n');
+    script.push_str(FIXED_SECTIONS);
+    script
+}
+
+fn bitmap_line(id: u16) -> String {
+    format!("{} BITMAP \"{}/{}\"\n", id, CARDS_DIR, card_file_name(id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_faces_then_the_back_and_cross() {
+        let script = resource_script(&[54, 2, 53, 1]);
+
+        assert!(script.starts_with("// Generated by stopbus-assets"));
+        assert!(script.contains(
+            "// Card face bitmaps (1-52)\n1 BITMAP \"cards/card01.bmp\"\n\
+             2 BITMAP \"cards/card02.bmp\"\n\n// Card back and empty-slot overlay\n\
+             53 BITMAP \"cards/card53.bmp\"\n54 BITMAP \"cards/card54.bmp\"\n\n// Icons\n"
+        ));
+        assert!(script.ends_with("}\n"));
+    }
+}
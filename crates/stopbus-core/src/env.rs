@@ -0,0 +1,468 @@
+//! Gym-style environment that lets an external agent play seat 0.
+//!
+//! The agent acts through the same intents as the human player, while seats 1-3 are driven
+//! by a chosen [`Strategy`]. Rewards are expressed from seat 0's point of view.
+//!
+//! # Observation layout
+//!
+//! [`Observation`] is a flat `f32` vector of [`OBSERVATION_LEN`] entries:
+//!
+//! | Range       | Meaning                                                      |
+//! |-------------|--------------------------------------------------------------|
+//! | `0..52`     | Seat 0 hand, multi-hot by `CardId - 1`                        |
+//! | `52..104`   | Face-up stack card, one-hot by `CardId - 1` (all zero if none) |
+//! | `104..108`  | Lives for seats 0-3, divided by 3                            |
+//! | `108..113`  | Sticker one-hot for seats 0-3, index 112 means nobody stuck   |
+//! | `113`       | Seat 0 hand score divided by 31                              |
+//!
+//! # Action space
+//!
+//! | Index | Action                                   |
+//! |-------|------------------------------------------|
+//! | 0-2   | Swap hand slot 0-2 with the stack card   |
+//! | 3     | Draw the next stack card                 |
+//! | 4     | Stick                                    |
+//! | 5     | End the turn (the OK button)             |
+
+use crate::{
+    card_suit, hand_max_score, ClassicStrategy, DriveReport, GameEvent, GameState, Strategy,
+    DECK_SIZE, HAND_SIZE, PLAYERS,
+};
+
+pub const OBSERVATION_LEN: usize = 114;
+pub const ACTION_COUNT: usize = 6;
+
+/// Reward for each life seat 0 loses.
+pub const LIFE_LOST_REWARD: f32 = -1.0;
+/// Reward when seat 0 is the last player standing.
+pub const WIN_REWARD: f32 = 1.0;
+/// Reward for an action that is masked out; the game state is left untouched.
+pub const ILLEGAL_ACTION_REWARD: f32 = -0.1;
+
+/// Default cap on agent steps per episode.
+pub const DEFAULT_MAX_STEPS: usize = 1_000;
+
+/// Cap on automated reports processed between two agent decisions. Rounds can stall once the
+/// deck is exhausted and nobody is able to stick.
+const MAX_AUTOMATION_STEPS: usize = 10_000;
+
+const HAND_OFFSET: usize = 0;
+const STACK_OFFSET: usize = HAND_OFFSET + DECK_SIZE;
+const LIVES_OFFSET: usize = STACK_OFFSET + DECK_SIZE;
+const STICKER_OFFSET: usize = LIVES_OFFSET + PLAYERS;
+const SCORE_OFFSET: usize = STICKER_OFFSET + PLAYERS + 1;
+
+pub type Observation = [f32; OBSERVATION_LEN];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Swap(usize),
+    Draw,
+    Stick,
+    EndTurn,
+}
+
+impl Action {
+    pub fn from_index(index: usize) -> Option<Self> {
+        match index {
+            0..=2 => Some(Action::Swap(index)),
+            3 => Some(Action::Draw),
+            4 => Some(Action::Stick),
+            5 => Some(Action::EndTurn),
+            _ => None,
+        }
+    }
+
+    pub fn index(self) -> usize {
+        match self {
+            Action::Swap(slot) => slot,
+            Action::Draw => 3,
+            Action::Stick => 4,
+            Action::EndTurn => 5,
+        }
+    }
+}
+
+/// Extra information returned alongside each step.
+#[derive(Debug, Clone, Default)]
+pub struct StepInfo {
+    pub events: Vec<GameEvent>,
+    pub illegal_action: bool,
+    /// The episode was cut short by the step cap or a stalled round.
+    pub truncated: bool,
+    pub winner: Option<usize>,
+    pub lives: [u8; PLAYERS],
+}
+
+#[derive(Debug, Clone)]
+pub struct StepResult {
+    pub observation: Observation,
+    pub reward: f32,
+    pub done: bool,
+    pub info: StepInfo,
+}
+
+type OpponentFactory = Box<dyn Fn(usize) -> Box<dyn Strategy> + Send>;
+
+pub struct StopBusEnv {
+    game: GameState,
+    opponents: OpponentFactory,
+    max_steps: usize,
+    steps: usize,
+    done: bool,
+}
+
+impl StopBusEnv {
+    /// Creates an environment whose opponents play the classic heuristic.
+    pub fn new() -> Self {
+        Self::with_opponents(|_| Box::new(ClassicStrategy::default()))
+    }
+
+    /// Creates an environment whose seats 1-3 are built by `factory` on every reset.
+    pub fn with_opponents<F>(factory: F) -> Self
+    where
+        F: Fn(usize) -> Box<dyn Strategy> + Send + 'static,
+    {
+        Self {
+            game: GameState::new(Some(0)),
+            opponents: Box::new(factory),
+            max_steps: DEFAULT_MAX_STEPS,
+            steps: 0,
+            done: true,
+        }
+    }
+
+    pub fn set_max_steps(&mut self, max_steps: usize) {
+        self.max_steps = max_steps;
+    }
+
+    pub fn game(&self) -> &GameState {
+        &self.game
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Starts a new game and plays opponents until seat 0 is on turn.
+    pub fn reset(&mut self, seed: Option<u64>) -> Observation {
+        self.game = GameState::new(seed);
+        for player in 1..PLAYERS {
+            self.game.set_strategy(player, (self.opponents)(player));
+        }
+
+        self.steps = 0;
+        self.done = false;
+
+        let report = self.game.start_fresh();
+        let mut info = StepInfo::default();
+        self.settle(report, &mut info);
+        self.done = info.truncated || self.game_finished(&info);
+
+        observe(&self.game)
+    }
+
+    /// Applies the action with the given index. Illegal actions leave the game untouched.
+    pub fn step(&mut self, action_index: usize) -> StepResult {
+        let mut info = StepInfo {
+            lives: *self.game.lives(),
+            ..StepInfo::default()
+        };
+
+        if self.done {
+            info.illegal_action = true;
+            return self.result(0.0, info);
+        }
+
+        let legal = self.action_mask();
+        let action = Action::from_index(action_index).filter(|action| legal[action.index()]);
+
+        let Some(action) = action else {
+            info.illegal_action = true;
+            return self.step_finished(ILLEGAL_ACTION_REWARD, info);
+        };
+
+        let lives_before = self.game.lives()[0];
+
+        let report = match action {
+            Action::Swap(slot) => self.game.human_swap_with_stack(slot),
+            Action::Draw => self.game.human_draw_next_card(),
+            Action::Stick => self.game.human_stick(),
+            Action::EndTurn => Some(self.game.advance_after_human_turn()),
+        };
+
+        if let Some(report) = report {
+            self.settle(report, &mut info);
+        }
+
+        let lives_after = self.game.lives()[0];
+        let mut reward = f32::from(lives_before.saturating_sub(lives_after)) * LIFE_LOST_REWARD;
+
+        if info.winner == Some(0) {
+            reward += WIN_REWARD;
+        }
+
+        self.step_finished(reward, info)
+    }
+
+    /// Legality of each action index for the current state.
+    pub fn action_mask(&self) -> [bool; ACTION_COUNT] {
+        let mut mask = [false; ACTION_COUNT];
+
+        if self.done || !self.game.awaiting_human() {
+            return mask;
+        }
+
+        let stack_card = self.game.stack_top_card();
+        for (slot, card) in self.game.hands[0].iter().enumerate() {
+            mask[Action::Swap(slot).index()] = card.is_some() && stack_card.is_some();
+        }
+
+        mask[Action::Draw.index()] = self.game.human_can_draw_next();
+        mask[Action::Stick.index()] = self.game.human_can_stick();
+        mask[Action::EndTurn.index()] = true;
+        mask
+    }
+
+    fn step_finished(&mut self, reward: f32, mut info: StepInfo) -> StepResult {
+        self.steps += 1;
+
+        if self.steps >= self.max_steps && !self.game_finished(&info) {
+            info.truncated = true;
+        }
+
+        self.done = info.truncated || self.game_finished(&info);
+        self.result(reward, info)
+    }
+
+    fn result(&self, reward: f32, mut info: StepInfo) -> StepResult {
+        info.lives = *self.game.lives();
+
+        StepResult {
+            observation: observe(&self.game),
+            reward,
+            done: self.done,
+            info,
+        }
+    }
+
+    fn game_finished(&self, info: &StepInfo) -> bool {
+        info.winner.is_some()
+            || self.game.lives()[0] == 0
+            || self.game.lives().iter().all(|&l| l == 0)
+    }
+
+    /// Processes automated reports until seat 0 must act or the game ends.
+    fn settle(&mut self, mut report: DriveReport, info: &mut StepInfo) {
+        let mut remaining = MAX_AUTOMATION_STEPS;
+
+        loop {
+            info.events.append(&mut report.events);
+
+            if report.game_over() {
+                info.winner = report.winner;
+                return;
+            }
+
+            if report.awaiting_human || self.game.lives()[0] == 0 {
+                return;
+            }
+
+            if remaining == 0 {
+                info.truncated = true;
+                return;
+            }
+
+            remaining -= 1;
+            report = self.game.continue_automation();
+        }
+    }
+}
+
+impl Default for StopBusEnv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Encodes the public state visible to seat 0 using the layout documented on this module.
+pub fn observe(game: &GameState) -> Observation {
+    let mut observation = [0.0; OBSERVATION_LEN];
+
+    for card in game.hands[0].iter().flatten() {
+        if let Some(index) = card_index(*card) {
+            observation[HAND_OFFSET + index] = 1.0;
+        }
+    }
+
+    if let Some(index) = game.stack_top_card().and_then(card_index) {
+        observation[STACK_OFFSET + index] = 1.0;
+    }
+
+    for (player, &lives) in game.lives().iter().enumerate() {
+        observation[LIVES_OFFSET + player] = f32::from(lives) / 3.0;
+    }
+
+    let sticker = game.stick_player().unwrap_or(PLAYERS);
+    observation[STICKER_OFFSET + sticker] = 1.0;
+
+    observation[SCORE_OFFSET] = f32::from(hand_max_score(&game.hands[0])) / 31.0;
+    observation
+}
+
+fn card_index(card: u8) -> Option<usize> {
+    card_suit(card).map(|_| usize::from(card) - 1)
+}
+
+const _: () = assert!(SCORE_OFFSET + 1 == OBSERVATION_LEN);
+const _: () = assert!(HAND_SIZE == 3, "action space assumes three hand slots");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn legal_actions(env: &StopBusEnv) -> Vec<usize> {
+        env.action_mask()
+            .iter()
+            .enumerate()
+            .filter_map(|(index, &legal)| legal.then_some(index))
+            .collect()
+    }
+
+    #[test]
+    fn action_indices_round_trip() {
+        for index in 0..ACTION_COUNT {
+            assert_eq!(Action::from_index(index).unwrap().index(), index);
+        }
+        assert!(Action::from_index(ACTION_COUNT).is_none());
+    }
+
+    #[test]
+    fn reset_is_deterministic_and_waits_for_seat_zero() {
+        let mut env = StopBusEnv::new();
+        let first = env.reset(Some(11));
+        let mut other = StopBusEnv::new();
+        let second = other.reset(Some(11));
+
+        assert_eq!(first, second);
+        assert!(env.game().awaiting_human());
+        assert!(!env.is_done());
+    }
+
+    #[test]
+    fn observation_layout_matches_documentation() {
+        let mut env = StopBusEnv::new();
+        let observation = env.reset(Some(5));
+        let game = env.game();
+
+        let hand_bits = observation[HAND_OFFSET..STACK_OFFSET]
+            .iter()
+            .filter(|&&v| v == 1.0)
+            .count();
+        assert_eq!(hand_bits, HAND_SIZE);
+
+        let stack = game.stack_top_card().unwrap() as usize;
+        assert_eq!(observation[STACK_OFFSET + stack - 1], 1.0);
+        assert_eq!(&observation[LIVES_OFFSET..STICKER_OFFSET], &[1.0; PLAYERS]);
+
+        let sticker = game.stick_player().unwrap_or(PLAYERS);
+        assert_eq!(observation[STICKER_OFFSET + sticker], 1.0);
+
+        let score = hand_max_score(&game.hands[0]) as f32 / 31.0;
+        assert_eq!(observation[SCORE_OFFSET], score);
+    }
+
+    #[test]
+    fn mask_reflects_turn_state() {
+        let mut env = StopBusEnv::new();
+        env.reset(Some(3));
+
+        let mask = env.action_mask();
+        assert!(mask[Action::Swap(0).index()]);
+        assert!(mask[Action::Draw.index()]);
+        assert!(mask[Action::EndTurn.index()]);
+        assert_eq!(mask[Action::Stick.index()], env.game().human_can_stick());
+
+        let result = env.step(Action::Draw.index());
+        assert!(!result.info.illegal_action);
+        assert!(!env.action_mask()[Action::Draw.index()]);
+        assert!(!env.action_mask()[Action::Stick.index()]);
+    }
+
+    #[test]
+    fn illegal_action_is_penalised_without_changing_state() {
+        let mut env = StopBusEnv::new();
+        let before = env.reset(Some(3));
+        env.step(Action::Draw.index());
+        let after_draw = observe(env.game());
+
+        let result = env.step(Action::Draw.index());
+        assert!(result.info.illegal_action);
+        assert_eq!(result.reward, ILLEGAL_ACTION_REWARD);
+        assert_eq!(result.observation, after_draw);
+        assert_ne!(before, after_draw);
+
+        assert!(env.step(ACTION_COUNT).info.illegal_action);
+    }
+
+    #[test]
+    fn random_legal_episodes_terminate() {
+        let mut rng = StdRng::seed_from_u64(9);
+
+        for seed in 0..20 {
+            let mut env = StopBusEnv::new();
+            env.reset(Some(seed));
+            let mut total = 0.0;
+
+            loop {
+                let actions = legal_actions(&env);
+                let action = actions[rng.gen_range(0..actions.len())];
+                let result = env.step(action);
+                total += result.reward;
+
+                if result.done {
+                    let lost = 3 - result.info.lives[0];
+                    let won = result.info.winner == Some(0);
+                    if !result.info.truncated {
+                        assert!(won || result.info.lives[0] == 0);
+                    }
+                    let expected =
+                        lost as f32 * LIFE_LOST_REWARD + if won { WIN_REWARD } else { 0.0 };
+                    assert_eq!(total, expected);
+                    break;
+                }
+            }
+
+            assert!(env.step(0).info.illegal_action);
+        }
+    }
+
+    #[test]
+    fn end_turn_only_episode_uses_opponent_factory() {
+        #[derive(Debug)]
+        struct AlwaysStick;
+
+        impl Strategy for AlwaysStick {
+            fn choose_move(&mut self, view: &crate::SeatView<'_>) -> crate::AutoMove {
+                if view.can_stick() {
+                    crate::AutoMove::Stick
+                } else {
+                    crate::AutoMove::Pass
+                }
+            }
+        }
+
+        let mut env = StopBusEnv::with_opponents(|_| Box::new(AlwaysStick));
+        env.reset(Some(21));
+
+        let mut steps = 0;
+        while !env.is_done() {
+            env.step(Action::EndTurn.index());
+            steps += 1;
+        }
+
+        assert!(steps < DEFAULT_MAX_STEPS);
+    }
+}
@@ -0,0 +1,239 @@
+//! Public record of the exchanges each seat makes with the face-up stack card during a round,
+//! and the table read that can be inferred from them.
+//!
+//! Every swap is visible to the whole table: everyone sees which card a player took and which
+//! card they put back. The read is a simple evidence model: each taken card adds weight to its
+//! suit (more for high cards), each dropped card halves the weight of its suit. Cards a player
+//! took and has not since put back are known to still be in their hand.
+
+use crate::{card_points, card_suit, CardId, Suit, HAND_SIZE, PLAYERS};
+
+pub const SUITS: [Suit; 4] = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades];
+
+/// Average points of a card whose rank is unknown (ace 11, court cards 10, pips face value).
+const AVERAGE_CARD_POINTS: f32 = 95.0 / 13.0;
+
+/// Minimum probability before a suit is reported as the one a player is collecting.
+const LIKELY_SUIT_THRESHOLD: f32 = 0.4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Exchange {
+    pub player: usize,
+    /// Card picked up from the stack.
+    pub taken: CardId,
+    /// Card left on the stack in its place.
+    pub dropped: CardId,
+    /// `round_turns` at the time of the swap.
+    pub turn: u16,
+}
+
+/// Visible exchanges for the current round, oldest first.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TableHistory {
+    exchanges: Vec<Exchange>,
+}
+
+impl TableHistory {
+    pub const fn new() -> Self {
+        Self {
+            exchanges: Vec::new(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.exchanges.clear();
+    }
+
+    pub fn record(&mut self, exchange: Exchange) {
+        self.exchanges.push(exchange);
+    }
+
+    pub fn exchanges(&self) -> &[Exchange] {
+        &self.exchanges
+    }
+
+    pub fn exchanges_for(&self, player: usize) -> impl Iterator<Item = &Exchange> {
+        self.exchanges
+            .iter()
+            .filter(move |exchange| exchange.player == player)
+    }
+
+    /// Cards the player picked up and has not put back since.
+    pub fn known_cards(&self, player: usize) -> Vec<CardId> {
+        let mut known: Vec<CardId> = Vec::with_capacity(HAND_SIZE);
+
+        for exchange in self.exchanges_for(player) {
+            known.retain(|&card| card != exchange.dropped);
+            known.push(exchange.taken);
+        }
+
+        // Only the most recent pick-ups can still be in a three-card hand.
+        let excess = known.len().saturating_sub(HAND_SIZE);
+        known.drain(..excess);
+        known
+    }
+
+    pub fn read(&self, player: usize) -> OpponentRead {
+        let mut weights = [1.0f32; 4];
+
+        for exchange in self.exchanges_for(player) {
+            if let Some(suit) = card_suit(exchange.taken) {
+                weights[suit_index(suit)] += 1.0 + f32::from(card_points(exchange.taken)) / 10.0;
+            }
+            if let Some(suit) = card_suit(exchange.dropped) {
+                weights[suit_index(suit)] *= 0.5;
+            }
+        }
+
+        let total: f32 = weights.iter().sum();
+        let suit_probabilities = weights.map(|weight| weight / total);
+
+        let known_cards = self.known_cards(player);
+        let unknown_slots = HAND_SIZE.saturating_sub(known_cards.len()) as f32;
+
+        let mut expected_score = 0.0;
+        let mut min_score = 0u8;
+        for (index, &suit) in SUITS.iter().enumerate() {
+            let known_points: u8 = known_cards
+                .iter()
+                .filter(|&&card| card_suit(card) == Some(suit))
+                .map(|&card| card_points(card))
+                .sum();
+            min_score = min_score.max(known_points);
+
+            let probability = suit_probabilities[index];
+            let estimate =
+                f32::from(known_points) + unknown_slots * AVERAGE_CARD_POINTS * probability;
+            expected_score += probability * estimate.min(31.0);
+        }
+
+        let (best_index, &best_probability) = suit_probabilities
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .expect("four suits");
+        let likely_suit = (best_probability >= LIKELY_SUIT_THRESHOLD).then_some(SUITS[best_index]);
+
+        OpponentRead {
+            player,
+            suit_probabilities,
+            likely_suit,
+            expected_score: expected_score.max(f32::from(min_score)),
+            min_score,
+            known_cards,
+            exchanges: self.exchanges_for(player).count(),
+        }
+    }
+
+    pub fn reads(&self) -> [OpponentRead; PLAYERS] {
+        std::array::from_fn(|player| self.read(player))
+    }
+}
+
+/// What the table can infer about one player's hand from their exchanges.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpponentRead {
+    pub player: usize,
+    /// Probability per suit, in [`SUITS`] order.
+    pub suit_probabilities: [f32; 4],
+    /// The suit the player is most likely collecting, if the evidence is strong enough.
+    pub likely_suit: Option<Suit>,
+    pub expected_score: f32,
+    /// Score guaranteed by the cards known to be in the hand.
+    pub min_score: u8,
+    pub known_cards: Vec<CardId>,
+    pub exchanges: usize,
+}
+
+impl OpponentRead {
+    pub fn suit_probability(&self, suit: Suit) -> f32 {
+        self.suit_probabilities[suit_index(suit)]
+    }
+}
+
+fn suit_index(suit: Suit) -> usize {
+    match suit {
+        Suit::Clubs => 0,
+        Suit::Diamonds => 1,
+        Suit::Hearts => 2,
+        Suit::Spades => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exchange(player: usize, taken: CardId, dropped: CardId) -> Exchange {
+        Exchange {
+            player,
+            taken,
+            dropped,
+            turn: 0,
+        }
+    }
+
+    #[test]
+    fn no_history_is_uniform() {
+        let history = TableHistory::new();
+        let read = history.read(2);
+
+        assert_eq!(read.suit_probabilities, [0.25; 4]);
+        assert_eq!(read.likely_suit, None);
+        assert_eq!(read.min_score, 0);
+        assert!(read.known_cards.is_empty());
+        assert_eq!(read.exchanges, 0);
+    }
+
+    #[test]
+    fn taking_a_suit_points_towards_it() {
+        let mut history = TableHistory::new();
+        // Ace of hearts and king of hearts taken, clubs dropped both times.
+        history.record(exchange(1, 27, 2));
+        history.record(exchange(1, 39, 5));
+        history.record(exchange(2, 14, 27));
+
+        let read = history.read(1);
+        assert_eq!(read.likely_suit, Some(Suit::Hearts));
+        assert!(read.suit_probability(Suit::Hearts) > read.suit_probability(Suit::Spades));
+        assert!(read.suit_probability(Suit::Clubs) < read.suit_probability(Suit::Spades));
+        assert_eq!(read.known_cards, vec![27, 39]);
+        assert_eq!(read.min_score, 21);
+        assert!(read.expected_score >= 21.0);
+        assert_eq!(read.exchanges, 2);
+
+        let others = history.read(3);
+        assert_eq!(others.exchanges, 0);
+    }
+
+    #[test]
+    fn dropping_a_taken_card_forgets_it() {
+        let mut history = TableHistory::new();
+        history.record(exchange(0, 27, 2));
+        history.record(exchange(0, 3, 27));
+
+        assert_eq!(history.known_cards(0), vec![3]);
+    }
+
+    #[test]
+    fn known_cards_never_exceed_a_hand() {
+        let mut history = TableHistory::new();
+        for (taken, dropped) in [(1, 40), (2, 41), (3, 42), (4, 43)] {
+            history.record(exchange(3, taken, dropped));
+        }
+
+        assert_eq!(history.known_cards(3), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn probabilities_sum_to_one() {
+        let mut history = TableHistory::new();
+        history.record(exchange(0, 10, 20));
+        history.record(exchange(0, 30, 40));
+
+        for read in history.reads() {
+            let total: f32 = read.suit_probabilities.iter().sum();
+            assert!((total - 1.0).abs() < 1e-5);
+        }
+    }
+}
@@ -0,0 +1,595 @@
+//! Tabular policy learned by self-play, and the versioned text format it is stored in.
+//!
+//! The policy is keyed on a small feature vector: hand score, the value of the face-up stack
+//! card (the best score gain from swapping it in), the seat's remaining lives, the turn
+//! number within the round and whether another seat has already stuck. Each state stores a
+//! value per [`PolicyAction`]; states the trainer never visited fall back to
+//! [`ClassicStrategy`].
+//!
+//! # File format
+//!
+//! ```text
+//! stopbus-policy 1
+//! # stage score gain lives turn stuck  action=value/visits ...
+//! opening 13 2 3 0 0 stick=-0.5/12 swap=-0.1/40 draw=-0.2/33
+//! ```
+//!
+//! Blank lines and lines starting with `#` are ignored. States that are not listed are
+//! unvisited.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::{AutoMove, ClassicStrategy, SeatView, Strategy, TurnStage};
+
+pub const POLICY_FORMAT_VERSION: u32 = 1;
+
+/// The shipped policy, relative to the repository or install folder.
+pub const LEARNED_POLICY_FILE: &str = "assets/ai/learned-policy.txt";
+
+const POLICY_MAGIC: &str = "stopbus-policy";
+
+const SCORE_BUCKETS: usize = 12;
+const GAIN_BUCKETS: usize = 8;
+const LIVES_BUCKETS: usize = 3;
+const TURN_BUCKETS: usize = 4;
+const STAGES: usize = 2;
+
+const STATE_COUNT: usize = STAGES * SCORE_BUCKETS * GAIN_BUCKETS * LIVES_BUCKETS * TURN_BUCKETS * 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PolicyAction {
+    Stick,
+    /// Swap the stack card into the slot that raises the score the most.
+    SwapBest,
+    Draw,
+    Pass,
+}
+
+impl PolicyAction {
+    pub const ALL: [PolicyAction; 4] = [
+        PolicyAction::Stick,
+        PolicyAction::SwapBest,
+        PolicyAction::Draw,
+        PolicyAction::Pass,
+    ];
+
+    fn index(self) -> usize {
+        match self {
+            PolicyAction::Stick => 0,
+            PolicyAction::SwapBest => 1,
+            PolicyAction::Draw => 2,
+            PolicyAction::Pass => 3,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            PolicyAction::Stick => "stick",
+            PolicyAction::SwapBest => "swap",
+            PolicyAction::Draw => "draw",
+            PolicyAction::Pass => "pass",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        PolicyAction::ALL
+            .into_iter()
+            .find(|action| action.name() == name)
+    }
+
+    /// Converts the action into the engine move for the given view.
+    pub fn to_move(self, view: &SeatView<'_>) -> AutoMove {
+        match self {
+            PolicyAction::Stick => AutoMove::Stick,
+            PolicyAction::SwapBest => view
+                .best_swap()
+                .map(|(slot, _)| AutoMove::Swap(slot))
+                .unwrap_or(AutoMove::Pass),
+            PolicyAction::Draw => AutoMove::Draw,
+            PolicyAction::Pass => AutoMove::Pass,
+        }
+    }
+}
+
+/// Bucketed features the policy is keyed on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PolicyState {
+    pub stage: TurnStage,
+    pub score: u8,
+    pub gain: u8,
+    pub lives: u8,
+    pub turn: u8,
+    pub stuck: bool,
+}
+
+impl PolicyState {
+    pub fn from_view(view: &SeatView<'_>) -> Self {
+        let score = view.score();
+        let gain = view.best_swap().map(|(_, best)| best - score).unwrap_or(0);
+        let lives = view.lives[view.player];
+
+        Self {
+            stage: view.stage,
+            score: score_bucket(score),
+            gain: gain_bucket(gain),
+            lives: lives.clamp(1, LIVES_BUCKETS as u8) - 1,
+            turn: (view.round_turns / 4).min(TURN_BUCKETS as u16 - 1) as u8,
+            stuck: view.stick_player.is_some(),
+        }
+    }
+
+    /// Actions the engine accepts for the view this state was built from.
+    pub fn legal_actions(view: &SeatView<'_>) -> Vec<PolicyAction> {
+        let mut actions = Vec::with_capacity(3);
+
+        match view.stage {
+            TurnStage::Opening => {
+                if view.can_stick() {
+                    actions.push(PolicyAction::Stick);
+                }
+                if view.best_swap().is_some() {
+                    actions.push(PolicyAction::SwapBest);
+                }
+                actions.push(PolicyAction::Draw);
+            }
+            TurnStage::AfterDraw => {
+                if view.best_swap().is_some() {
+                    actions.push(PolicyAction::SwapBest);
+                }
+                actions.push(PolicyAction::Pass);
+            }
+        }
+
+        actions
+    }
+
+    fn index(&self) -> usize {
+        let stage = match self.stage {
+            TurnStage::Opening => 0,
+            TurnStage::AfterDraw => 1,
+        };
+
+        let mut index = stage;
+        index = index * SCORE_BUCKETS + self.score as usize;
+        index = index * GAIN_BUCKETS + self.gain as usize;
+        index = index * LIVES_BUCKETS + self.lives as usize;
+        index = index * TURN_BUCKETS + self.turn as usize;
+        index * 2 + self.stuck as usize
+    }
+
+    fn from_index(mut index: usize) -> Self {
+        let stuck = index % 2 == 1;
+        index /= 2;
+        let turn = (index % TURN_BUCKETS) as u8;
+        index /= TURN_BUCKETS;
+        let lives = (index % LIVES_BUCKETS) as u8;
+        index /= LIVES_BUCKETS;
+        let gain = (index % GAIN_BUCKETS) as u8;
+        index /= GAIN_BUCKETS;
+        let score = (index % SCORE_BUCKETS) as u8;
+        index /= SCORE_BUCKETS;
+        let stage = if index == 0 {
+            TurnStage::Opening
+        } else {
+            TurnStage::AfterDraw
+        };
+
+        Self {
+            stage,
+            score,
+            gain,
+            lives,
+            turn,
+            stuck,
+        }
+    }
+}
+
+fn score_bucket(score: u8) -> u8 {
+    match score {
+        0..=10 => 0,
+        11..=13 => 1,
+        14..=16 => 2,
+        17..=18 => 3,
+        19..=20 => 4,
+        21..=22 => 5,
+        23 => 6,
+        24 => 7,
+        25 => 8,
+        26..=27 => 9,
+        28..=29 => 10,
+        _ => 11,
+    }
+}
+
+fn gain_bucket(gain: u8) -> u8 {
+    match gain {
+        0 => 0,
+        1..=2 => 1,
+        3..=4 => 2,
+        5..=6 => 3,
+        7..=9 => 4,
+        10..=12 => 5,
+        13..=18 => 6,
+        _ => 7,
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct ActionValue {
+    value: f32,
+    visits: u32,
+}
+
+/// Action values for every policy state.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PolicyTable {
+    entries: Vec<[ActionValue; 4]>,
+}
+
+impl PolicyTable {
+    pub fn new() -> Self {
+        Self {
+            entries: vec![[ActionValue::default(); 4]; STATE_COUNT],
+        }
+    }
+
+    pub fn value(&self, state: &PolicyState, action: PolicyAction) -> Option<f32> {
+        let entry = self.entries[state.index()][action.index()];
+        (entry.visits > 0).then_some(entry.value)
+    }
+
+    pub fn visits(&self, state: &PolicyState, action: PolicyAction) -> u32 {
+        self.entries[state.index()][action.index()].visits
+    }
+
+    /// Folds one observed return into the running mean for the state and action.
+    pub fn record(&mut self, state: &PolicyState, action: PolicyAction, reward: f32) {
+        let entry = &mut self.entries[state.index()][action.index()];
+        entry.visits = entry.visits.saturating_add(1);
+        entry.value += (reward - entry.value) / entry.visits as f32;
+    }
+
+    /// Picks the highest-valued legal action, or `None` if none of them has been tried.
+    pub fn best_action(&self, state: &PolicyState, legal: &[PolicyAction]) -> Option<PolicyAction> {
+        legal
+            .iter()
+            .filter_map(|&action| self.value(state, action).map(|value| (action, value)))
+            .fold(
+                None,
+                |best: Option<(PolicyAction, f32)>, candidate| match best {
+                    Some((_, value)) if value >= candidate.1 => best,
+                    _ => Some(candidate),
+                },
+            )
+            .map(|(action, _)| action)
+    }
+
+    pub fn visited_states(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.iter().any(|value| value.visits > 0))
+            .count()
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!("{} {}\n", POLICY_MAGIC, POLICY_FORMAT_VERSION);
+        text.push_str("# stage score gain lives turn stuck  action=value/visits ...\n");
+
+        for (index, entry) in self.entries.iter().enumerate() {
+            if entry.iter().all(|value| value.visits == 0) {
+                continue;
+            }
+
+            let state = PolicyState::from_index(index);
+            let stage = match state.stage {
+                TurnStage::Opening => "opening",
+                TurnStage::AfterDraw => "after-draw",
+            };
+            text.push_str(&format!(
+                "{} {} {} {} {} {}",
+                stage, state.score, state.gain, state.lives, state.turn, state.stuck as u8
+            ));
+
+            for action in PolicyAction::ALL {
+                let value = entry[action.index()];
+                if value.visits > 0 {
+                    text.push_str(&format!(
+                        " {}={}/{}",
+                        action.name(),
+                        value.value,
+                        value.visits
+                    ));
+                }
+            }
+            text.push('\n');
+        }
+
+        text
+    }
+
+    pub fn from_text(text: &str) -> Result<Self, PolicyError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+        let (_, header) = lines.next().ok_or(PolicyError::MissingHeader)?;
+        let mut header_parts = header.split_whitespace();
+        if header_parts.next() != Some(POLICY_MAGIC) {
+            return Err(PolicyError::MissingHeader);
+        }
+        let version = header_parts
+            .next()
+            .and_then(|value| value.parse::<u32>().ok())
+            .ok_or(PolicyError::MissingHeader)?;
+        if version != POLICY_FORMAT_VERSION {
+            return Err(PolicyError::UnsupportedVersion(version));
+        }
+
+        let mut table = PolicyTable::new();
+        for (line_number, line) in lines {
+            let invalid = |reason: &str| PolicyError::InvalidLine {
+                line: line_number,
+                reason: reason.to_string(),
+            };
+
+            let mut parts = line.split_whitespace();
+            let stage = match parts.next() {
+                Some("opening") => TurnStage::Opening,
+                Some("after-draw") => TurnStage::AfterDraw,
+                _ => return Err(invalid("unknown stage")),
+            };
+
+            let mut feature = |limit: usize, name: &str| -> Result<u8, PolicyError> {
+                parts
+                    .next()
+                    .and_then(|value| value.parse::<u8>().ok())
+                    .filter(|&value| (value as usize) < limit)
+                    .ok_or_else(|| invalid(&format!("bad {} bucket", name)))
+            };
+
+            let state = PolicyState {
+                stage,
+                score: feature(SCORE_BUCKETS, "score")?,
+                gain: feature(GAIN_BUCKETS, "gain")?,
+                lives: feature(LIVES_BUCKETS, "lives")?,
+                turn: feature(TURN_BUCKETS, "turn")?,
+                stuck: feature(2, "stuck")? == 1,
+            };
+
+            for item in parts {
+                let (name, rest) = item
+                    .split_once('=')
+                    .ok_or_else(|| invalid("expected action=value/visits"))?;
+                let action =
+                    PolicyAction::from_name(name).ok_or_else(|| invalid("unknown action"))?;
+                let (value, visits) = rest
+                    .split_once('/')
+                    .ok_or_else(|| invalid("expected value/visits"))?;
+                let value = value
+                    .parse::<f32>()
+                    .map_err(|_| invalid("bad action value"))?;
+                let visits = visits
+                    .parse::<u32>()
+                    .map_err(|_| invalid("bad visit count"))?;
+
+                table.entries[state.index()][action.index()] = ActionValue { value, visits };
+            }
+        }
+
+        Ok(table)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), PolicyError> {
+        fs::write(path, self.to_text()).map_err(PolicyError::Io)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, PolicyError> {
+        let text = fs::read_to_string(path).map_err(PolicyError::Io)?;
+        Self::from_text(&text)
+    }
+}
+
+impl Default for PolicyTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug)]
+pub enum PolicyError {
+    Io(io::Error),
+    MissingHeader,
+    UnsupportedVersion(u32),
+    InvalidLine { line: usize, reason: String },
+}
+
+impl fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyError::Io(err) => write!(f, "failed to access policy file: {}", err),
+            PolicyError::MissingHeader => {
+                write!(f, "missing '{} <version>' header", POLICY_MAGIC)
+            }
+            PolicyError::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported policy version {} (expected {})",
+                version, POLICY_FORMAT_VERSION
+            ),
+            PolicyError::InvalidLine { line, reason } => {
+                write!(f, "line {}: {}", line, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PolicyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PolicyError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Plays a seat greedily from a learned [`PolicyTable`].
+#[derive(Clone, Debug)]
+pub struct LearnedStrategy {
+    table: Arc<PolicyTable>,
+}
+
+impl LearnedStrategy {
+    pub fn new(table: Arc<PolicyTable>) -> Self {
+        Self { table }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, PolicyError> {
+        PolicyTable::load(path).map(|table| Self::new(Arc::new(table)))
+    }
+
+    pub fn table(&self) -> &Arc<PolicyTable> {
+        &self.table
+    }
+}
+
+impl Strategy for LearnedStrategy {
+    fn choose_move(&mut self, view: &SeatView<'_>) -> AutoMove {
+        let state = PolicyState::from_view(view);
+        let legal = PolicyState::legal_actions(view);
+
+        match self.table.best_action(&state, &legal) {
+            Some(action) => action.to_move(view),
+            None => ClassicStrategy::default().choose_move(view),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CardId, TableHistory, HAND_SIZE, PLAYERS};
+
+    static NO_HISTORY: TableHistory = TableHistory::new();
+
+    fn view<'a>(
+        stage: TurnStage,
+        hand: &'a [Option<CardId>; HAND_SIZE],
+        stack_card: Option<CardId>,
+        lives: &'a [u8; PLAYERS],
+    ) -> SeatView<'a> {
+        SeatView {
+            player: 1,
+            stage,
+            hand,
+            stack_card,
+            lives,
+            stick_player: None,
+            round_turns: 5,
+            history: &NO_HISTORY,
+        }
+    }
+
+    #[test]
+    fn state_index_round_trips() {
+        for index in 0..STATE_COUNT {
+            assert_eq!(PolicyState::from_index(index).index(), index);
+        }
+    }
+
+    #[test]
+    fn features_come_from_the_view() {
+        let lives = [3, 2, 3, 3];
+        let hand = [Some(2), Some(3), Some(15)];
+        let seat = view(TurnStage::Opening, &hand, Some(1), &lives);
+        let state = PolicyState::from_view(&seat);
+
+        assert_eq!(state.score, score_bucket(5));
+        assert_eq!(state.gain, gain_bucket(11));
+        assert_eq!(state.lives, 1);
+        assert_eq!(state.turn, 1);
+        assert!(!state.stuck);
+
+        assert_eq!(
+            PolicyState::legal_actions(&seat),
+            vec![
+                PolicyAction::Stick,
+                PolicyAction::SwapBest,
+                PolicyAction::Draw
+            ]
+        );
+    }
+
+    #[test]
+    fn unvisited_states_fall_back_to_classic() {
+        let lives = [3; PLAYERS];
+        let hand = [Some(13), Some(12), Some(11)];
+        let seat = view(TurnStage::Opening, &hand, Some(2), &lives);
+
+        let mut learned = LearnedStrategy::new(Arc::new(PolicyTable::new()));
+        assert_eq!(learned.choose_move(&seat), AutoMove::Stick);
+    }
+
+    #[test]
+    fn greedy_choice_uses_recorded_values() {
+        let lives = [3; PLAYERS];
+        let hand = [Some(13), Some(12), Some(11)];
+        let seat = view(TurnStage::Opening, &hand, Some(2), &lives);
+        let state = PolicyState::from_view(&seat);
+
+        let mut table = PolicyTable::new();
+        table.record(&state, PolicyAction::Stick, -1.0);
+        table.record(&state, PolicyAction::Draw, 0.0);
+        table.record(&state, PolicyAction::Draw, -0.5);
+
+        assert_eq!(table.value(&state, PolicyAction::Draw), Some(-0.25));
+        assert_eq!(table.visits(&state, PolicyAction::Draw), 2);
+
+        let mut learned = LearnedStrategy::new(Arc::new(table));
+        assert_eq!(learned.choose_move(&seat), AutoMove::Draw);
+    }
+
+    #[test]
+    fn text_format_round_trips() {
+        let lives = [3; PLAYERS];
+        let hand = [Some(2), Some(3), Some(15)];
+        let seat = view(TurnStage::AfterDraw, &hand, Some(1), &lives);
+        let state = PolicyState::from_view(&seat);
+
+        let mut table = PolicyTable::new();
+        table.record(&state, PolicyAction::SwapBest, 0.125);
+        table.record(&state, PolicyAction::Pass, -0.75);
+
+        let text = table.to_text();
+        assert!(text.starts_with("stopbus-policy 1\n"));
+
+        let parsed = PolicyTable::from_text(&text).unwrap();
+        assert_eq!(parsed, table);
+        assert_eq!(parsed.visited_states(), 1);
+    }
+
+    #[test]
+    fn rejects_bad_files() {
+        assert!(matches!(
+            PolicyTable::from_text(""),
+            Err(PolicyError::MissingHeader)
+        ));
+        assert!(matches!(
+            PolicyTable::from_text("stopbus-policy 9\n"),
+            Err(PolicyError::UnsupportedVersion(9))
+        ));
+        assert!(matches!(
+            PolicyTable::from_text("stopbus-policy 1\nopening 99 0 0 0 0\n"),
+            Err(PolicyError::InvalidLine { line: 2, .. })
+        ));
+        assert!(matches!(
+            PolicyTable::from_text("stopbus-policy 1\nopening 1 0 0 0 0 fold=1/1\n"),
+            Err(PolicyError::InvalidLine { line: 2, .. })
+        ));
+    }
+}
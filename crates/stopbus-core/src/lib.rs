@@ -1,6 +1,22 @@
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 
+use history::Exchange;
+
+pub mod env;
+pub mod history;
+pub mod learned;
+pub mod narration;
+pub mod params;
+pub mod sim;
+pub mod strategy;
+
+pub use history::{OpponentRead, TableHistory};
+pub use learned::LearnedStrategy;
+pub use narration::Narrator;
+pub use params::{AiParams, AiPresets};
+pub use strategy::{AutoMove, ClassicStrategy, SeatView, Strategy, TurnStage};
+
 pub type CardId = u8;
 pub const DECK_SIZE: usize = 52;
 pub const PLAYERS: usize = 4;
@@ -26,7 +42,7 @@ pub enum MessageKind {
     Alert,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct GameEvent {
     pub kind: MessageKind,
     pub text: String,
@@ -55,6 +71,35 @@ pub struct DriveReport {
     pub winner: Option<usize>,
     pub draw: bool,
     pub turn_sequence: Vec<usize>,
+    /// Set on the report for the step that ended a round.
+    pub round_summary: Option<RoundSummary>,
+}
+
+/// How a round ended: every hand, the scores and who lost lives, for a showdown view.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoundSummary {
+    /// Final hands of the players who were still in the round; `None` for seats already out.
+    pub hands: [Option<[Option<CardId>; HAND_SIZE]>; PLAYERS],
+    pub round_scores: [u8; PLAYERS],
+    pub stick_player: Option<usize>,
+    pub stop_player: Option<usize>,
+    pub lives_before: [u8; PLAYERS],
+    pub lives_after: [u8; PLAYERS],
+}
+
+impl RoundSummary {
+    pub fn played(&self, player: usize) -> bool {
+        self.hands[player].is_some()
+    }
+
+    pub fn lives_lost(&self, player: usize) -> u8 {
+        self.lives_before[player].saturating_sub(self.lives_after[player])
+    }
+
+    /// True when the player lost their last life this round.
+    pub fn knocked_out(&self, player: usize) -> bool {
+        self.lives_before[player] > 0 && self.lives_after[player] == 0
+    }
 }
 
 impl DriveReport {
@@ -84,6 +129,8 @@ pub struct GameState {
     human_old_stack_card: Option<CardId>,
     human_can_draw_next: bool,
     human_can_stick: bool,
+    strategies: [Box<dyn Strategy>; PLAYERS],
+    history: TableHistory,
 }
 
 impl GameState {
@@ -114,6 +161,10 @@ impl GameState {
             human_old_stack_card: None,
             human_can_draw_next: false,
             human_can_stick: false,
+            strategies: std::array::from_fn(|_| {
+                Box::new(ClassicStrategy::default()) as Box<dyn Strategy>
+            }),
+            history: TableHistory::new(),
         };
 
         state.shuffle_deck();
@@ -167,25 +218,54 @@ impl GameState {
                 winner: alive.first().copied(),
                 draw: alive.is_empty(),
                 turn_sequence: Vec::new(),
+                round_summary: None,
             };
         }
 
         self.end_human_turn();
+        self.hand_over_from_human(Vec::new())
+    }
+
+    /// Plays the human seat's turn with the built-in AI, then advances the game as if OK
+    /// had been pressed. Returns `None` when the human is not currently on turn.
+    pub fn auto_play_human_turn(&mut self) -> Option<DriveReport> {
+        if !self.awaiting_human || self.lives[0] == 0 {
+            return None;
+        }
 
+        let mut events = Vec::new();
+        self.execute_auto_turn(0, &mut events);
+
+        self.awaiting_human = false;
+        self.human_can_draw_next = false;
+        self.human_can_stick = false;
+        self.human_old_stack_card = None;
+
+        let mut report = self.hand_over_from_human(events);
+        report.turn_sequence.insert(0, 0);
+        Some(report)
+    }
+
+    fn hand_over_from_human(&mut self, mut events: Vec<GameEvent>) -> DriveReport {
         if let Some(next) = self.next_alive_after(0) {
             self.current_player = next;
-            return self.drive_round_step(Vec::new());
+            return self.drive_round_step(events);
         }
 
         // Everyone dead
-        let mut events = Vec::new();
-        if let FinishResult::GameOver { winner, draw } = self.finish_round(&mut events) {
+        if let FinishResult::GameOver {
+            winner,
+            draw,
+            summary,
+        } = self.finish_round(&mut events)
+        {
             return DriveReport {
                 events,
                 awaiting_human: false,
                 winner,
                 draw,
                 turn_sequence: Vec::new(),
+                round_summary: Some(summary),
             };
         }
         unreachable!();
@@ -221,6 +301,12 @@ impl GameState {
         Some(self.advance_after_human_turn())
     }
 
+    /// Replaces the policy used for the given seat's automated turns. Seat 0 only uses it
+    /// through [`GameState::auto_play_human_turn`].
+    pub fn set_strategy(&mut self, player: usize, strategy: Box<dyn Strategy>) {
+        self.strategies[player] = strategy;
+    }
+
     pub fn set_lives(&mut self, lives: [u8; PLAYERS]) {
         self.lives = lives;
         self.finished = false;
@@ -238,6 +324,12 @@ impl GameState {
         self.awaiting_human && self.human_can_stick
     }
 
+    pub fn human_can_draw_next(&self) -> bool {
+        self.awaiting_human
+            && self.human_can_draw_next
+            && self.human_old_stack_card == self.current_stack_card()
+    }
+
     pub fn current_player(&self) -> usize {
         self.current_player
     }
@@ -254,6 +346,11 @@ impl GameState {
         self.stack_index
     }
 
+    /// Exchanges with the stack made so far this round, visible to every seat.
+    pub fn history(&self) -> &TableHistory {
+        &self.history
+    }
+
     pub fn stack_top_card(&self) -> Option<CardId> {
         self.deck.get(self.stack_index).copied()
     }
@@ -288,11 +385,10 @@ impl GameState {
             return None;
         }
 
-        let stack_card = self.current_stack_card()?;
-        let hand_card = self.hands[0][slot]?;
+        self.current_stack_card()?;
+        self.hands[0][slot]?;
 
-        self.hands[0][slot] = Some(stack_card);
-        self.deck[self.stack_index] = hand_card;
+        self.swap_with_stack(0, slot);
         self.update_round_scores();
 
         let stack_matches_old = self.human_old_stack_card == Some(self.deck[self.stack_index]);
@@ -320,6 +416,7 @@ impl GameState {
                 winner: None,
                 draw: false,
                 turn_sequence: Vec::new(),
+                round_summary: None,
             });
         }
 
@@ -337,6 +434,7 @@ impl GameState {
             winner: None,
             draw: false,
             turn_sequence: Vec::new(),
+            round_summary: None,
         }
     }
 
@@ -352,6 +450,7 @@ impl GameState {
         self.pending_new_round = false;
         self.round_scores = [0; PLAYERS];
         self.round_turns = 0;
+        self.history.clear();
 
         self.deal_round();
 
@@ -391,22 +490,28 @@ impl GameState {
 
             if self.detect_stop_bus(&mut events) {
                 match self.finish_round(&mut events) {
-                    FinishResult::Continue => {
+                    FinishResult::Continue(summary) => {
                         return DriveReport {
                             events,
                             awaiting_human: false,
                             winner: None,
                             draw: false,
                             turn_sequence: Vec::new(),
+                            round_summary: Some(summary),
                         };
                     }
-                    FinishResult::GameOver { winner, draw } => {
+                    FinishResult::GameOver {
+                        winner,
+                        draw,
+                        summary,
+                    } => {
                         return DriveReport {
                             events,
                             awaiting_human: false,
                             winner,
                             draw,
                             turn_sequence: Vec::new(),
+                            round_summary: Some(summary),
                         };
                     }
                 }
@@ -426,28 +531,35 @@ impl GameState {
                     winner,
                     draw,
                     turn_sequence: Vec::new(),
+                    round_summary: None,
                 };
             }
 
             if let Some(stick) = self.stick_player {
                 if self.current_player == stick {
                     match self.finish_round(&mut events) {
-                        FinishResult::Continue => {
+                        FinishResult::Continue(summary) => {
                             return DriveReport {
                                 events,
                                 awaiting_human: false,
                                 winner: None,
                                 draw: false,
                                 turn_sequence: Vec::new(),
+                                round_summary: Some(summary),
                             };
                         }
-                        FinishResult::GameOver { winner, draw } => {
+                        FinishResult::GameOver {
+                            winner,
+                            draw,
+                            summary,
+                        } => {
                             return DriveReport {
                                 events,
                                 awaiting_human: false,
                                 winner,
                                 draw,
                                 turn_sequence: Vec::new(),
+                                round_summary: Some(summary),
                             };
                         }
                     }
@@ -467,6 +579,7 @@ impl GameState {
                         winner: None,
                         draw: true,
                         turn_sequence: Vec::new(),
+                        round_summary: None,
                     };
                 }
 
@@ -477,6 +590,7 @@ impl GameState {
                     winner: None,
                     draw: false,
                     turn_sequence: Vec::new(),
+                    round_summary: None,
                 };
             }
 
@@ -487,13 +601,19 @@ impl GameState {
                 }
 
                 // Everyone dead
-                if let FinishResult::GameOver { winner, draw } = self.finish_round(&mut events) {
+                if let FinishResult::GameOver {
+                    winner,
+                    draw,
+                    summary,
+                } = self.finish_round(&mut events)
+                {
                     return DriveReport {
                         events,
                         awaiting_human: false,
                         winner,
                         draw,
                         turn_sequence: Vec::new(),
+                        round_summary: Some(summary),
                     };
                 }
                 unreachable!();
@@ -506,13 +626,19 @@ impl GameState {
                 self.current_player = next;
             } else {
                 // Everyone dead
-                if let FinishResult::GameOver { winner, draw } = self.finish_round(&mut events) {
+                if let FinishResult::GameOver {
+                    winner,
+                    draw,
+                    summary,
+                } = self.finish_round(&mut events)
+                {
                     return DriveReport {
                         events,
                         awaiting_human: false,
                         winner,
                         draw,
                         turn_sequence: Vec::new(),
+                        round_summary: Some(summary),
                     };
                 }
                 unreachable!();
@@ -524,6 +650,7 @@ impl GameState {
                 winner: None,
                 draw: false,
                 turn_sequence: vec![active],
+                round_summary: None,
             };
         }
     }
@@ -553,6 +680,15 @@ impl GameState {
     fn finish_round(&mut self, events: &mut Vec<GameEvent>) -> FinishResult {
         self.update_round_scores();
 
+        let lives_before = self.lives;
+        let mut hands = [None; PLAYERS];
+        for (player, hand) in hands.iter_mut().enumerate() {
+            if lives_before[player] > 0 {
+                *hand = Some(self.hands[player]);
+            }
+        }
+        let stick_player = self.stick_player;
+
         let mut life_losses: Vec<LifeLossInfo> = Vec::new();
         let stop_player = self.stop_player;
 
@@ -570,6 +706,15 @@ impl GameState {
             }
         }
 
+        let summary = RoundSummary {
+            hands,
+            round_scores: self.round_scores,
+            stick_player,
+            stop_player,
+            lives_before,
+            lives_after: self.lives,
+        };
+
         self.stop_player = None;
         self.stick_player = None;
         self.stick_player_score = None;
@@ -620,6 +765,7 @@ impl GameState {
                 FinishResult::GameOver {
                     winner: None,
                     draw: true,
+                    summary,
                 }
             }
             1 => {
@@ -641,6 +787,7 @@ impl GameState {
                 FinishResult::GameOver {
                     winner: Some(winner),
                     draw: false,
+                    summary,
                 }
             }
             _ => {
@@ -654,7 +801,7 @@ impl GameState {
                     events.push(event);
                 }
                 self.pending_new_round = true;
-                FinishResult::Continue
+                FinishResult::Continue(summary)
             }
         }
     }
@@ -719,17 +866,22 @@ impl GameState {
 
     fn execute_auto_turn(&mut self, player: usize, events: &mut Vec<GameEvent>) {
         self.update_round_scores();
-        let base_score = self.round_scores[player];
 
-        if self.stick_player.is_none() && base_score > 25 {
-            self.mark_player_sticking(player, events);
-            self.complete_turn();
-            return;
-        }
-
-        if self.try_swap_for_improvement(player, base_score, true) {
-            self.complete_turn();
-            return;
+        match self.choose_auto_move(player, TurnStage::Opening) {
+            AutoMove::Stick if self.stick_player.is_none() => {
+                self.mark_player_sticking(player, events);
+                self.complete_turn();
+                return;
+            }
+            AutoMove::Swap(slot) if self.try_swap(player, slot) => {
+                self.complete_turn();
+                return;
+            }
+            AutoMove::Draw => {}
+            _ => {
+                self.complete_turn();
+                return;
+            }
         }
 
         if !self.advance_stack_pointer(events) {
@@ -738,12 +890,28 @@ impl GameState {
         }
 
         self.update_round_scores();
-        let second_base = self.round_scores[player];
-        let _ = self.try_swap_for_improvement(player, second_base, false);
+        if let AutoMove::Swap(slot) = self.choose_auto_move(player, TurnStage::AfterDraw) {
+            let _ = self.try_swap(player, slot);
+        }
 
         self.complete_turn();
     }
 
+    fn choose_auto_move(&mut self, player: usize, stage: TurnStage) -> AutoMove {
+        let view = SeatView {
+            player,
+            stage,
+            hand: &self.hands[player],
+            stack_card: self.deck.get(self.stack_index).copied(),
+            lives: &self.lives,
+            stick_player: self.stick_player,
+            round_turns: self.round_turns,
+            history: &self.history,
+        };
+
+        self.strategies[player].choose_move(&view)
+    }
+
     fn mark_player_sticking(&mut self, player: usize, _events: &mut [GameEvent]) {
         self.update_round_scores();
         let score = self.round_scores[player];
@@ -755,46 +923,17 @@ impl GameState {
         self.stick_player_score = Some(score);
     }
 
-    fn try_swap_for_improvement(
-        &mut self,
-        player: usize,
-        base_score: u8,
-        require_gt_six: bool,
-    ) -> bool {
-        let Some(stack_card) = self.current_stack_card() else {
+    fn try_swap(&mut self, player: usize, slot: usize) -> bool {
+        if slot >= HAND_SIZE
+            || self.hands[player][slot].is_none()
+            || self.current_stack_card().is_none()
+        {
             return false;
-        };
-
-        let mut best_slot = None;
-        let mut best_score = base_score;
-
-        for slot in 0..HAND_SIZE {
-            if self.hands[player][slot].is_some() {
-                let mut temp = self.hands[player];
-                temp[slot] = Some(stack_card);
-                let score = hand_max_score(&temp);
-                if score > best_score {
-                    best_score = score;
-                    best_slot = Some(slot);
-                }
-            }
-        }
-
-        let improvement = if require_gt_six {
-            best_score > base_score && best_score > 6
-        } else {
-            best_score > base_score
-        };
-
-        if improvement {
-            if let Some(slot) = best_slot {
-                self.swap_with_stack(player, slot);
-                self.update_round_scores();
-                return true;
-            }
         }
 
-        false
+        self.swap_with_stack(player, slot);
+        self.update_round_scores();
+        true
     }
 
     fn advance_stack_pointer(&mut self, events: &mut Vec<GameEvent>) -> bool {
@@ -847,6 +986,12 @@ impl GameState {
             let stack_card = self.deck[self.stack_index];
             self.hands[player][slot] = Some(stack_card);
             self.deck[self.stack_index] = card;
+            self.history.record(Exchange {
+                player,
+                taken: stack_card,
+                dropped: card,
+                turn: self.round_turns,
+            });
         }
     }
 
@@ -908,8 +1053,12 @@ impl Default for GameState {
 
 #[derive(Debug)]
 enum FinishResult {
-    Continue,
-    GameOver { winner: Option<usize>, draw: bool },
+    Continue(RoundSummary),
+    GameOver {
+        winner: Option<usize>,
+        draw: bool,
+        summary: RoundSummary,
+    },
 }
 
 fn ordered_deck() -> [CardId; DECK_SIZE] {
@@ -1118,6 +1267,28 @@ mod tests {
         assert!(!game.human_can_stick);
     }
 
+    #[test]
+    fn test_swaps_are_recorded_in_public_history() {
+        let mut game = GameState::new(Some(123));
+        game.start_new_round();
+        game.awaiting_human = true;
+
+        let hand_card = game.hands[0][1].unwrap();
+        let stack_card = game.stack_top_card().unwrap();
+        game.human_swap_with_stack(1).unwrap();
+
+        let exchanges = game.history().exchanges();
+        let last = exchanges.last().unwrap();
+        assert_eq!(last.player, 0);
+        assert_eq!(last.taken, stack_card);
+        assert_eq!(last.dropped, hand_card);
+        assert_eq!(game.history().known_cards(0), vec![stack_card]);
+
+        // The new round is driven up to the human's turn, so only AI swaps remain.
+        game.start_new_round();
+        assert!(game.history().exchanges_for(0).next().is_none());
+    }
+
     #[test]
     fn test_human_swap_invalid_indices() {
         let mut game = GameState::new(None);
@@ -1359,7 +1530,8 @@ mod tests {
             awaiting_human: false,
             winner: None,
             draw: false,
-            turn_sequence: vec![]
+            turn_sequence: vec![],
+            round_summary: None,
         }
         .game_over());
         assert!(DriveReport {
@@ -1367,7 +1539,8 @@ mod tests {
             awaiting_human: false,
             winner: Some(0),
             draw: false,
-            turn_sequence: vec![]
+            turn_sequence: vec![],
+            round_summary: None,
         }
         .game_over());
         assert!(DriveReport {
@@ -1375,7 +1548,8 @@ mod tests {
             awaiting_human: false,
             winner: None,
             draw: true,
-            turn_sequence: vec![]
+            turn_sequence: vec![],
+            round_summary: None,
         }
         .game_over());
 
@@ -1383,6 +1557,48 @@ mod tests {
         assert_eq!(game2.lives(), &[3, 3, 3, 3]);
     }
 
+    #[test]
+    fn round_summaries_show_every_hand_before_the_next_deal() {
+        let mut game = GameState::new(Some(2024));
+        let mut report = game.start_fresh();
+        let mut summaries = 0;
+
+        while !report.game_over() {
+            let lives_before = *game.lives();
+            report = sim::next_report(&mut game, &report);
+
+            let Some(summary) = &report.round_summary else {
+                continue;
+            };
+            summaries += 1;
+
+            assert_eq!(summary.lives_before, lives_before);
+            assert_eq!(&summary.lives_after, game.lives());
+            for (player, &before) in lives_before.iter().enumerate() {
+                assert_eq!(summary.played(player), before > 0);
+                match summary.hands[player] {
+                    Some(hand) => {
+                        assert_eq!(hand, game.hands[player]);
+                        assert_eq!(summary.round_scores[player], hand_max_score(&hand));
+                    }
+                    None => assert_eq!(summary.round_scores[player], 0),
+                }
+                assert!(summary.lives_lost(player) <= 1);
+            }
+
+            let losers = (0..PLAYERS).filter(|&p| summary.lives_lost(p) > 0).count();
+            assert!(losers > 0);
+            if let Some(stopper) = summary.stop_player {
+                assert_eq!(summary.round_scores[stopper], 31);
+                assert_eq!(summary.lives_lost(stopper), 0);
+            }
+        }
+
+        assert!(summaries > 1);
+        let last = report.round_summary.expect("the final round is summarised");
+        assert!((0..PLAYERS).any(|player| last.knocked_out(player)));
+    }
+
     #[test]
     fn test_human_draw_deck_overflow() {
         let mut game = GameState::new(None);
@@ -1724,6 +1940,53 @@ mod tests {
         assert!(report.draw);
     }
 
+    fn play_seeded_autoplay_game(seed: u64) -> (GameState, DriveReport, usize) {
+        let mut game = GameState::new(Some(seed));
+        let mut report = game.start_fresh();
+        let mut steps = 0;
+
+        while !report.game_over() && steps < 10_000 {
+            report = if report.awaiting_human {
+                game.auto_play_human_turn()
+                    .expect("human seat should be on turn")
+            } else {
+                game.continue_automation()
+            };
+            steps += 1;
+        }
+
+        (game, report, steps)
+    }
+
+    #[test]
+    fn test_auto_play_human_turn_requires_human_turn() {
+        let mut game = GameState::new(Some(7));
+        game.start_game();
+        assert!(game.auto_play_human_turn().is_none());
+
+        let report = game.start_new_round();
+        if report.awaiting_human {
+            let report = game.auto_play_human_turn().unwrap();
+            assert_eq!(report.turn_sequence.first(), Some(&0));
+            assert!(!game.awaiting_human() || report.awaiting_human);
+        }
+    }
+
+    #[test]
+    fn test_seeded_autoplay_game_is_reproducible() {
+        let (game, report, steps) = play_seeded_autoplay_game(2024);
+        let (replay, replay_report, replay_steps) = play_seeded_autoplay_game(2024);
+
+        assert!(report.game_over());
+        assert_eq!(report.winner, replay_report.winner);
+        assert_eq!(game.lives(), replay.lives());
+        assert_eq!(steps, replay_steps);
+
+        assert_eq!(report.winner, Some(2));
+        assert_eq!(game.lives(), &[0, 0, 1, 0]);
+        assert_eq!(steps, 68);
+    }
+
     #[test]
     fn test_impossible_branches() {
         // join_name_list with 0
@@ -0,0 +1,351 @@
+//! Short spoken-style sentences describing the table, for screen readers and text front ends.
+//!
+//! [`describe_table`] reads out what the human can see. A [`Narrator`] follows a game from
+//! report to report and describes what happened since the previous one: the exchanges
+//! other players made with the stack, who stuck, the showdown at the end of each round and
+//! whose turn it is. Game events are not repeated; front ends already show their text.
+
+use crate::history::Exchange;
+use crate::{
+    card_rank, card_suit, hand_max_score, CardId, DriveReport, GameState, RoundSummary, Suit,
+};
+use crate::{HAND_SIZE, PLAYERS};
+
+pub fn card_name(card: CardId) -> String {
+    let (Some(rank), Some(suit)) = (card_rank(card), card_suit(card)) else {
+        return "no card".to_string();
+    };
+
+    let rank = match rank {
+        1 => "Ace".to_string(),
+        11 => "Jack".to_string(),
+        12 => "Queen".to_string(),
+        13 => "King".to_string(),
+        value => value.to_string(),
+    };
+
+    format!("{} of {}", rank, suit_name(suit))
+}
+
+fn suit_name(suit: Suit) -> &'static str {
+    match suit {
+        Suit::Clubs => "clubs",
+        Suit::Diamonds => "diamonds",
+        Suit::Hearts => "hearts",
+        Suit::Spades => "spades",
+    }
+}
+
+fn player_name(player: usize) -> String {
+    if player == 0 {
+        "You".to_string()
+    } else {
+        format!("Player {}", player + 1)
+    }
+}
+
+/// "Ace of hearts, 10 of hearts, 4 of clubs, score 21"
+pub fn describe_hand(hand: &[Option<CardId>; HAND_SIZE]) -> String {
+    let cards: Vec<String> = hand.iter().flatten().map(|&card| card_name(card)).collect();
+
+    if cards.is_empty() {
+        return "no cards".to_string();
+    }
+
+    format!("{}, score {}", cards.join(", "), hand_max_score(hand))
+}
+
+/// "Your hand: Ace of hearts, 10 of hearts, 4 of clubs, score 21. Stack shows King of hearts."
+pub fn describe_table(game: &GameState) -> String {
+    let stack = match game.stack_top_card() {
+        Some(card) => format!("Stack shows {}.", card_name(card)),
+        None => "The stack is empty.".to_string(),
+    };
+
+    format!("Your hand: {}. {}", describe_hand(&game.hands[0]), stack)
+}
+
+/// "Lives: you 3, player 2 2, player 3 out, player 4 1."
+pub fn describe_lives(game: &GameState) -> String {
+    let entries: Vec<String> = game
+        .lives()
+        .iter()
+        .enumerate()
+        .map(|(player, &lives)| {
+            let name = if player == 0 {
+                "you".to_string()
+            } else {
+                format!("player {}", player + 1)
+            };
+            if lives == 0 {
+                format!("{} out", name)
+            } else {
+                format!("{} {}", name, lives)
+            }
+        })
+        .collect();
+
+    format!("Lives: {}.", entries.join(", "))
+}
+
+/// "score 27, stuck, lost a life": how the round went for one player.
+pub fn describe_round_result(summary: &RoundSummary, player: usize) -> String {
+    let mut parts = vec![format!("score {}", summary.round_scores[player])];
+
+    if summary.stop_player == Some(player) {
+        parts.push("stopped the bus".to_string());
+    }
+    if summary.stick_player == Some(player) {
+        parts.push("stuck".to_string());
+    }
+    if summary.knocked_out(player) {
+        parts.push("knocked out".to_string());
+    } else if summary.lives_lost(player) > 0 {
+        parts.push("lost a life".to_string());
+    }
+
+    parts.join(", ")
+}
+
+/// "Showdown." followed by one sentence per player still in the round.
+pub fn describe_round_summary(summary: &RoundSummary) -> Vec<String> {
+    let mut sentences = vec!["Showdown.".to_string()];
+
+    for (player, hand) in summary.hands.iter().enumerate() {
+        let Some(hand) = hand else {
+            continue;
+        };
+        let cards: Vec<String> = hand.iter().flatten().map(|&card| card_name(card)).collect();
+        sentences.push(format!(
+            "{}: {}, {}.",
+            player_name(player),
+            cards.join(", "),
+            describe_round_result(summary, player)
+        ));
+    }
+
+    sentences
+}
+
+fn describe_exchange(exchange: &Exchange) -> String {
+    let verb = if exchange.player == 0 {
+        "take"
+    } else {
+        "takes"
+    };
+    format!(
+        "{} {} {} and {} {}.",
+        player_name(exchange.player),
+        verb,
+        card_name(exchange.taken),
+        if exchange.player == 0 {
+            "leave"
+        } else {
+            "leaves"
+        },
+        card_name(exchange.dropped)
+    )
+}
+
+/// Follows a game and narrates each [`DriveReport`].
+#[derive(Clone, Debug, Default)]
+pub struct Narrator {
+    seen_exchanges: Vec<Exchange>,
+    seen_stick: Option<usize>,
+}
+
+impl Narrator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sentences describing what changed since the previous report, in table order.
+    pub fn narrate(&mut self, game: &GameState, report: &DriveReport) -> Vec<String> {
+        let mut sentences = Vec::new();
+
+        let exchanges = game.history().exchanges();
+        if !exchanges.starts_with(&self.seen_exchanges) {
+            // The history restarts with every round.
+            self.seen_exchanges.clear();
+        }
+        for exchange in &exchanges[self.seen_exchanges.len()..] {
+            sentences.push(describe_exchange(exchange));
+        }
+        self.seen_exchanges = exchanges.to_vec();
+
+        let stick_player = game.stick_player();
+        if let Some(player) = stick_player.filter(|_| stick_player != self.seen_stick) {
+            sentences.push(if player == 0 {
+                "You stick.".to_string()
+            } else {
+                format!("Player {} sticks.", player + 1)
+            });
+        }
+        self.seen_stick = stick_player;
+
+        if let Some(summary) = &report.round_summary {
+            sentences.extend(describe_round_summary(summary));
+        }
+
+        if report.game_over() {
+            sentences.push(describe_lives(game));
+            sentences.push(match report.winner {
+                Some(0) => "Game over. You win.".to_string(),
+                Some(winner) if winner < PLAYERS => {
+                    format!("Game over. Player {} wins.", winner + 1)
+                }
+                _ => "Game over. It's a draw.".to_string(),
+            });
+        } else if report.awaiting_human {
+            sentences.push(format!("Your turn. {}", describe_table(game)));
+        }
+
+        sentences
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_cards_like_a_person_would() {
+        assert_eq!(card_name(1), "Ace of clubs");
+        assert_eq!(card_name(10), "10 of clubs");
+        assert_eq!(card_name(24), "Jack of diamonds");
+        assert_eq!(card_name(38), "Queen of hearts");
+        assert_eq!(card_name(52), "King of spades");
+        assert_eq!(card_name(0), "no card");
+    }
+
+    #[test]
+    fn describes_the_humans_view() {
+        let mut game = GameState::new(Some(7));
+        game.hands[0] = [Some(27), Some(36), Some(4)];
+
+        let text = describe_table(&game);
+        assert!(
+            text.starts_with("Your hand: Ace of hearts, 10 of hearts, 4 of clubs, score 21."),
+            "{}",
+            text
+        );
+
+        assert_eq!(describe_hand(&[None; HAND_SIZE]), "no cards");
+    }
+
+    #[test]
+    fn narrates_a_game_until_the_human_moves() {
+        let mut game = GameState::new(Some(2024));
+        let mut narrator = Narrator::new();
+
+        let mut report = game.start_fresh();
+        let mut sentences = narrator.narrate(&game, &report);
+        while !report.awaiting_human && !report.game_over() {
+            report = game.continue_automation();
+            sentences.extend(narrator.narrate(&game, &report));
+        }
+
+        let last = sentences.last().expect("some narration");
+        assert!(last.starts_with("Your turn. Your hand: "), "{}", last);
+        assert!(last.contains(&describe_table(&game)));
+
+        let swaps = sentences.iter().filter(|s| s.contains(" takes ")).count();
+        assert_eq!(swaps, game.history().exchanges().len());
+
+        // Nothing new happened, so only the turn prompt repeats.
+        assert_eq!(narrator.narrate(&game, &report), vec![last.clone()]);
+    }
+
+    #[test]
+    fn announces_the_humans_swap_and_stick() {
+        let mut game = GameState::new(Some(11));
+        let mut narrator = Narrator::new();
+        let mut report = game.start_fresh();
+        while !report.awaiting_human {
+            report = game.continue_automation();
+        }
+        narrator.narrate(&game, &report);
+
+        let taken = game.stack_top_card().unwrap();
+        let dropped = game.hands[0][0].unwrap();
+        let report = game.human_swap_with_stack(0).unwrap();
+        let sentences = narrator.narrate(&game, &report);
+
+        assert_eq!(
+            sentences[0],
+            format!(
+                "You take {} and leave {}.",
+                card_name(taken),
+                card_name(dropped)
+            )
+        );
+
+        if let Some(report) = game.human_stick() {
+            let sentences = narrator.narrate(&game, &report);
+            assert!(sentences.contains(&"You stick.".to_string()));
+        }
+    }
+
+    #[test]
+    fn reads_out_the_showdown() {
+        let summary = RoundSummary {
+            hands: [
+                Some([Some(27), Some(36), Some(4)]),
+                None,
+                Some([Some(1), Some(11), Some(13)]),
+                Some([Some(2), Some(3), Some(5)]),
+            ],
+            round_scores: [21, 0, 31, 5],
+            stick_player: Some(0),
+            stop_player: Some(2),
+            lives_before: [2, 0, 3, 1],
+            lives_after: [1, 0, 3, 0],
+        };
+
+        assert_eq!(
+            describe_round_summary(&summary),
+            [
+                "Showdown.",
+                "You: Ace of hearts, 10 of hearts, 4 of clubs, score 21, stuck, lost a life.",
+                "Player 3: Ace of clubs, Jack of clubs, King of clubs, score 31, stopped the bus.",
+                "Player 4: 2 of clubs, 3 of clubs, 5 of clubs, score 5, knocked out.",
+            ]
+        );
+
+        let mut game = GameState::new(Some(5));
+        let mut narrator = Narrator::new();
+        let report = DriveReport {
+            events: Vec::new(),
+            awaiting_human: false,
+            winner: None,
+            draw: false,
+            turn_sequence: Vec::new(),
+            round_summary: Some(summary),
+        };
+        game.start_fresh();
+        let sentences = narrator.narrate(&game, &report);
+        assert!(sentences.contains(&"Showdown.".to_string()));
+    }
+
+    #[test]
+    fn game_over_reads_the_lives_and_winner() {
+        let mut game = GameState::new(Some(2024));
+        let mut narrator = Narrator::new();
+        let outcome = crate::sim::play_to_completion(&mut game);
+        let report = DriveReport {
+            events: Vec::new(),
+            awaiting_human: false,
+            winner: outcome.winner,
+            draw: outcome.draw,
+            turn_sequence: Vec::new(),
+            round_summary: None,
+        };
+
+        let sentences = narrator.narrate(&game, &report);
+        assert_eq!(
+            sentences.last().unwrap(),
+            &format!("Game over. Player {} wins.", outcome.winner.unwrap() + 1)
+        );
+        assert!(sentences.contains(&describe_lives(&game)));
+    }
+}
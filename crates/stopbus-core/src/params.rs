@@ -0,0 +1,290 @@
+//! Tunable thresholds for [`ClassicStrategy`](crate::ClassicStrategy) and the difficulty
+//! presets built from them.
+//!
+//! # Preset file format
+//!
+//! ```text
+//! stopbus-presets 1
+//! # name stick_above first_swap_above draw_swap_above
+//! easy 18 0 20
+//! normal 25 6 0
+//! hard 30 15 0
+//! ```
+//!
+//! Blank lines and lines starting with `#` are ignored. Presets keep their file order.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub const PRESETS_FORMAT_VERSION: u32 = 1;
+
+/// The shipped presets, relative to the repository or install folder.
+pub const PRESETS_FILE: &str = "assets/ai/presets.txt";
+
+const PRESETS_MAGIC: &str = "stopbus-presets";
+
+/// Highest meaningful threshold: no hand scores above 31.
+pub const MAX_THRESHOLD: u8 = 31;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AiParams {
+    /// Stick at the start of a turn when the hand scores more than this.
+    pub stick_above: u8,
+    /// Take the face-up stack card when it lifts the hand above this score.
+    pub first_swap_above: u8,
+    /// Take a freshly drawn card when it lifts the hand above this score.
+    pub draw_swap_above: u8,
+}
+
+impl AiParams {
+    /// The thresholds hard-coded in the original AutoPlay routine.
+    pub const CLASSIC: AiParams = AiParams {
+        stick_above: 25,
+        first_swap_above: 6,
+        draw_swap_above: 0,
+    };
+
+    pub fn clamped(self) -> Self {
+        Self {
+            stick_above: self.stick_above.min(MAX_THRESHOLD),
+            first_swap_above: self.first_swap_above.min(MAX_THRESHOLD),
+            draw_swap_above: self.draw_swap_above.min(MAX_THRESHOLD),
+        }
+    }
+}
+
+impl Default for AiParams {
+    fn default() -> Self {
+        Self::CLASSIC
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AiPreset {
+    pub name: String,
+    pub params: AiParams,
+}
+
+/// Named parameter sets, e.g. the output of the tuner.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AiPresets {
+    presets: Vec<AiPreset>,
+}
+
+impl AiPresets {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn presets(&self) -> &[AiPreset] {
+        &self.presets
+    }
+
+    pub fn get(&self, name: &str) -> Option<AiParams> {
+        self.presets
+            .iter()
+            .find(|preset| preset.name.eq_ignore_ascii_case(name))
+            .map(|preset| preset.params)
+    }
+
+    /// Adds a preset, replacing any existing one with the same name.
+    pub fn insert(&mut self, name: &str, params: AiParams) {
+        match self
+            .presets
+            .iter_mut()
+            .find(|preset| preset.name.eq_ignore_ascii_case(name))
+        {
+            Some(preset) => preset.params = params,
+            None => self.presets.push(AiPreset {
+                name: name.to_string(),
+                params,
+            }),
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!("{} {}\n", PRESETS_MAGIC, PRESETS_FORMAT_VERSION);
+        text.push_str("# name stick_above first_swap_above draw_swap_above\n");
+
+        for preset in &self.presets {
+            let params = preset.params;
+            text.push_str(&format!(
+                "{} {} {} {}\n",
+                preset.name, params.stick_above, params.first_swap_above, params.draw_swap_above
+            ));
+        }
+
+        text
+    }
+
+    pub fn from_text(text: &str) -> Result<Self, PresetsError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+        let (_, header) = lines.next().ok_or(PresetsError::MissingHeader)?;
+        let mut header_parts = header.split_whitespace();
+        if header_parts.next() != Some(PRESETS_MAGIC) {
+            return Err(PresetsError::MissingHeader);
+        }
+        let version = header_parts
+            .next()
+            .and_then(|value| value.parse::<u32>().ok())
+            .ok_or(PresetsError::MissingHeader)?;
+        if version != PRESETS_FORMAT_VERSION {
+            return Err(PresetsError::UnsupportedVersion(version));
+        }
+
+        let mut presets = AiPresets::new();
+        for (line_number, line) in lines {
+            let invalid = |reason: &str| PresetsError::InvalidLine {
+                line: line_number,
+                reason: reason.to_string(),
+            };
+
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let [name, stick, first_swap, draw_swap] = parts[..] else {
+                return Err(invalid("expected a name and three thresholds"));
+            };
+
+            let threshold = |value: &str| {
+                value
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|&value| value <= MAX_THRESHOLD)
+                    .ok_or_else(|| invalid(&format!("bad threshold '{}'", value)))
+            };
+
+            if presets.get(name).is_some() {
+                return Err(invalid(&format!("duplicate preset '{}'", name)));
+            }
+            presets.insert(
+                name,
+                AiParams {
+                    stick_above: threshold(stick)?,
+                    first_swap_above: threshold(first_swap)?,
+                    draw_swap_above: threshold(draw_swap)?,
+                },
+            );
+        }
+
+        Ok(presets)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), PresetsError> {
+        fs::write(path, self.to_text()).map_err(PresetsError::Io)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, PresetsError> {
+        let text = fs::read_to_string(path).map_err(PresetsError::Io)?;
+        Self::from_text(&text)
+    }
+}
+
+#[derive(Debug)]
+pub enum PresetsError {
+    Io(io::Error),
+    MissingHeader,
+    UnsupportedVersion(u32),
+    InvalidLine { line: usize, reason: String },
+}
+
+impl fmt::Display for PresetsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PresetsError::Io(err) => write!(f, "failed to access presets file: {}", err),
+            PresetsError::MissingHeader => {
+                write!(f, "missing '{} <version>' header", PRESETS_MAGIC)
+            }
+            PresetsError::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported presets version {} (expected {})",
+                version, PRESETS_FORMAT_VERSION
+            ),
+            PresetsError::InvalidLine { line, reason } => {
+                write!(f, "line {}: {}", line, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PresetsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PresetsError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_format_round_trips() {
+        let mut presets = AiPresets::new();
+        presets.insert(
+            "easy",
+            AiParams {
+                stick_above: 20,
+                first_swap_above: 2,
+                draw_swap_above: 1,
+            },
+        );
+        presets.insert("normal", AiParams::CLASSIC);
+
+        let text = presets.to_text();
+        assert!(text.starts_with("stopbus-presets 1\n"));
+        assert!(text.contains("normal 25 6 0\n"));
+
+        let parsed = AiPresets::from_text(&text).unwrap();
+        assert_eq!(parsed, presets);
+        assert_eq!(parsed.get("NORMAL"), Some(AiParams::CLASSIC));
+        assert_eq!(parsed.get("hard"), None);
+    }
+
+    #[test]
+    fn insert_replaces_existing_names() {
+        let mut presets = AiPresets::new();
+        presets.insert("hard", AiParams::CLASSIC);
+        presets.insert(
+            "Hard",
+            AiParams {
+                stick_above: 27,
+                ..AiParams::CLASSIC
+            },
+        );
+
+        assert_eq!(presets.presets().len(), 1);
+        assert_eq!(presets.get("hard").unwrap().stick_above, 27);
+    }
+
+    #[test]
+    fn rejects_bad_files() {
+        assert!(matches!(
+            AiPresets::from_text("# nothing\n"),
+            Err(PresetsError::MissingHeader)
+        ));
+        assert!(matches!(
+            AiPresets::from_text("stopbus-presets 2\n"),
+            Err(PresetsError::UnsupportedVersion(2))
+        ));
+        assert!(matches!(
+            AiPresets::from_text("stopbus-presets 1\neasy 20 3\n"),
+            Err(PresetsError::InvalidLine { line: 2, .. })
+        ));
+        assert!(matches!(
+            AiPresets::from_text("stopbus-presets 1\neasy 20 3 40\n"),
+            Err(PresetsError::InvalidLine { line: 2, .. })
+        ));
+        assert!(matches!(
+            AiPresets::from_text("stopbus-presets 1\neasy 1 1 1\neasy 2 2 2\n"),
+            Err(PresetsError::InvalidLine { line: 3, .. })
+        ));
+    }
+}
@@ -0,0 +1,87 @@
+//! Headless simulation of whole games with every seat automated.
+
+use crate::{DriveReport, GameState, Strategy, PLAYERS};
+
+/// Cap on reports processed per game. Rounds can stall once the deck is exhausted and
+/// nobody is able to stick; such games are reported as stalled.
+pub const MAX_GAME_STEPS: usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameOutcome {
+    pub winner: Option<usize>,
+    pub draw: bool,
+    pub stalled: bool,
+    pub lives: [u8; PLAYERS],
+    /// Number of reports processed, including the opening deal.
+    pub steps: usize,
+}
+
+/// Plays a seeded game to completion, seat 0 included, using the given strategies.
+pub fn play_game(seed: u64, strategies: [Box<dyn Strategy>; PLAYERS]) -> GameOutcome {
+    let mut game = GameState::new(Some(seed));
+    for (player, strategy) in strategies.into_iter().enumerate() {
+        game.set_strategy(player, strategy);
+    }
+
+    play_to_completion(&mut game)
+}
+
+/// Drives an already configured game until it finishes or stalls.
+pub fn play_to_completion(game: &mut GameState) -> GameOutcome {
+    let mut report = game.start_fresh();
+    let mut steps = 1;
+
+    while !report.game_over() && steps < MAX_GAME_STEPS {
+        report = next_report(game, &report);
+        steps += 1;
+    }
+
+    GameOutcome {
+        winner: report.winner,
+        draw: report.draw,
+        stalled: !report.game_over(),
+        lives: *game.lives(),
+        steps,
+    }
+}
+
+pub fn next_report(game: &mut GameState, report: &DriveReport) -> DriveReport {
+    if report.awaiting_human {
+        if let Some(next) = game.auto_play_human_turn() {
+            return next;
+        }
+    }
+
+    game.continue_automation()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ClassicStrategy;
+
+    fn classic_seats() -> [Box<dyn Strategy>; PLAYERS] {
+        std::array::from_fn(|_| Box::new(ClassicStrategy::default()) as Box<dyn Strategy>)
+    }
+
+    #[test]
+    fn seeded_games_are_reproducible() {
+        let first = play_game(2024, classic_seats());
+        let second = play_game(2024, classic_seats());
+
+        assert_eq!(first, second);
+        assert_eq!(first.winner, Some(2));
+        assert_eq!(first.lives, [0, 0, 1, 0]);
+        assert!(!first.stalled);
+    }
+
+    #[test]
+    fn stalled_games_are_reported() {
+        // Seed 1241 exhausts the deck with nobody able to stick.
+        let outcome = play_game(1241, classic_seats());
+
+        assert!(outcome.stalled);
+        assert_eq!(outcome.winner, None);
+        assert_eq!(outcome.steps, MAX_GAME_STEPS);
+    }
+}
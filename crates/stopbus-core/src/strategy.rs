@@ -0,0 +1,223 @@
+use std::fmt;
+
+use crate::{hand_max_score, AiParams, CardId, TableHistory, HAND_SIZE, PLAYERS};
+
+/// Point in an automated turn at which a strategy is consulted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TurnStage {
+    /// Start of the turn: the seat may stick, swap with the stack card or draw.
+    Opening,
+    /// After drawing the next stack card: the seat may swap with it or pass.
+    AfterDraw,
+}
+
+/// Move chosen by a strategy for the current stage of its turn.
+///
+/// Moves that are not legal at the current stage are treated as [`AutoMove::Pass`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AutoMove {
+    Stick,
+    Swap(usize),
+    Draw,
+    Pass,
+}
+
+/// Public information available to a seat when it decides its move.
+#[derive(Clone, Copy, Debug)]
+pub struct SeatView<'a> {
+    pub player: usize,
+    pub stage: TurnStage,
+    pub hand: &'a [Option<CardId>; HAND_SIZE],
+    pub stack_card: Option<CardId>,
+    pub lives: &'a [u8; PLAYERS],
+    pub stick_player: Option<usize>,
+    pub round_turns: u16,
+    pub history: &'a TableHistory,
+}
+
+impl SeatView<'_> {
+    pub fn score(&self) -> u8 {
+        hand_max_score(self.hand)
+    }
+
+    pub fn can_stick(&self) -> bool {
+        self.stage == TurnStage::Opening && self.stick_player.is_none()
+    }
+
+    pub fn score_after_swap(&self, slot: usize) -> Option<u8> {
+        let stack_card = self.stack_card?;
+        self.hand.get(slot).copied().flatten()?;
+
+        let mut temp = *self.hand;
+        temp[slot] = Some(stack_card);
+        Some(hand_max_score(&temp))
+    }
+
+    /// Returns the first slot whose swap gives the highest score above the current one.
+    pub fn best_swap(&self) -> Option<(usize, u8)> {
+        let mut best = None;
+        let mut best_score = self.score();
+
+        for slot in 0..HAND_SIZE {
+            if let Some(score) = self.score_after_swap(slot) {
+                if score > best_score {
+                    best_score = score;
+                    best = Some((slot, score));
+                }
+            }
+        }
+
+        best
+    }
+}
+
+/// Decision policy for an automated seat.
+pub trait Strategy: fmt::Debug + Send + Sync {
+    fn choose_move(&mut self, view: &SeatView<'_>) -> AutoMove;
+}
+
+/// The original AutoPlay heuristic: stick above a threshold, otherwise take the stack card
+/// when it lifts the hand above a second threshold, otherwise draw and take the new card if
+/// it helps enough. [`AiParams::CLASSIC`] reproduces the original game.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ClassicStrategy {
+    pub params: AiParams,
+}
+
+impl ClassicStrategy {
+    pub fn new(params: AiParams) -> Self {
+        Self { params }
+    }
+}
+
+impl Strategy for ClassicStrategy {
+    fn choose_move(&mut self, view: &SeatView<'_>) -> AutoMove {
+        let base_score = view.score();
+        let params = &self.params;
+
+        match view.stage {
+            TurnStage::Opening => {
+                if view.can_stick() && base_score > params.stick_above {
+                    return AutoMove::Stick;
+                }
+
+                match view.best_swap() {
+                    Some((slot, score)) if score > params.first_swap_above => AutoMove::Swap(slot),
+                    _ => AutoMove::Draw,
+                }
+            }
+            TurnStage::AfterDraw => match view.best_swap() {
+                Some((slot, score)) if score > params.draw_swap_above => AutoMove::Swap(slot),
+                _ => AutoMove::Pass,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static NO_HISTORY: TableHistory = TableHistory::new();
+
+    fn view<'a>(
+        stage: TurnStage,
+        hand: &'a [Option<CardId>; HAND_SIZE],
+        stack_card: Option<CardId>,
+        lives: &'a [u8; PLAYERS],
+    ) -> SeatView<'a> {
+        SeatView {
+            player: 1,
+            stage,
+            hand,
+            stack_card,
+            lives,
+            stick_player: None,
+            round_turns: 0,
+            history: &NO_HISTORY,
+        }
+    }
+
+    #[test]
+    fn best_swap_prefers_first_highest_slot() {
+        let lives = [3; PLAYERS];
+        let hand = [Some(2), Some(3), Some(15)];
+        let seat = view(TurnStage::Opening, &hand, Some(1), &lives);
+
+        assert_eq!(seat.score(), 5);
+        assert_eq!(seat.score_after_swap(2), Some(16));
+        assert_eq!(seat.best_swap(), Some((2, 16)));
+    }
+
+    #[test]
+    fn score_after_swap_ignores_empty_slots() {
+        let lives = [3; PLAYERS];
+        let hand = [None, Some(3), Some(4)];
+        let seat = view(TurnStage::Opening, &hand, Some(1), &lives);
+
+        assert_eq!(seat.score_after_swap(0), None);
+        assert_eq!(seat.score_after_swap(HAND_SIZE), None);
+    }
+
+    #[test]
+    fn classic_sticks_above_twenty_five() {
+        let lives = [3; PLAYERS];
+        let hand = [Some(13), Some(12), Some(11)];
+        let mut seat = view(TurnStage::Opening, &hand, Some(2), &lives);
+
+        assert_eq!(
+            ClassicStrategy::default().choose_move(&seat),
+            AutoMove::Stick
+        );
+
+        seat.stick_player = Some(0);
+        assert_eq!(
+            ClassicStrategy::default().choose_move(&seat),
+            AutoMove::Draw
+        );
+    }
+
+    #[test]
+    fn classic_first_swap_must_beat_six() {
+        let lives = [3; PLAYERS];
+        let hand = [Some(2), Some(15), Some(28)];
+        let seat = view(TurnStage::Opening, &hand, Some(3), &lives);
+
+        // 2 + 3 of clubs is an improvement, but only to 5.
+        assert_eq!(seat.best_swap(), Some((1, 5)));
+        assert_eq!(
+            ClassicStrategy::default().choose_move(&seat),
+            AutoMove::Draw
+        );
+
+        let after_draw = SeatView {
+            stage: TurnStage::AfterDraw,
+            ..seat
+        };
+        assert_eq!(
+            ClassicStrategy::default().choose_move(&after_draw),
+            AutoMove::Swap(1)
+        );
+    }
+
+    #[test]
+    fn params_move_the_thresholds() {
+        let lives = [3; PLAYERS];
+        let hand = [Some(13), Some(12), Some(11)];
+        let seat = view(TurnStage::Opening, &hand, Some(2), &lives);
+
+        let mut cautious = ClassicStrategy::new(AiParams {
+            stick_above: 30,
+            ..AiParams::CLASSIC
+        });
+        assert_eq!(cautious.choose_move(&seat), AutoMove::Draw);
+
+        let hand = [Some(2), Some(15), Some(28)];
+        let after_draw = view(TurnStage::AfterDraw, &hand, Some(3), &lives);
+        let mut picky = ClassicStrategy::new(AiParams {
+            draw_swap_above: 5,
+            ..AiParams::CLASSIC
+        });
+        assert_eq!(picky.choose_move(&after_draw), AutoMove::Pass);
+    }
+}
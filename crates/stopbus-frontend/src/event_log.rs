@@ -0,0 +1,276 @@
+//! The game log: every event and narration sentence with the time it happened.
+//!
+//! The log keeps at most [`EventLog::retention`] entries, dropping the oldest first. Shells
+//! show it through a [`LogFilter`]; the Windows panel hides narration, which already has
+//! its own label, while the terminal can list just the alerts.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use stopbus_core::{GameEvent, MessageKind};
+
+/// Entries kept when no retention is given.
+pub const DEFAULT_RETENTION: usize = 200;
+
+/// How long an alert toast stays up before it hides itself.
+pub const TOAST_DURATION_MS: u32 = 4000;
+
+/// Joins the non-blank lines of an event's text with spaces, for one-line displays.
+pub fn single_line(text: &str) -> String {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    lines.join(" ")
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogKind {
+    Info,
+    Alert,
+    Narration,
+}
+
+impl From<MessageKind> for LogKind {
+    fn from(kind: MessageKind) -> Self {
+        match kind {
+            MessageKind::Info => LogKind::Info,
+            MessageKind::Alert => LogKind::Alert,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogEntry {
+    /// Position in the log since it was created; unaffected by retention.
+    pub sequence: u64,
+    /// Time since the session started.
+    pub elapsed: Duration,
+    pub kind: LogKind,
+    pub text: String,
+}
+
+impl LogEntry {
+    /// "03:07", or "1:03:07" once the session passes an hour.
+    pub fn timestamp(&self) -> String {
+        let seconds = self.elapsed.as_secs();
+        let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+        if hours > 0 {
+            format!("{}:{:02}:{:02}", hours, minutes, seconds)
+        } else {
+            format!("{:02}:{:02}", minutes, seconds)
+        }
+    }
+
+    /// "[03:07] Player 2 loses a life." with any line breaks folded into spaces.
+    pub fn line(&self) -> String {
+        format!("[{}] {}", self.timestamp(), single_line(&self.text))
+    }
+
+    /// Alerts are worth interrupting the user for; everything else only goes in the log.
+    pub fn wants_toast(&self) -> bool {
+        self.kind == LogKind::Alert
+    }
+}
+
+/// Which kinds of entry to show.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LogFilter {
+    pub info: bool,
+    pub alerts: bool,
+    pub narration: bool,
+}
+
+impl LogFilter {
+    pub const ALL: LogFilter = LogFilter {
+        info: true,
+        alerts: true,
+        narration: true,
+    };
+
+    /// Game events without the narration.
+    pub const EVENTS: LogFilter = LogFilter {
+        info: true,
+        alerts: true,
+        narration: false,
+    };
+
+    pub const ALERTS: LogFilter = LogFilter {
+        info: false,
+        alerts: true,
+        narration: false,
+    };
+
+    pub fn allows(&self, kind: LogKind) -> bool {
+        match kind {
+            LogKind::Info => self.info,
+            LogKind::Alert => self.alerts,
+            LogKind::Narration => self.narration,
+        }
+    }
+}
+
+impl Default for LogFilter {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventLog {
+    entries: VecDeque<LogEntry>,
+    retention: usize,
+    next_sequence: u64,
+}
+
+impl EventLog {
+    pub fn new(retention: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            retention: retention.max(1),
+            next_sequence: 0,
+        }
+    }
+
+    pub fn retention(&self) -> usize {
+        self.retention
+    }
+
+    /// Changes how many entries are kept, dropping the oldest if the log is now too long.
+    pub fn set_retention(&mut self, retention: usize) {
+        self.retention = retention.max(1);
+        self.trim();
+    }
+
+    pub fn push(&mut self, elapsed: Duration, kind: LogKind, text: impl Into<String>) -> &LogEntry {
+        self.entries.push_back(LogEntry {
+            sequence: self.next_sequence,
+            elapsed,
+            kind,
+            text: text.into(),
+        });
+        self.next_sequence += 1;
+        self.trim();
+
+        self.entries.back().expect("entry just pushed")
+    }
+
+    pub fn record(&mut self, elapsed: Duration, event: &GameEvent) -> &LogEntry {
+        self.push(elapsed, event.kind.into(), event.text.clone())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Entries still kept, oldest first.
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &LogEntry> + '_ {
+        self.entries.iter()
+    }
+
+    pub fn filtered(&self, filter: LogFilter) -> impl DoubleEndedIterator<Item = &LogEntry> + '_ {
+        self.entries
+            .iter()
+            .filter(move |entry| filter.allows(entry.kind))
+    }
+
+    /// Entries pushed after `sequence`, for shells that append to a panel incrementally.
+    pub fn since(&self, sequence: u64) -> impl DoubleEndedIterator<Item = &LogEntry> + '_ {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.sequence > sequence)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn trim(&mut self) {
+        while self.entries.len() > self.retention {
+            self.entries.pop_front();
+        }
+    }
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_RETENTION)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(seconds: u64) -> Duration {
+        Duration::from_secs(seconds)
+    }
+
+    #[test]
+    fn entries_carry_a_timestamp_and_one_line_text() {
+        let mut log = EventLog::default();
+        let entry = log.record(
+            secs(187),
+            &GameEvent::alert("Round over.\n\nYou lose a life."),
+        );
+
+        assert_eq!(entry.sequence, 0);
+        assert_eq!(entry.kind, LogKind::Alert);
+        assert_eq!(entry.timestamp(), "03:07");
+        assert_eq!(entry.line(), "[03:07] Round over. You lose a life.");
+        assert!(entry.wants_toast());
+
+        let late = log.push(secs(3 * 3600 + 61), LogKind::Info, "Late");
+        assert_eq!(late.timestamp(), "3:01:01");
+        assert!(!late.wants_toast());
+    }
+
+    #[test]
+    fn retention_drops_the_oldest_entries() {
+        let mut log = EventLog::new(3);
+        for index in 0..5 {
+            log.push(secs(index), LogKind::Info, format!("event {}", index));
+        }
+
+        let texts: Vec<&str> = log.entries().map(|entry| entry.text.as_str()).collect();
+        assert_eq!(texts, ["event 2", "event 3", "event 4"]);
+        assert_eq!(log.entries().next().unwrap().sequence, 2);
+
+        log.set_retention(1);
+        assert_eq!(log.len(), 1);
+        assert_eq!(log.entries().next().unwrap().text, "event 4");
+
+        log.set_retention(0);
+        assert_eq!(log.retention(), 1);
+        log.clear();
+        assert!(log.is_empty());
+    }
+
+    #[test]
+    fn filters_select_kinds() {
+        let mut log = EventLog::default();
+        log.record(secs(1), &GameEvent::info("dealt"));
+        log.push(secs(2), LogKind::Narration, "Your turn.");
+        log.record(secs(3), &GameEvent::alert("out"));
+
+        let texts = |filter| -> Vec<String> {
+            log.filtered(filter)
+                .map(|entry| entry.text.clone())
+                .collect()
+        };
+
+        assert_eq!(texts(LogFilter::ALL), ["dealt", "Your turn.", "out"]);
+        assert_eq!(texts(LogFilter::EVENTS), ["dealt", "out"]);
+        assert_eq!(texts(LogFilter::ALERTS), ["out"]);
+        assert_eq!(LogFilter::default(), LogFilter::ALL);
+
+        let newer: Vec<u64> = log.since(0).map(|entry| entry.sequence).collect();
+        assert_eq!(newer, [1, 2]);
+    }
+}
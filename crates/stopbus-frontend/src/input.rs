@@ -0,0 +1,287 @@
+//! Pointer gestures on the table: clicks and drag-and-drop between the hand and the stack.
+//!
+//! ```text
+//! Idle --press--> Pressed --move past DRAG_THRESHOLD--> Dragging
+//!   ^               |                                      |
+//!   +---release-----+------------release (drop)------------+
+//! ```
+//!
+//! A click on a hand card swaps it with the face-up stack card and a click on the deck
+//! draws the next card, as before. Dragging a hand card onto the face-up card, or the
+//! face-up card onto a hand card, swaps them; dragging the deck onto the face-up card
+//! draws. Any other release, or [`DragInput::cancel`], does nothing.
+
+use crate::{Intent, Target};
+
+/// Distance in pixels the pointer must travel before a press becomes a drag.
+pub const DRAG_THRESHOLD: i32 = 4;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InputState {
+    #[default]
+    Idle,
+    Pressed {
+        source: Target,
+        origin: (i32, i32),
+    },
+    Dragging {
+        source: Target,
+        origin: (i32, i32),
+        position: (i32, i32),
+    },
+}
+
+/// A drag in progress, for drawing the card under the pointer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Drag {
+    pub source: Target,
+    /// Where the pointer went down; the card moves by `position - origin`.
+    pub origin: (i32, i32),
+    pub position: (i32, i32),
+}
+
+impl Drag {
+    pub fn offset(&self) -> (i32, i32) {
+        (
+            self.position.0 - self.origin.0,
+            self.position.1 - self.origin.1,
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DragInput {
+    state: InputState,
+}
+
+impl DragInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn state(&self) -> InputState {
+        self.state
+    }
+
+    /// The card or pile the current gesture started on.
+    pub fn source(&self) -> Option<Target> {
+        match self.state {
+            InputState::Idle => None,
+            InputState::Pressed { source, .. } | InputState::Dragging { source, .. } => {
+                Some(source)
+            }
+        }
+    }
+
+    pub fn drag(&self) -> Option<Drag> {
+        match self.state {
+            InputState::Dragging {
+                source,
+                origin,
+                position,
+            } => Some(Drag {
+                source,
+                origin,
+                position,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Starts a gesture on `target`; pressing empty table leaves the machine idle.
+    pub fn press(&mut self, target: Option<Target>, position: (i32, i32)) {
+        self.state = match target {
+            Some(source) => InputState::Pressed {
+                source,
+                origin: position,
+            },
+            None => InputState::Idle,
+        };
+    }
+
+    /// Follows the pointer. Returns true when a drag started or moved and needs redrawing.
+    pub fn move_to(&mut self, position: (i32, i32)) -> bool {
+        match self.state {
+            InputState::Idle => false,
+            InputState::Pressed { source, origin } => {
+                let moved = (position.0 - origin.0)
+                    .abs()
+                    .max((position.1 - origin.1).abs());
+                if moved < DRAG_THRESHOLD {
+                    return false;
+                }
+                self.state = InputState::Dragging {
+                    source,
+                    origin,
+                    position,
+                };
+                true
+            }
+            InputState::Dragging {
+                source,
+                origin,
+                position: previous,
+            } => {
+                if previous == position {
+                    return false;
+                }
+                self.state = InputState::Dragging {
+                    source,
+                    origin,
+                    position,
+                };
+                true
+            }
+        }
+    }
+
+    /// Ends the gesture over `target` and returns the move it asks for, if any.
+    pub fn release(&mut self, target: Option<Target>, position: (i32, i32)) -> Option<Intent> {
+        self.move_to(position);
+
+        let state = std::mem::take(&mut self.state);
+        let target = target?;
+
+        match state {
+            InputState::Idle => None,
+            InputState::Pressed { source, .. } if source == target => match source {
+                Target::HandCard(slot) => Some(Intent::SwapWithStack(slot)),
+                Target::Stack => Some(Intent::DrawNextCard),
+                Target::TopCard => None,
+            },
+            InputState::Pressed { .. } => None,
+            InputState::Dragging { source, .. } => match (source, target) {
+                (Target::HandCard(slot), Target::TopCard)
+                | (Target::TopCard, Target::HandCard(slot)) => Some(Intent::SwapWithStack(slot)),
+                (Target::Stack, Target::TopCard) => Some(Intent::DrawNextCard),
+                _ => None,
+            },
+        }
+    }
+
+    /// Abandons the gesture. Returns true when a drag was showing and needs clearing.
+    pub fn cancel(&mut self) -> bool {
+        let was_dragging = matches!(self.state, InputState::Dragging { .. });
+        self.state = InputState::Idle;
+        was_dragging
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TARGETS: [Option<Target>; 6] = [
+        None,
+        Some(Target::HandCard(0)),
+        Some(Target::HandCard(1)),
+        Some(Target::HandCard(2)),
+        Some(Target::Stack),
+        Some(Target::TopCard),
+    ];
+
+    fn click(source: Option<Target>, target: Option<Target>) -> Option<Intent> {
+        let mut input = DragInput::new();
+        input.press(source, (100, 100));
+        input.release(target, (101, 101))
+    }
+
+    fn drag(source: Option<Target>, target: Option<Target>) -> Option<Intent> {
+        let mut input = DragInput::new();
+        input.press(source, (100, 100));
+        input.move_to((140, 90));
+        input.release(target, (200, 50))
+    }
+
+    #[test]
+    fn clicks_keep_the_classic_behaviour() {
+        for source in TARGETS {
+            for target in TARGETS {
+                let expected = match (source, target) {
+                    (Some(Target::HandCard(a)), Some(Target::HandCard(b))) if a == b => {
+                        Some(Intent::SwapWithStack(a))
+                    }
+                    (Some(Target::Stack), Some(Target::Stack)) => Some(Intent::DrawNextCard),
+                    _ => None,
+                };
+                assert_eq!(
+                    click(source, target),
+                    expected,
+                    "{:?} -> {:?}",
+                    source,
+                    target
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn drops_swap_in_either_direction_or_draw() {
+        for source in TARGETS {
+            for target in TARGETS {
+                let expected = match (source, target) {
+                    (Some(Target::HandCard(slot)), Some(Target::TopCard))
+                    | (Some(Target::TopCard), Some(Target::HandCard(slot))) => {
+                        Some(Intent::SwapWithStack(slot))
+                    }
+                    (Some(Target::Stack), Some(Target::TopCard)) => Some(Intent::DrawNextCard),
+                    _ => None,
+                };
+                assert_eq!(
+                    drag(source, target),
+                    expected,
+                    "{:?} -> {:?}",
+                    source,
+                    target
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn small_moves_stay_a_press() {
+        let mut input = DragInput::new();
+        input.press(Some(Target::HandCard(1)), (10, 10));
+
+        assert!(!input.move_to((12, 13)));
+        assert!(matches!(input.state(), InputState::Pressed { .. }));
+        assert_eq!(input.drag(), None);
+
+        assert!(input.move_to((10, 10 + DRAG_THRESHOLD)));
+        let drag = input.drag().unwrap();
+        assert_eq!(drag.source, Target::HandCard(1));
+        assert_eq!(drag.offset(), (0, DRAG_THRESHOLD));
+
+        assert!(!input.move_to((10, 10 + DRAG_THRESHOLD)));
+        assert!(input.move_to((30, 40)));
+        assert_eq!(input.drag().unwrap().offset(), (20, 30));
+    }
+
+    #[test]
+    fn cancel_and_release_return_to_idle() {
+        let mut input = DragInput::new();
+        input.press(Some(Target::TopCard), (0, 0));
+        assert!(!input.cancel());
+        assert_eq!(input.state(), InputState::Idle);
+
+        input.press(Some(Target::TopCard), (0, 0));
+        input.move_to((50, 50));
+        assert!(input.cancel());
+        assert_eq!(input.release(Some(Target::HandCard(0)), (50, 50)), None);
+
+        input.press(Some(Target::HandCard(2)), (0, 0));
+        input.release(None, (0, 0));
+        assert_eq!(input.state(), InputState::Idle);
+        assert_eq!(input.source(), None);
+    }
+
+    #[test]
+    fn pressing_empty_table_does_nothing() {
+        let mut input = DragInput::new();
+        input.press(None, (5, 5));
+
+        assert_eq!(input.state(), InputState::Idle);
+        assert!(!input.move_to((50, 50)));
+        assert_eq!(input.release(Some(Target::TopCard), (50, 50)), None);
+    }
+}
@@ -0,0 +1,235 @@
+//! Keyboard play: 1–3 focus a hand slot, S swaps it with the stack, D draws the next card,
+//! K sticks, Enter ends the turn and N deals. Left/Right move the focus and Escape clears it.
+//! While the showdown is up, Enter moves on to the next round.
+
+use stopbus_core::HAND_SIZE;
+
+use crate::Intent;
+
+/// A key press, already translated from the platform's key codes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Enter,
+    Escape,
+    Left,
+    Right,
+}
+
+/// The parts of the table state that decide what a key does.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct KeyContext {
+    pub awaiting_human: bool,
+    pub can_stick: bool,
+    pub focused_slot: Option<usize>,
+    /// The end-of-round showdown is on screen.
+    pub showdown: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyAction {
+    /// Move the focus highlight; `None` removes it.
+    Focus(Option<usize>),
+    Intent(Intent),
+    Ignored,
+}
+
+pub fn map_key(key: Key, context: KeyContext) -> KeyAction {
+    if key == Key::Char('n') || key == Key::Char('N') {
+        return KeyAction::Intent(Intent::Deal);
+    }
+
+    if context.showdown && key == Key::Enter {
+        return KeyAction::Intent(Intent::EndTurn);
+    }
+
+    if !context.awaiting_human {
+        return KeyAction::Ignored;
+    }
+
+    match key {
+        Key::Char(c) => match c.to_ascii_lowercase() {
+            digit @ '1'..='9' => {
+                let slot = digit as usize - '1' as usize;
+                if slot < HAND_SIZE {
+                    KeyAction::Focus(Some(slot))
+                } else {
+                    KeyAction::Ignored
+                }
+            }
+            's' => match context.focused_slot {
+                Some(slot) => KeyAction::Intent(Intent::SwapWithStack(slot)),
+                None => KeyAction::Ignored,
+            },
+            'd' => KeyAction::Intent(Intent::DrawNextCard),
+            'k' if context.can_stick => KeyAction::Intent(Intent::Stick),
+            _ => KeyAction::Ignored,
+        },
+        Key::Enter => KeyAction::Intent(Intent::EndTurn),
+        Key::Escape => match context.focused_slot {
+            Some(_) => KeyAction::Focus(None),
+            None => KeyAction::Ignored,
+        },
+        Key::Left => KeyAction::Focus(Some(match context.focused_slot {
+            Some(slot) => (slot + HAND_SIZE - 1) % HAND_SIZE,
+            None => HAND_SIZE - 1,
+        })),
+        Key::Right => KeyAction::Focus(Some(match context.focused_slot {
+            Some(slot) => (slot + 1) % HAND_SIZE,
+            None => 0,
+        })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every combination of `awaiting_human` and `can_stick`, with and without a focused
+    /// slot, plus the showdown, which only comes up off the human's turn.
+    fn contexts() -> Vec<KeyContext> {
+        let mut contexts = Vec::new();
+        for awaiting_human in [false, true] {
+            for can_stick in [false, true] {
+                for focused_slot in [None, Some(0), Some(2)] {
+                    contexts.push(KeyContext {
+                        awaiting_human,
+                        can_stick,
+                        focused_slot,
+                        showdown: false,
+                    });
+                }
+            }
+        }
+        contexts.push(KeyContext {
+            showdown: true,
+            ..KeyContext::default()
+        });
+        contexts
+    }
+
+    fn expect(key: Key, rule: impl Fn(KeyContext) -> KeyAction) {
+        for context in contexts() {
+            assert_eq!(
+                map_key(key, context),
+                rule(context),
+                "{:?} in {:?}",
+                key,
+                context
+            );
+        }
+    }
+
+    #[test]
+    fn digits_focus_hand_slots_on_the_humans_turn() {
+        for (key, slot) in [('1', 0), ('2', 1), ('3', 2)] {
+            expect(Key::Char(key), |context| {
+                if context.awaiting_human {
+                    KeyAction::Focus(Some(slot))
+                } else {
+                    KeyAction::Ignored
+                }
+            });
+        }
+
+        expect(Key::Char('4'), |_| KeyAction::Ignored);
+        expect(Key::Char('0'), |_| KeyAction::Ignored);
+    }
+
+    #[test]
+    fn s_swaps_the_focused_slot() {
+        for key in ['s', 'S'] {
+            expect(Key::Char(key), |context| match context {
+                KeyContext {
+                    awaiting_human: true,
+                    focused_slot: Some(slot),
+                    ..
+                } => KeyAction::Intent(Intent::SwapWithStack(slot)),
+                _ => KeyAction::Ignored,
+            });
+        }
+    }
+
+    #[test]
+    fn d_draws_on_the_humans_turn() {
+        for key in ['d', 'D'] {
+            expect(Key::Char(key), |context| {
+                if context.awaiting_human {
+                    KeyAction::Intent(Intent::DrawNextCard)
+                } else {
+                    KeyAction::Ignored
+                }
+            });
+        }
+    }
+
+    #[test]
+    fn k_sticks_only_when_sticking_is_allowed() {
+        for key in ['k', 'K'] {
+            expect(Key::Char(key), |context| {
+                if context.awaiting_human && context.can_stick {
+                    KeyAction::Intent(Intent::Stick)
+                } else {
+                    KeyAction::Ignored
+                }
+            });
+        }
+    }
+
+    #[test]
+    fn enter_ends_the_turn_or_the_showdown() {
+        expect(Key::Enter, |context| {
+            if context.awaiting_human || context.showdown {
+                KeyAction::Intent(Intent::EndTurn)
+            } else {
+                KeyAction::Ignored
+            }
+        });
+    }
+
+    #[test]
+    fn n_always_deals() {
+        for key in ['n', 'N'] {
+            expect(Key::Char(key), |_| KeyAction::Intent(Intent::Deal));
+        }
+    }
+
+    #[test]
+    fn arrows_cycle_and_escape_clears_the_focus() {
+        expect(Key::Right, |context| {
+            if !context.awaiting_human {
+                return KeyAction::Ignored;
+            }
+            KeyAction::Focus(Some(match context.focused_slot {
+                None => 0,
+                Some(2) => 0,
+                Some(slot) => slot + 1,
+            }))
+        });
+
+        expect(Key::Left, |context| {
+            if !context.awaiting_human {
+                return KeyAction::Ignored;
+            }
+            KeyAction::Focus(Some(match context.focused_slot {
+                None | Some(0) => 2,
+                Some(slot) => slot - 1,
+            }))
+        });
+
+        expect(Key::Escape, |context| {
+            if context.awaiting_human && context.focused_slot.is_some() {
+                KeyAction::Focus(None)
+            } else {
+                KeyAction::Ignored
+            }
+        });
+    }
+
+    #[test]
+    fn other_keys_are_ignored() {
+        for key in ['x', ' ', 'q'] {
+            expect(Key::Char(key), |_| KeyAction::Ignored);
+        }
+    }
+}
@@ -0,0 +1,491 @@
+//! Table geometry computed from the client size, DPI scale and seat count.
+//!
+//! All positions are designed on the client area of the original fixed 600x400 window
+//! ([`BASE_CLIENT_SIZE`]) and scaled uniformly to fit the real client area. The scale is
+//! rounded down to [`SCALE_STEP`] so card bitmaps stay crisp at the common DPI settings,
+//! and the table is centred in any space left over.
+
+use stopbus_core::HAND_SIZE;
+
+use crate::Target;
+
+/// Client area of the original window with four seats, in design units.
+pub const BASE_CLIENT_SIZE: (i32, i32) = (584, 342);
+
+pub const CARD_SIZE: (i32, i32) = (71, 96);
+
+pub const BUTTON_SIZE: (i32, i32) = (50, 50);
+
+/// Height of the default dialog font the original text positions were laid out for.
+pub const FONT_HEIGHT: i32 = 16;
+
+pub const SCALE_STEP: f32 = 0.25;
+
+/// Smallest scale used at 96 DPI; it grows with the DPI scale so text stays readable.
+pub const MIN_SCALE: f32 = 0.5;
+
+/// Height of the game log panel along the bottom of the window at 96 DPI.
+pub const EVENT_LOG_HEIGHT: i32 = 96;
+
+/// Seats the original lives table was laid out for; more seats push the controls down.
+const BASE_SEATS: usize = 4;
+
+const STACK_POSITION: (i32, i32) = (10, 40);
+
+const TOP_CARD_POSITION: (i32, i32) = (110, 40);
+
+const HAND_POSITIONS: [(i32, i32); HAND_SIZE] = [(10, 200), (110, 200), (210, 200)];
+
+const DECK_LABEL_POSITION: (i32, i32) = (20, 15);
+
+const HAND_LABEL_POSITION: (i32, i32) = (20, 170);
+
+const LIVES_HEADING_POSITION: (i32, i32) = (400, 55);
+
+const SEAT_LABEL_X: i32 = 400;
+
+const SEAT_BASE_Y: i32 = 75;
+
+const SEAT_STEP: i32 = 15;
+
+const LIVES_VALUE_X: i32 = 470;
+
+const POINTER_X: i32 = 380;
+
+const STICK_MARKER_X: i32 = 360;
+
+const START_PLAYER_POSITION: (i32, i32) = (400, 150);
+
+const BUTTON_STICK_POSITION: (i32, i32) = (400, 200);
+
+const BUTTON_DEAL_POSITION: (i32, i32) = (500, 200);
+
+const BUTTON_EXIT_POSITION: (i32, i32) = (500, 260);
+
+const BUTTON_OK_POSITION: (i32, i32) = (400, 260);
+
+/// Two lines of narration text along the bottom of the table.
+const NARRATION_RECT: Rect = Rect::new(10, 312, 564, 30);
+
+/// Alert toast, in the gap between the stack and the hand.
+const TOAST_RECT: Rect = Rect::new(10, 138, 380, 28);
+
+/// Showdown rows fill the card area left of the lives table, down to the narration.
+const SHOWDOWN_TOP: i32 = 10;
+
+const SHOWDOWN_LEFT: i32 = 10;
+
+/// Row pitch with four seats; more seats share the same height in thinner rows.
+const SHOWDOWN_ROW_STEP: i32 = 62;
+
+const SHOWDOWN_ROW_GAP: i32 = 7;
+
+const SHOWDOWN_CARD_GAP: i32 = 5;
+
+const SHOWDOWN_TEXT_GAP: i32 = 10;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Rect {
+    pub const fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn right(&self) -> i32 {
+        self.x + self.width
+    }
+
+    pub fn bottom(&self) -> i32 {
+        self.y + self.height
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.x < other.right()
+            && other.x < self.right()
+            && self.y < other.bottom()
+            && other.y < self.bottom()
+    }
+}
+
+/// One player's row in the end-of-round showdown: their cards, then two lines of text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ShowdownRow {
+    pub cards: [Rect; HAND_SIZE],
+    pub name: (i32, i32),
+    pub detail: (i32, i32),
+}
+
+/// Text anchors for one row of the lives table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SeatRow {
+    pub label: (i32, i32),
+    pub lives: (i32, i32),
+    pub pointer: (i32, i32),
+    pub stick_marker: (i32, i32),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layout {
+    pub scale: f32,
+    pub client: Rect,
+    pub font_height: i32,
+    pub deck_label: (i32, i32),
+    pub hand_label: (i32, i32),
+    pub lives_heading: (i32, i32),
+    pub start_player: (i32, i32),
+    pub stack: Rect,
+    pub top_card: Rect,
+    pub hand: [Rect; HAND_SIZE],
+    pub seats: Vec<SeatRow>,
+    pub stick_button: Rect,
+    pub deal_button: Rect,
+    pub exit_button: Rect,
+    pub ok_button: Rect,
+    pub narration: Rect,
+    pub toast: Rect,
+    pub showdown: Vec<ShowdownRow>,
+}
+
+impl Layout {
+    pub fn new(client_width: i32, client_height: i32, dpi_scale: f32, seats: usize) -> Self {
+        let (design_width, design_height) = Self::design_size(seats);
+
+        let fit = (client_width as f32 / design_width as f32)
+            .min(client_height as f32 / design_height as f32);
+
+        let minimum = MIN_SCALE * dpi_scale.max(1.0);
+        let scale = ((fit / SCALE_STEP).floor() * SCALE_STEP).max(minimum);
+
+        let origin = (
+            ((client_width as f32 - design_width as f32 * scale) / 2.0).max(0.0) as i32,
+            ((client_height as f32 - design_height as f32 * scale) / 2.0).max(0.0) as i32,
+        );
+
+        let point = |(x, y): (i32, i32)| {
+            (
+                origin.0 + (x as f32 * scale).round() as i32,
+                origin.1 + (y as f32 * scale).round() as i32,
+            )
+        };
+
+        let rect = |position: (i32, i32), (width, height): (i32, i32)| {
+            let (x, y) = point(position);
+            Rect::new(
+                x,
+                y,
+                (width as f32 * scale).round() as i32,
+                (height as f32 * scale).round() as i32,
+            )
+        };
+
+        let shift = Self::extra_seat_height(seats);
+        let below_table = |(x, y): (i32, i32)| (x, y + shift);
+
+        let showdown_step =
+            SHOWDOWN_ROW_STEP.min((NARRATION_RECT.y + shift - SHOWDOWN_TOP) / seats.max(1) as i32);
+        let showdown_card_height = showdown_step - SHOWDOWN_ROW_GAP;
+        let showdown_card_width = showdown_card_height * CARD_SIZE.0 / CARD_SIZE.1;
+        let showdown_text_x = SHOWDOWN_LEFT
+            + (showdown_card_width + SHOWDOWN_CARD_GAP) * HAND_SIZE as i32
+            + SHOWDOWN_TEXT_GAP;
+
+        let showdown_rows = (0..seats)
+            .map(|seat| {
+                let y = SHOWDOWN_TOP + showdown_step * seat as i32;
+                let text_y = y + showdown_card_height / 2 - FONT_HEIGHT;
+                ShowdownRow {
+                    cards: std::array::from_fn(|slot| {
+                        rect(
+                            (
+                                SHOWDOWN_LEFT
+                                    + (showdown_card_width + SHOWDOWN_CARD_GAP) * slot as i32,
+                                y,
+                            ),
+                            (showdown_card_width, showdown_card_height),
+                        )
+                    }),
+                    name: point((showdown_text_x, text_y)),
+                    detail: point((showdown_text_x, text_y + FONT_HEIGHT)),
+                }
+            })
+            .collect();
+
+        let seat_rows = (0..seats)
+            .map(|seat| {
+                let y = SEAT_BASE_Y + SEAT_STEP * seat as i32;
+                SeatRow {
+                    label: point((SEAT_LABEL_X, y)),
+                    lives: point((LIVES_VALUE_X, y)),
+                    pointer: point((POINTER_X, y)),
+                    stick_marker: point((STICK_MARKER_X, y)),
+                }
+            })
+            .collect();
+
+        Self {
+            scale,
+            client: Rect::new(0, 0, client_width, client_height),
+            font_height: (FONT_HEIGHT as f32 * scale).round() as i32,
+            deck_label: point(DECK_LABEL_POSITION),
+            hand_label: point(HAND_LABEL_POSITION),
+            lives_heading: point(LIVES_HEADING_POSITION),
+            start_player: point(below_table(START_PLAYER_POSITION)),
+            stack: rect(STACK_POSITION, CARD_SIZE),
+            top_card: rect(TOP_CARD_POSITION, CARD_SIZE),
+            hand: HAND_POSITIONS.map(|position| rect(position, CARD_SIZE)),
+            seats: seat_rows,
+            stick_button: rect(below_table(BUTTON_STICK_POSITION), BUTTON_SIZE),
+            deal_button: rect(below_table(BUTTON_DEAL_POSITION), BUTTON_SIZE),
+            exit_button: rect(below_table(BUTTON_EXIT_POSITION), BUTTON_SIZE),
+            ok_button: rect(below_table(BUTTON_OK_POSITION), BUTTON_SIZE),
+            narration: rect(
+                below_table((NARRATION_RECT.x, NARRATION_RECT.y)),
+                (NARRATION_RECT.width, NARRATION_RECT.height),
+            ),
+            toast: rect(
+                (TOAST_RECT.x, TOAST_RECT.y),
+                (TOAST_RECT.width, TOAST_RECT.height),
+            ),
+            showdown: showdown_rows,
+        }
+    }
+
+    /// Splits a client area into the table's height and the game log panel below it. The
+    /// panel grows with the DPI scale but never takes more than a third of the height.
+    pub fn split_event_log(client_width: i32, client_height: i32, dpi_scale: f32) -> (i32, Rect) {
+        let height = ((EVENT_LOG_HEIGHT as f32 * dpi_scale.max(1.0)).round() as i32)
+            .min(client_height.max(0) / 3);
+        let table_height = client_height.max(0) - height;
+
+        (
+            table_height,
+            Rect::new(0, table_height, client_width, height),
+        )
+    }
+
+    /// Design-unit client size needed for `seats` rows in the lives table.
+    pub fn design_size(seats: usize) -> (i32, i32) {
+        (
+            BASE_CLIENT_SIZE.0,
+            BASE_CLIENT_SIZE.1 + Self::extra_seat_height(seats),
+        )
+    }
+
+    /// Client size that shows the table at exactly the DPI scale.
+    pub fn preferred_client_size(dpi_scale: f32, seats: usize) -> (i32, i32) {
+        let (width, height) = Self::design_size(seats);
+        (
+            (width as f32 * dpi_scale).ceil() as i32,
+            (height as f32 * dpi_scale).ceil() as i32,
+        )
+    }
+
+    fn extra_seat_height(seats: usize) -> i32 {
+        SEAT_STEP * seats.saturating_sub(BASE_SEATS) as i32
+    }
+
+    pub fn hand_slot_at(&self, x: i32, y: i32) -> Option<usize> {
+        self.hand.iter().position(|rect| rect.contains(x, y))
+    }
+
+    pub fn stack_contains(&self, x: i32, y: i32) -> bool {
+        self.stack.contains(x, y)
+    }
+
+    pub fn hit_test(&self, x: i32, y: i32) -> Option<Target> {
+        if let Some(slot) = self.hand_slot_at(x, y) {
+            return Some(Target::HandCard(slot));
+        }
+
+        if self.top_card.contains(x, y) {
+            return Some(Target::TopCard);
+        }
+
+        self.stack_contains(x, y).then_some(Target::Stack)
+    }
+
+    pub fn buttons(&self) -> [Rect; 4] {
+        [
+            self.stick_button,
+            self.deal_button,
+            self.exit_button,
+            self.ok_button,
+        ]
+    }
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self::new(BASE_CLIENT_SIZE.0, BASE_CLIENT_SIZE.1, 1.0, BASE_SEATS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn original_window_keeps_the_original_positions() {
+        let layout = Layout::default();
+
+        assert_eq!(layout.scale, 1.0);
+        assert_eq!(layout.stack, Rect::new(10, 40, 71, 96));
+        assert_eq!(layout.top_card, Rect::new(110, 40, 71, 96));
+        assert_eq!(layout.hand[2], Rect::new(210, 200, 71, 96));
+        assert_eq!(layout.seats[3].lives, (470, 120));
+        assert_eq!(layout.seats[0].pointer, (380, 75));
+        assert_eq!(layout.start_player, (400, 150));
+        assert_eq!(layout.exit_button, Rect::new(500, 260, 50, 50));
+        assert_eq!(layout.font_height, FONT_HEIGHT);
+        assert_eq!(layout.narration.bottom(), BASE_CLIENT_SIZE.1);
+        assert_eq!(layout.showdown[1].cards[0], Rect::new(10, 72, 40, 55));
+    }
+
+    #[test]
+    fn high_dpi_doubles_every_element() {
+        let (width, height) = Layout::preferred_client_size(2.0, 4);
+        let layout = Layout::new(width, height, 2.0, 4);
+        let base = Layout::default();
+
+        assert_eq!(layout.scale, 2.0);
+        assert_eq!(layout.stack, Rect::new(20, 80, 142, 192));
+        assert_eq!(layout.ok_button.x, base.ok_button.x * 2);
+        assert_eq!(layout.seats[2].label.1, base.seats[2].label.1 * 2);
+        assert_eq!(layout.font_height, FONT_HEIGHT * 2);
+    }
+
+    #[test]
+    fn scale_snaps_down_and_table_is_centred() {
+        let layout = Layout::new(800, 400, 1.0, 4);
+
+        // 400 / 342 fits 1.17, which snaps to the 1.0 step; the spare room is split evenly.
+        assert_eq!(layout.scale, 1.0);
+        assert_eq!(layout.stack.x, 10 + (800 - 584) / 2);
+        assert_eq!(layout.stack.y, 40 + (400 - 342) / 2);
+    }
+
+    #[test]
+    fn small_windows_shrink_to_the_dpi_minimum() {
+        let layout = Layout::new(300, 180, 1.0, 4);
+        assert_eq!(layout.scale, 0.5);
+        assert_eq!(layout.stack.width, 36);
+
+        let tiny = Layout::new(100, 100, 1.0, 4);
+        assert_eq!(tiny.scale, MIN_SCALE);
+
+        let tiny_high_dpi = Layout::new(100, 100, 2.0, 4);
+        assert_eq!(tiny_high_dpi.scale, 1.0);
+    }
+
+    #[test]
+    fn extra_seats_push_the_controls_down_without_overlap() {
+        for seats in 2..=10 {
+            let (width, height) = Layout::preferred_client_size(1.0, seats);
+            let layout = Layout::new(width, height, 1.0, seats);
+
+            assert_eq!(layout.scale, 1.0, "{} seats", seats);
+            assert_eq!(layout.seats.len(), seats);
+
+            let last_row = layout.seats.last().unwrap().label.1;
+            assert!(layout.start_player.1 > last_row, "{} seats", seats);
+            assert!(layout.stick_button.y >= layout.start_player.1 + FONT_HEIGHT);
+
+            assert!(layout.narration.bottom() <= height);
+            assert!(layout
+                .hand
+                .iter()
+                .all(|card| !card.intersects(&layout.narration)));
+
+            let buttons = layout.buttons();
+            for (index, button) in buttons.iter().enumerate() {
+                assert!(button.right() <= width && button.bottom() <= height);
+                for other in &buttons[index + 1..] {
+                    assert!(!button.intersects(other));
+                }
+                for card in layout.hand.iter().chain([&layout.stack, &layout.top_card]) {
+                    assert!(!button.intersects(card));
+                }
+                assert!(!button.intersects(&layout.narration));
+                assert!(!button.intersects(&layout.toast));
+            }
+
+            for card in layout.hand.iter().chain([&layout.stack, &layout.top_card]) {
+                assert!(!card.intersects(&layout.toast));
+            }
+
+            assert_eq!(layout.showdown.len(), seats);
+            let cards: Vec<Rect> = layout.showdown.iter().flat_map(|row| row.cards).collect();
+            for (index, card) in cards.iter().enumerate() {
+                assert!(card.height >= FONT_HEIGHT * 2, "{} seats", seats);
+                assert!(card.right() < STICK_MARKER_X && !card.intersects(&layout.narration));
+                for other in &cards[index + 1..] {
+                    assert!(!card.intersects(other));
+                }
+            }
+            for row in &layout.showdown {
+                assert!(row.name.0 > row.cards[HAND_SIZE - 1].right());
+                assert!(row.detail.1 + FONT_HEIGHT <= row.cards[0].bottom());
+            }
+        }
+    }
+
+    #[test]
+    fn event_log_takes_the_bottom_of_the_window() {
+        let (width, height) = Layout::preferred_client_size(1.0, 4);
+        let (table, log) = Layout::split_event_log(width, height + EVENT_LOG_HEIGHT, 1.0);
+        assert_eq!(table, height);
+        assert_eq!(log, Rect::new(0, height, width, EVENT_LOG_HEIGHT));
+
+        let (_, log) = Layout::split_event_log(width, 2 * (height + EVENT_LOG_HEIGHT), 2.0);
+        assert_eq!(log.height, EVENT_LOG_HEIGHT * 2);
+
+        let (table, log) = Layout::split_event_log(200, 150, 1.0);
+        assert_eq!((table, log.height), (100, 50));
+        assert_eq!(log.bottom(), 150);
+    }
+
+    #[test]
+    fn hit_testing_follows_the_scaled_rects() {
+        let layout = Layout::new(1168, 684, 2.0, 4);
+
+        let card = layout.hand[1];
+        assert_eq!(layout.hit_test(card.x, card.y), Some(Target::HandCard(1)));
+        assert_eq!(
+            layout.hit_test(card.right() - 1, card.bottom() - 1),
+            Some(Target::HandCard(1))
+        );
+        assert_eq!(layout.hit_test(card.right(), card.y), None);
+
+        let stack = layout.stack;
+        assert_eq!(
+            layout.hit_test(stack.x + stack.width / 2, stack.y + stack.height / 2),
+            Some(Target::Stack)
+        );
+        assert_eq!(
+            layout.hit_test(layout.top_card.x, layout.top_card.y),
+            Some(Target::TopCard)
+        );
+        assert_eq!(
+            layout.hit_test(layout.top_card.right(), layout.top_card.y),
+            None
+        );
+
+        // The original fixed positions now miss the doubled cards.
+        assert_eq!(layout.hand_slot_at(215, 205), None);
+    }
+}
@@ -0,0 +1,15 @@
+//! Platform-neutral front-end logic shared by the Stop the Bus shells.
+
+pub mod event_log;
+pub mod input;
+pub mod keyboard;
+pub mod layout;
+pub mod placement;
+pub mod presenter;
+
+pub use event_log::{EventLog, LogEntry, LogFilter, LogKind};
+pub use input::{Drag, DragInput, InputState};
+pub use keyboard::{Key, KeyAction, KeyContext};
+pub use layout::{Layout, Rect, SeatRow, ShowdownRow};
+pub use placement::Placement;
+pub use presenter::{Effect, Intent, Presenter, Target, ViewModel, TURN_TIMER_INTERVAL_MS};
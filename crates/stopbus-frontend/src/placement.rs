@@ -0,0 +1,255 @@
+//! Brings saved window positions back on screen after the monitor layout changes.
+//!
+//! A window counts as reachable while enough of its title bar lies on one monitor's work
+//! area to grab it. When the main window is not, it moves onto the nearest monitor and
+//! the cheat windows follow by the same offset, the way `offset_cheat_windows` keeps them
+//! beside it when it is dragged. Any cheat window still out of reach is clamped onto the
+//! nearest monitor, cascading when its title bar would land on another window's.
+
+use crate::layout::Rect;
+
+/// Title bar height assumed for the reachability test.
+pub const TITLE_BAR_HEIGHT: i32 = 24;
+
+/// Width of title bar that must be on a monitor for the window to count as reachable.
+pub const MIN_VISIBLE_WIDTH: i32 = 48;
+
+/// Offset between windows that would otherwise land on each other.
+pub const CASCADE_STEP: i32 = 24;
+
+/// Where the windows go; `main` is `None` when it was `None` on the way in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Placement {
+    pub main: Option<Rect>,
+    pub windows: Vec<Rect>,
+}
+
+/// Moves `main` and the `windows` placed around it until each can be reached on one of
+/// `work_areas`. Without work areas there is nothing to measure against and every rect
+/// is returned as it was.
+pub fn recover(main: Option<Rect>, windows: &[Rect], work_areas: &[Rect]) -> Placement {
+    if work_areas.is_empty() {
+        return Placement {
+            main,
+            windows: windows.to_vec(),
+        };
+    }
+
+    let mut placed = Vec::with_capacity(windows.len() + 1);
+
+    let (main, (dx, dy)) = match main {
+        Some(rect) if !reachable(&rect, work_areas) => {
+            let moved = clamp(&rect, nearest_area(&rect, work_areas));
+            placed.push(moved);
+            (Some(moved), (moved.x - rect.x, moved.y - rect.y))
+        }
+        Some(rect) => {
+            placed.push(rect);
+            (Some(rect), (0, 0))
+        }
+        None => (None, (0, 0)),
+    };
+
+    let mut recovered = Vec::with_capacity(windows.len());
+    for window in windows {
+        let shifted = offset(window, dx, dy);
+        let rect = if reachable(&shifted, work_areas) {
+            shifted
+        } else {
+            cascade(
+                clamp(&shifted, nearest_area(&shifted, work_areas)),
+                &placed,
+                work_areas,
+            )
+        };
+        placed.push(rect);
+        recovered.push(rect);
+    }
+
+    Placement {
+        main,
+        windows: recovered,
+    }
+}
+
+/// True when a grabbable part of the title bar lies on one work area.
+pub fn reachable(rect: &Rect, work_areas: &[Rect]) -> bool {
+    let title = Rect::new(
+        rect.x,
+        rect.y,
+        rect.width,
+        TITLE_BAR_HEIGHT.min(rect.height),
+    );
+    let needed = MIN_VISIBLE_WIDTH.min(rect.width);
+
+    work_areas.iter().any(|area| {
+        intersection(&title, area)
+            .is_some_and(|common| common.height == title.height && common.width >= needed)
+    })
+}
+
+fn intersection(a: &Rect, b: &Rect) -> Option<Rect> {
+    let left = a.x.max(b.x);
+    let top = a.y.max(b.y);
+    let right = a.right().min(b.right());
+    let bottom = a.bottom().min(b.bottom());
+
+    (left < right && top < bottom).then(|| Rect::new(left, top, right - left, bottom - top))
+}
+
+/// The work area sharing most of `rect`, or the one closest to it when none does.
+fn nearest_area<'a>(rect: &Rect, work_areas: &'a [Rect]) -> &'a Rect {
+    let overlap = |area: &Rect| {
+        intersection(rect, area).map_or(0, |common| {
+            i64::from(common.width) * i64::from(common.height)
+        })
+    };
+    let distance = |area: &Rect| {
+        let (cx, cy) = (rect.x + rect.width / 2, rect.y + rect.height / 2);
+        let dx = i64::from((area.x - cx).max(cx - area.right()).max(0));
+        let dy = i64::from((area.y - cy).max(cy - area.bottom()).max(0));
+        dx * dx + dy * dy
+    };
+
+    work_areas
+        .iter()
+        .max_by_key(|area| (overlap(area), -distance(area)))
+        .expect("at least one work area")
+}
+
+/// Moves `rect` inside `area`, keeping its top left corner inside when it is too big.
+fn clamp(rect: &Rect, area: &Rect) -> Rect {
+    let x = rect.x.min(area.right() - rect.width).max(area.x);
+    let y = rect.y.min(area.bottom() - rect.height).max(area.y);
+
+    Rect::new(x, y, rect.width, rect.height)
+}
+
+fn offset(rect: &Rect, dx: i32, dy: i32) -> Rect {
+    Rect::new(
+        rect.x.saturating_add(dx),
+        rect.y.saturating_add(dy),
+        rect.width,
+        rect.height,
+    )
+}
+
+/// Steps `rect` down and right while its title bar would sit on an already placed one,
+/// giving up once a step would leave its work area.
+fn cascade(mut rect: Rect, placed: &[Rect], work_areas: &[Rect]) -> Rect {
+    let covers = |rect: &Rect, other: &Rect| {
+        (rect.x - other.x).abs() < CASCADE_STEP && (rect.y - other.y).abs() < TITLE_BAR_HEIGHT
+    };
+    let area = *nearest_area(&rect, work_areas);
+
+    for _ in 0..=placed.len() {
+        if !placed.iter().any(|other| covers(&rect, other)) {
+            break;
+        }
+        let next = offset(&rect, CASCADE_STEP, CASCADE_STEP);
+        if next.right() > area.right() || next.bottom() > area.bottom() {
+            break;
+        }
+        rect = next;
+    }
+
+    rect
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEFT: Rect = Rect::new(-1920, 0, 1920, 1080);
+    const PRIMARY: Rect = Rect::new(0, 0, 1920, 1040);
+    const RIGHT_PORTRAIT: Rect = Rect::new(1920, -400, 1080, 1880);
+
+    const MAIN: Rect = Rect::new(2100, 100, 420, 460);
+
+    /// Cheat windows laid out under and beside the main window on the right monitor.
+    fn cheats() -> Vec<Rect> {
+        vec![
+            Rect::new(2100, 580, 320, 240),
+            Rect::new(2440, 580, 260, 180),
+            Rect::new(2540, 100, 220, 160),
+        ]
+    }
+
+    #[test]
+    fn reachable_windows_stay_put() {
+        let areas = [LEFT, PRIMARY, RIGHT_PORTRAIT];
+        let placement = recover(Some(MAIN), &cheats(), &areas);
+
+        assert_eq!(placement.main, Some(MAIN));
+        assert_eq!(placement.windows, cheats());
+
+        let straddling = Rect::new(1700, 300, 420, 460);
+        let on_left = Rect::new(-1500, 200, 420, 460);
+        let placement = recover(Some(straddling), &[on_left], &areas);
+        assert_eq!(placement.main, Some(straddling));
+        assert_eq!(placement.windows, [on_left]);
+    }
+
+    #[test]
+    fn unplugged_monitor_moves_the_group_together() {
+        let placement = recover(Some(MAIN), &cheats(), &[LEFT, PRIMARY]);
+
+        let main = placement.main.unwrap();
+        assert_eq!(main, Rect::new(1500, 100, 420, 460));
+        assert!(placement.windows.iter().all(|w| reachable(w, &[PRIMARY])));
+
+        // The first two follow the main window and can still be reached; the third would
+        // be past the monitor edge, so it is clamped.
+        assert_eq!(placement.windows[0], Rect::new(1500, 580, 320, 240));
+        assert_eq!(placement.windows[1], Rect::new(1840, 580, 260, 180));
+        assert_eq!(placement.windows[2], Rect::new(1700, 100, 220, 160));
+    }
+
+    #[test]
+    fn windows_left_on_a_lost_monitor_are_clamped_and_cascaded() {
+        let main = Rect::new(100, 100, 420, 460);
+        let lost = [
+            Rect::new(-1800, 300, 320, 240),
+            Rect::new(-1800, 300, 260, 180),
+            Rect::new(-1780, 310, 220, 160),
+        ];
+
+        let placement = recover(Some(main), &lost, &[PRIMARY, RIGHT_PORTRAIT]);
+
+        assert_eq!(placement.main, Some(main));
+        assert_eq!(
+            placement.windows,
+            [
+                Rect::new(0, 300, 320, 240),
+                Rect::new(24, 324, 260, 180),
+                Rect::new(48, 358, 220, 160),
+            ]
+        );
+    }
+
+    #[test]
+    fn title_bar_must_be_reachable() {
+        // Most of the window shows, but its title bar is above the top of the monitor.
+        let hidden_title = Rect::new(200, -30, 300, 400);
+        assert!(!reachable(&hidden_title, &[PRIMARY]));
+        assert_eq!(
+            recover(None, &[hidden_title], &[PRIMARY]).windows,
+            [Rect::new(200, 0, 300, 400)]
+        );
+
+        // A sliver of title bar is not enough to grab.
+        assert!(!reachable(&Rect::new(1900, 10, 300, 200), &[PRIMARY]));
+        assert!(reachable(&Rect::new(1860, 10, 300, 200), &[PRIMARY]));
+    }
+
+    #[test]
+    fn oversized_and_unmeasured_windows() {
+        let huge = Rect::new(5000, 5000, 2500, 1500);
+        let placement = recover(Some(huge), &[], &[PRIMARY]);
+        assert_eq!(placement.main, Some(Rect::new(0, 0, 2500, 1500)));
+
+        let placement = recover(Some(huge), &cheats(), &[]);
+        assert_eq!(placement.main, Some(huge));
+        assert_eq!(placement.windows, cheats());
+    }
+}
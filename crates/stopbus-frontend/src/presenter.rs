@@ -0,0 +1,929 @@
+//! UI rules for a Stop the Bus table, independent of any windowing system.
+//!
+//! A shell feeds the [`Presenter`] user intents, pointer presses and timer ticks. Each call
+//! returns the [`Effect`]s the shell must carry out, in order; [`Presenter::view`] describes
+//! what to draw and which controls are enabled.
+
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use stopbus_core::{
+    CardId, DriveReport, GameEvent, GameState, Narrator, RoundSummary, HAND_SIZE, PLAYERS,
+};
+
+use crate::event_log::{EventLog, LogKind};
+use crate::input::{Drag, DragInput};
+use crate::keyboard::{map_key, Key, KeyAction, KeyContext};
+
+/// Delay between highlighting successive AI turns.
+pub const TURN_TIMER_INTERVAL_MS: u32 = 500;
+
+/// Something the user asked for through a button, menu or key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Intent {
+    Deal,
+    Stick,
+    /// The OK button: the human has finished their turn, or has seen the showdown.
+    EndTurn,
+    SwapWithStack(usize),
+    DrawNextCard,
+}
+
+/// Part of the table under the pointer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Target {
+    HandCard(usize),
+    /// The face-down deck the next card is drawn from.
+    Stack,
+    /// The face-up card on top of the stack.
+    TopCard,
+}
+
+/// Side effect the shell must perform.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Effect {
+    /// Tell the user about a game event.
+    ShowEvent(GameEvent),
+    /// Read a narration sentence out, e.g. through a screen reader.
+    Announce(String),
+    /// Redraw the table.
+    Repaint,
+    /// Redraw auxiliary panels such as the cheat windows.
+    RefreshPanels,
+    StartTurnTimer {
+        interval_ms: u32,
+    },
+    StopTurnTimer,
+    /// Call [`Presenter::continue_automation`] once the earlier effects are done.
+    ContinueAutomation,
+}
+
+/// Everything a shell needs to draw the table and its controls.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ViewModel {
+    pub hand: [Option<CardId>; HAND_SIZE],
+    pub stack_top: Option<CardId>,
+    pub lives: [u8; PLAYERS],
+    pub round_start_player: usize,
+    /// Player the turn pointer is next to, following the turn animation when one is running.
+    pub pointer_player: usize,
+    pub stick_player: Option<usize>,
+    pub awaiting_human: bool,
+    pub ok_enabled: bool,
+    pub stick_enabled: bool,
+    pub animating: bool,
+    /// Hand slot highlighted for keyboard play.
+    pub focused_slot: Option<usize>,
+    /// Card being dragged, drawn under the pointer.
+    pub drag: Option<Drag>,
+    /// The round that just ended, shown in place of the table until the user moves on.
+    pub showdown: Option<RoundSummary>,
+}
+
+#[derive(Debug)]
+pub struct Presenter {
+    game: GameState,
+    awaiting_human: bool,
+    ok_enabled: bool,
+    stick_enabled: bool,
+    input: DragInput,
+    focused_slot: Option<usize>,
+    pending_turns: VecDeque<usize>,
+    turn_indicator_override: Option<usize>,
+    turn_timer_active: bool,
+    automation_pending: bool,
+    showdown: Option<RoundSummary>,
+    narrator: Option<Narrator>,
+    log: EventLog,
+    started: Instant,
+}
+
+impl Presenter {
+    pub fn new(game: GameState) -> Self {
+        Self {
+            game,
+            awaiting_human: false,
+            ok_enabled: false,
+            stick_enabled: false,
+            input: DragInput::new(),
+            focused_slot: None,
+            pending_turns: VecDeque::new(),
+            turn_indicator_override: None,
+            turn_timer_active: false,
+            automation_pending: false,
+            showdown: None,
+            narrator: None,
+            log: EventLog::default(),
+            started: Instant::now(),
+        }
+    }
+
+    /// Turns [`Effect::Announce`] narration of each report on or off. Off by default.
+    pub fn set_narration(&mut self, enabled: bool) {
+        self.narrator = enabled.then(Narrator::new);
+    }
+
+    pub fn narration_enabled(&self) -> bool {
+        self.narrator.is_some()
+    }
+
+    /// Every event shown so far, and the narration when it is on.
+    pub fn log(&self) -> &EventLog {
+        &self.log
+    }
+
+    pub fn log_mut(&mut self) -> &mut EventLog {
+        &mut self.log
+    }
+
+    /// Logs a message from the shell itself, such as a settings import problem, at the
+    /// current point in the session.
+    pub fn log_note(&mut self, kind: LogKind, text: impl Into<String>) {
+        self.log.push(self.started.elapsed(), kind, text);
+    }
+
+    pub fn game(&self) -> &GameState {
+        &self.game
+    }
+
+    pub fn game_mut(&mut self) -> &mut GameState {
+        &mut self.game
+    }
+
+    /// True while the human may act on the table.
+    pub fn awaiting_human(&self) -> bool {
+        self.awaiting_human && self.game.awaiting_human()
+    }
+
+    pub fn automation_pending(&self) -> bool {
+        self.automation_pending
+    }
+
+    pub fn turn_timer_active(&self) -> bool {
+        self.turn_timer_active
+    }
+
+    pub fn pending_turns(&self) -> impl Iterator<Item = usize> + '_ {
+        self.pending_turns.iter().copied()
+    }
+
+    pub fn turn_indicator_override(&self) -> Option<usize> {
+        self.turn_indicator_override
+    }
+
+    /// What the current pointer gesture started on.
+    pub fn pressed(&self) -> Option<Target> {
+        self.input.source()
+    }
+
+    pub fn focused_slot(&self) -> Option<usize> {
+        self.focused_slot
+    }
+
+    /// The summary being shown; the next round waits until [`Intent::EndTurn`] dismisses it.
+    pub fn showdown(&self) -> Option<&RoundSummary> {
+        self.showdown.as_ref()
+    }
+
+    pub fn view(&self) -> ViewModel {
+        ViewModel {
+            hand: self.game.hands[0],
+            stack_top: self.game.stack_top_card(),
+            lives: *self.game.lives(),
+            round_start_player: self.game.round_start_player(),
+            pointer_player: self
+                .turn_indicator_override
+                .unwrap_or_else(|| self.game.current_player()),
+            stick_player: self.game.stick_player(),
+            awaiting_human: self.awaiting_human,
+            ok_enabled: self.ok_enabled,
+            stick_enabled: self.stick_enabled,
+            animating: self.turn_timer_active,
+            focused_slot: self.focused_slot,
+            drag: self.input.drag(),
+            showdown: self.showdown.clone(),
+        }
+    }
+
+    pub fn handle_intent(&mut self, intent: Intent) -> Vec<Effect> {
+        if intent == Intent::EndTurn && self.showdown.is_some() {
+            return self.dismiss_showdown();
+        }
+
+        let report = match intent {
+            Intent::Deal => {
+                self.showdown = None;
+                Some(self.game.start_fresh())
+            }
+            Intent::Stick => self.game.human_stick(),
+            Intent::EndTurn => Some(self.game.advance_after_human_turn()),
+            Intent::SwapWithStack(slot) => self.game.human_swap_with_stack(slot),
+            Intent::DrawNextCard => self.game.human_draw_next_card(),
+        };
+
+        match report {
+            Some(report) => self.process_report(report),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn key_press(&mut self, key: Key) -> Vec<Effect> {
+        if key == Key::Escape && self.input.drag().is_some() {
+            return self.pointer_cancel();
+        }
+
+        let context = KeyContext {
+            awaiting_human: self.awaiting_human(),
+            can_stick: self.stick_enabled && self.game.human_can_stick(),
+            focused_slot: self.focused_slot,
+            showdown: self.showdown.is_some(),
+        };
+
+        match map_key(key, context) {
+            KeyAction::Focus(slot) if slot != self.focused_slot => {
+                self.focused_slot = slot;
+                vec![Effect::Repaint]
+            }
+            KeyAction::Focus(_) | KeyAction::Ignored => Vec::new(),
+            KeyAction::Intent(intent) => {
+                if matches!(intent, Intent::SwapWithStack(_)) {
+                    self.focused_slot = None;
+                }
+                self.handle_intent(intent)
+            }
+        }
+    }
+
+    /// Starts a pointer gesture. Presses only count while the human is on turn.
+    pub fn pointer_down(&mut self, target: Option<Target>, position: (i32, i32)) {
+        let target = if self.awaiting_human() { target } else { None };
+        self.input.press(target, position);
+    }
+
+    pub fn pointer_move(&mut self, position: (i32, i32)) -> Vec<Effect> {
+        if self.input.move_to(position) {
+            vec![Effect::Repaint]
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Completes a click or drop over `target`; see [`crate::input`] for what each does.
+    pub fn pointer_up(&mut self, target: Option<Target>, position: (i32, i32)) -> Vec<Effect> {
+        let was_dragging = self.input.drag().is_some();
+        let intent = self.input.release(target, position);
+
+        let mut effects = match intent {
+            Some(intent) if self.awaiting_human() => self.handle_intent(intent),
+            _ => Vec::new(),
+        };
+
+        if was_dragging && !effects.contains(&Effect::Repaint) {
+            effects.push(Effect::Repaint);
+        }
+        effects
+    }
+
+    /// Drops the gesture without acting, e.g. when the shell loses the mouse capture.
+    pub fn pointer_cancel(&mut self) -> Vec<Effect> {
+        if self.input.cancel() {
+            vec![Effect::Repaint]
+        } else {
+            Vec::new()
+        }
+    }
+
+    pub fn process_report(&mut self, report: DriveReport) -> Vec<Effect> {
+        let elapsed = self.started.elapsed();
+
+        let mut effects: Vec<Effect> = Vec::new();
+        for event in &report.events {
+            self.log.record(elapsed, event);
+            effects.push(Effect::ShowEvent(event.clone()));
+        }
+
+        if let Some(narrator) = &mut self.narrator {
+            for sentence in narrator.narrate(&self.game, &report) {
+                self.log.push(elapsed, LogKind::Narration, sentence.clone());
+                effects.push(Effect::Announce(sentence));
+            }
+        }
+
+        if report.round_summary.is_some() {
+            self.showdown = report.round_summary.clone();
+        }
+
+        self.update_button_states(&report);
+
+        self.automation_pending = !report.awaiting_human && !report.game_over();
+
+        effects.push(Effect::Repaint);
+        effects.push(Effect::RefreshPanels);
+
+        if report.turn_sequence.is_empty() {
+            if self.automation_pending && self.showdown.is_none() {
+                effects.push(Effect::ContinueAutomation);
+            }
+            return effects;
+        }
+
+        self.enqueue_turn_animation(report.turn_sequence, &mut effects);
+        effects
+    }
+
+    /// Runs the AI seats after a report that left automation pending.
+    pub fn continue_automation(&mut self) -> Vec<Effect> {
+        if !self.automation_pending || self.showdown.is_some() {
+            return Vec::new();
+        }
+
+        self.automation_pending = false;
+        let report = self.game.continue_automation();
+        self.process_report(report)
+    }
+
+    pub fn timer_tick(&mut self) -> Vec<Effect> {
+        let mut effects = Vec::new();
+        self.advance_turn_animation(&mut effects);
+        effects
+    }
+
+    /// Cancels any turn animation without driving the game further, e.g. when the window closes.
+    pub fn shutdown(&mut self) -> Vec<Effect> {
+        self.automation_pending = false;
+
+        let mut effects = Vec::new();
+        self.stop_turn_animation(&mut effects);
+        effects
+    }
+
+    /// Clears the showdown and lets the next round start.
+    fn dismiss_showdown(&mut self) -> Vec<Effect> {
+        self.showdown = None;
+        self.ok_enabled = self.awaiting_human;
+
+        let mut effects = vec![Effect::Repaint];
+        if self.automation_pending && !self.turn_timer_active {
+            effects.push(Effect::ContinueAutomation);
+        }
+        effects
+    }
+
+    fn update_button_states(&mut self, report: &DriveReport) {
+        let game_over = report.game_over();
+        let human_alive = self.game.lives()[0] > 0;
+
+        self.awaiting_human = report.awaiting_human && human_alive && !game_over;
+        self.ok_enabled = self.awaiting_human || self.showdown.is_some();
+        self.stick_enabled = self.awaiting_human && self.game.human_can_stick();
+
+        if !self.awaiting_human {
+            self.input.cancel();
+            self.focused_slot = None;
+        }
+    }
+
+    fn enqueue_turn_animation(&mut self, turns: Vec<usize>, effects: &mut Vec<Effect>) {
+        if turns.is_empty() {
+            return;
+        }
+
+        self.pending_turns.extend(turns);
+
+        if !self.turn_timer_active {
+            self.turn_timer_active = true;
+            effects.push(Effect::StartTurnTimer {
+                interval_ms: TURN_TIMER_INTERVAL_MS,
+            });
+            self.advance_turn_animation(effects);
+        }
+    }
+
+    fn advance_turn_animation(&mut self, effects: &mut Vec<Effect>) {
+        if let Some(next_player) = self.pending_turns.pop_front() {
+            self.turn_indicator_override = Some(next_player);
+            effects.push(Effect::Repaint);
+        } else {
+            self.stop_turn_animation(effects);
+        }
+    }
+
+    fn stop_turn_animation(&mut self, effects: &mut Vec<Effect>) {
+        if self.turn_timer_active {
+            self.turn_timer_active = false;
+            effects.push(Effect::StopTurnTimer);
+        }
+
+        self.pending_turns.clear();
+
+        if self.turn_indicator_override.take().is_some() {
+            effects.push(Effect::Repaint);
+        }
+
+        if self.automation_pending && self.showdown.is_none() {
+            effects.push(Effect::ContinueAutomation);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(awaiting_human: bool, turn_sequence: Vec<usize>) -> DriveReport {
+        DriveReport {
+            events: Vec::new(),
+            awaiting_human,
+            winner: None,
+            draw: false,
+            turn_sequence,
+            round_summary: None,
+        }
+    }
+
+    /// A presenter whose game is on the human's turn, with the deal already shown.
+    fn presenter_on_human_turn() -> Presenter {
+        let mut presenter = Presenter::new(GameState::new(Some(123)));
+        let mut effects = presenter.handle_intent(Intent::Deal);
+
+        // Drain AI animations and automation until the human is up.
+        for _ in 0..100 {
+            if presenter.awaiting_human() && !presenter.turn_timer_active() {
+                return presenter;
+            }
+
+            effects = if effects.contains(&Effect::ContinueAutomation) {
+                presenter.continue_automation()
+            } else {
+                presenter.timer_tick()
+            };
+        }
+
+        panic!("human never got a turn");
+    }
+
+    #[test]
+    fn events_are_shown_before_repainting() {
+        let mut presenter = Presenter::new(GameState::new(Some(1)));
+        let mut report = report(true, Vec::new());
+        report.events = vec![GameEvent::info("first"), GameEvent::alert("second")];
+
+        let effects = presenter.process_report(report);
+
+        assert_eq!(
+            effects,
+            vec![
+                Effect::ShowEvent(GameEvent::info("first")),
+                Effect::ShowEvent(GameEvent::alert("second")),
+                Effect::Repaint,
+                Effect::RefreshPanels,
+            ]
+        );
+    }
+
+    #[test]
+    fn ok_follows_awaiting_human_and_stick_follows_the_game() {
+        let presenter = presenter_on_human_turn();
+        let view = presenter.view();
+
+        assert!(view.awaiting_human);
+        assert!(view.ok_enabled);
+        assert_eq!(view.stick_enabled, presenter.game().human_can_stick());
+    }
+
+    #[test]
+    fn buttons_disable_when_the_human_is_out() {
+        let mut presenter = Presenter::new(GameState::new(Some(1)));
+        presenter.game_mut().set_lives([0, 3, 3, 3]);
+
+        presenter.process_report(report(true, Vec::new()));
+        let view = presenter.view();
+
+        assert!(!view.awaiting_human);
+        assert!(!view.ok_enabled);
+        assert!(!view.stick_enabled);
+    }
+
+    #[test]
+    fn buttons_disable_when_the_game_is_over() {
+        let mut presenter = Presenter::new(GameState::new(Some(1)));
+        let mut over = report(true, Vec::new());
+        over.winner = Some(0);
+
+        let effects = presenter.process_report(over);
+        let view = presenter.view();
+
+        assert!(!view.ok_enabled);
+        assert!(!view.stick_enabled);
+        assert!(!presenter.automation_pending());
+        assert!(!effects.contains(&Effect::ContinueAutomation));
+    }
+
+    #[test]
+    fn report_without_turns_requests_automation() {
+        let mut presenter = Presenter::new(GameState::new(Some(1)));
+
+        let effects = presenter.process_report(report(false, Vec::new()));
+
+        assert!(presenter.automation_pending());
+        assert_eq!(effects.last(), Some(&Effect::ContinueAutomation));
+        assert!(!presenter.turn_timer_active());
+    }
+
+    #[test]
+    fn turn_sequence_starts_the_timer_and_highlights_the_first_turn() {
+        let mut presenter = Presenter::new(GameState::new(Some(1)));
+
+        let effects = presenter.process_report(report(true, vec![1, 2, 3]));
+
+        assert_eq!(
+            effects,
+            vec![
+                Effect::Repaint,
+                Effect::RefreshPanels,
+                Effect::StartTurnTimer {
+                    interval_ms: TURN_TIMER_INTERVAL_MS
+                },
+                Effect::Repaint,
+            ]
+        );
+        assert!(presenter.turn_timer_active());
+        assert_eq!(presenter.turn_indicator_override(), Some(1));
+        assert_eq!(presenter.view().pointer_player, 1);
+        assert_eq!(presenter.pending_turns().collect::<Vec<_>>(), vec![2, 3]);
+    }
+
+    #[test]
+    fn timer_ticks_walk_the_queue_then_stop() {
+        let mut presenter = Presenter::new(GameState::new(Some(1)));
+        presenter.process_report(report(true, vec![1, 2]));
+
+        assert_eq!(presenter.timer_tick(), vec![Effect::Repaint]);
+        assert_eq!(presenter.view().pointer_player, 2);
+
+        assert_eq!(
+            presenter.timer_tick(),
+            vec![Effect::StopTurnTimer, Effect::Repaint]
+        );
+        assert!(!presenter.turn_timer_active());
+        assert_eq!(presenter.turn_indicator_override(), None);
+        assert_eq!(
+            presenter.view().pointer_player,
+            presenter.game().current_player()
+        );
+    }
+
+    #[test]
+    fn animation_end_resumes_pending_automation() {
+        let mut presenter = Presenter::new(GameState::new(Some(1)));
+        presenter.process_report(report(false, vec![2]));
+        assert!(presenter.automation_pending());
+
+        assert_eq!(
+            presenter.timer_tick(),
+            vec![
+                Effect::StopTurnTimer,
+                Effect::Repaint,
+                Effect::ContinueAutomation
+            ]
+        );
+    }
+
+    #[test]
+    fn turns_queue_behind_a_running_animation() {
+        let mut presenter = Presenter::new(GameState::new(Some(1)));
+        presenter.process_report(report(true, vec![1]));
+
+        let effects = presenter.process_report(report(true, vec![2, 3]));
+
+        assert_eq!(effects, vec![Effect::Repaint, Effect::RefreshPanels]);
+        assert_eq!(presenter.turn_indicator_override(), Some(1));
+        assert_eq!(presenter.pending_turns().collect::<Vec<_>>(), vec![2, 3]);
+    }
+
+    #[test]
+    fn continue_automation_is_ignored_unless_pending() {
+        let mut presenter = Presenter::new(GameState::new(Some(1)));
+        presenter.process_report(report(true, Vec::new()));
+
+        assert!(presenter.continue_automation().is_empty());
+    }
+
+    #[test]
+    fn continue_automation_drives_the_game() {
+        let mut presenter = Presenter::new(GameState::new(Some(7)));
+        presenter.game_mut().start_game();
+        presenter.process_report(report(false, Vec::new()));
+
+        let effects = presenter.continue_automation();
+
+        assert!(!effects.is_empty());
+        assert!(effects.contains(&Effect::Repaint));
+    }
+
+    #[test]
+    fn shutdown_stops_the_timer_without_resuming_automation() {
+        let mut presenter = Presenter::new(GameState::new(Some(1)));
+        presenter.process_report(report(false, vec![1, 2]));
+
+        let effects = presenter.shutdown();
+
+        assert_eq!(effects, vec![Effect::StopTurnTimer, Effect::Repaint]);
+        assert!(!presenter.automation_pending());
+        assert_eq!(presenter.pending_turns().count(), 0);
+    }
+
+    #[test]
+    fn clicking_a_hand_card_swaps_it_with_the_stack() {
+        let mut presenter = presenter_on_human_turn();
+        let stack_card = presenter.game().stack_top_card();
+
+        presenter.pointer_down(Some(Target::HandCard(1)), (120, 220));
+        assert_eq!(presenter.pressed(), Some(Target::HandCard(1)));
+
+        let effects = presenter.pointer_up(Some(Target::HandCard(1)), (120, 220));
+
+        assert!(effects.contains(&Effect::Repaint));
+        assert_eq!(presenter.view().hand[1], stack_card);
+        assert_eq!(presenter.pressed(), None);
+    }
+
+    #[test]
+    fn releasing_elsewhere_cancels_the_click() {
+        let mut presenter = presenter_on_human_turn();
+        let hand = presenter.view().hand;
+
+        presenter.pointer_down(Some(Target::HandCard(0)), (20, 220));
+        let effects = presenter.pointer_up(Some(Target::HandCard(2)), (220, 220));
+
+        assert!(effects.is_empty());
+        assert_eq!(presenter.view().hand, hand);
+    }
+
+    #[test]
+    fn clicking_the_stack_draws_the_next_card() {
+        let mut presenter = presenter_on_human_turn();
+        let stack_index = presenter.game().stack_index();
+
+        presenter.pointer_down(Some(Target::Stack), (20, 50));
+        presenter.pointer_up(Some(Target::Stack), (20, 50));
+
+        assert_eq!(presenter.game().stack_index(), stack_index + 1);
+        assert!(!presenter.view().stick_enabled);
+    }
+
+    #[test]
+    fn presses_are_ignored_off_turn() {
+        let mut presenter = Presenter::new(GameState::new(Some(1)));
+
+        presenter.pointer_down(Some(Target::Stack), (20, 50));
+
+        assert_eq!(presenter.pressed(), None);
+        assert!(presenter
+            .pointer_up(Some(Target::Stack), (20, 50))
+            .is_empty());
+    }
+
+    #[test]
+    fn dragging_the_top_card_onto_the_hand_swaps_it() {
+        let mut presenter = presenter_on_human_turn();
+        let stack_card = presenter.game().stack_top_card();
+        let hand_card = presenter.game().hands[0][2];
+
+        presenter.pointer_down(Some(Target::TopCard), (120, 50));
+        assert_eq!(presenter.pointer_move((160, 120)), vec![Effect::Repaint]);
+        let drag = presenter.view().drag.unwrap();
+        assert_eq!(drag.source, Target::TopCard);
+        assert_eq!(drag.offset(), (40, 70));
+
+        let effects = presenter.pointer_up(Some(Target::HandCard(2)), (230, 220));
+
+        assert!(effects.contains(&Effect::Repaint));
+        assert_eq!(presenter.game().hands[0][2], stack_card);
+        assert_eq!(presenter.game().stack_top_card(), hand_card);
+        assert_eq!(presenter.view().drag, None);
+    }
+
+    #[test]
+    fn cancelled_drags_repaint_without_acting() {
+        let mut presenter = presenter_on_human_turn();
+        let hand = presenter.view().hand;
+
+        presenter.pointer_down(Some(Target::HandCard(0)), (20, 220));
+        presenter.pointer_move((100, 100));
+        assert_eq!(presenter.key_press(Key::Escape), vec![Effect::Repaint]);
+        assert_eq!(presenter.view().drag, None);
+        assert!(presenter
+            .pointer_up(Some(Target::TopCard), (120, 50))
+            .is_empty());
+
+        presenter.pointer_down(Some(Target::HandCard(0)), (20, 220));
+        presenter.pointer_move((300, 300));
+        assert_eq!(
+            presenter.pointer_up(None, (300, 300)),
+            vec![Effect::Repaint]
+        );
+        assert_eq!(presenter.view().hand, hand);
+        assert!(presenter.pointer_cancel().is_empty());
+    }
+
+    #[test]
+    fn ending_the_turn_hands_over_to_the_ai() {
+        let mut presenter = presenter_on_human_turn();
+
+        presenter.handle_intent(Intent::EndTurn);
+
+        assert!(!presenter.view().ok_enabled);
+        assert!(presenter.turn_timer_active());
+        assert_eq!(presenter.turn_indicator_override(), Some(1));
+    }
+
+    #[test]
+    fn rejected_intents_produce_no_effects() {
+        let mut presenter = Presenter::new(GameState::new(Some(1)));
+
+        assert!(presenter.handle_intent(Intent::Stick).is_empty());
+        assert!(presenter
+            .handle_intent(Intent::SwapWithStack(HAND_SIZE))
+            .is_empty());
+        assert!(presenter.handle_intent(Intent::DrawNextCard).is_empty());
+    }
+
+    #[test]
+    fn keys_focus_a_slot_and_swap_it() {
+        let mut presenter = presenter_on_human_turn();
+        let stack_card = presenter.game().stack_top_card();
+
+        assert_eq!(presenter.key_press(Key::Char('2')), vec![Effect::Repaint]);
+        assert_eq!(presenter.view().focused_slot, Some(1));
+        assert!(presenter.key_press(Key::Char('2')).is_empty());
+
+        let effects = presenter.key_press(Key::Char('s'));
+
+        assert!(effects.contains(&Effect::Repaint));
+        assert_eq!(presenter.game().hands[0][1], stack_card);
+        assert_eq!(presenter.focused_slot(), None);
+    }
+
+    #[test]
+    fn keys_are_ignored_off_turn_except_deal() {
+        let mut presenter = Presenter::new(GameState::new(Some(5)));
+        presenter.process_report(report(false, Vec::new()));
+
+        for key in [Key::Char('1'), Key::Char('k'), Key::Char('d'), Key::Enter] {
+            assert!(presenter.key_press(key).is_empty(), "{:?}", key);
+        }
+        assert_eq!(presenter.focused_slot(), None);
+
+        assert!(presenter
+            .key_press(Key::Char('n'))
+            .contains(&Effect::Repaint));
+    }
+
+    #[test]
+    fn focus_clears_when_the_turn_ends() {
+        let mut presenter = presenter_on_human_turn();
+        presenter.key_press(Key::Right);
+        assert_eq!(presenter.focused_slot(), Some(0));
+
+        presenter.key_press(Key::Enter);
+
+        assert_eq!(presenter.view().focused_slot, None);
+    }
+
+    #[test]
+    fn narration_is_announced_only_when_enabled() {
+        let mut presenter = presenter_on_human_turn();
+        let effects = presenter.handle_intent(Intent::SwapWithStack(0));
+        assert!(!effects
+            .iter()
+            .any(|effect| matches!(effect, Effect::Announce(_))));
+
+        presenter.set_narration(true);
+        let effects = presenter.handle_intent(Intent::Deal);
+        let announcements: Vec<&String> = effects
+            .iter()
+            .filter_map(|effect| match effect {
+                Effect::Announce(text) => Some(text),
+                _ => None,
+            })
+            .collect();
+
+        assert!(!announcements.is_empty());
+        let first_announcement = effects
+            .iter()
+            .position(|effect| matches!(effect, Effect::Announce(_)))
+            .unwrap();
+        let repaint = effects.iter().position(|e| *e == Effect::Repaint).unwrap();
+        assert!(first_announcement < repaint);
+    }
+
+    #[test]
+    fn events_and_narration_go_into_the_log() {
+        let mut presenter = Presenter::new(GameState::new(Some(1)));
+        let mut report = report(true, Vec::new());
+        report.events = vec![GameEvent::info("first"), GameEvent::alert("second")];
+        presenter.process_report(report);
+
+        presenter.log_note(LogKind::Info, "STOPBUS.INI line 2: x=1 was not imported.");
+
+        let kinds: Vec<LogKind> = presenter.log().entries().map(|entry| entry.kind).collect();
+        assert_eq!(kinds, [LogKind::Info, LogKind::Alert, LogKind::Info]);
+        assert_eq!(
+            presenter
+                .log()
+                .entries()
+                .last()
+                .map(|entry| entry.text.as_str()),
+            Some("STOPBUS.INI line 2: x=1 was not imported.")
+        );
+
+        let mut presenter = presenter_on_human_turn();
+        presenter.set_narration(true);
+        presenter.log_mut().clear();
+        let effects = presenter.handle_intent(Intent::SwapWithStack(0));
+
+        let announced: Vec<&String> = effects
+            .iter()
+            .filter_map(|effect| match effect {
+                Effect::Announce(text) => Some(text),
+                _ => None,
+            })
+            .collect();
+        let logged: Vec<&String> = presenter
+            .log()
+            .filtered(crate::LogFilter {
+                info: false,
+                alerts: false,
+                narration: true,
+            })
+            .map(|entry| &entry.text)
+            .collect();
+        assert!(!logged.is_empty());
+        assert_eq!(logged, announced);
+    }
+
+    /// Drives a presenter until a round ends, returning it with the showdown up.
+    fn presenter_at_showdown(seed: u64) -> Presenter {
+        let mut presenter = Presenter::new(GameState::new(Some(seed)));
+        let mut effects = presenter.handle_intent(Intent::Deal);
+
+        for _ in 0..1000 {
+            if presenter.showdown().is_some() {
+                return presenter;
+            }
+
+            effects = if effects.contains(&Effect::ContinueAutomation) {
+                presenter.continue_automation()
+            } else if presenter.turn_timer_active() {
+                presenter.timer_tick()
+            } else {
+                presenter.handle_intent(Intent::EndTurn)
+            };
+        }
+
+        panic!("no round ended");
+    }
+
+    #[test]
+    fn showdown_holds_the_next_deal_until_dismissed() {
+        let mut presenter = presenter_at_showdown(2024);
+        let summary = presenter.showdown().unwrap().clone();
+
+        let view = presenter.view();
+        assert_eq!(view.showdown, Some(summary.clone()));
+        assert!(view.ok_enabled);
+        assert!(!view.awaiting_human);
+        assert_eq!(&summary.lives_after, presenter.game().lives());
+
+        // Nothing moves on behind the showdown.
+        assert!(presenter.continue_automation().is_empty());
+        assert!(presenter.timer_tick().is_empty());
+        assert!(presenter.key_press(Key::Char('d')).is_empty());
+
+        let effects = presenter.key_press(Key::Enter);
+        assert_eq!(presenter.showdown(), None);
+        assert_eq!(effects, vec![Effect::Repaint, Effect::ContinueAutomation]);
+        assert!(!presenter.continue_automation().is_empty());
+    }
+
+    #[test]
+    fn dealing_clears_the_showdown() {
+        let mut presenter = presenter_at_showdown(7);
+
+        presenter.handle_intent(Intent::Deal);
+
+        assert_eq!(presenter.showdown(), None);
+        assert_eq!(presenter.game().lives(), &[3; PLAYERS]);
+    }
+
+    #[test]
+    fn sticking_disables_the_stick_button() {
+        let mut presenter = presenter_on_human_turn();
+        assert!(presenter.view().stick_enabled);
+
+        presenter.handle_intent(Intent::Stick);
+
+        assert!(!presenter.view().stick_enabled);
+    }
+}
@@ -0,0 +1,341 @@
+//! Finding your way around a help file as the WinHelp window allowed: following jumps and
+//! pop-ups, the Contents, Back and Browse buttons, the keyword search and the context
+//! numbers a program opens topics with.
+//!
+//! A [`Browser`] holds no text layout; front ends render [`Browser::current`] themselves.
+
+use std::path::Path;
+
+use crate::{HelpError, HelpFile, Hotspot, HotspotKind, Picture, Topic};
+
+/// A help file and the reader's place in it.
+#[derive(Clone, Debug)]
+pub struct Browser {
+    help: HelpFile,
+    /// The hotspots of each picture, by picture name.
+    hotspots: Vec<(String, Vec<Hotspot>)>,
+    /// Topics visited, as indices into the help file's topics. Pop-ups are never added.
+    history: Vec<usize>,
+    position: usize,
+}
+
+/// A keyword from the search list and the topics it leads to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Keyword<'a> {
+    pub keyword: &'a str,
+    pub topics: Vec<&'a Topic>,
+}
+
+impl Browser {
+    /// Opens the help file at its contents topic.
+    pub fn new(help: HelpFile, pictures: Vec<(String, Picture)>) -> Result<Self, HelpError> {
+        help.check()?;
+        let contents = help.contents().map(|topic| help.index(topic));
+        let Some(contents) = contents else {
+            return Err(HelpError::UnknownTopic {
+                from: crate::PROJECT_FILE.to_string(),
+                target: "contents".to_string(),
+            });
+        };
+
+        Ok(Self {
+            help,
+            hotspots: pictures
+                .into_iter()
+                .map(|(name, picture)| (name, picture.hotspots))
+                .collect(),
+            history: vec![contents],
+            position: 0,
+        })
+    }
+
+    /// Reads the help file and its pictures from the repository at `root`.
+    pub fn load(root: &Path) -> Result<Self, HelpError> {
+        let help = HelpFile::load(root)?;
+        let pictures = help.pictures(root)?;
+        Self::new(help, pictures)
+    }
+
+    pub fn help(&self) -> &HelpFile {
+        &self.help
+    }
+
+    /// The topic in the main window.
+    pub fn current(&self) -> &Topic {
+        &self.help.topics[self.history[self.position]]
+    }
+
+    /// The jumps and pop-ups on a picture, in the order the picture lists them.
+    pub fn hotspots<'a>(&'a self, picture: &'a str) -> impl Iterator<Item = &'a Hotspot> {
+        self.hotspots
+            .iter()
+            .filter(move |(name, _)| name.eq_ignore_ascii_case(picture))
+            .flat_map(|(_, hotspots)| hotspots)
+            .filter(|hotspot| hotspot.kind != HotspotKind::Macro)
+    }
+
+    /// Follows a jump to the topic called `context`. A pop-up topic is returned without
+    /// leaving the current topic, as WinHelp showed it in a window of its own.
+    pub fn open(&mut self, context: &str) -> Option<&Topic> {
+        let index = self.help.index(self.help.topic(context)?);
+        if !self.help.topics[index].popup {
+            self.visit(index);
+        }
+        Some(&self.help.topics[index])
+    }
+
+    /// Opens the topic a program asks for by context number, such as
+    /// [`HC_OPTIONS`](crate::HC_OPTIONS) from the Options dialog's Help button.
+    pub fn open_context(&mut self, number: u32) -> Option<&Topic> {
+        let context = self.help.project.context(number)?.to_string();
+        self.open(&context)
+    }
+
+    /// The Contents button.
+    pub fn contents(&mut self) -> &Topic {
+        let contents = self
+            .help
+            .contents()
+            .expect("checked when the browser was made");
+        let index = self.help.index(contents);
+        self.visit(index);
+        self.current()
+    }
+
+    /// The Back button: the topic read before this one, if any.
+    pub fn back(&mut self) -> Option<&Topic> {
+        self.position = self.position.checked_sub(1)?;
+        Some(self.current())
+    }
+
+    /// Returns to the topic `back` left, if no other jump has been followed since.
+    pub fn forward(&mut self) -> Option<&Topic> {
+        if self.position + 1 >= self.history.len() {
+            return None;
+        }
+        self.position += 1;
+        Some(self.current())
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        self.position > 0
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        self.position + 1 < self.history.len()
+    }
+
+    /// The `<<` Browse button.
+    pub fn browse_previous(&mut self) -> Option<&Topic> {
+        let (previous, _) = self.help.browse(self.current());
+        let index = self.help.index(previous?);
+        self.visit(index);
+        Some(self.current())
+    }
+
+    /// The `>>` Browse button.
+    pub fn browse_next(&mut self) -> Option<&Topic> {
+        let (_, next) = self.help.browse(self.current());
+        let index = self.help.index(next?);
+        self.visit(index);
+        Some(self.current())
+    }
+
+    /// Keywords that start with `text`, or have a word that does, in alphabetical order
+    /// with the topics each leads to. Case is ignored, and empty text lists every keyword.
+    pub fn search(&self, text: &str) -> Vec<Keyword<'_>> {
+        let text = text.trim().to_lowercase();
+        let matches = |keyword: &str| {
+            let keyword = keyword.to_lowercase();
+            keyword.starts_with(&text)
+                || keyword
+                    .split(|c: char| !c.is_alphanumeric())
+                    .any(|word| word.starts_with(&text))
+        };
+
+        let mut found: Vec<Keyword> = Vec::new();
+        for topic in &self.help.topics {
+            for keyword in topic.keywords.iter().filter(|keyword| matches(keyword)) {
+                match found
+                    .iter_mut()
+                    .find(|found| found.keyword.eq_ignore_ascii_case(keyword))
+                {
+                    Some(found) => found.topics.push(topic),
+                    None => found.push(Keyword {
+                        keyword,
+                        topics: vec![topic],
+                    }),
+                }
+            }
+        }
+        found.sort_by_key(|found| found.keyword.to_lowercase());
+        found
+    }
+
+    /// Moves to `index`, dropping any topics `back` had stepped away from.
+    fn visit(&mut self, index: usize) {
+        if self.history[self.position] == index {
+            return;
+        }
+        self.history.truncate(self.position + 1);
+        self.history.push(index);
+        self.position += 1;
+    }
+}
+
+impl HelpFile {
+    /// The position of one of this file's topics.
+    fn index(&self, topic: &Topic) -> usize {
+        self.topics
+            .iter()
+            .position(|other| std::ptr::eq(other, topic))
+            .expect("topic belongs to this help file")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::{Block, Project, Span, HC_OPTIONS};
+
+    fn topic(context: &str, keywords: &[&str], browse: Option<&str>, popup: bool) -> Topic {
+        Topic {
+            context: context.to_string(),
+            title: context.to_string(),
+            keywords: keywords.iter().map(|keyword| keyword.to_string()).collect(),
+            browse: browse.map(str::to_string),
+            popup,
+            heading: Vec::new(),
+            blocks: vec![Block::Paragraph(vec![Span::Text {
+                text: context.to_string(),
+                style: Default::default(),
+            }])],
+        }
+    }
+
+    fn browser() -> Browser {
+        let help = HelpFile {
+            project: Project {
+                contents: Some("index".to_string()),
+                map: vec![("setup".to_string(), 7)],
+                ..Project::default()
+            },
+            topics: vec![
+                topic("first", &["Cards", "Dealing cards"], Some("game:1"), false),
+                topic("index", &[], None, false),
+                topic("second", &["Scores"], Some("game:2"), false),
+                topic("setup", &["Options", "cards"], None, false),
+                topic("note", &[], None, true),
+            ],
+        };
+        Browser::new(help, Vec::new()).unwrap()
+    }
+
+    fn current(browser: &Browser) -> &str {
+        &browser.current().context
+    }
+
+    #[test]
+    fn starts_at_the_contents_and_keeps_a_history() {
+        let mut browser = browser();
+        assert_eq!(current(&browser), "index");
+        assert!(!browser.can_go_back());
+        assert_eq!(browser.back(), None);
+
+        browser.open("FIRST").unwrap();
+        browser.open("second").unwrap();
+        browser.open("second").unwrap();
+        assert_eq!(browser.back().unwrap().context, "first");
+        assert_eq!(browser.back().unwrap().context, "index");
+        assert_eq!(browser.back(), None);
+        assert!(browser.can_go_forward());
+        assert_eq!(browser.forward().unwrap().context, "first");
+
+        // A new jump after going back forgets the topics ahead.
+        browser.open("setup").unwrap();
+        assert!(!browser.can_go_forward());
+        assert_eq!(browser.forward(), None);
+        assert_eq!(browser.back().unwrap().context, "first");
+
+        assert_eq!(browser.contents().context, "index");
+        assert_eq!(browser.back().unwrap().context, "first");
+        assert!(browser.open("missing").is_none());
+        assert_eq!(current(&browser), "first");
+    }
+
+    #[test]
+    fn pop_ups_do_not_leave_the_current_topic() {
+        let mut browser = browser();
+        browser.open("first").unwrap();
+
+        assert_eq!(browser.open("note").unwrap().context, "note");
+        assert_eq!(current(&browser), "first");
+        assert_eq!(browser.back().unwrap().context, "index");
+    }
+
+    #[test]
+    fn opens_topics_by_context_number_and_browse_sequence() {
+        let mut browser = browser();
+        assert_eq!(browser.open_context(7).unwrap().context, "setup");
+        assert!(browser.open_context(8).is_none());
+        assert_eq!(current(&browser), "setup");
+
+        assert_eq!(browser.browse_next(), None);
+        browser.open("first").unwrap();
+        assert_eq!(browser.browse_previous(), None);
+        assert_eq!(browser.browse_next().unwrap().context, "second");
+        assert_eq!(browser.browse_next(), None);
+        assert_eq!(browser.back().unwrap().context, "first");
+    }
+
+    #[test]
+    fn searches_keywords_by_their_words() {
+        let browser = browser();
+        let found = |text: &str| -> Vec<(String, Vec<String>)> {
+            browser
+                .search(text)
+                .into_iter()
+                .map(|found| {
+                    let topics = found.topics.iter().map(|topic| topic.context.clone());
+                    (found.keyword.to_string(), topics.collect())
+                })
+                .collect()
+        };
+
+        assert_eq!(
+            found("CARD"),
+            [
+                (
+                    "Cards".to_string(),
+                    vec!["first".to_string(), "setup".to_string()]
+                ),
+                ("Dealing cards".to_string(), vec!["first".to_string()]),
+            ]
+        );
+        assert_eq!(found("sc").len(), 1);
+        assert_eq!(found("ards"), []);
+        assert_eq!(found(" ").len(), 4);
+    }
+
+    #[test]
+    fn the_options_dialog_opens_the_options_topic() {
+        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../..");
+        let mut browser = Browser::load(&root).unwrap();
+        assert_eq!(current(&browser), "main_contents");
+
+        let options = browser.open_context(HC_OPTIONS).unwrap();
+        assert_eq!(options.context, "options");
+        assert_eq!(
+            browser.hotspots("OPTNWIN.SHG").count(),
+            5,
+            "cheat options, save on exit and the three buttons"
+        );
+        assert_eq!(browser.browse_previous().unwrap().context, "play");
+
+        let found = browser.search("rules");
+        assert_eq!(found[0].keyword, "Rules of the game");
+        assert_eq!(found[0].topics[0].context, "rules");
+    }
+}
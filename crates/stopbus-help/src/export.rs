@@ -0,0 +1,527 @@
+//! The help file as linked Markdown and HTML pages, one per topic, with the pictures as
+//! PNG files.
+//!
+//! Pages are named after their context strings in lower case, so a front end can open
+//! `html/options.html` for [`HC_OPTIONS`](crate::HC_OPTIONS) without reading the help file.
+//! `map.txt` lists every context number with the page it opens. Hotspots become image
+//! maps in HTML and a list of links under the picture in Markdown.
+
+use std::path::PathBuf;
+
+use stopbus_image::png;
+
+use crate::{Block, HelpFile, HotspotKind, Picture, Span, Style, Topic};
+
+pub const MARKDOWN_DIR: &str = "md";
+pub const HTML_DIR: &str = "html";
+pub const IMAGES_DIR: &str = "images";
+pub const MAP_FILE: &str = "map.txt";
+
+const GENERATED: &str = "Generated by stopbus-assets from HELP/STOPBUS.RTF; do not edit.";
+
+/// Every exported file, relative to the output directory, in a fixed order.
+pub fn files(help: &HelpFile, pictures: &[(String, Picture)]) -> Vec<(PathBuf, Vec<u8>)> {
+    let mut files = Vec::new();
+
+    for topic in &help.topics {
+        let name = page_name(&topic.context);
+        files.push((
+            PathBuf::from(MARKDOWN_DIR).join(format!("{}.md", name)),
+            Page::new(help, pictures, Format::Markdown)
+                .render(topic)
+                .into_bytes(),
+        ));
+        files.push((
+            PathBuf::from(HTML_DIR).join(format!("{}.html", name)),
+            Page::new(help, pictures, Format::Html)
+                .render(topic)
+                .into_bytes(),
+        ));
+    }
+    for (name, picture) in pictures {
+        files.push((
+            PathBuf::from(IMAGES_DIR).join(image_name(name)),
+            png::encode(&picture.image),
+        ));
+    }
+
+    let mut map = format!(
+        "# {}\n# Context number, topic, Markdown page, HTML page.\n",
+        GENERATED
+    );
+    for (context, number) in &help.project.map {
+        let name = help
+            .topic(context)
+            .map_or_else(|| page_name(context), |topic| page_name(&topic.context));
+        map.push_str(&format!(
+            "{} {} {}/{}.md {}/{}.html\n",
+            number, context, MARKDOWN_DIR, name, HTML_DIR, name
+        ));
+    }
+    files.push((PathBuf::from(MAP_FILE), map.into_bytes()));
+
+    files
+}
+
+/// The file name, without extension, of a topic's pages.
+pub fn page_name(context: &str) -> String {
+    context.to_ascii_lowercase()
+}
+
+/// `mainwin.shg` becomes `mainwin.png`.
+pub fn image_name(picture: &str) -> String {
+    let stem = picture.rsplit_once('.').map_or(picture, |(stem, _)| stem);
+    format!("{}.png", stem.to_ascii_lowercase())
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Markdown,
+    Html,
+}
+
+struct Page<'a> {
+    help: &'a HelpFile,
+    pictures: &'a [(String, Picture)],
+    format: Format,
+}
+
+impl<'a> Page<'a> {
+    fn new(help: &'a HelpFile, pictures: &'a [(String, Picture)], format: Format) -> Self {
+        Self {
+            help,
+            pictures,
+            format,
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self.format {
+            Format::Markdown => "md",
+            Format::Html => "html",
+        }
+    }
+
+    fn link(&self, target: &str) -> String {
+        let name = self
+            .help
+            .topic(target)
+            .map_or_else(|| page_name(target), |topic| page_name(&topic.context));
+        format!("{}.{}", name, self.extension())
+    }
+
+    fn render(&self, topic: &Topic) -> String {
+        let mut body = String::new();
+        for block in &topic.blocks {
+            body.push_str(&self.block(block));
+        }
+        let heading = if topic.heading.is_empty() {
+            escape(&topic.title, self.format)
+        } else {
+            self.spans(&topic.heading, true)
+        };
+        let footer = self.footer(topic);
+
+        match self.format {
+            Format::Markdown => {
+                let mut page = format!("<!-- {} -->\n\n# {}\n\n{}", GENERATED, heading, body);
+                if !footer.is_empty() {
+                    page.push_str(&format!("---\n\n{}\n", footer.join(" | ")));
+                }
+                page
+            }
+            Format::Html => {
+                let mut head = format!(
+                    "<!DOCTYPE html>\n<!-- {} -->\n<html lang=\"en\">\n<head>\n\
+                     <meta charset=\"utf-8\">\n<title>{}</title>\n",
+                    GENERATED,
+                    escape(&self.window_title(topic), Format::Html)
+                );
+                if !topic.keywords.is_empty() {
+                    head.push_str(&format!(
+                        "<meta name=\"keywords\" content=\"{}\">\n",
+                        escape(&topic.keywords.join(", "), Format::Html)
+                    ));
+                }
+                let mut page = format!("{}</head>\n<body>\n<h1>{}</h1>\n{}", head, heading, body);
+                if !footer.is_empty() {
+                    page.push_str(&format!("<nav>{}</nav>\n", footer.join(" | ")));
+                }
+                page.push_str("</body>\n</html>\n");
+                page
+            }
+        }
+    }
+
+    fn window_title(&self, topic: &Topic) -> String {
+        match &self.help.project.title {
+            Some(title) => format!("{} - {}", topic.title, title),
+            None => topic.title.clone(),
+        }
+    }
+
+    /// Links to the contents and to the neighbours in the browse sequence, as WinHelp's
+    /// Contents and Browse buttons offered. Pop-ups have none.
+    fn footer(&self, topic: &Topic) -> Vec<String> {
+        if topic.popup {
+            return Vec::new();
+        }
+
+        let mut links = Vec::new();
+        if let Some(contents) = self.help.contents() {
+            if contents.context != topic.context {
+                links.push(self.anchor("Contents", &contents.context, false));
+            }
+        }
+        let (previous, next) = self.help.browse(topic);
+        if let Some(previous) = previous {
+            links.push(self.anchor(&format!("« {}", previous.title), &previous.context, false));
+        }
+        if let Some(next) = next {
+            links.push(self.anchor(&format!("{} »", next.title), &next.context, false));
+        }
+        links
+    }
+
+    fn anchor(&self, text: &str, target: &str, popup: bool) -> String {
+        let text = escape(text, self.format);
+        let href = self.link(target);
+        match (self.format, popup) {
+            (Format::Markdown, _) => format!("[{}]({})", text, href),
+            (Format::Html, false) => format!("<a href=\"{}\">{}</a>", href, text),
+            (Format::Html, true) => {
+                format!("<a class=\"popup\" href=\"{}\">{}</a>", href, text)
+            }
+        }
+    }
+
+    fn block(&self, block: &Block) -> String {
+        let md = self.format == Format::Markdown;
+        match block {
+            Block::Heading(spans) if md => format!("## {}\n\n", self.spans(spans, true)),
+            Block::Heading(spans) => format!("<h2>{}</h2>\n", self.spans(spans, true)),
+            Block::Paragraph(spans) if md => format!("{}\n\n", self.spans(spans, false)),
+            Block::Paragraph(spans) => format!("<p>{}</p>\n", self.spans(spans, false)),
+            Block::Lines(lines) => {
+                let lines: Vec<String> = lines.iter().map(|line| self.spans(line, false)).collect();
+                if md {
+                    // A backslash before the newline breaks the line.
+                    format!("> {}\n\n", lines.join("\\\n> "))
+                } else {
+                    format!("<blockquote>{}</blockquote>\n", lines.join("<br>\n"))
+                }
+            }
+            Block::Bullets(items) => {
+                let items = items.iter().map(|item| self.spans(item, false));
+                if md {
+                    let items: Vec<String> = items.map(|item| format!("- {}\n", item)).collect();
+                    format!("{}\n", items.concat())
+                } else {
+                    let items: Vec<String> =
+                        items.map(|item| format!("<li>{}</li>\n", item)).collect();
+                    format!("<ul>\n{}</ul>\n", items.concat())
+                }
+            }
+            Block::Terms(terms) => {
+                if md {
+                    let items: Vec<String> = terms
+                        .iter()
+                        .map(|(term, body)| {
+                            format!(
+                                "- {} {}\n",
+                                self.spans(term, false),
+                                self.spans(body, false)
+                            )
+                        })
+                        .collect();
+                    format!("{}\n", items.concat())
+                } else {
+                    let items: Vec<String> = terms
+                        .iter()
+                        .map(|(term, body)| {
+                            format!(
+                                "<dt>{}</dt>\n<dd>{}</dd>\n",
+                                self.spans(term, false),
+                                self.spans(body, false)
+                            )
+                        })
+                        .collect();
+                    format!("<dl>\n{}</dl>\n", items.concat())
+                }
+            }
+        }
+    }
+
+    /// Inline text; headings drop bold and italic, which their level already says.
+    fn spans(&self, spans: &[Span], heading: bool) -> String {
+        let mut out = String::new();
+        for span in spans {
+            match span {
+                Span::Text { text, style } => {
+                    let style = if heading { Style::default() } else { *style };
+                    out.push_str(&self.styled(text, style));
+                }
+                Span::Link {
+                    text,
+                    target,
+                    popup,
+                } => out.push_str(&self.anchor(text, target, *popup)),
+                Span::Picture(name) => out.push_str(&self.picture(name, heading)),
+            }
+        }
+        out
+    }
+
+    /// Text with its style, keeping spaces at either end outside the markup.
+    fn styled(&self, text: &str, style: Style) -> String {
+        let inner = text.trim();
+        if inner.is_empty() || style == Style::default() {
+            return escape(text, self.format);
+        }
+        let leading = &text[..text.len() - text.trim_start().len()];
+        let trailing = &text[text.trim_end().len()..];
+
+        let mut marked = if style.code {
+            match self.format {
+                Format::Markdown => format!("`{}`", inner),
+                Format::Html => format!("<code>{}</code>", escape(inner, Format::Html)),
+            }
+        } else {
+            escape(inner, self.format)
+        };
+        let wrap = |marked: String, markdown: &str, tag: &str| match self.format {
+            Format::Markdown => format!("{0}{1}{0}", markdown, marked),
+            Format::Html => format!("<{0}>{1}</{0}>", tag, marked),
+        };
+        if style.italic {
+            marked = wrap(marked, "*", "i");
+        }
+        if style.bold {
+            marked = wrap(marked, "**", "b");
+        }
+        format!("{}{}{}", leading, marked, trailing)
+    }
+
+    /// A picture, with its hotspots as an image map or a list of links. Pictures in a
+    /// heading are icons and get no list.
+    fn picture(&self, name: &str, inline: bool) -> String {
+        let src = format!("../{}/{}", IMAGES_DIR, image_name(name));
+        let Some((_, picture)) = self.pictures.iter().find(|(known, _)| known == name) else {
+            return match self.format {
+                Format::Markdown => format!("![]({})", src),
+                Format::Html => format!("<img src=\"{}\" alt=\"\">", src),
+            };
+        };
+        let (width, height) = (picture.image.width, picture.image.height);
+        let targets: Vec<(&crate::Hotspot, &Topic)> = picture
+            .hotspots
+            .iter()
+            .filter(|hotspot| hotspot.kind != HotspotKind::Macro)
+            .filter_map(|hotspot| Some((hotspot, self.help.topic(&hotspot.target)?)))
+            .collect();
+
+        match self.format {
+            Format::Markdown => {
+                let mut out = format!("![]({})", src);
+                if !inline && !targets.is_empty() {
+                    out.push_str("\n\n");
+                    for (hotspot, topic) in &targets {
+                        out.push_str(&format!(
+                            "- {}\n",
+                            self.anchor(
+                                &topic.title,
+                                &topic.context,
+                                hotspot.kind == HotspotKind::Popup
+                            )
+                        ));
+                    }
+                    out.truncate(out.len() - 1);
+                }
+                out
+            }
+            Format::Html if targets.is_empty() => format!(
+                "<img src=\"{}\" width=\"{}\" height=\"{}\" alt=\"\">",
+                src, width, height
+            ),
+            Format::Html => {
+                let map = image_name(name).trim_end_matches(".png").to_string();
+                let mut out = format!(
+                    "<img src=\"{}\" width=\"{}\" height=\"{}\" alt=\"\" usemap=\"#{}\">\n\
+                     <map name=\"{}\">\n",
+                    src, width, height, map, map
+                );
+                for (hotspot, topic) in &targets {
+                    out.push_str(&format!(
+                        "<area shape=\"rect\" coords=\"{},{},{},{}\" href=\"{}\" alt=\"{}\">\n",
+                        hotspot.x,
+                        hotspot.y,
+                        u32::from(hotspot.x) + u32::from(hotspot.width),
+                        u32::from(hotspot.y) + u32::from(hotspot.height),
+                        self.link(&topic.context),
+                        escape(&topic.title, Format::Html)
+                    ));
+                }
+                out.push_str("</map>");
+                out
+            }
+        }
+    }
+}
+
+fn escape(text: &str, format: Format) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match (format, c) {
+            (Format::Markdown, '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>' | '#') => {
+                out.push('\\');
+                out.push(c);
+            }
+            (Format::Html, '&') => out.push_str("&amp;"),
+            (Format::Html, '<') => out.push_str("&lt;"),
+            (Format::Html, '>') => out.push_str("&gt;"),
+            (Format::Html, '"') => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Project;
+
+    fn text(text: &str, style: Style) -> Span {
+        Span::Text {
+            text: text.to_string(),
+            style,
+        }
+    }
+
+    fn sample() -> HelpFile {
+        let bold = Style {
+            bold: true,
+            ..Style::default()
+        };
+        let code = Style {
+            code: true,
+            ..Style::default()
+        };
+        let topic = |context: &str, title: &str, browse: Option<&str>, blocks| Topic {
+            context: context.to_string(),
+            title: title.to_string(),
+            keywords: vec![title.to_string()],
+            browse: browse.map(str::to_string),
+            popup: false,
+            heading: Vec::new(),
+            blocks,
+        };
+
+        HelpFile {
+            project: Project {
+                title: Some("Help".to_string()),
+                contents: Some("main".to_string()),
+                map: vec![("Rules".to_string(), 1)],
+                ..Project::default()
+            },
+            topics: vec![
+                topic(
+                    "main",
+                    "Contents",
+                    None,
+                    vec![Block::Bullets(vec![vec![Span::Link {
+                        text: "Rules".to_string(),
+                        target: "RULES".to_string(),
+                        popup: false,
+                    }]])],
+                ),
+                topic(
+                    "rules",
+                    "Rules & scoring",
+                    Some("game:01"),
+                    vec![
+                        Block::Heading(vec![text("Sticking", bold)]),
+                        Block::Paragraph(vec![
+                            text("Press", Style::default()),
+                            text(" GAME|DEAL", code),
+                            text(" to", Style::default()),
+                            text(" stick ", bold),
+                            text("at 2*3.", Style::default()),
+                        ]),
+                        Block::Lines(vec![
+                            vec![text("Hertford", Style::default())],
+                            vec![text("Oxford", Style::default())],
+                        ]),
+                    ],
+                ),
+                topic("scores", "Scoring", Some("game:02"), Vec::new()),
+            ],
+        }
+    }
+
+    #[test]
+    fn renders_linked_markdown() {
+        let help = sample();
+        let page = Page::new(&help, &[], Format::Markdown);
+
+        assert_eq!(
+            page.render(&help.topics[0]),
+            format!(
+                "<!-- {} -->\n\n# Contents\n\n- [Rules](rules.md)\n\n",
+                GENERATED
+            )
+        );
+        assert_eq!(
+            page.render(&help.topics[1]),
+            format!(
+                "<!-- {} -->\n\n# Rules & scoring\n\n## Sticking\n\n\
+                 Press `GAME|DEAL` to **stick** at 2\\*3.\n\n> Hertford\\\n> Oxford\n\n\
+                 ---\n\n[Contents](main.md) | [Scoring »](scores.md)\n",
+                GENERATED
+            )
+        );
+    }
+
+    #[test]
+    fn renders_linked_html() {
+        let help = sample();
+        let page = Page::new(&help, &[], Format::Html).render(&help.topics[1]);
+
+        assert!(page.contains("<title>Rules &amp; scoring - Help</title>\n"));
+        assert!(page.contains("<meta name=\"keywords\" content=\"Rules &amp; scoring\">\n"));
+        assert!(page.contains(
+            "<p>Press <code>GAME|DEAL</code> to <b>stick</b> at 2*3.</p>\n\
+             <blockquote>Hertford<br>\nOxford</blockquote>\n"
+        ));
+        assert!(page.ends_with(
+            "<nav><a href=\"main.html\">Contents</a> | \
+             <a href=\"scores.html\">Scoring »</a></nav>\n</body>\n</html>\n"
+        ));
+    }
+
+    #[test]
+    fn lists_pages_and_the_context_map() {
+        let files = files(&sample(), &[]);
+        let names: Vec<String> = files
+            .iter()
+            .map(|(path, _)| path.to_string_lossy().replace('\\', "/"))
+            .collect();
+
+        assert_eq!(
+            names,
+            [
+                "md/main.md",
+                "html/main.html",
+                "md/rules.md",
+                "html/rules.html",
+                "md/scores.md",
+                "html/scores.html",
+                "map.txt",
+            ]
+        );
+        let map = String::from_utf8(files[6].1.clone()).unwrap();
+        assert!(map.ends_with("\n1 Rules md/rules.md html/rules.html\n"));
+        assert_eq!(image_name("MAINWIN.SHG"), "mainwin.png");
+    }
+}
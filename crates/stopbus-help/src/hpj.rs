@@ -0,0 +1,122 @@
+//! The help project file, `STOPBUS.HPJ`, as the Windows 3.1 help compiler read it.
+//!
+//! Only the sections that shape the help file are kept: `[OPTIONS]` for its title,
+//! contents topic and copyright, `[FILES]` for the topic sources and `[MAP]` for the
+//! context numbers programs pass to WinHelp. Window and macro settings are ignored.
+
+/// What the project file says about the help file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Project {
+    pub title: Option<String>,
+    /// The context string of the contents topic.
+    pub contents: Option<String>,
+    pub copyright: Option<String>,
+    /// Topic files, relative to the project file.
+    pub files: Vec<String>,
+    /// Context strings and the numbers programs open them by, in file order.
+    pub map: Vec<(String, u32)>,
+}
+
+impl Project {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut project = Project::default();
+        let mut section = String::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split(';').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(name) = line
+                .strip_prefix('[')
+                .and_then(|rest| rest.strip_suffix(']'))
+            {
+                section = name.trim().to_ascii_uppercase();
+                continue;
+            }
+            let error = |reason: &str| format!("line {}: {}", index + 1, reason);
+
+            match section.as_str() {
+                "OPTIONS" => {
+                    let (key, value) = line
+                        .split_once('=')
+                        .ok_or_else(|| error("expected 'option = value'"))?;
+                    let value = Some(value.trim().to_string());
+                    match key.trim().to_ascii_lowercase().as_str() {
+                        "title" => project.title = value,
+                        "contents" => project.contents = value,
+                        "copyright" => project.copyright = value,
+                        _ => {}
+                    }
+                }
+                "FILES" => project.files.push(line.to_string()),
+                "MAP" => project.map.push(map_entry(line).ok_or_else(|| {
+                    error("expected 'context number' or '#define context number'")
+                })?),
+                "" => return Err(error("text before the first section")),
+                _ => {}
+            }
+        }
+
+        Ok(project)
+    }
+
+    /// The context string mapped to `number`.
+    pub fn context(&self, number: u32) -> Option<&str> {
+        self.map
+            .iter()
+            .find(|(_, mapped)| *mapped == number)
+            .map(|(context, _)| context.as_str())
+    }
+}
+
+/// `context number`, `context=number` or `#define context number`, with decimal or
+/// `0x` hexadecimal numbers.
+fn map_entry(line: &str) -> Option<(String, u32)> {
+    let line = line.strip_prefix("#define").unwrap_or(line);
+    let mut words = line.split(|c: char| c.is_whitespace() || c == '=');
+    let mut next = || words.find(|word| !word.is_empty());
+    let context = next()?;
+    let number = next()?;
+    let number = match number
+        .strip_prefix("0x")
+        .or_else(|| number.strip_prefix("0X"))
+    {
+        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+        None => number.parse().ok()?,
+    };
+
+    Some((context.to_string(), number))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_options_files_and_map() {
+        let project = Project::parse(
+            "; comment\n[OPTIONS]\ntitle = Stop the Bus Help\ncontents = main_contents\n\
+             compress = 1\n\n[FILES]\nstopbus.rtf\n\n[MAP]\noptions 1\n#define rules 0x10\n\
+             scores=3 ; trailing comment\n\n[WINDOWS]\nmain = \"Help\",,,, (192,192,192 )\n",
+        )
+        .unwrap();
+
+        assert_eq!(project.title.as_deref(), Some("Stop the Bus Help"));
+        assert_eq!(project.contents.as_deref(), Some("main_contents"));
+        assert_eq!(project.files, ["stopbus.rtf"]);
+        assert_eq!(project.context(1), Some("options"));
+        assert_eq!(project.context(16), Some("rules"));
+        assert_eq!(project.context(3), Some("scores"));
+        assert_eq!(project.context(2), None);
+
+        assert_eq!(
+            Project::parse("[MAP]\noptions one\n").unwrap_err(),
+            "line 2: expected 'context number' or '#define context number'"
+        );
+        assert_eq!(
+            Project::parse("title = x\n").unwrap_err(),
+            "line 1: text before the first section"
+        );
+    }
+}
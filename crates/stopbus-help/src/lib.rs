@@ -0,0 +1,442 @@
+//! The 1994 WinHelp file, read from the sources it was compiled from: the project file
+//! `HELP/STOPBUS.HPJ`, the topics in `HELP/STOPBUS.RTF` and the pictures in `help_bmp`.
+//!
+//! Topics keep their WinHelp context strings, so the numbers in the project's `[MAP]`
+//! still open the topic the 1994 program asked for. [`Browser`] moves around the file as
+//! the WinHelp window did. [`export`] turns the file into linked Markdown and HTML pages;
+//! `stopbus-assets` writes them under `docs/help`.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub mod browser;
+pub mod export;
+pub mod hpj;
+pub mod rtf;
+pub mod shg;
+
+pub use browser::{Browser, Keyword};
+pub use hpj::Project;
+pub use shg::{Hotspot, HotspotKind, Picture};
+
+/// Inputs, relative to the repository root.
+pub const HELP_DIR: &str = "HELP";
+pub const PROJECT_FILE: &str = "STOPBUS.HPJ";
+pub const PICTURE_DIR: &str = "help_bmp";
+
+/// The context number the Options dialog's Help button opened.
+pub const HC_OPTIONS: u32 = 1;
+
+/// A help file: its project settings and topics.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HelpFile {
+    pub project: Project,
+    pub topics: Vec<Topic>,
+}
+
+/// One page of help.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Topic {
+    /// The `#` footnote that jumps and context numbers name the topic by.
+    pub context: String,
+    /// The `$` footnote, or for a pop-up the text of its first line.
+    pub title: String,
+    /// The `K` footnote, for the keyword search.
+    pub keywords: Vec<String>,
+    /// The `+` footnote, such as `Thegame:02`, ordering the Browse buttons.
+    pub browse: Option<String>,
+    /// Untitled topics only ever appear in pop-up windows.
+    pub popup: bool,
+    /// The line at the top of the topic, which WinHelp kept from scrolling.
+    pub heading: Vec<Span>,
+    pub blocks: Vec<Block>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Block {
+    Heading(Vec<Span>),
+    Paragraph(Vec<Span>),
+    /// Short indented lines, such as a postal address.
+    Lines(Vec<Vec<Span>>),
+    Bullets(Vec<Vec<Span>>),
+    /// A term in bold and what it means, set with a hanging indent.
+    Terms(Vec<(Vec<Span>, Vec<Span>)>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Span {
+    Text {
+        text: String,
+        style: Style,
+    },
+    /// A jump to another topic, or a pop-up showing it.
+    Link {
+        text: String,
+        target: String,
+        popup: bool,
+    },
+    /// A picture file from [`PICTURE_DIR`], named in lower case.
+    Picture(String),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Style {
+    pub bold: bool,
+    pub italic: bool,
+    /// Small print, which the source uses for menu commands and file names.
+    pub code: bool,
+}
+
+impl HelpFile {
+    /// Reads the project and its topic files from the repository at `root`.
+    pub fn load(root: &Path) -> Result<Self, HelpError> {
+        let help_dir = root.join(HELP_DIR);
+        let project = Project::parse(&read_text(&help_dir.join(PROJECT_FILE))?)
+            .map_err(|reason| HelpError::Project { reason })?;
+
+        let mut topics = Vec::new();
+        for file in &project.files {
+            let text = read_text(&find_file(&help_dir, file)?)?;
+            topics.extend(rtf::parse(&text).map_err(|reason| HelpError::Topics {
+                file: file.clone(),
+                reason,
+            })?);
+        }
+
+        let help = HelpFile { project, topics };
+        help.check()?;
+        Ok(help)
+    }
+
+    /// The topic with this context string; like WinHelp, case is ignored.
+    pub fn topic(&self, context: &str) -> Option<&Topic> {
+        self.topics
+            .iter()
+            .find(|topic| topic.context.eq_ignore_ascii_case(context))
+    }
+
+    /// The project's contents topic, or the first topic if it names none.
+    pub fn contents(&self) -> Option<&Topic> {
+        match &self.project.contents {
+            Some(context) => self.topic(context),
+            None => self.topics.first(),
+        }
+    }
+
+    /// The topic a program opens with context number `number`.
+    pub fn mapped(&self, number: u32) -> Option<&Topic> {
+        self.topic(self.project.context(number)?)
+    }
+
+    /// The topics before and after `topic` in its browse sequence.
+    pub fn browse(&self, topic: &Topic) -> (Option<&Topic>, Option<&Topic>) {
+        let Some(sequence) = &topic.browse else {
+            return (None, None);
+        };
+        let group = |browse: &str| browse.split(':').next().unwrap_or("").to_string();
+
+        let mut members: Vec<&Topic> = self
+            .topics
+            .iter()
+            .filter(|other| {
+                other
+                    .browse
+                    .as_deref()
+                    .is_some_and(|browse| group(browse) == group(sequence))
+            })
+            .collect();
+        members.sort_by_key(|other| other.browse.as_deref());
+        let index = members
+            .iter()
+            .position(|other| other.context == topic.context)
+            .expect("topic is in its own sequence");
+
+        (
+            index.checked_sub(1).map(|before| members[before]),
+            members.get(index + 1).copied(),
+        )
+    }
+
+    /// The pictures the topics show, in the order they first appear, read from
+    /// [`PICTURE_DIR`].
+    pub fn pictures(&self, root: &Path) -> Result<Vec<(String, Picture)>, HelpError> {
+        let dir = root.join(PICTURE_DIR);
+        let mut names: Vec<&str> = Vec::new();
+        for topic in &self.topics {
+            for span in topic.spans() {
+                if let Span::Picture(name) = span {
+                    if !names.contains(&name.as_str()) {
+                        names.push(name);
+                    }
+                }
+            }
+        }
+
+        let mut pictures = Vec::new();
+        for name in names {
+            let path = find_file(&dir, name)?;
+            let data = fs::read(&path).map_err(|err| HelpError::Io { path, err })?;
+            let picture = Picture::read(name, &data).map_err(|reason| HelpError::Picture {
+                name: name.to_string(),
+                reason,
+            })?;
+            for hotspot in &picture.hotspots {
+                if hotspot.kind != HotspotKind::Macro && self.topic(&hotspot.target).is_none() {
+                    return Err(HelpError::UnknownTopic {
+                        from: name.to_string(),
+                        target: hotspot.target.clone(),
+                    });
+                }
+            }
+            pictures.push((name.to_string(), picture));
+        }
+        Ok(pictures)
+    }
+
+    /// Every context string is used once and every jump, pop-up and mapped number leads
+    /// somewhere.
+    fn check(&self) -> Result<(), HelpError> {
+        for (index, topic) in self.topics.iter().enumerate() {
+            if self.topics[..index]
+                .iter()
+                .any(|other| other.context.eq_ignore_ascii_case(&topic.context))
+            {
+                return Err(HelpError::DuplicateTopic(topic.context.clone()));
+            }
+            for span in topic.spans() {
+                if let Span::Link { target, .. } = span {
+                    if self.topic(target).is_none() {
+                        return Err(HelpError::UnknownTopic {
+                            from: topic.context.clone(),
+                            target: target.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        let named = self.project.contents.iter();
+        let mapped = self.project.map.iter().map(|(context, _)| context);
+        for context in named.chain(mapped) {
+            if self.topic(context).is_none() {
+                return Err(HelpError::UnknownTopic {
+                    from: PROJECT_FILE.to_string(),
+                    target: context.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Topic {
+    /// Every span in the topic, heading first.
+    pub fn spans(&self) -> impl Iterator<Item = &Span> {
+        let blocks = self
+            .blocks
+            .iter()
+            .flat_map(|block| -> Box<dyn Iterator<Item = &Span> + '_> {
+                match block {
+                    Block::Heading(spans) | Block::Paragraph(spans) => Box::new(spans.iter()),
+                    Block::Lines(lines) | Block::Bullets(lines) => Box::new(lines.iter().flatten()),
+                    Block::Terms(terms) => Box::new(
+                        terms
+                            .iter()
+                            .flat_map(|(term, body)| term.iter().chain(body.iter())),
+                    ),
+                }
+            });
+        self.heading.iter().chain(blocks)
+    }
+}
+
+/// Reads a file as the 8-bit text the help compiler took, one character per byte.
+fn read_text(path: &Path) -> Result<String, HelpError> {
+    let data = fs::read(path).map_err(|err| HelpError::Io {
+        path: path.to_path_buf(),
+        err,
+    })?;
+    Ok(data.iter().map(|&byte| char::from(byte)).collect())
+}
+
+/// A file in `dir`, matched without regard to case as DOS names were.
+fn find_file(dir: &Path, name: &str) -> Result<PathBuf, HelpError> {
+    let io_error = |err| HelpError::Io {
+        path: dir.to_path_buf(),
+        err,
+    };
+    for entry in fs::read_dir(dir).map_err(io_error)? {
+        let entry = entry.map_err(io_error)?;
+        if entry
+            .file_name()
+            .to_string_lossy()
+            .eq_ignore_ascii_case(name)
+        {
+            return Ok(entry.path());
+        }
+    }
+    Err(HelpError::Io {
+        path: dir.join(name),
+        err: io::ErrorKind::NotFound.into(),
+    })
+}
+
+#[derive(Debug)]
+pub enum HelpError {
+    Io {
+        path: PathBuf,
+        err: io::Error,
+    },
+    Project {
+        reason: String,
+    },
+    Topics {
+        file: String,
+        reason: String,
+    },
+    Picture {
+        name: String,
+        reason: String,
+    },
+    DuplicateTopic(String),
+    /// A jump, hotspot or project setting names a topic that does not exist.
+    UnknownTopic {
+        from: String,
+        target: String,
+    },
+}
+
+impl fmt::Display for HelpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HelpError::Io { path, err } => write!(f, "{}: {}", path.display(), err),
+            HelpError::Project { reason } => write!(f, "{}: {}", PROJECT_FILE, reason),
+            HelpError::Topics { file, reason } => write!(f, "{}: {}", file, reason),
+            HelpError::Picture { name, reason } => write!(f, "{}: {}", name, reason),
+            HelpError::DuplicateTopic(context) => {
+                write!(f, "more than one topic is called '{}'", context)
+            }
+            HelpError::UnknownTopic { from, target } => {
+                write!(f, "{} refers to a missing topic '{}'", from, target)
+            }
+        }
+    }
+}
+
+impl std::error::Error for HelpError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HelpError::Io { err, .. } => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo_root() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../..")
+    }
+
+    #[test]
+    fn reads_the_1994_help_file() {
+        let help = HelpFile::load(&repo_root()).unwrap();
+
+        assert_eq!(help.project.title.as_deref(), Some("Stop the Bus Help"));
+        assert_eq!(help.topics.len(), 22);
+        assert_eq!(help.contents().unwrap().title, "Contents");
+        assert_eq!(
+            help.mapped(HC_OPTIONS).unwrap().title,
+            "Setting game options"
+        );
+        assert_eq!(help.topic("CANCELBUT").unwrap().title, "Cancel button");
+
+        let rules = help.topic("rules").unwrap();
+        assert_eq!(rules.keywords, ["Rules of the game"]);
+        let (previous, next) = help.browse(rules);
+        assert_eq!(previous.map(|topic| topic.context.as_str()), None);
+        assert_eq!(next.map(|topic| topic.context.as_str()), Some("scores"));
+
+        let popups: Vec<&str> = help
+            .topics
+            .iter()
+            .filter(|topic| topic.popup)
+            .map(|topic| topic.title.as_str())
+            .collect();
+        assert_eq!(
+            popups,
+            [
+                "Title",
+                "Menu bar",
+                "Main deck of cards",
+                "Your hand of cards",
+                "Player information",
+                "Game control buttons",
+                "Cheat options",
+                "Save on exit",
+                "OK button",
+                "Cancel button",
+                "Help button",
+            ]
+        );
+
+        let pictures = help.pictures(&repo_root()).unwrap();
+        let names: Vec<&str> = pictures.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "mainicon.bmp",
+                "mainwin.shg",
+                "optnwin.shg",
+                "cardwind.bmp",
+                "stacwind.bmp",
+                "scorwind.bmp",
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_links_and_context_strings() {
+        let topic = |context: &str, target: &str| Topic {
+            context: context.to_string(),
+            title: context.to_string(),
+            keywords: Vec::new(),
+            browse: None,
+            popup: false,
+            heading: Vec::new(),
+            blocks: vec![Block::Paragraph(vec![Span::Link {
+                text: "see".to_string(),
+                target: target.to_string(),
+                popup: false,
+            }])],
+        };
+        let mut help = HelpFile {
+            project: Project::default(),
+            topics: vec![topic("a", "B"), topic("b", "a")],
+        };
+        assert!(help.check().is_ok());
+
+        help.topics.push(topic("A", "b"));
+        assert_eq!(
+            help.check().unwrap_err().to_string(),
+            "more than one topic is called 'A'"
+        );
+
+        help.topics[2] = topic("c", "d");
+        assert_eq!(
+            help.check().unwrap_err().to_string(),
+            "c refers to a missing topic 'd'"
+        );
+
+        help.topics.pop();
+        help.project.map.push(("options".to_string(), HC_OPTIONS));
+        assert_eq!(
+            help.check().unwrap_err().to_string(),
+            "STOPBUS.HPJ refers to a missing topic 'options'"
+        );
+    }
+}
@@ -0,0 +1,816 @@
+//! Help topics from the rich text the help compiler read.
+//!
+//! WinHelp marks up topics with ordinary RTF: `\page` ends a topic and footnotes name
+//! it, `#` giving its context string, `$` its title, `K` its keywords and `+` its place
+//! in a browse sequence. Double-underlined text followed by hidden text jumps to the topic
+//! the hidden text names; single-underlined text opens it as a pop-up. `{bml file}` places
+//! a picture. Everything else that matters is bold, italic and font size, which the
+//! source uses for headings and for menu and file names.
+
+use crate::{Block, Span, Style, Topic};
+
+/// Point sizes are given in half points; headings are 12 points or more.
+const HEADING_SIZE: i32 = 24;
+/// Text below 10 points is how the source sets menu commands and file names.
+const BODY_SIZE: i32 = 20;
+/// The size `\plain` returns to.
+const DEFAULT_SIZE: i32 = 24;
+
+/// Destinations whose text is not topic text.
+const SKIPPED: [&str; 9] = [
+    "colortbl",
+    "stylesheet",
+    "info",
+    "pict",
+    "header",
+    "footer",
+    "object",
+    "field",
+    "xe",
+];
+
+/// The topics in an RTF file, in order.
+pub fn parse(text: &str) -> Result<Vec<Topic>, String> {
+    let mut reader = Reader::default();
+    for token in tokens(text) {
+        reader.token(token)?;
+    }
+    reader.end_topic()?;
+    Ok(reader.topics)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Word(String, Option<i32>),
+    /// A character given as `\'hh`, which depends on the font.
+    Byte(u8),
+    Text(char),
+    /// `\*`: the group is a destination to skip if unknown.
+    Ignorable,
+}
+
+fn tokens(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => tokens.push(Token::Open),
+            '}' => tokens.push(Token::Close),
+            '\r' | '\n' => {}
+            '\\' => match chars.next() {
+                Some(letter) if letter.is_ascii_alphabetic() => {
+                    let mut word = letter.to_string();
+                    while let Some(&next) = chars.peek().filter(|c| c.is_ascii_alphabetic()) {
+                        word.push(next);
+                        chars.next();
+                    }
+                    let mut digits = String::new();
+                    if chars.peek() == Some(&'-') {
+                        digits.push('-');
+                        chars.next();
+                    }
+                    while let Some(&next) = chars.peek().filter(|c| c.is_ascii_digit()) {
+                        digits.push(next);
+                        chars.next();
+                    }
+                    if chars.peek() == Some(&' ') {
+                        chars.next();
+                    }
+                    tokens.push(Token::Word(word, digits.parse().ok()));
+                }
+                Some('\'') => {
+                    let hex: String = chars.by_ref().take(2).collect();
+                    if let Ok(byte) = u8::from_str_radix(&hex, 16) {
+                        tokens.push(Token::Byte(byte));
+                    }
+                }
+                Some('\r' | '\n') => tokens.push(Token::Word("par".to_string(), None)),
+                Some('~') => tokens.push(Token::Text('\u{a0}')),
+                Some('_') => tokens.push(Token::Text('-')),
+                Some('*') => tokens.push(Token::Ignorable),
+                Some('-') | None => {}
+                Some(other) => tokens.push(Token::Text(other)),
+            },
+            other => tokens.push(Token::Text(other)),
+        }
+    }
+    tokens
+}
+
+/// Character formatting, which groups save and restore.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Format {
+    bold: bool,
+    italic: bool,
+    /// `\uldb`: a jump.
+    double_underline: bool,
+    /// `\ul`: a pop-up.
+    underline: bool,
+    hidden: bool,
+    font: i32,
+    size: i32,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Self {
+            bold: false,
+            italic: false,
+            double_underline: false,
+            underline: false,
+            hidden: false,
+            font: 0,
+            size: DEFAULT_SIZE,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Destination {
+    Text,
+    Skip,
+    FontTable,
+    /// A footnote and the mark before it.
+    Footnote(char),
+}
+
+#[derive(Clone, Debug)]
+struct Group {
+    format: Format,
+    destination: Destination,
+    /// Whether anything has been read since the group opened, after which a control word
+    /// no longer names the destination.
+    started: bool,
+}
+
+#[derive(Default)]
+struct Reader {
+    groups: Vec<Group>,
+    format: Format,
+    destination: Option<Destination>,
+    started: bool,
+    /// Fonts named Symbol, whose `\'b7` is a bullet.
+    symbol_fonts: Vec<i32>,
+    font_name: (i32, String),
+    footnote: String,
+    /// Paragraph formatting: the non-scrolling region and hanging indents.
+    keep_next: bool,
+    hanging: bool,
+    runs: Vec<(String, Format)>,
+    topic: Draft,
+    topics: Vec<Topic>,
+}
+
+/// A topic being read.
+#[derive(Default)]
+struct Draft {
+    context: Option<String>,
+    title: Option<String>,
+    keywords: Vec<String>,
+    browse: Option<String>,
+    heading: Vec<Span>,
+    blocks: Vec<Block>,
+    /// A blank paragraph came after the last block, so lists and lines start afresh.
+    gap: bool,
+    /// The last paragraph was a single link, which lists with its neighbours.
+    after_link: bool,
+}
+
+impl Reader {
+    fn destination(&self) -> &Destination {
+        self.destination.as_ref().unwrap_or(&Destination::Text)
+    }
+
+    fn token(&mut self, token: Token) -> Result<(), String> {
+        let first = !self.started;
+        self.started = true;
+
+        match token {
+            Token::Open => {
+                self.groups.push(Group {
+                    format: self.format,
+                    destination: self.destination().clone(),
+                    started: true,
+                });
+                self.started = false;
+            }
+            Token::Close => self.close_group()?,
+            _ if *self.destination() == Destination::Skip => {}
+            Token::Ignorable => self.destination = Some(Destination::Skip),
+            Token::Word(word, parameter) => self.word(&word, parameter, first)?,
+            Token::Byte(byte) => {
+                let c = if byte == 0xB7 && self.symbol_fonts.contains(&self.format.font) {
+                    '•'
+                } else {
+                    windows_1252(byte)
+                };
+                self.text(c);
+            }
+            Token::Text(c) => self.text(c),
+        }
+        Ok(())
+    }
+
+    fn close_group(&mut self) -> Result<(), String> {
+        let Some(group) = self.groups.pop() else {
+            return Err("unbalanced '}'".to_string());
+        };
+
+        // Only the group that started a destination ends it.
+        let ended = group.destination != *self.destination();
+        match self.destination() {
+            _ if !ended => {}
+            Destination::Footnote(mark) => {
+                let mark = *mark;
+                let text = std::mem::take(&mut self.footnote);
+                self.footnote(mark, text.trim())?;
+            }
+            Destination::FontTable => self.end_font_name(),
+            _ => {}
+        }
+
+        self.format = group.format;
+        self.destination = Some(group.destination);
+        self.started = group.started;
+        Ok(())
+    }
+
+    fn word(&mut self, word: &str, parameter: Option<i32>, first: bool) -> Result<(), String> {
+        let on = parameter != Some(0);
+
+        if first && SKIPPED.contains(&word) {
+            self.destination = Some(Destination::Skip);
+            return Ok(());
+        }
+        match word {
+            "fonttbl" => self.destination = Some(Destination::FontTable),
+            "footnote" => {
+                let mark = self.take_mark();
+                self.destination = Some(Destination::Footnote(mark));
+            }
+            "plain" => self.format = Format::default(),
+            "pard" => {
+                self.keep_next = false;
+                self.hanging = false;
+            }
+            "keepn" => self.keep_next = true,
+            "fi" => self.hanging = parameter.unwrap_or(0) < 0,
+            "b" => self.format.bold = on,
+            "i" => self.format.italic = on,
+            "ul" => self.format.underline = on,
+            "ulnone" => self.format.underline = false,
+            "uldb" => self.format.double_underline = on,
+            "v" => self.format.hidden = on,
+            "f" => {
+                let font = parameter.unwrap_or(0);
+                if *self.destination() == Destination::FontTable {
+                    self.end_font_name();
+                    self.font_name = (font, String::new());
+                } else {
+                    self.format.font = font;
+                }
+            }
+            "fs" => self.format.size = parameter.unwrap_or(DEFAULT_SIZE),
+            "tab" => self.text('\t'),
+            "par" | "line" => self.end_paragraph(),
+            "page" => {
+                self.end_paragraph();
+                self.end_topic()?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn text(&mut self, c: char) {
+        match self.destination() {
+            Destination::Text => match self.runs.last_mut() {
+                Some((text, format)) if *format == self.format => text.push(c),
+                _ => self.runs.push((c.to_string(), self.format)),
+            },
+            Destination::Footnote(_) => self.footnote.push(c),
+            Destination::FontTable => self.font_name.1.push(c),
+            Destination::Skip => {}
+        }
+    }
+
+    /// The character before a footnote, which says what the footnote is for.
+    fn take_mark(&mut self) -> char {
+        let mark = self.runs.last_mut().and_then(|(text, _)| text.pop());
+        if self.runs.last().is_some_and(|(text, _)| text.is_empty()) {
+            self.runs.pop();
+        }
+        mark.unwrap_or('*')
+    }
+
+    fn end_font_name(&mut self) {
+        let (font, name) = std::mem::take(&mut self.font_name);
+        if name.trim_end_matches(';').trim() == "Symbol" {
+            self.symbol_fonts.push(font);
+        }
+    }
+
+    fn footnote(&mut self, mark: char, text: &str) -> Result<(), String> {
+        let draft = &mut self.topic;
+        match mark {
+            '#' => {
+                if let Some(context) = &draft.context {
+                    return Err(format!(
+                        "topic '{}' has a second context string '{}'",
+                        context, text
+                    ));
+                }
+                draft.context = Some(text.to_string());
+            }
+            '$' => draft.title = Some(text.to_string()),
+            'K' => draft.keywords.extend(
+                text.split(';')
+                    .map(str::trim)
+                    .filter(|keyword| !keyword.is_empty())
+                    .map(str::to_string),
+            ),
+            '+' => draft.browse = Some(text.to_string()),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn end_paragraph(&mut self) {
+        let runs = std::mem::take(&mut self.runs);
+        let draft = &mut self.topic;
+
+        let heading = runs
+            .iter()
+            .any(|(text, format)| !format.hidden && !text.trim().is_empty())
+            && runs.iter().all(|(text, format)| {
+                format.hidden
+                    || text.trim().is_empty()
+                    || format.bold && format.size >= HEADING_SIZE
+            });
+        let spans = spans(&runs);
+        if spans.is_empty() {
+            draft.gap = true;
+            draft.after_link = false;
+            return;
+        }
+
+        let tab = spans
+            .iter()
+            .position(|span| matches!(span, Span::Text { text, .. } if text.contains('\t')));
+        let single_link = matches!(spans.as_slice(), [Span::Link { .. }]);
+
+        if self.keep_next && draft.heading.is_empty() && draft.blocks.is_empty() {
+            draft.heading = tidy(spans);
+        } else if let (true, Some(index)) = (self.hanging, tab) {
+            let (term, body) = split_at_tab(spans, index);
+            let (term, body) = (tidy(term), tidy(body));
+            if plain_text(&term) == "•" {
+                draft.push_bullet(body);
+            } else {
+                draft.push_term(term, body);
+            }
+        } else if starts_with_tab(&spans) {
+            draft.push_line(tidy(spans));
+        } else if single_link {
+            if draft.after_link {
+                draft.push_bullet(spans);
+            } else {
+                draft.blocks.push(Block::Bullets(vec![spans]));
+            }
+        } else if heading {
+            draft.blocks.push(Block::Heading(tidy(spans)));
+        } else {
+            draft.blocks.push(Block::Paragraph(tidy(spans)));
+        }
+
+        draft.gap = false;
+        draft.after_link = single_link;
+    }
+
+    fn end_topic(&mut self) -> Result<(), String> {
+        self.end_paragraph();
+        let mut draft = std::mem::take(&mut self.topic);
+        let Some(context) = draft.context.take() else {
+            if draft.heading.is_empty() && draft.blocks.is_empty() {
+                return Ok(());
+            }
+            return Err(format!(
+                "topic {} has no context string",
+                self.topics.len() + 1
+            ));
+        };
+
+        let popup = draft.title.is_none();
+        if draft.heading.is_empty() {
+            if let Some(Block::Heading(_)) = draft.blocks.first() {
+                let Block::Heading(spans) = draft.blocks.remove(0) else {
+                    unreachable!()
+                };
+                draft.heading = spans;
+            } else if let Some(title) = &draft.title {
+                draft.heading = vec![Span::Text {
+                    text: title.clone(),
+                    style: Style::default(),
+                }];
+            }
+        }
+        let title = draft
+            .title
+            .unwrap_or_else(|| plain_text(&draft.heading).trim().to_string());
+
+        self.topics.push(Topic {
+            context,
+            title,
+            keywords: draft.keywords,
+            browse: draft.browse,
+            popup,
+            heading: draft.heading,
+            blocks: draft.blocks,
+        });
+        Ok(())
+    }
+}
+
+impl Draft {
+    fn push_bullet(&mut self, item: Vec<Span>) {
+        match self.blocks.last_mut() {
+            Some(Block::Bullets(items)) if !self.gap => items.push(item),
+            _ => self.blocks.push(Block::Bullets(vec![item])),
+        }
+    }
+
+    fn push_term(&mut self, term: Vec<Span>, body: Vec<Span>) {
+        match self.blocks.last_mut() {
+            Some(Block::Terms(terms)) if !self.gap => terms.push((term, body)),
+            _ => self.blocks.push(Block::Terms(vec![(term, body)])),
+        }
+    }
+
+    fn push_line(&mut self, line: Vec<Span>) {
+        match self.blocks.last_mut() {
+            Some(Block::Lines(lines)) if !self.gap => lines.push(line),
+            _ => self.blocks.push(Block::Lines(vec![line])),
+        }
+    }
+}
+
+/// Turns formatted runs into spans: underlined text and the hidden text after it become
+/// links, and `{bml file}` becomes a picture.
+fn spans(runs: &[(String, Format)]) -> Vec<Span> {
+    let mut spans = Vec::new();
+    let mut link: Option<(String, bool, Style)> = None;
+
+    for (text, format) in runs {
+        let style = Style {
+            bold: format.bold,
+            italic: format.italic,
+            code: format.size < BODY_SIZE,
+        };
+        if format.hidden {
+            if let Some((label, popup, _)) = link.take() {
+                // `context@file` and `context>window` name another file or window.
+                let target = text
+                    .trim()
+                    .split(['@', '>'])
+                    .next()
+                    .unwrap_or("")
+                    .to_string();
+                spans.push(Span::Link {
+                    text: label,
+                    target,
+                    popup,
+                });
+            }
+            continue;
+        }
+        if format.double_underline || format.underline {
+            // `\ul` added to double-underlined text starts a pop-up of its own: the source
+            // leaves `\uldb` switched on across topics.
+            let popup = format.underline;
+            match &mut link {
+                Some((label, same, _)) if *same == popup => label.push_str(text),
+                _ => {
+                    if let Some((label, _, style)) = link.take() {
+                        push_text(&mut spans, &label, style);
+                    }
+                    link = Some((text.clone(), popup, style));
+                }
+            }
+            continue;
+        }
+
+        // Underlined text with no target is just text.
+        if let Some((label, _, style)) = link.take() {
+            push_text(&mut spans, &label, style);
+        }
+        push_text(&mut spans, text, style);
+    }
+    if let Some((label, _, style)) = link {
+        push_text(&mut spans, &label, style);
+    }
+
+    if spans.iter().all(|span| match span {
+        Span::Text { text, .. } => text.trim().is_empty(),
+        _ => false,
+    }) {
+        spans.clear();
+    }
+    spans
+}
+
+/// Adds text, splitting out `{bml file}`, `{bmc file}` and `{bmr file}` pictures.
+fn push_text(spans: &mut Vec<Span>, text: &str, style: Style) {
+    let mut rest = text;
+    while let Some(start) = rest.find("{bm") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        let command = &rest[start + 1..start + end];
+        let Some(file) = command
+            .strip_prefix("bml ")
+            .or_else(|| command.strip_prefix("bmc "))
+            .or_else(|| command.strip_prefix("bmr "))
+        else {
+            break;
+        };
+
+        push_plain(spans, &rest[..start], style);
+        spans.push(Span::Picture(file.trim().to_ascii_lowercase()));
+        rest = &rest[start + end + 1..];
+    }
+    push_plain(spans, rest, style);
+}
+
+fn push_plain(spans: &mut Vec<Span>, text: &str, style: Style) {
+    if text.is_empty() {
+        return;
+    }
+    match spans.last_mut() {
+        Some(Span::Text {
+            text: last,
+            style: last_style,
+        }) if *last_style == style => last.push_str(text),
+        _ => spans.push(Span::Text {
+            text: text.to_string(),
+            style,
+        }),
+    }
+}
+
+fn starts_with_tab(spans: &[Span]) -> bool {
+    match spans.first() {
+        Some(Span::Text { text, .. }) => text.trim_start_matches(' ').starts_with('\t'),
+        _ => false,
+    }
+}
+
+/// The spans before and after the first tab, which is in span `index`.
+fn split_at_tab(mut spans: Vec<Span>, index: usize) -> (Vec<Span>, Vec<Span>) {
+    let mut body = spans.split_off(index);
+    if let Span::Text { text, style } = body.remove(0) {
+        let (before, after) = text.split_once('\t').unwrap_or((&text, ""));
+        push_plain(&mut spans, before, style);
+        body.insert(
+            0,
+            Span::Text {
+                text: after.to_string(),
+                style,
+            },
+        );
+    }
+    (spans, body)
+}
+
+/// Collapses white space, tabs included, trims both ends and drops empty text.
+fn tidy(spans: Vec<Span>) -> Vec<Span> {
+    let mut tidied: Vec<Span> = Vec::new();
+    // Whether the text so far ends in a space, or is empty.
+    let mut space = true;
+    let collapse = |text: &str, space: &mut bool| {
+        let mut out = String::new();
+        for c in text.chars() {
+            if c.is_whitespace() && c != '\u{a0}' {
+                if !*space {
+                    out.push(' ');
+                }
+                *space = true;
+            } else {
+                out.push(c);
+                *space = false;
+            }
+        }
+        out
+    };
+
+    for span in spans {
+        match span {
+            Span::Text { text, style } => {
+                let text = collapse(&text, &mut space);
+                push_plain(&mut tidied, &text, style);
+            }
+            Span::Link {
+                text,
+                target,
+                popup,
+            } => tidied.push(Span::Link {
+                text: collapse(&text, &mut space),
+                target,
+                popup,
+            }),
+            Span::Picture(name) => {
+                space = false;
+                tidied.push(Span::Picture(name));
+            }
+        }
+    }
+
+    if let Some(Span::Text { text, .. }) = tidied.last_mut() {
+        text.truncate(text.trim_end().len());
+    }
+    if let Some(Span::Link { text, .. }) = tidied.last_mut() {
+        text.truncate(text.trim_end().len());
+    }
+    tidied.retain(|span| !matches!(span, Span::Text { text, .. } if text.is_empty()));
+    tidied
+}
+
+/// The text of some spans with their formatting dropped.
+pub(crate) fn plain_text(spans: &[Span]) -> String {
+    spans
+        .iter()
+        .map(|span| match span {
+            Span::Text { text, .. } | Span::Link { text, .. } => text.as_str(),
+            Span::Picture(_) => "",
+        })
+        .collect()
+}
+
+/// The characters Windows 3.1 put in the range Latin-1 leaves to control codes.
+fn windows_1252(byte: u8) -> char {
+    match byte {
+        0x85 => '…',
+        0x91 => '‘',
+        0x92 => '’',
+        0x93 => '“',
+        0x94 => '”',
+        0x95 => '•',
+        0x96 => '–',
+        0x97 => '—',
+        _ => char::from(byte),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(text: &str) -> Span {
+        Span::Text {
+            text: text.to_string(),
+            style: Style::default(),
+        }
+    }
+
+    fn bold(text: &str) -> Span {
+        Span::Text {
+            text: text.to_string(),
+            style: Style {
+                bold: true,
+                ..Style::default()
+            },
+        }
+    }
+
+    fn jump(text: &str, target: &str) -> Span {
+        Span::Link {
+            text: text.to_string(),
+            target: target.to_string(),
+            popup: false,
+        }
+    }
+
+    const HEADER: &str = "{\\rtf1\\ansi \\deff0{\\fonttbl{\\f0\\fmodern Courier;}\
+        {\\f1\\froman Symbol;}}{\\colortbl;\\red0\\green0\\blue0;}\
+        {\\info{\\title HELP}}\\pard\\plain \\f5\\fs20\n";
+
+    #[test]
+    fn reads_footnotes_headings_and_links() {
+        let rtf = format!(
+            "{}{{\\plain \\f5\\up6 \n#{{\\footnote main}}${{\\footnote Contents}}\
+             K{{\\footnote Contents; Index}}}}\n\\pard\\keepn\n\\f4\\fs36\\b\
+             {{\\{{bml icon.bmp\\}}}} Contents\\par\n\\pard\\plain\\f4\\fs20\nStop the Bus is\n \
+             a card game.\\par\n\\par\n\\b\\f4\\fs24 The game\\par\\plain\\uldb\\f4\\fs20\n\
+             Rules of the game\\plain\\uldb\\v rules\\plain\\uldb\\f4\\fs20\\par\n\
+             Scoring\\plain\\uldb\\v scores@other.hlp\\plain\\uldb\\f4\\fs20\\par\n\\page\n\
+             #{{\\footnote rules}}${{\\footnote Rules}}+{{\\footnote game:01}}\n\
+             \\pard\\keepn\\f4\\fs30\\b Rules\\par\n\\pard\\plain\\f4\\fs20\n\
+             Choose\\f4\\fs18  GAME|DEAL\\f4\\fs20 \\uldb  or \\ul the deck\\v\ndeck\\plain\\f4\\fs20  \\'A3\\'A95.\\par\n\
+             \\page\n#{{\\footnote deck}}\n{{\\f4\\fs24\\b Deck}}\\f4\\fs20\\par\n\
+             The {{\\b deck}}.\n\\page\n}}",
+            HEADER
+        );
+        let topics = parse(&rtf).unwrap();
+
+        assert_eq!(topics.len(), 3);
+        let main = &topics[0];
+        assert_eq!(main.context, "main");
+        assert_eq!(main.title, "Contents");
+        assert_eq!(main.keywords, ["Contents", "Index"]);
+        assert!(!main.popup);
+        assert_eq!(
+            main.heading,
+            [Span::Picture("icon.bmp".to_string()), bold(" Contents")]
+        );
+        assert_eq!(
+            main.blocks,
+            [
+                Block::Paragraph(vec![text("Stop the Bus is a card game.")]),
+                Block::Heading(vec![bold("The game")]),
+                Block::Bullets(vec![
+                    vec![jump("Rules of the game", "rules")],
+                    vec![jump("Scoring", "scores")],
+                ]),
+            ]
+        );
+
+        let rules = &topics[1];
+        assert_eq!(rules.browse.as_deref(), Some("game:01"));
+        assert_eq!(rules.heading, [bold("Rules")]);
+        assert_eq!(
+            rules.blocks,
+            [Block::Paragraph(vec![
+                text("Choose"),
+                Span::Text {
+                    text: " GAME|DEAL".to_string(),
+                    style: Style {
+                        code: true,
+                        ..Style::default()
+                    },
+                },
+                text(" or "),
+                Span::Link {
+                    text: "the deck".to_string(),
+                    target: "deck".to_string(),
+                    popup: true,
+                },
+                text(" £©5."),
+            ])]
+        );
+
+        let deck = &topics[2];
+        assert!(deck.popup);
+        assert_eq!(deck.title, "Deck");
+        assert_eq!(deck.heading, [bold("Deck")]);
+        assert_eq!(
+            deck.blocks,
+            [Block::Paragraph(vec![
+                text("The "),
+                bold("deck"),
+                text(".")
+            ])]
+        );
+    }
+
+    #[test]
+    fn reads_hanging_lists_and_indented_lines() {
+        let rtf = format!(
+            "{}#{{\\footnote list}}\n\\pard\\plain\\f4\\fs20\n\\tab Martin\\par\n\\tab Oxford\\par\n\
+             \\par\n\\tx360\\li360\\fi-360\n{{\\f1\\'B7}}\n\\tab Swap a card.\\par\n{{\\f1\\'B7}}\n\
+             \\tab Take the\n next card.\\par\n\\pard\n\\par\n\\tx1000\\li1000\\fi-1000\n\
+             {{\\b Stick}}\n\\tab sticks.\\par\n{{\\b OK    }}\n\\tab passes.\\par\n\\pard\n\\page\n}}",
+            HEADER
+        );
+        let topics = parse(&rtf).unwrap();
+
+        assert_eq!(
+            topics[0].blocks,
+            [
+                Block::Lines(vec![vec![text("Martin")], vec![text("Oxford")]]),
+                Block::Bullets(vec![
+                    vec![text("Swap a card.")],
+                    vec![text("Take the next card.")],
+                ]),
+                Block::Terms(vec![
+                    (vec![bold("Stick")], vec![text("sticks.")]),
+                    (vec![bold("OK")], vec![text("passes.")]),
+                ]),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_broken_topics() {
+        assert_eq!(
+            parse("{\\rtf1 Some text\\par\\page}").unwrap_err(),
+            "topic 1 has no context string"
+        );
+        assert_eq!(
+            parse("{\\rtf1 #{\\footnote a}#{\\footnote b}\\page}").unwrap_err(),
+            "topic 'a' has a second context string 'b'"
+        );
+        assert_eq!(parse("{\\rtf1 }}").unwrap_err(), "unbalanced '}'");
+    }
+}
@@ -0,0 +1,378 @@
+//! Help pictures: plain bitmaps, and segmented hypergraphics (`.SHG`) whose rectangles
+//! jump to or pop up other topics when clicked.
+//!
+//! An `.SHG` file holds one or more pictures, each a bitmap packed the help compiler's
+//! way followed by its hotspots. Sizes in the picture header are "compressed" integers:
+//! a 16-bit word is stored in one byte when it fits, and a 32-bit word in two bytes, the
+//! low bit saying which. Only the first picture of a file is read; the help compiler
+//! writes more only for other display resolutions.
+
+use stopbus_image::bmp;
+use stopbus_image::Image;
+
+const MAGIC: u16 = 0x706C;
+const MAGIC_COMPRESSED: u16 = 0x506C;
+
+const DDB: u8 = 5;
+const DIB: u8 = 6;
+
+/// What clicking a hotspot does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HotspotKind {
+    Jump,
+    Popup,
+    /// Runs a help macro rather than opening a topic.
+    Macro,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hotspot {
+    pub kind: HotspotKind,
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+    /// The name the hotspot editor gave it, such as `Hotspot 1`.
+    pub name: String,
+    /// The topic's context string, or the macro.
+    pub target: String,
+}
+
+/// A picture and its hotspots, if it has any.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Picture {
+    pub image: Image,
+    pub hotspots: Vec<Hotspot>,
+}
+
+impl Picture {
+    /// Reads a picture file, telling `.shg` and `.mrb` files from bitmaps by `name`.
+    pub fn read(name: &str, data: &[u8]) -> Result<Picture, String> {
+        let lower = name.to_ascii_lowercase();
+        if lower.ends_with(".shg") || lower.ends_with(".mrb") {
+            return read_hypergraphic(data);
+        }
+
+        let image = stopbus_image::decode(data).map_err(|err| err.to_string())?;
+        Ok(Picture {
+            image,
+            hotspots: Vec::new(),
+        })
+    }
+}
+
+fn read_hypergraphic(data: &[u8]) -> Result<Picture, String> {
+    let mut header = Cursor::new(data);
+    let magic = header.u16()?;
+    if magic != MAGIC && magic != MAGIC_COMPRESSED {
+        return Err("not a segmented hypergraphic".to_string());
+    }
+    if header.u16()? == 0 {
+        return Err("the file holds no pictures".to_string());
+    }
+    let start = header.u32()? as usize;
+
+    let mut picture = Cursor::new(data.get(start..).ok_or("picture offset is past the end")?);
+    let kind = picture.u8()?;
+    let packing = picture.u8()?;
+    if kind != DIB && kind != DDB {
+        return Err(format!("picture type {} is not a bitmap", kind));
+    }
+
+    let _x_dpi = picture.compressed_u32()?;
+    let _y_dpi = picture.compressed_u32()?;
+    let planes = picture.compressed_u16()?;
+    let bit_count = picture.compressed_u16()?;
+    let width = picture.compressed_u32()?;
+    let height = picture.compressed_u32()?;
+    let mut colors_used = picture.compressed_u32()?;
+    let _colors_important = picture.compressed_u32()?;
+    let packed_size = picture.compressed_u32()? as usize;
+    let hotspot_size = picture.compressed_u32()? as usize;
+    let packed_offset = picture.u32()? as usize;
+    let hotspot_offset = picture.u32()? as usize;
+
+    if colors_used == 0 && bit_count <= 8 {
+        colors_used = 1 << bit_count;
+    }
+    let palette = if kind == DIB {
+        picture.bytes(colors_used as usize * 4)?.to_vec()
+    } else {
+        // Device-dependent bitmaps are monochrome.
+        vec![0, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0]
+    };
+
+    let body = picture.data;
+    let packed = body
+        .get(packed_offset..packed_offset + packed_size)
+        .ok_or("pixel data runs past the end")?;
+    let pixels = unpack(packed, packing)?;
+
+    let mut dib = Vec::with_capacity(40 + palette.len() + pixels.len());
+    dib.extend(bmp::INFO_HEADER_SIZE.to_le_bytes());
+    dib.extend(width.to_le_bytes());
+    dib.extend(height.to_le_bytes());
+    dib.extend(planes.max(1).to_le_bytes());
+    dib.extend(bit_count.to_le_bytes());
+    dib.extend(bmp::BI_RGB.to_le_bytes());
+    dib.extend((pixels.len() as u32).to_le_bytes());
+    dib.extend([0; 8]);
+    dib.extend((palette.len() as u32 / 4).to_le_bytes());
+    dib.extend([0; 4]);
+    dib.extend(&palette);
+    dib.extend(&pixels);
+    let image = bmp::decode_dib(&dib).map_err(|err| err.to_string())?;
+
+    let hotspots = match hotspot_size {
+        0 => Vec::new(),
+        _ => hotspots(
+            body.get(hotspot_offset..hotspot_offset + hotspot_size)
+                .ok_or("hotspots run past the end")?,
+        )?,
+    };
+
+    Ok(Picture { image, hotspots })
+}
+
+/// Undoes the packing: 0 is none, 1 run-length, 2 LZ77 and 3 LZ77 then run-length.
+fn unpack(data: &[u8], packing: u8) -> Result<Vec<u8>, String> {
+    match packing {
+        0 => Ok(data.to_vec()),
+        1 => run_length(data),
+        2 => lz77(data),
+        3 => run_length(&lz77(data)?),
+        other => Err(format!("unknown packing method {}", other)),
+    }
+}
+
+/// A count byte then either that many literal bytes, when its top bit is set, or one
+/// byte to repeat that many times.
+fn run_length(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut output = Vec::new();
+    let mut position = 0;
+
+    while let Some(&count) = data.get(position) {
+        let length = usize::from(count & 0x7F);
+        if count & 0x80 != 0 {
+            let literal = data
+                .get(position + 1..position + 1 + length)
+                .ok_or("run is cut short")?;
+            output.extend_from_slice(literal);
+            position += 1 + length;
+        } else {
+            let &byte = data.get(position + 1).ok_or("run is cut short")?;
+            output.extend(std::iter::repeat_n(byte, length));
+            position += 2;
+        }
+    }
+    Ok(output)
+}
+
+/// A flag byte for every eight items, a set bit marking a two-byte back reference: a
+/// 12-bit distance less one and a 4-bit length less three.
+fn lz77(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut output: Vec<u8> = Vec::new();
+    let mut input = data.iter().copied();
+
+    while let Some(flags) = input.next() {
+        for bit in 0..8 {
+            if flags & (1 << bit) == 0 {
+                match input.next() {
+                    Some(byte) => output.push(byte),
+                    None => return Ok(output),
+                }
+                continue;
+            }
+
+            let (Some(low), Some(high)) = (input.next(), input.next()) else {
+                return Ok(output);
+            };
+            let code = usize::from(u16::from_le_bytes([low, high]));
+            let distance = (code & 0x0FFF) + 1;
+            let length = (code >> 12) + 3;
+            if distance > output.len() {
+                return Err("back reference reaches before the start".to_string());
+            }
+            let start = output.len() - distance;
+            for offset in 0..length {
+                output.push(output[start + offset]);
+            }
+        }
+    }
+    Ok(output)
+}
+
+/// A version byte, a count, the size of the macro data, 15 bytes for each hotspot, the
+/// macro data, then each hotspot's name and target as NUL-terminated strings.
+fn hotspots(data: &[u8]) -> Result<Vec<Hotspot>, String> {
+    let mut cursor = Cursor::new(data);
+    let _version = cursor.u8()?;
+    let count = usize::from(cursor.u16()?);
+    let macro_size = cursor.u32()? as usize;
+
+    let mut shapes = Vec::with_capacity(count);
+    for _ in 0..count {
+        let kind = match cursor.u8()? {
+            0xE2 | 0xE6 => HotspotKind::Popup,
+            0xE3 | 0xE7 => HotspotKind::Jump,
+            0xC8 | 0xCC => HotspotKind::Macro,
+            other => return Err(format!("unknown hotspot type {:#04x}", other)),
+        };
+        let _flags = cursor.bytes(2)?;
+        let rectangle = [cursor.u16()?, cursor.u16()?, cursor.u16()?, cursor.u16()?];
+        let _hash = cursor.u32()?;
+        shapes.push((kind, rectangle));
+    }
+    cursor.bytes(macro_size)?;
+
+    shapes
+        .into_iter()
+        .map(|(kind, [x, y, width, height])| {
+            Ok(Hotspot {
+                kind,
+                x,
+                y,
+                width,
+                height,
+                name: cursor.string()?,
+                target: cursor.string()?,
+            })
+        })
+        .collect()
+}
+
+struct Cursor<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8], String> {
+        let bytes = self
+            .data
+            .get(self.position..self.position + count)
+            .ok_or("picture header is cut short")?;
+        self.position += count;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// One byte holding the value doubled, or two holding it doubled plus one.
+    fn compressed_u16(&mut self) -> Result<u16, String> {
+        let first = self.u8()?;
+        if first & 1 == 0 {
+            Ok(u16::from(first >> 1))
+        } else {
+            Ok(u16::from_le_bytes([first, self.u8()?]) >> 1)
+        }
+    }
+
+    /// Two bytes holding the value doubled, or four holding it doubled plus one.
+    fn compressed_u32(&mut self) -> Result<u32, String> {
+        let low = self.u16()?;
+        if low & 1 == 0 {
+            Ok(u32::from(low >> 1))
+        } else {
+            Ok((u32::from(self.u16()?) << 16 | u32::from(low)) >> 1)
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let rest = &self.data[self.position..];
+        let end = rest
+            .iter()
+            .position(|&byte| byte == 0)
+            .ok_or("hotspot string is not terminated")?;
+        self.position += end + 1;
+        Ok(rest[..end].iter().map(|&byte| char::from(byte)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn picture(name: &str) -> Picture {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../help_bmp")
+            .join(name);
+        let data = fs::read(&path).unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+        Picture::read(name, &data).unwrap()
+    }
+
+    #[test]
+    fn reads_the_main_window_hotspots() {
+        let main = picture("MAINWIN.SHG");
+
+        assert_eq!((main.image.width, main.image.height), (600, 400));
+        assert_eq!(main.hotspots.len(), 6);
+        assert_eq!(
+            main.hotspots[0],
+            Hotspot {
+                kind: HotspotKind::Popup,
+                x: 19,
+                y: 0,
+                width: 560,
+                height: 20,
+                name: "Hotspot 1".to_string(),
+                target: "mw_area1".to_string(),
+            }
+        );
+        let options = picture("OPTNWIN.SHG");
+        let targets: Vec<&str> = options
+            .hotspots
+            .iter()
+            .map(|hotspot| hotspot.target.as_str())
+            .collect();
+        assert_eq!(
+            targets,
+            ["op_area1", "op_area2", "okbut", "cancelbut", "helpbut"]
+        );
+
+        let icon = picture("MAINICON.BMP");
+        assert_eq!((icon.image.width, icon.image.height), (36, 36));
+        assert!(icon.hotspots.is_empty());
+    }
+
+    #[test]
+    fn unpacks_runs_and_back_references() {
+        assert_eq!(
+            run_length(&[0x83, 1, 2, 3, 0x04, 9]).unwrap(),
+            [1, 2, 3, 9, 9, 9, 9]
+        );
+        assert_eq!(run_length(&[0x85, 1]).unwrap_err(), "run is cut short");
+
+        // "ab", then a reference two back for five bytes: "ababab" and one more "a".
+        assert_eq!(lz77(&[0b100, b'a', b'b', 0x01, 0x20]).unwrap(), b"abababa");
+        assert_eq!(
+            lz77(&[0b1, 0x00, 0x00]).unwrap_err(),
+            "back reference reaches before the start"
+        );
+
+        let mut cursor = Cursor::new(&[0xC0, 0x00, 0x03, 0x10, 0x02, 0x00, 0x20, 0x03, 0x02]);
+        assert_eq!(cursor.compressed_u32().unwrap(), 96);
+        assert_eq!(cursor.compressed_u32().unwrap(), 0x0002_1003 >> 1);
+        assert_eq!(cursor.compressed_u16().unwrap(), 16);
+        assert_eq!(cursor.compressed_u16().unwrap(), 0x0203 >> 1);
+    }
+}
@@ -0,0 +1,696 @@
+//! Windows bitmaps: `.bmp` files and the bare DIBs stored in `RT_BITMAP` resources.
+//!
+//! Both the OS/2 1.x `BITMAPCOREHEADER` the original card art uses and the Windows
+//! `BITMAPINFOHEADER` family are read. Uncompressed (`BI_RGB`) data at 1, 4, 8, 16, 24 and
+//! 32 bits per pixel decodes to RGBA, as do `BI_RLE8` and `BI_RLE4` run-length data. Rows
+//! may run bottom-up (positive height) or top-down (negative height, uncompressed only).
+
+use std::fmt;
+
+use crate::Image;
+
+/// `BITMAPFILEHEADER`, in front of the DIB in a `.bmp` file.
+pub const FILE_HEADER_SIZE: usize = 14;
+
+/// `BITMAPCOREHEADER`, from OS/2 1.x: 16-bit sizes and three-byte palette entries.
+pub const CORE_HEADER_SIZE: u32 = 12;
+
+/// `BITMAPINFOHEADER`; later versions (V4, V5) extend it and start the same way.
+pub const INFO_HEADER_SIZE: u32 = 40;
+
+pub const BI_RGB: u32 = 0;
+pub const BI_RLE8: u32 = 1;
+pub const BI_RLE4: u32 = 2;
+pub const BI_BITFIELDS: u32 = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Header {
+    size: u32,
+    width: i32,
+    height: i32,
+    bit_count: u16,
+    compression: u32,
+    palette_entries: usize,
+    /// 3 for `RGBTRIPLE` entries after a core header, 4 for `RGBQUAD`.
+    palette_entry_size: usize,
+}
+
+impl Header {
+    fn parse(dib: &[u8]) -> Result<Self, BmpError> {
+        let size = read_u32(dib, 0).ok_or(BmpError::TruncatedHeader)?;
+        if size != CORE_HEADER_SIZE && size < INFO_HEADER_SIZE {
+            return Err(BmpError::UnsupportedHeader(size));
+        }
+        if dib.len() < size as usize {
+            return Err(BmpError::TruncatedHeader);
+        }
+
+        if size == CORE_HEADER_SIZE {
+            let field = |offset| read_u16(dib, offset).unwrap_or(0);
+            let bit_count = field(10);
+
+            return Ok(Self {
+                size,
+                width: field(4).into(),
+                height: field(6).into(),
+                bit_count,
+                compression: BI_RGB,
+                palette_entries: if bit_count <= 8 { 1 << bit_count } else { 0 },
+                palette_entry_size: 3,
+            });
+        }
+
+        let width = read_u32(dib, 4).unwrap_or(0) as i32;
+        let height = read_u32(dib, 8).unwrap_or(0) as i32;
+        let bit_count = read_u16(dib, 14).unwrap_or(0);
+        let compression = read_u32(dib, 16).unwrap_or(0);
+        let colors_used = read_u32(dib, 32).unwrap_or(0) as usize;
+
+        let palette_entries = match (bit_count, colors_used) {
+            (1 | 4 | 8, 0) => 1 << bit_count,
+            (_, used) => used,
+        };
+
+        Ok(Self {
+            size,
+            width,
+            height,
+            bit_count,
+            compression,
+            palette_entries,
+            palette_entry_size: 4,
+        })
+    }
+
+    fn rows(&self) -> usize {
+        self.height.unsigned_abs() as usize
+    }
+
+    /// Bytes per stored row, padded to four bytes.
+    fn stride(&self) -> usize {
+        (self.width as usize * self.bit_count as usize).div_ceil(32) * 4
+    }
+
+    /// Row `row` of the output image, counted from the top, as stored row index.
+    fn stored_row(&self, row: usize) -> usize {
+        if self.height > 0 {
+            self.rows() - 1 - row
+        } else {
+            row
+        }
+    }
+}
+
+/// Decodes a `.bmp` file.
+pub fn decode_file(data: &[u8]) -> Result<Image, BmpError> {
+    if data.len() < FILE_HEADER_SIZE || &data[..2] != b"BM" {
+        return Err(BmpError::NotBmp);
+    }
+
+    let bits_offset =
+        read_u32(data, 10).and_then(|offset| (offset as usize).checked_sub(FILE_HEADER_SIZE));
+    decode(&data[FILE_HEADER_SIZE..], bits_offset)
+}
+
+/// Decodes a DIB as stored in a resource: the header, the palette, then the pixels.
+pub fn decode_dib(dib: &[u8]) -> Result<Image, BmpError> {
+    decode(dib, None)
+}
+
+fn decode(dib: &[u8], bits_offset: Option<usize>) -> Result<Image, BmpError> {
+    let header = Header::parse(dib)?;
+
+    if header.width <= 0 || header.height == 0 || header.height == i32::MIN {
+        return Err(BmpError::BadDimensions {
+            width: header.width,
+            height: header.height,
+        });
+    }
+    if !matches!(header.bit_count, 1 | 4 | 8 | 16 | 24 | 32) {
+        return Err(BmpError::UnsupportedBitCount(header.bit_count));
+    }
+
+    let palette_start = header.size as usize;
+    let entry_size = header.palette_entry_size;
+    let palette_end = header
+        .palette_entries
+        .saturating_mul(entry_size)
+        .saturating_add(palette_start);
+    if dib.len() < palette_end {
+        return Err(BmpError::TruncatedPalette {
+            expected: header.palette_entries,
+            available: (dib.len() - palette_start) / entry_size,
+        });
+    }
+    let palette: Vec<[u8; 4]> = dib[palette_start..palette_end]
+        .chunks_exact(entry_size)
+        .map(|entry| [entry[2], entry[1], entry[0], 0xFF])
+        .collect();
+
+    // Files whose offset points into the header or palette are taken to have the pixels
+    // straight after the palette, as in a resource.
+    let bits_start = match bits_offset {
+        Some(offset) if offset >= palette_end && offset <= dib.len() => offset,
+        _ => palette_end,
+    };
+    let bits = &dib[bits_start..];
+
+    match (header.compression, header.bit_count) {
+        (BI_RGB, _) => decode_rgb(&header, &palette, bits),
+        (BI_RLE8, 8) | (BI_RLE4, 4) if header.height > 0 => decode_rle(&header, &palette, bits),
+        (BI_RLE8, 8) | (BI_RLE4, 4) => Err(BmpError::BadDimensions {
+            width: header.width,
+            height: header.height,
+        }),
+        (other, _) => Err(BmpError::UnsupportedCompression(other)),
+    }
+}
+
+/// Most pixels a run-length bitmap may have. Two bytes can end the bitmap whatever its
+/// size, so the data cannot bound it; this is far beyond any card art.
+const MAX_RLE_PIXELS: usize = 1 << 24;
+
+/// Expands run-length data. Each pair of bytes is a count and a colour index (two
+/// alternating indices for RLE4), or a zero and an escape: 0 ends the row, 1 ends the
+/// bitmap, 2 skips ahead by the next two bytes, and 3 or more starts that many literal
+/// pixels padded to a 16-bit boundary. Pixels never written keep palette entry 0.
+fn decode_rle(header: &Header, palette: &[[u8; 4]], bits: &[u8]) -> Result<Image, BmpError> {
+    let width = header.width as usize;
+    let rows = header.rows();
+    let four_bit = header.compression == BI_RLE4;
+
+    let pixels = width
+        .checked_mul(rows)
+        .filter(|&pixels| pixels <= MAX_RLE_PIXELS)
+        .ok_or(BmpError::BadDimensions {
+            width: header.width,
+            height: header.height,
+        })?;
+    let mut indices = vec![0u8; pixels];
+    let (mut x, mut y) = (0usize, 0usize);
+    let mut offset = 0;
+
+    let next = |offset: &mut usize| -> Result<u8, BmpError> {
+        let byte = *bits
+            .get(*offset)
+            .ok_or(BmpError::TruncatedRle { offset: *offset })?;
+        *offset += 1;
+        Ok(byte)
+    };
+
+    while y < rows {
+        let count = next(&mut offset)?;
+        let value = next(&mut offset)?;
+
+        if count > 0 {
+            for index in 0..usize::from(count) {
+                if x < width {
+                    indices[y * width + x] = match (four_bit, index % 2) {
+                        (false, _) => value,
+                        (true, 0) => value >> 4,
+                        (true, _) => value & 0x0F,
+                    };
+                }
+                x += 1;
+            }
+            continue;
+        }
+
+        match value {
+            0 => (x, y) = (0, y + 1),
+            1 => break,
+            2 => {
+                x += usize::from(next(&mut offset)?);
+                y += usize::from(next(&mut offset)?);
+            }
+            literal => {
+                let literal = usize::from(literal);
+                let bytes = if four_bit {
+                    literal.div_ceil(2)
+                } else {
+                    literal
+                };
+                let data = bits
+                    .get(offset..offset + bytes)
+                    .ok_or(BmpError::TruncatedRle { offset })?;
+
+                for index in 0..literal {
+                    if x < width {
+                        indices[y * width + x] = match (four_bit, index % 2) {
+                            (false, _) => data[index],
+                            (true, 0) => data[index / 2] >> 4,
+                            (true, _) => data[index / 2] & 0x0F,
+                        };
+                    }
+                    x += 1;
+                }
+                offset += bytes + bytes % 2;
+            }
+        }
+    }
+
+    let mut image = Image::new(width as u32, rows as u32);
+    let colour = |index: u8| {
+        palette
+            .get(usize::from(index))
+            .copied()
+            .unwrap_or([0, 0, 0, 0xFF])
+    };
+    for (stored_row, row) in indices.chunks_exact(width).enumerate() {
+        let y = header.stored_row(stored_row);
+        for (x, &index) in row.iter().enumerate() {
+            image.set_pixel(x as u32, y as u32, colour(index));
+        }
+    }
+
+    Ok(image)
+}
+
+fn decode_rgb(header: &Header, palette: &[[u8; 4]], bits: &[u8]) -> Result<Image, BmpError> {
+    let width = header.width as usize;
+    let rows = header.rows();
+    let stride = header.stride();
+
+    // The last row's padding is sometimes left off.
+    let row_bytes = (width * header.bit_count as usize).div_ceil(8);
+    if bits.len() < stride * (rows - 1) + row_bytes {
+        return Err(BmpError::BadStride {
+            stride,
+            rows,
+            available: bits.len(),
+        });
+    }
+
+    let mut image = Image::new(width as u32, rows as u32);
+    let colour = |index: usize| palette.get(index).copied().unwrap_or([0, 0, 0, 0xFF]);
+
+    for row in 0..rows {
+        let start = header.stored_row(row) * stride;
+        let data = &bits[start..start + row_bytes];
+
+        for x in 0..width {
+            let rgba = match header.bit_count {
+                1 => colour(usize::from(data[x / 8] >> (7 - x % 8) & 1)),
+                4 => colour(usize::from(data[x / 2] >> (4 * (1 - x % 2)) & 0x0F)),
+                8 => colour(usize::from(data[x])),
+                16 => {
+                    let value = u16::from_le_bytes([data[x * 2], data[x * 2 + 1]]);
+                    let channel = |shift: u16| {
+                        let five = ((value >> shift) & 0x1F) as u8;
+                        five << 3 | five >> 2
+                    };
+                    [channel(10), channel(5), channel(0), 0xFF]
+                }
+                24 => [data[x * 3 + 2], data[x * 3 + 1], data[x * 3], 0xFF],
+                _ => [
+                    data[x * 4 + 2],
+                    data[x * 4 + 1],
+                    data[x * 4],
+                    data[x * 4 + 3],
+                ],
+            };
+            image.set_pixel(x as u32, row as u32, rgba);
+        }
+    }
+
+    // In BI_RGB data the fourth byte is reserved and usually zero; only treat it as alpha
+    // when something actually set it.
+    if header.bit_count == 32 && image.rgba.chunks_exact(4).all(|pixel| pixel[3] == 0) {
+        for pixel in image.rgba.chunks_exact_mut(4) {
+            pixel[3] = 0xFF;
+        }
+    }
+
+    Ok(image)
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BmpError {
+    /// The file does not start with `BM`.
+    NotBmp,
+    TruncatedHeader,
+    /// A DIB header size this decoder does not read.
+    UnsupportedHeader(u32),
+    BadDimensions {
+        width: i32,
+        height: i32,
+    },
+    UnsupportedBitCount(u16),
+    UnsupportedCompression(u32),
+    /// The header promised `expected` palette entries but only `available` fit.
+    TruncatedPalette {
+        expected: usize,
+        available: usize,
+    },
+    /// The pixel data is too short for `rows` rows of `stride` bytes.
+    BadStride {
+        stride: usize,
+        rows: usize,
+        available: usize,
+    },
+    /// Run-length data ended before the end-of-bitmap marker or the last row.
+    TruncatedRle {
+        offset: usize,
+    },
+}
+
+impl fmt::Display for BmpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BmpError::NotBmp => write!(f, "not a BMP file"),
+            BmpError::TruncatedHeader => write!(f, "bitmap header is truncated"),
+            BmpError::UnsupportedHeader(size) => {
+                write!(f, "unsupported {}-byte bitmap header", size)
+            }
+            BmpError::BadDimensions { width, height } => {
+                write!(f, "bad bitmap size {}x{}", width, height)
+            }
+            BmpError::UnsupportedBitCount(bits) => {
+                write!(f, "unsupported {} bits per pixel", bits)
+            }
+            BmpError::UnsupportedCompression(compression) => {
+                write!(f, "unsupported bitmap compression {}", compression)
+            }
+            BmpError::TruncatedPalette {
+                expected,
+                available,
+            } => write!(
+                f,
+                "palette has room for {} of {} entries",
+                available, expected
+            ),
+            BmpError::BadStride {
+                stride,
+                rows,
+                available,
+            } => write!(
+                f,
+                "pixel data has {} bytes, too few for {} rows of {} bytes",
+                available, rows, stride
+            ),
+            BmpError::TruncatedRle { offset } => {
+                write!(f, "run-length data ends early at byte {}", offset)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BmpError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn fixture(dir: &str, id: u16) -> Vec<u8> {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../..")
+            .join(dir)
+            .join(format!("card{:02}.bmp", id));
+        fs::read(&path).unwrap_or_else(|err| panic!("{}: {}", path.display(), err))
+    }
+
+    /// A DIB with a `BITMAPINFOHEADER`.
+    fn dib(width: i32, height: i32, bits: u16, palette: &[[u8; 3]], pixels: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend(INFO_HEADER_SIZE.to_le_bytes());
+        data.extend(width.to_le_bytes());
+        data.extend(height.to_le_bytes());
+        data.extend(1u16.to_le_bytes());
+        data.extend(bits.to_le_bytes());
+        data.extend(BI_RGB.to_le_bytes());
+        data.extend([0; 12]);
+        data.extend((palette.len() as u32).to_le_bytes());
+        data.extend([0; 4]);
+        for &[r, g, b] in palette {
+            data.extend([b, g, r, 0]);
+        }
+        data.extend(pixels);
+        data
+    }
+
+    const RED: [u8; 4] = [0xFF, 0, 0, 0xFF];
+    const BLUE: [u8; 4] = [0, 0, 0xFF, 0xFF];
+
+    #[test]
+    fn decodes_palette_depths_bottom_up() {
+        let palette = [[0xFF, 0, 0], [0, 0, 0xFF]];
+
+        // Two rows of three pixels, stored bottom row first.
+        let one_bit = dib(
+            3,
+            2,
+            1,
+            &palette,
+            &[0b0100_0000, 0, 0, 0, 0b1010_0000, 0, 0, 0],
+        );
+        let image = decode_dib(&one_bit).unwrap();
+        assert_eq!((image.width, image.height), (3, 2));
+        assert_eq!(image.pixel(0, 0), BLUE);
+        assert_eq!(image.pixel(1, 0), RED);
+        assert_eq!(image.pixel(1, 1), BLUE);
+
+        let four_bit = dib(3, -2, 4, &palette, &[0x01, 0x00, 0, 0, 0x10, 0x10, 0, 0]);
+        let image = decode_dib(&four_bit).unwrap();
+        assert_eq!(image.pixel(1, 0), BLUE);
+        assert_eq!(image.pixel(2, 1), BLUE);
+        assert_eq!(image.pixel(1, 1), RED);
+
+        let eight_bit = dib(2, 1, 8, &palette, &[1, 7]);
+        let image = decode_dib(&eight_bit).unwrap();
+        assert_eq!(image.pixel(0, 0), BLUE);
+        assert_eq!(image.pixel(1, 0), [0, 0, 0, 0xFF]);
+    }
+
+    #[test]
+    fn decodes_true_colour_depths() {
+        let image = decode_dib(&dib(1, 1, 24, &[], &[0x30, 0x20, 0x10, 0])).unwrap();
+        assert_eq!(image.pixel(0, 0), [0x10, 0x20, 0x30, 0xFF]);
+
+        let image = decode_dib(&dib(1, 1, 16, &[], &[0x1F, 0x7C])).unwrap();
+        assert_eq!(image.pixel(0, 0), [0xFF, 0, 0xFF, 0xFF]);
+
+        let opaque = decode_dib(&dib(2, 1, 32, &[], &[1, 2, 3, 0, 4, 5, 6, 0])).unwrap();
+        assert_eq!(opaque.pixel(1, 0), [6, 5, 4, 0xFF]);
+        let alpha = decode_dib(&dib(2, 1, 32, &[], &[1, 2, 3, 0, 4, 5, 6, 0x80])).unwrap();
+        assert_eq!(alpha.pixel(0, 0), [3, 2, 1, 0]);
+        assert_eq!(alpha.to_bgra()[4..8], [4, 5, 6, 0x80]);
+    }
+
+    #[test]
+    fn reports_typed_errors() {
+        assert_eq!(decode_file(b"PK\x03\x04"), Err(BmpError::NotBmp));
+        assert_eq!(decode_dib(&[40, 0, 0]), Err(BmpError::TruncatedHeader));
+
+        let mut compressed = dib(2, 2, 8, &[[0, 0, 0]], &[0, 0, 0, 0]);
+        compressed[16] = BI_BITFIELDS as u8;
+        assert_eq!(
+            decode_dib(&compressed),
+            Err(BmpError::UnsupportedCompression(BI_BITFIELDS))
+        );
+
+        let mut short_palette = dib(2, 2, 8, &[[0, 0, 0], [1, 1, 1]], &[]);
+        short_palette[32] = 200;
+        assert_eq!(
+            decode_dib(&short_palette),
+            Err(BmpError::TruncatedPalette {
+                expected: 200,
+                available: 2
+            })
+        );
+
+        assert_eq!(
+            decode_dib(&dib(5, 2, 24, &[], &[0; 20])),
+            Err(BmpError::BadStride {
+                stride: 16,
+                rows: 2,
+                available: 20
+            })
+        );
+        assert_eq!(
+            decode_dib(&dib(0, 2, 24, &[], &[])),
+            Err(BmpError::BadDimensions {
+                width: 0,
+                height: 2
+            })
+        );
+        assert_eq!(
+            decode_dib(&dib(1, 1, 2, &[], &[0; 4])),
+            Err(BmpError::UnsupportedBitCount(2))
+        );
+        assert_eq!(
+            BmpError::TruncatedPalette {
+                expected: 16,
+                available: 3
+            }
+            .to_string(),
+            "palette has room for 3 of 16 entries"
+        );
+    }
+
+    #[test]
+    fn decodes_every_card_resource() {
+        for id in 1..=54 {
+            let image = decode_file(&fixture("crates/stopbus-ui/resources/cards", id))
+                .unwrap_or_else(|err| panic!("card {}: {}", id, err));
+
+            assert_eq!((image.width, image.height), (71, 96), "card {}", id);
+            assert!(image.rgba.chunks_exact(4).all(|pixel| pixel[3] == 0xFF));
+        }
+
+        // Card faces have a white border; the back is patterned to the edge.
+        let face = decode_file(&fixture("crates/stopbus-ui/resources/cards", 1)).unwrap();
+        assert_eq!(face.pixel(35, 1), [0xFF, 0xFF, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn original_art_matches_the_converted_cards() {
+        for id in 1..=54 {
+            let original = decode_file(&fixture("assets/cards-os2", id))
+                .unwrap_or_else(|err| panic!("card {}: {}", id, err));
+            let converted = decode_file(&fixture("crates/stopbus-ui/resources/cards", id)).unwrap();
+
+            assert_eq!(original, converted, "card {}", id);
+        }
+    }
+
+    /// A DIB with a `BITMAPCOREHEADER`.
+    fn core_dib(width: u16, height: u16, bits: u16, palette: &[[u8; 3]], pixels: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend(CORE_HEADER_SIZE.to_le_bytes());
+        data.extend(width.to_le_bytes());
+        data.extend(height.to_le_bytes());
+        data.extend(1u16.to_le_bytes());
+        data.extend(bits.to_le_bytes());
+        for &[r, g, b] in palette {
+            data.extend([b, g, r]);
+        }
+        data.extend(pixels);
+        data
+    }
+
+    #[test]
+    fn decodes_core_headers() {
+        let image = decode_dib(&core_dib(
+            2,
+            2,
+            1,
+            &[[0xFF, 0, 0], [0, 0, 0xFF]],
+            &[0b1000_0000, 0, 0, 0, 0b0100_0000, 0, 0, 0],
+        ))
+        .unwrap();
+        assert_eq!(image.pixel(0, 0), RED);
+        assert_eq!(image.pixel(1, 0), BLUE);
+        assert_eq!(image.pixel(0, 1), BLUE);
+
+        assert_eq!(
+            decode_dib(&core_dib(2, 2, 4, &[[0, 0, 0]; 5], &[])),
+            Err(BmpError::TruncatedPalette {
+                expected: 16,
+                available: 5
+            })
+        );
+
+        let image = decode_dib(&core_dib(1, 1, 24, &[], &[3, 2, 1, 0])).unwrap();
+        assert_eq!(image.pixel(0, 0), [1, 2, 3, 0xFF]);
+    }
+
+    fn rle(compression: u32, width: i32, height: i32, palette: &[[u8; 3]], data: &[u8]) -> Vec<u8> {
+        let bits = if compression == BI_RLE4 { 4 } else { 8 };
+        let mut dib = dib(width, height, bits, palette, data);
+        dib[16] = compression as u8;
+        dib
+    }
+
+    /// Rows of palette indices, top first.
+    fn indices(image: &Image, palette: &[[u8; 3]]) -> Vec<Vec<usize>> {
+        (0..image.height)
+            .map(|y| {
+                (0..image.width)
+                    .map(|x| {
+                        let [r, g, b, _] = image.pixel(x, y);
+                        palette.iter().position(|&c| c == [r, g, b]).unwrap()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    const GREYS: [[u8; 3]; 4] = [[0, 0, 0], [1, 1, 1], [2, 2, 2], [3, 3, 3]];
+
+    #[test]
+    fn decodes_rle8_runs_literals_and_deltas() {
+        #[rustfmt::skip]
+        let data = [
+            3, 1, 0, 3, 2, 3, 2, 0,  // bottom row: run of three 1s, literal 2 3 2, padded
+            0, 0,                    // end of row
+            0, 2, 2, 1,              // skip right 2, up 1
+            1, 3, 0, 1,              // one 3, then end of bitmap
+            9, 9,                    // ignored
+        ];
+
+        let image = decode_dib(&rle(BI_RLE8, 6, 3, &GREYS, &data)).unwrap();
+        assert_eq!(
+            indices(&image, &GREYS),
+            [[0, 0, 3, 0, 0, 0], [0, 0, 0, 0, 0, 0], [1, 1, 1, 2, 3, 2],]
+        );
+    }
+
+    #[test]
+    fn decodes_rle4_nibbles() {
+        #[rustfmt::skip]
+        let data = [
+            5, 0x12,                 // 1 2 1 2 1
+            0, 0,
+            0, 3, 0x32, 0x10,        // literal 3 2 1, two bytes already even
+            2, 0x33,                 // 3 3
+            0, 1,
+        ];
+
+        let image = decode_dib(&rle(BI_RLE4, 5, 2, &GREYS, &data)).unwrap();
+        assert_eq!(indices(&image, &GREYS), [[3, 2, 1, 3, 3], [1, 2, 1, 2, 1]]);
+    }
+
+    #[test]
+    fn rle_errors() {
+        assert_eq!(
+            decode_dib(&rle(BI_RLE8, 4, 2, &GREYS, &[2, 1, 0, 0, 0, 5, 1])),
+            Err(BmpError::TruncatedRle { offset: 6 })
+        );
+        assert_eq!(
+            decode_dib(&rle(BI_RLE8, 4, -2, &GREYS, &[0, 1])),
+            Err(BmpError::BadDimensions {
+                width: 4,
+                height: -2
+            })
+        );
+
+        let mut mismatched = rle(BI_RLE4, 4, 2, &GREYS, &[0, 1]);
+        mismatched[14] = 8;
+        assert_eq!(
+            decode_dib(&mismatched),
+            Err(BmpError::UnsupportedCompression(BI_RLE4))
+        );
+
+        // Two bytes would end this bitmap, but it is refused before anything is allocated.
+        assert_eq!(
+            decode_dib(&rle(BI_RLE8, 200_000, 200_000, &GREYS, &[0, 1])),
+            Err(BmpError::BadDimensions {
+                width: 200_000,
+                height: 200_000
+            })
+        );
+    }
+}
@@ -0,0 +1,500 @@
+//! Animated GIF files, for recordings of whole games.
+//!
+//! [`Encoder`] takes frames one at a time so a long animation never has to be held in
+//! memory. Each frame after the first only stores the rectangle that changed, left on
+//! screen by the next, and carries its own colour table: exact when the rectangle has at
+//! most 256 colours, otherwise a fixed 6x7x6 colour cube. Alpha is ignored. The animation
+//! loops forever.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::Image;
+
+pub const SIGNATURE: &[u8; 6] = b"GIF89a";
+
+/// Longest code the LZW dictionary may hold, in bits.
+const MAX_CODE_SIZE: u32 = 12;
+
+/// Levels of red, green and blue in the fallback colour cube.
+const CUBE: [u32; 3] = [6, 7, 6];
+
+/// Graphic control extension flag: leave the frame in place under the next one.
+const DISPOSE_KEEP: u8 = 1 << 2;
+
+pub struct Encoder {
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+    previous: Option<Image>,
+}
+
+impl Encoder {
+    pub fn new(width: u32, height: u32) -> Result<Self, GifError> {
+        if width == 0 || height == 0 || width > 0xFFFF || height > 0xFFFF {
+            return Err(GifError::BadDimensions { width, height });
+        }
+
+        let mut data = SIGNATURE.to_vec();
+        data.extend((width as u16).to_le_bytes());
+        data.extend((height as u16).to_le_bytes());
+        // No global colour table, background index and aspect ratio unused.
+        data.extend([0, 0, 0]);
+        // The NETSCAPE2.0 extension, with a loop count of 0 for ever.
+        data.extend([0x21, 0xFF, 11]);
+        data.extend(b"NETSCAPE2.0");
+        data.extend([3, 1, 0, 0, 0]);
+
+        Ok(Self {
+            data,
+            width,
+            height,
+            previous: None,
+        })
+    }
+
+    /// Adds `image`, shown for `delay_ms` rounded to the format's hundredths of a second.
+    pub fn add_frame(&mut self, image: &Image, delay_ms: u32) -> Result<(), GifError> {
+        if (image.width, image.height) != (self.width, self.height) {
+            return Err(GifError::SizeMismatch {
+                expected: (self.width, self.height),
+                found: (image.width, image.height),
+            });
+        }
+
+        let (x, y, width, height) = match &self.previous {
+            Some(previous) => changed_area(previous, image),
+            None => (0, 0, image.width, image.height),
+        };
+        let mut pixels = Vec::with_capacity(width as usize * height as usize);
+        for row in y..y + height {
+            for column in x..x + width {
+                let [red, green, blue, _] = image.pixel(column, row);
+                pixels.push([red, green, blue]);
+            }
+        }
+        let (palette, indices) = index_colors(&pixels);
+
+        let delay = (delay_ms.saturating_add(5) / 10).min(0xFFFF) as u16;
+        self.data.extend([0x21, 0xF9, 4, DISPOSE_KEEP]);
+        self.data.extend(delay.to_le_bytes());
+        self.data.extend([0, 0]);
+
+        // Colour tables hold a power of two entries, at least two.
+        let table_bits = (palette.len().max(2).next_power_of_two().trailing_zeros()).max(1);
+        self.data.push(0x2C);
+        for value in [x, y, width, height] {
+            self.data.extend((value as u16).to_le_bytes());
+        }
+        self.data.push(0x80 | (table_bits - 1) as u8);
+        for index in 0..1usize << table_bits {
+            self.data
+                .extend(palette.get(index).copied().unwrap_or([0, 0, 0]));
+        }
+
+        let min_code_size = table_bits.max(2);
+        self.data.push(min_code_size as u8);
+        for block in lzw(&indices, min_code_size).chunks(255) {
+            self.data.push(block.len() as u8);
+            self.data.extend(block);
+        }
+        self.data.push(0);
+
+        self.previous = Some(image.clone());
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Vec<u8> {
+        self.data.push(0x3B);
+        self.data
+    }
+}
+
+/// The smallest rectangle holding every pixel that differs, as x, y, width and height; a
+/// single pixel when nothing does, so the frame still takes its time on screen.
+fn changed_area(previous: &Image, image: &Image) -> (u32, u32, u32, u32) {
+    let mut area: Option<(u32, u32, u32, u32)> = None;
+    for y in 0..image.height {
+        for x in 0..image.width {
+            if previous.pixel(x, y)[..3] == image.pixel(x, y)[..3] {
+                continue;
+            }
+            area = Some(match area {
+                Some((left, top, right, bottom)) => {
+                    (left.min(x), top.min(y), right.max(x), bottom.max(y))
+                }
+                None => (x, y, x, y),
+            });
+        }
+    }
+    match area {
+        Some((left, top, right, bottom)) => (left, top, right - left + 1, bottom - top + 1),
+        None => (0, 0, 1, 1),
+    }
+}
+
+/// A colour table for `pixels` and each pixel's index into it.
+fn index_colors(pixels: &[[u8; 3]]) -> (Vec<[u8; 3]>, Vec<u8>) {
+    let mut palette: Vec<[u8; 3]> = pixels.to_vec();
+    palette.sort_unstable();
+    palette.dedup();
+
+    if palette.len() <= 256 {
+        let lookup: HashMap<[u8; 3], u8> = palette
+            .iter()
+            .enumerate()
+            .map(|(index, color)| (*color, index as u8))
+            .collect();
+        let indices = pixels.iter().map(|color| lookup[color]).collect();
+        return (palette, indices);
+    }
+
+    let level = |value: u8, levels: u32| (u32::from(value) * (levels - 1) + 127) / 255;
+    let value = |level: u32, levels: u32| (level * 255 / (levels - 1)) as u8;
+    let mut palette = Vec::with_capacity(256);
+    for red in 0..CUBE[0] {
+        for green in 0..CUBE[1] {
+            for blue in 0..CUBE[2] {
+                palette.push([
+                    value(red, CUBE[0]),
+                    value(green, CUBE[1]),
+                    value(blue, CUBE[2]),
+                ]);
+            }
+        }
+    }
+    let indices = pixels
+        .iter()
+        .map(|&[red, green, blue]| {
+            let (red, green, blue) = (
+                level(red, CUBE[0]),
+                level(green, CUBE[1]),
+                level(blue, CUBE[2]),
+            );
+            ((red * CUBE[1] + green) * CUBE[2] + blue) as u8
+        })
+        .collect();
+    (palette, indices)
+}
+
+/// GIF's variable-length LZW, least significant bit first, starting with a clear code
+/// and clearing again whenever the dictionary fills.
+fn lzw(indices: &[u8], min_code_size: u32) -> Vec<u8> {
+    let clear = 1u32 << min_code_size;
+    let end = clear + 1;
+
+    let mut writer = BitWriter::default();
+    let mut dictionary: HashMap<(u32, u8), u32> = HashMap::new();
+    let mut next = end + 1;
+    let mut code_size = min_code_size + 1;
+    writer.write(clear, code_size);
+
+    let Some((&first, rest)) = indices.split_first() else {
+        writer.write(end, code_size);
+        return writer.finish();
+    };
+    let mut prefix = u32::from(first);
+    for &index in rest {
+        if let Some(&code) = dictionary.get(&(prefix, index)) {
+            prefix = code;
+            continue;
+        }
+
+        writer.write(prefix, code_size);
+        dictionary.insert((prefix, index), next);
+        next += 1;
+        if next == 1 << MAX_CODE_SIZE {
+            writer.write(clear, code_size);
+            dictionary.clear();
+            next = end + 1;
+            code_size = min_code_size + 1;
+        } else if next > 1 << code_size {
+            code_size += 1;
+        }
+        prefix = u32::from(index);
+    }
+    writer.write(prefix, code_size);
+    writer.write(end, code_size);
+    writer.finish()
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u32, size: u32) {
+        self.buffer |= code << self.bits;
+        self.bits += size;
+        while self.bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GifError {
+    /// Zero, or more than the 65535 pixels a GIF can say.
+    BadDimensions { width: u32, height: u32 },
+    /// A frame that is not the size of the animation.
+    SizeMismatch {
+        expected: (u32, u32),
+        found: (u32, u32),
+    },
+}
+
+impl fmt::Display for GifError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GifError::BadDimensions { width, height } => {
+                write!(f, "a GIF cannot be {}x{}", width, height)
+            }
+            GifError::SizeMismatch { expected, found } => write!(
+                f,
+                "frame is {}x{} but the animation is {}x{}",
+                found.0, found.1, expected.0, expected.1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GifError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// What a decoder makes of one frame: delay, area, colour table and pixel indices.
+    #[derive(Debug, PartialEq)]
+    struct Decoded {
+        delay: u16,
+        area: (u16, u16, u16, u16),
+        palette: Vec<[u8; 3]>,
+        indices: Vec<u8>,
+    }
+
+    fn decode_frames(gif: &[u8]) -> Vec<Decoded> {
+        assert_eq!(&gif[..6], SIGNATURE);
+        assert_eq!(gif[10] & 0x80, 0, "no global colour table");
+        let mut position = 13;
+        let mut delay = 0;
+        let mut frames = Vec::new();
+        let sub_blocks = |position: &mut usize| {
+            let mut data = Vec::new();
+            while gif[*position] != 0 {
+                let length = gif[*position] as usize;
+                data.extend(&gif[*position + 1..*position + 1 + length]);
+                *position += 1 + length;
+            }
+            *position += 1;
+            data
+        };
+        loop {
+            match gif[position] {
+                0x21 => {
+                    let label = gif[position + 1];
+                    position += 2;
+                    let data = sub_blocks(&mut position);
+                    if label == 0xF9 {
+                        delay = u16::from_le_bytes([data[1], data[2]]);
+                    }
+                }
+                0x2C => {
+                    let word = |at: usize| u16::from_le_bytes([gif[at], gif[at + 1]]);
+                    let area = (
+                        word(position + 1),
+                        word(position + 3),
+                        word(position + 5),
+                        word(position + 7),
+                    );
+                    let packed = gif[position + 9];
+                    let entries = 2usize << (packed & 7);
+                    position += 10;
+                    let palette = gif[position..position + entries * 3]
+                        .chunks(3)
+                        .map(|rgb| [rgb[0], rgb[1], rgb[2]])
+                        .collect();
+                    position += entries * 3;
+                    let min_code_size = u32::from(gif[position]);
+                    position += 1;
+                    let indices = unlzw(&sub_blocks(&mut position), min_code_size);
+                    frames.push(Decoded {
+                        delay,
+                        area,
+                        palette,
+                        indices,
+                    });
+                }
+                0x3B => {
+                    assert_eq!(position, gif.len() - 1);
+                    return frames;
+                }
+                other => panic!("unexpected block {:#04x}", other),
+            }
+        }
+    }
+
+    /// A straightforward LZW decoder, the way GIF readers grow their code size.
+    fn unlzw(data: &[u8], min_code_size: u32) -> Vec<u8> {
+        let clear = 1usize << min_code_size;
+        let reset = || -> Vec<Vec<u8>> {
+            (0..clear + 2)
+                .map(|code| {
+                    if code < clear {
+                        vec![code as u8]
+                    } else {
+                        Vec::new()
+                    }
+                })
+                .collect()
+        };
+        let mut table = reset();
+        let mut code_size = min_code_size + 1;
+        let mut previous: Option<Vec<u8>> = None;
+        let mut output = Vec::new();
+        let mut bit = 0;
+        loop {
+            let mut code = 0;
+            for offset in 0..code_size as usize {
+                let index = bit + offset;
+                code |= usize::from((data[index / 8] >> (index % 8)) & 1) << offset;
+            }
+            bit += code_size as usize;
+
+            if code == clear {
+                table = reset();
+                code_size = min_code_size + 1;
+                previous = None;
+                continue;
+            }
+            if code == clear + 1 {
+                return output;
+            }
+            let entry = match (table.get(code), &previous) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(previous)) => [previous.as_slice(), &previous[..1]].concat(),
+                (None, None) => panic!("code {} before any entry", code),
+            };
+            if let Some(previous) = previous {
+                if table.len() < 1 << MAX_CODE_SIZE {
+                    table.push([previous.as_slice(), &entry[..1]].concat());
+                }
+            }
+            if table.len() == 1 << code_size && code_size < MAX_CODE_SIZE {
+                code_size += 1;
+            }
+            output.extend(&entry);
+            previous = Some(entry);
+        }
+    }
+
+    fn image(width: u32, height: u32, color: impl Fn(u32, u32) -> [u8; 3]) -> Image {
+        let mut image = Image::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let [red, green, blue] = color(x, y);
+                image.set_pixel(x, y, [red, green, blue, 0xFF]);
+            }
+        }
+        image
+    }
+
+    #[test]
+    fn lzw_round_trips() {
+        let mut seed = 1u32;
+        let mut noise = |modulus: u32| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            ((seed >> 16) % modulus) as u8
+        };
+        let inputs = [
+            (Vec::new(), 2),
+            (vec![3], 2),
+            (vec![0; 10_000], 2),
+            ((0..20_000).map(|_| noise(4)).collect(), 2),
+            ((0..50_000).map(|_| noise(256)).collect(), 8),
+        ];
+        for (indices, min_code_size) in inputs {
+            assert_eq!(unlzw(&lzw(&indices, min_code_size), min_code_size), indices);
+        }
+    }
+
+    #[test]
+    fn stores_the_changed_rectangle_of_each_frame() {
+        let first = image(5, 4, |_, _| [0, 0x80, 0]);
+        let mut second = first.clone();
+        second.set_pixel(1, 1, [0xFF, 0xFF, 0, 0xFF]);
+        second.set_pixel(3, 2, [0, 0, 0, 0xFF]);
+
+        let mut encoder = Encoder::new(5, 4).unwrap();
+        encoder.add_frame(&first, 500).unwrap();
+        encoder.add_frame(&second, 2000).unwrap();
+        encoder.add_frame(&second, 4).unwrap();
+        let gif = encoder.finish();
+        assert_eq!(&gif[6..10], [5, 0, 4, 0]);
+
+        let frames = decode_frames(&gif);
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0].delay, 50);
+        assert_eq!(frames[0].area, (0, 0, 5, 4));
+        assert_eq!(frames[0].palette, [[0, 0x80, 0], [0, 0, 0]]);
+        assert_eq!(frames[0].indices, [0; 20]);
+
+        assert_eq!(frames[1].delay, 200);
+        assert_eq!(frames[1].area, (1, 1, 3, 2));
+        assert_eq!(
+            &frames[1].palette[..3],
+            [[0, 0, 0], [0, 0x80, 0], [0xFF, 0xFF, 0]]
+        );
+        assert_eq!(frames[1].indices, [2, 1, 1, 1, 1, 0]);
+
+        assert_eq!(frames[2].delay, 0);
+        assert_eq!(frames[2].area, (0, 0, 1, 1));
+    }
+
+    #[test]
+    fn falls_back_to_a_colour_cube() {
+        let colorful = image(20, 20, |x, y| [(x * 13) as u8, (y * 13) as u8, 7]);
+        let mut encoder = Encoder::new(20, 20).unwrap();
+        encoder.add_frame(&colorful, 100).unwrap();
+
+        let frames = decode_frames(&encoder.finish());
+        let frame = &frames[0];
+        assert_eq!(frame.palette.len(), 256);
+        assert_eq!(frame.palette[251], [0xFF, 0xFF, 0xFF]);
+        assert_eq!(frame.palette[frame.indices[0] as usize], [0, 0, 0]);
+        assert_eq!(frame.palette[frame.indices[399] as usize], [0xFF, 0xFF, 0]);
+    }
+
+    #[test]
+    fn reports_typed_errors() {
+        assert_eq!(
+            Encoder::new(0, 4).err(),
+            Some(GifError::BadDimensions {
+                width: 0,
+                height: 4
+            })
+        );
+        assert!(Encoder::new(0x1_0000, 1).is_err());
+
+        let mut encoder = Encoder::new(2, 2).unwrap();
+        assert_eq!(
+            encoder.add_frame(&Image::new(3, 2), 0),
+            Err(GifError::SizeMismatch {
+                expected: (2, 2),
+                found: (3, 2)
+            })
+        );
+    }
+}
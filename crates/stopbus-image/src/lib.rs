@@ -0,0 +1,132 @@
+//! Image decoding for the card art, and PNG and GIF encoding for generated pictures,
+//! without any platform graphics API.
+
+use std::fmt;
+
+pub mod bmp;
+pub mod gif;
+pub mod png;
+mod zlib;
+
+pub use bmp::BmpError;
+pub use gif::GifError;
+pub use png::PngError;
+
+/// An 8-bit RGBA image stored top row first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    /// `width * height * 4` bytes: red, green, blue, alpha.
+    pub rgba: Vec<u8>,
+}
+
+impl Image {
+    /// A fully transparent image.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            rgba: vec![0; width as usize * height as usize * 4],
+        }
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let index = (y as usize * self.width as usize + x as usize) * 4;
+        [
+            self.rgba[index],
+            self.rgba[index + 1],
+            self.rgba[index + 2],
+            self.rgba[index + 3],
+        ]
+    }
+
+    pub fn set_pixel(&mut self, x: u32, y: u32, rgba: [u8; 4]) {
+        let index = (y as usize * self.width as usize + x as usize) * 4;
+        self.rgba[index..index + 4].copy_from_slice(&rgba);
+    }
+
+    /// Blends every pixel over an opaque background colour, leaving the image opaque.
+    pub fn flatten(&mut self, background: [u8; 3]) {
+        for pixel in self.rgba.chunks_exact_mut(4) {
+            let alpha = u16::from(pixel[3]);
+            for (channel, &under) in pixel[..3].iter_mut().zip(&background) {
+                let blended = u16::from(*channel) * alpha + u16::from(under) * (255 - alpha);
+                *channel = ((blended + 127) / 255) as u8;
+            }
+            pixel[3] = 0xFF;
+        }
+    }
+
+    /// The pixels as blue, green, red, alpha, the order Windows DIB sections use.
+    pub fn to_bgra(&self) -> Vec<u8> {
+        let mut bgra = self.rgba.clone();
+        for pixel in bgra.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+        bgra
+    }
+}
+
+/// Decodes a `.bmp` or `.png` file, telling them apart by their first bytes.
+pub fn decode(data: &[u8]) -> Result<Image, ImageError> {
+    if data.starts_with(b"BM") {
+        bmp::decode_file(data).map_err(ImageError::Bmp)
+    } else if data.starts_with(&png::SIGNATURE) {
+        png::decode(data).map_err(ImageError::Png)
+    } else {
+        Err(ImageError::UnknownFormat)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ImageError {
+    Bmp(BmpError),
+    Png(PngError),
+    /// Neither a BMP nor a PNG.
+    UnknownFormat,
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageError::Bmp(err) => write!(f, "{}", err),
+            ImageError::Png(err) => write!(f, "{}", err),
+            ImageError::UnknownFormat => write!(f, "not a BMP or PNG image"),
+        }
+    }
+}
+
+impl std::error::Error for ImageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ImageError::Bmp(err) => Some(err),
+            ImageError::Png(err) => Some(err),
+            ImageError::UnknownFormat => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_tells_formats_apart() {
+        assert_eq!(decode(b"GIF89a"), Err(ImageError::UnknownFormat));
+        assert!(matches!(decode(b"BM"), Err(ImageError::Bmp(_))));
+        assert!(matches!(decode(&png::SIGNATURE), Err(ImageError::Png(_))));
+    }
+
+    #[test]
+    fn flatten_blends_over_the_background() {
+        let mut image = Image::new(3, 1);
+        image.set_pixel(0, 0, [200, 100, 0, 0xFF]);
+        image.set_pixel(1, 0, [255, 255, 255, 128]);
+        image.flatten([0, 128, 0]);
+
+        assert_eq!(image.pixel(0, 0), [200, 100, 0, 0xFF]);
+        assert_eq!(image.pixel(1, 0), [128, 192, 128, 0xFF]);
+        assert_eq!(image.pixel(2, 0), [0, 128, 0, 0xFF]);
+    }
+}
@@ -0,0 +1,736 @@
+//! PNG files, for theme art drawn with modern tools.
+//!
+//! Every standard colour type and bit depth decodes to RGBA, interlaced or not, with
+//! `tRNS` transparency applied. Sixteen-bit samples keep their high byte. Ancillary chunks
+//! such as gamma and text are skipped.
+//!
+//! [`encode`] writes the other way, for generated pictures: 8-bit RGB, or RGBA when any
+//! pixel is see-through, with no ancillary chunks so the bytes depend only on the pixels.
+
+use std::fmt;
+
+use crate::zlib;
+use crate::Image;
+
+pub const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
+const GRAYSCALE: u8 = 0;
+const RGB: u8 = 2;
+const INDEXED: u8 = 3;
+const GRAYSCALE_ALPHA: u8 = 4;
+const RGBA: u8 = 6;
+
+/// Where each Adam7 pass starts and how far apart its pixels are: x, y, dx, dy.
+const ADAM7: [(usize, usize, usize, usize); 7] = [
+    (0, 0, 8, 8),
+    (4, 0, 8, 8),
+    (0, 4, 4, 8),
+    (2, 0, 4, 4),
+    (0, 2, 2, 4),
+    (1, 0, 2, 2),
+    (0, 1, 1, 2),
+];
+
+#[derive(Clone, Copy, Debug)]
+struct Header {
+    width: usize,
+    height: usize,
+    bit_depth: u8,
+    color_type: u8,
+    interlaced: bool,
+}
+
+impl Header {
+    fn channels(&self) -> usize {
+        match self.color_type {
+            RGB => 3,
+            GRAYSCALE_ALPHA => 2,
+            RGBA => 4,
+            _ => 1,
+        }
+    }
+
+    fn bits_per_pixel(&self) -> usize {
+        self.channels() * self.bit_depth as usize
+    }
+}
+
+/// The colour type and palette data that turn samples into RGBA.
+struct Colors {
+    palette: Vec<[u8; 4]>,
+    /// The `tRNS` key colour for grayscale and RGB images, at the image's bit depth.
+    transparent: Option<[u16; 3]>,
+}
+
+pub fn decode(data: &[u8]) -> Result<Image, PngError> {
+    if data.len() < SIGNATURE.len() || data[..8] != SIGNATURE {
+        return Err(PngError::NotPng);
+    }
+
+    let mut header = None;
+    let mut palette: Vec<[u8; 4]> = Vec::new();
+    let mut transparency = None;
+    let mut compressed = Vec::new();
+    let mut offset = SIGNATURE.len();
+
+    loop {
+        let length = data
+            .get(offset..offset + 4)
+            .map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+            .ok_or(PngError::Truncated)?;
+        let chunk = data
+            .get(offset + 4..offset + 8 + length)
+            .ok_or(PngError::Truncated)?;
+        let stored_crc = data
+            .get(offset + 8 + length..offset + 12 + length)
+            .map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .ok_or(PngError::Truncated)?;
+        offset += 12 + length;
+
+        let (kind, body) = chunk.split_at(4);
+        let name = String::from_utf8_lossy(kind).into_owned();
+        if crc32(chunk) != stored_crc {
+            return Err(PngError::BadCrc(name));
+        }
+
+        match kind {
+            b"IHDR" => header = Some(parse_header(body)?),
+            b"PLTE" => {
+                if body.len() % 3 != 0 || body.len() > 256 * 3 {
+                    return Err(PngError::BadChunk(name));
+                }
+                palette = body
+                    .chunks_exact(3)
+                    .map(|rgb| [rgb[0], rgb[1], rgb[2], 0xFF])
+                    .collect();
+            }
+            b"tRNS" => transparency = Some(body.to_vec()),
+            b"IDAT" => compressed.extend_from_slice(body),
+            b"IEND" => break,
+            // Lower-case first letters mark chunks a decoder may skip.
+            _ if kind[0].is_ascii_lowercase() => {}
+            _ => return Err(PngError::UnsupportedChunk(name)),
+        }
+    }
+
+    let header = header.ok_or(PngError::MissingChunk("IHDR"))?;
+    if compressed.is_empty() {
+        return Err(PngError::MissingChunk("IDAT"));
+    }
+    if header.color_type == INDEXED && palette.is_empty() {
+        return Err(PngError::MissingChunk("PLTE"));
+    }
+
+    let mut colors = Colors {
+        palette,
+        transparent: None,
+    };
+    if let Some(alpha) = transparency {
+        let sample = |index: usize| {
+            alpha
+                .get(index * 2..index * 2 + 2)
+                .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+        };
+        match header.color_type {
+            INDEXED => {
+                for (entry, &value) in colors.palette.iter_mut().zip(&alpha) {
+                    entry[3] = value;
+                }
+            }
+            GRAYSCALE => {
+                let gray = sample(0).ok_or(PngError::BadChunk("tRNS".to_string()))?;
+                colors.transparent = Some([gray; 3]);
+            }
+            RGB => {
+                let rgb = (0..3)
+                    .map(sample)
+                    .collect::<Option<Vec<u16>>>()
+                    .ok_or(PngError::BadChunk("tRNS".to_string()))?;
+                colors.transparent = Some([rgb[0], rgb[1], rgb[2]]);
+            }
+            _ => return Err(PngError::BadChunk("tRNS".to_string())),
+        }
+    }
+
+    let raw = zlib::inflate_zlib(&compressed).map_err(PngError::Compression)?;
+    decode_pixels(&header, &colors, &raw)
+}
+
+fn parse_header(body: &[u8]) -> Result<Header, PngError> {
+    if body.len() != 13 {
+        return Err(PngError::BadChunk("IHDR".to_string()));
+    }
+    let width = u32::from_be_bytes([body[0], body[1], body[2], body[3]]);
+    let height = u32::from_be_bytes([body[4], body[5], body[6], body[7]]);
+    let (bit_depth, color_type) = (body[8], body[9]);
+
+    if width == 0 || height == 0 || width > 1 << 24 || height > 1 << 24 {
+        return Err(PngError::BadDimensions { width, height });
+    }
+    let depth_allowed = match color_type {
+        GRAYSCALE => matches!(bit_depth, 1 | 2 | 4 | 8 | 16),
+        INDEXED => matches!(bit_depth, 1 | 2 | 4 | 8),
+        RGB | GRAYSCALE_ALPHA | RGBA => matches!(bit_depth, 8 | 16),
+        _ => false,
+    };
+    if !depth_allowed {
+        return Err(PngError::UnsupportedFormat {
+            color_type,
+            bit_depth,
+        });
+    }
+    if body[10] != 0 || body[11] != 0 || body[12] > 1 {
+        return Err(PngError::BadChunk("IHDR".to_string()));
+    }
+
+    Ok(Header {
+        width: width as usize,
+        height: height as usize,
+        bit_depth,
+        color_type,
+        interlaced: body[12] == 1,
+    })
+}
+
+/// Where each pass starts and how far apart its pixels are, as in [`ADAM7`].
+fn passes(header: &Header) -> &'static [(usize, usize, usize, usize)] {
+    if header.interlaced {
+        &ADAM7
+    } else {
+        &[(0, 0, 1, 1)]
+    }
+}
+
+/// Bytes of filtered rows the header calls for, or `None` if that does not fit in memory.
+fn filtered_size(header: &Header) -> Option<usize> {
+    let bits_per_pixel = header.bits_per_pixel();
+    passes(header)
+        .iter()
+        .try_fold(0usize, |total, &(x0, y0, dx, dy)| {
+            let columns = header.width.saturating_sub(x0).div_ceil(dx);
+            let rows = header.height.saturating_sub(y0).div_ceil(dy);
+            if columns == 0 || rows == 0 {
+                return Some(total);
+            }
+            let stride = columns.checked_mul(bits_per_pixel)?.div_ceil(8);
+            total.checked_add(rows.checked_mul(stride + 1)?)
+        })
+}
+
+/// Undoes the row filters of each pass and spreads the pixels into the image.
+fn decode_pixels(header: &Header, colors: &Colors, raw: &[u8]) -> Result<Image, PngError> {
+    // A header can claim far more pixels than a small file holds; check the data covers
+    // them before allocating the image.
+    let expected = filtered_size(header).ok_or(PngError::BadDimensions {
+        width: header.width as u32,
+        height: header.height as u32,
+    })?;
+    if raw.len() < expected {
+        return Err(PngError::ShortData {
+            expected,
+            available: raw.len(),
+        });
+    }
+
+    let mut image = Image::new(header.width as u32, header.height as u32);
+    let bits_per_pixel = header.bits_per_pixel();
+    let filter_step = bits_per_pixel.div_ceil(8);
+    let mut offset = 0;
+
+    for &(x0, y0, dx, dy) in passes(header) {
+        let columns = header.width.saturating_sub(x0).div_ceil(dx);
+        let rows = header.height.saturating_sub(y0).div_ceil(dy);
+        if columns == 0 || rows == 0 {
+            continue;
+        }
+
+        let stride = (columns * bits_per_pixel).div_ceil(8);
+        let needed = rows * (stride + 1);
+        let pass = raw
+            .get(offset..offset + needed)
+            .ok_or(PngError::ShortData {
+                expected: offset + needed,
+                available: raw.len(),
+            })?;
+        offset += needed;
+
+        let mut previous = vec![0u8; stride];
+        let mut current = vec![0u8; stride];
+        for (row, line) in pass.chunks_exact(stride + 1).enumerate() {
+            current.copy_from_slice(&line[1..]);
+            unfilter(line[0], &mut current, &previous, filter_step)?;
+
+            for column in 0..columns {
+                let rgba = pixel(header, colors, &current, column);
+                image.set_pixel((x0 + column * dx) as u32, (y0 + row * dy) as u32, rgba);
+            }
+            std::mem::swap(&mut previous, &mut current);
+        }
+    }
+
+    Ok(image)
+}
+
+fn unfilter(filter: u8, row: &mut [u8], previous: &[u8], step: usize) -> Result<(), PngError> {
+    for index in 0..row.len() {
+        let left = if index >= step { row[index - step] } else { 0 };
+        let up = previous[index];
+        let up_left = if index >= step {
+            previous[index - step]
+        } else {
+            0
+        };
+
+        let predicted = match filter {
+            0 => 0,
+            1 => left,
+            2 => up,
+            3 => ((u16::from(left) + u16::from(up)) / 2) as u8,
+            4 => paeth(left, up, up_left),
+            other => return Err(PngError::BadFilter(other)),
+        };
+        row[index] = row[index].wrapping_add(predicted);
+    }
+    Ok(())
+}
+
+fn paeth(left: u8, up: u8, up_left: u8) -> u8 {
+    let estimate = i16::from(left) + i16::from(up) - i16::from(up_left);
+    let distance = |value: u8| (estimate - i16::from(value)).abs();
+
+    if distance(left) <= distance(up) && distance(left) <= distance(up_left) {
+        left
+    } else if distance(up) <= distance(up_left) {
+        up
+    } else {
+        up_left
+    }
+}
+
+/// Sample `index` of a row, at the image's bit depth.
+fn sample(row: &[u8], depth: u8, index: usize) -> u16 {
+    match depth {
+        16 => u16::from_be_bytes([row[index * 2], row[index * 2 + 1]]),
+        8 => u16::from(row[index]),
+        _ => {
+            let bit = index * depth as usize;
+            let shift = 8 - depth as usize - bit % 8;
+            u16::from(row[bit / 8] >> shift) & ((1 << depth) - 1)
+        }
+    }
+}
+
+fn pixel(header: &Header, colors: &Colors, row: &[u8], column: usize) -> [u8; 4] {
+    let depth = header.bit_depth;
+    let channels = header.channels();
+    let raw = |channel: usize| sample(row, depth, column * channels + channel);
+    // Scales a sample to eight bits: 16-bit samples keep their high byte, small ones
+    // repeat to fill it.
+    let scale = |value: u16| match depth {
+        16 => (value >> 8) as u8,
+        8 => value as u8,
+        _ => (u32::from(value) * 255 / ((1 << depth) - 1)) as u8,
+    };
+    let opaque_unless = |samples: [u16; 3]| {
+        if colors.transparent == Some(samples) {
+            0
+        } else {
+            0xFF
+        }
+    };
+
+    match header.color_type {
+        GRAYSCALE => {
+            let gray = raw(0);
+            let level = scale(gray);
+            [level, level, level, opaque_unless([gray; 3])]
+        }
+        RGB => {
+            let rgb = [raw(0), raw(1), raw(2)];
+            [
+                scale(rgb[0]),
+                scale(rgb[1]),
+                scale(rgb[2]),
+                opaque_unless(rgb),
+            ]
+        }
+        INDEXED => colors
+            .palette
+            .get(raw(0) as usize)
+            .copied()
+            .unwrap_or([0, 0, 0, 0xFF]),
+        GRAYSCALE_ALPHA => {
+            let level = scale(raw(0));
+            [level, level, level, scale(raw(1))]
+        }
+        _ => [scale(raw(0)), scale(raw(1)), scale(raw(2)), scale(raw(3))],
+    }
+}
+
+/// A PNG file holding `image`.
+pub fn encode(image: &Image) -> Vec<u8> {
+    let opaque = image.rgba.chunks_exact(4).all(|pixel| pixel[3] == 0xFF);
+    let (color_type, channels) = if opaque { (RGB, 3) } else { (RGBA, 4) };
+
+    let width = image.width as usize;
+    let stride = width * channels;
+    let mut raw = Vec::with_capacity((stride + 1) * image.height as usize);
+    let mut previous = vec![0u8; stride];
+    let mut current = Vec::with_capacity(stride);
+    for row in image.rgba.chunks_exact(width * 4) {
+        current.clear();
+        for pixel in row.chunks_exact(4) {
+            current.extend_from_slice(&pixel[..channels]);
+        }
+        let (filter, filtered) = best_filter(&current, &previous, channels);
+        raw.push(filter);
+        raw.extend(filtered);
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    let mut header = Vec::with_capacity(13);
+    header.extend(image.width.to_be_bytes());
+    header.extend(image.height.to_be_bytes());
+    header.extend([8, color_type, 0, 0, 0]);
+
+    let mut png = SIGNATURE.to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &zlib::deflate_zlib(&raw));
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+/// The row filtered each way, keeping the one whose bytes sum smallest as signed values,
+/// the usual guess at what compresses best.
+fn best_filter(row: &[u8], previous: &[u8], step: usize) -> (u8, Vec<u8>) {
+    (0..=4u8)
+        .map(|filter| {
+            let filtered: Vec<u8> = (0..row.len())
+                .map(|index| {
+                    let left = if index >= step { row[index - step] } else { 0 };
+                    let up = previous[index];
+                    let up_left = if index >= step {
+                        previous[index - step]
+                    } else {
+                        0
+                    };
+                    let predicted = match filter {
+                        0 => 0,
+                        1 => left,
+                        2 => up,
+                        3 => ((u16::from(left) + u16::from(up)) / 2) as u8,
+                        _ => paeth(left, up, up_left),
+                    };
+                    row[index].wrapping_sub(predicted)
+                })
+                .collect();
+            (filter, filtered)
+        })
+        .min_by_key(|(_, filtered)| {
+            filtered
+                .iter()
+                .map(|&byte| u64::from((byte as i8).unsigned_abs()))
+                .sum::<u64>()
+        })
+        .expect("five filters")
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8], body: &[u8]) {
+    png.extend((body.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(body);
+    png.extend(crc32(&png[start..]).to_be_bytes());
+}
+
+/// CRC-32 as PNG chunks use it, over the chunk type and data.
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                0xEDB8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PngError {
+    /// The data does not start with the PNG signature.
+    NotPng,
+    /// A chunk runs past the end of the data.
+    Truncated,
+    BadCrc(String),
+    /// A chunk whose contents make no sense, such as a palette of 100 bytes.
+    BadChunk(String),
+    /// A chunk every decoder must understand that this one does not.
+    UnsupportedChunk(String),
+    MissingChunk(&'static str),
+    BadDimensions {
+        width: u32,
+        height: u32,
+    },
+    UnsupportedFormat {
+        color_type: u8,
+        bit_depth: u8,
+    },
+    Compression(&'static str),
+    BadFilter(u8),
+    /// The decompressed rows need `expected` bytes but there are only `available`.
+    ShortData {
+        expected: usize,
+        available: usize,
+    },
+}
+
+impl fmt::Display for PngError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PngError::NotPng => write!(f, "not a PNG file"),
+            PngError::Truncated => write!(f, "PNG data ends in the middle of a chunk"),
+            PngError::BadCrc(chunk) => write!(f, "{} chunk fails its CRC check", chunk),
+            PngError::BadChunk(chunk) => write!(f, "malformed {} chunk", chunk),
+            PngError::UnsupportedChunk(chunk) => {
+                write!(f, "unsupported critical chunk {}", chunk)
+            }
+            PngError::MissingChunk(chunk) => write!(f, "no {} chunk", chunk),
+            PngError::BadDimensions { width, height } => {
+                write!(f, "bad image size {}x{}", width, height)
+            }
+            PngError::UnsupportedFormat {
+                color_type,
+                bit_depth,
+            } => write!(
+                f,
+                "colour type {} at {} bits is not a PNG format",
+                color_type, bit_depth
+            ),
+            PngError::Compression(reason) => write!(f, "bad image data: {}", reason),
+            PngError::BadFilter(filter) => write!(f, "unknown row filter {}", filter),
+            PngError::ShortData {
+                expected,
+                available,
+            } => write!(
+                f,
+                "image data has {} bytes, {} are needed",
+                available, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PngError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn asset(name: &str) -> Vec<u8> {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../assets")
+            .join(name);
+        fs::read(&path).unwrap_or_else(|err| panic!("{}: {}", path.display(), err))
+    }
+
+    /// A PNG whose rows (filter bytes included) are stored uncompressed.
+    fn png(
+        size: (u32, u32),
+        depth: u8,
+        color: u8,
+        interlace: u8,
+        extra: &[(&[u8], &[u8])],
+        rows: &[u8],
+    ) -> Vec<u8> {
+        let mut ihdr = Vec::new();
+        ihdr.extend(size.0.to_be_bytes());
+        ihdr.extend(size.1.to_be_bytes());
+        ihdr.extend([depth, color, 0, 0, interlace]);
+
+        let mut idat = vec![0x78, 0x01, 0x01];
+        idat.extend((rows.len() as u16).to_le_bytes());
+        idat.extend((!(rows.len() as u16)).to_le_bytes());
+        idat.extend_from_slice(rows);
+        idat.extend(zlib::adler32(rows).to_be_bytes());
+
+        let mut png = SIGNATURE.to_vec();
+        write_chunk(&mut png, b"IHDR", &ihdr);
+        for (kind, body) in extra {
+            write_chunk(&mut png, kind, body);
+        }
+        write_chunk(&mut png, b"IDAT", &idat);
+        write_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+
+    #[test]
+    fn decodes_the_checked_in_icons() {
+        // Expected pixels come from an independent decode of the same files.
+        let icon = decode(&asset("original-icons/icon_001.png")).unwrap();
+        assert_eq!((icon.width, icon.height), (32, 32));
+        assert_eq!(icon.pixel(0, 0), [0, 0, 0, 0]);
+        assert_eq!(icon.pixel(16, 16), [0x80, 0x80, 0x80, 0xFF]);
+        assert_eq!(icon.pixel(10, 5), [0, 0, 0, 0xFF]);
+        assert_eq!(
+            icon.rgba.iter().map(|&b| u64::from(b)).sum::<u64>(),
+            390_112
+        );
+
+        let legacy = decode(&asset("stopbus-legacy.png")).unwrap();
+        assert_eq!(legacy.pixel(16, 16), [0, 0x80, 0x80, 0xFF]);
+        assert_eq!(legacy.pixel(10, 5), [0x80, 0x80, 0x80, 0xFF]);
+        assert_eq!(
+            legacy.rgba.iter().map(|&b| u64::from(b)).sum::<u64>(),
+            600_210
+        );
+    }
+
+    #[test]
+    fn decodes_every_colour_type() {
+        // 2x2 of each, one row per filter so all of them run.
+        let gray = png(
+            (2, 2),
+            8,
+            GRAYSCALE,
+            0,
+            &[(b"tRNS", &[0, 10])],
+            &[0, 10, 200, 3, 5, 5],
+        );
+        let image = decode(&gray).unwrap();
+        assert_eq!(image.pixel(0, 0), [10, 10, 10, 0]);
+        assert_eq!(image.pixel(1, 0), [200, 200, 200, 0xFF]);
+        assert_eq!(image.pixel(0, 1), [10, 10, 10, 0]);
+        // Average filter: 5 + (10 + 200) / 2, the left neighbour being the decoded 10.
+        assert_eq!(image.pixel(1, 1), [110, 110, 110, 0xFF]);
+
+        let rgb = png(
+            (1, 2),
+            16,
+            RGB,
+            0,
+            &[],
+            &[0, 1, 0, 2, 0, 3, 0, 2, 1, 0, 1, 0, 1, 0],
+        );
+        let image = decode(&rgb).unwrap();
+        assert_eq!(image.pixel(0, 0), [1, 2, 3, 0xFF]);
+        assert_eq!(image.pixel(0, 1), [2, 3, 4, 0xFF]);
+
+        let palette = [
+            (&b"PLTE"[..], &[255, 0, 0, 0, 0, 255][..]),
+            (b"tRNS", &[128]),
+        ];
+        let indexed = png((3, 1), 2, INDEXED, 0, &palette, &[4, 0b0001_0000]);
+        let image = decode(&indexed).unwrap();
+        assert_eq!(image.pixel(0, 0), [255, 0, 0, 128]);
+        assert_eq!(image.pixel(1, 0), [0, 0, 255, 0xFF]);
+        assert_eq!(image.pixel(2, 0), [255, 0, 0, 128]);
+
+        let gray_alpha = png((1, 1), 8, GRAYSCALE_ALPHA, 0, &[], &[0, 50, 60]);
+        assert_eq!(decode(&gray_alpha).unwrap().pixel(0, 0), [50, 50, 50, 60]);
+
+        let one_bit = png((9, 1), 1, GRAYSCALE, 0, &[], &[0, 0b1000_0001, 0b1000_0000]);
+        let image = decode(&one_bit).unwrap();
+        let levels: Vec<u8> = (0..9).map(|x| image.pixel(x, 0)[0]).collect();
+        assert_eq!(levels, [255, 0, 0, 0, 0, 0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn decodes_interlaced_images() {
+        // A 3x3 RGBA image: passes 1, 4 (x=2), 5 (y=2), 6 (x=1, rows 0 and 2) and 7 (row 1).
+        let px = |v: u8| [v, v, v, 0xFF];
+        let mut rows = Vec::new();
+        rows.push(0);
+        rows.extend(px(1));
+        rows.push(0);
+        rows.extend(px(2));
+        rows.push(0);
+        rows.extend(px(3));
+        rows.extend(px(4));
+        for v in [5, 6] {
+            rows.push(0);
+            rows.extend(px(v));
+        }
+        rows.push(0);
+        for v in [7, 8, 9] {
+            rows.extend(px(v));
+        }
+
+        let image = decode(&png((3, 3), 8, RGBA, 1, &[], &rows)).unwrap();
+        let levels: Vec<u8> = (0..9).map(|i| image.pixel(i % 3, i / 3)[0]).collect();
+        assert_eq!(levels, [1, 5, 2, 7, 8, 9, 3, 6, 4]);
+    }
+
+    #[test]
+    fn encodes_what_it_decodes() {
+        let icon = decode(&asset("original-icons/icon_001.png")).unwrap();
+        let encoded = encode(&icon);
+        assert_eq!(encoded[25], RGBA);
+        assert_eq!(decode(&encoded).unwrap(), icon);
+        assert_eq!(encode(&icon), encoded);
+
+        let mut opaque = icon.clone();
+        opaque.flatten([0, 0x80, 0]);
+        let encoded = encode(&opaque);
+        assert_eq!(encoded[25], RGB);
+        assert_eq!(decode(&encoded).unwrap(), opaque);
+    }
+
+    #[test]
+    fn reports_typed_errors() {
+        let good = png((1, 1), 8, GRAYSCALE, 0, &[], &[0, 1]);
+        assert!(decode(&good).is_ok());
+
+        assert_eq!(decode(b"BM not a png"), Err(PngError::NotPng));
+        assert_eq!(decode(&good[..good.len() - 6]), Err(PngError::Truncated));
+
+        let mut bad_crc = good.clone();
+        bad_crc[20] ^= 1;
+        assert_eq!(decode(&bad_crc), Err(PngError::BadCrc("IHDR".to_string())));
+
+        assert_eq!(
+            decode(&png((1, 1), 16, INDEXED, 0, &[], &[0, 1])),
+            Err(PngError::UnsupportedFormat {
+                color_type: 3,
+                bit_depth: 16
+            })
+        );
+        assert_eq!(
+            decode(&png((1, 1), 8, GRAYSCALE, 0, &[], &[7, 1])),
+            Err(PngError::BadFilter(7))
+        );
+        assert_eq!(
+            decode(&png((2, 2), 8, GRAYSCALE, 0, &[], &[0, 1, 2])),
+            Err(PngError::ShortData {
+                expected: 6,
+                available: 3
+            })
+        );
+        assert_eq!(
+            decode(&png((100_000, 100_000), 8, RGBA, 0, &[], &[0, 1, 2])),
+            Err(PngError::ShortData {
+                expected: 100_000 * (100_000 * 4 + 1),
+                available: 3
+            }),
+            "refused before allocating the image"
+        );
+        assert!(matches!(
+            decode(&png((100_000, 100_000), 8, RGBA, 1, &[], &[0, 1, 2])),
+            Err(PngError::ShortData { available: 3, .. })
+        ));
+        assert_eq!(
+            decode(&png((1, 1), 8, INDEXED, 0, &[], &[0, 0])),
+            Err(PngError::MissingChunk("PLTE"))
+        );
+        assert_eq!(
+            decode(&png((1, 1), 8, GRAYSCALE, 0, &[(b"ZZZZ", &[])], &[0, 1])),
+            Err(PngError::UnsupportedChunk("ZZZZ".to_string()))
+        );
+    }
+}
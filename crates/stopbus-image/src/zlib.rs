@@ -0,0 +1,523 @@
+//! The zlib stream inside PNG `IDAT` chunks: a two-byte header, DEFLATE data (RFC 1951)
+//! and an Adler-32 checksum of the result.
+//!
+//! The inflater decodes Huffman codes a bit at a time, canonical-code style, which is
+//! plenty for card-sized images and keeps the tables small. The deflater is as plain:
+//! greedy LZ77 matching written with the fixed codes, so the same input always gives the
+//! same bytes.
+
+/// Maximum bits in a DEFLATE Huffman code.
+const MAX_BITS: usize = 15;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// The longest back reference and how far it may reach.
+const MAX_MATCH: usize = 258;
+const MIN_MATCH: usize = 3;
+const WINDOW: usize = 32 * 1024;
+/// How many earlier positions with the same three-byte hash are tried for each match.
+const MAX_CHAIN: usize = 64;
+const HASH_BITS: u32 = 15;
+
+/// Order the code length code lengths are sent in a dynamic block header.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// Decompresses a zlib stream, checking its header and checksum.
+pub(crate) fn inflate_zlib(data: &[u8]) -> Result<Vec<u8>, &'static str> {
+    let (&cmf, &flg) = match data {
+        [cmf, flg, ..] => (cmf, flg),
+        _ => return Err("zlib header is missing"),
+    };
+    if cmf & 0x0F != 8 || cmf >> 4 > 7 || (u16::from(cmf) << 8 | u16::from(flg)) % 31 != 0 {
+        return Err("bad zlib header");
+    }
+    if flg & 0x20 != 0 {
+        return Err("zlib preset dictionaries are not supported");
+    }
+
+    let mut bits = Bits::new(&data[2..]);
+    let output = inflate(&mut bits)?;
+
+    let checksum = bits.aligned_tail();
+    match checksum.get(..4) {
+        Some(bytes)
+            if u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) == adler32(&output) =>
+        {
+            Ok(output)
+        }
+        Some(_) => Err("zlib checksum does not match"),
+        None => Err("zlib checksum is missing"),
+    }
+}
+
+pub(crate) fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += u32::from(byte);
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    b << 16 | a
+}
+
+/// Compresses `data` into a zlib stream of one fixed-code DEFLATE block.
+pub(crate) fn deflate_zlib(data: &[u8]) -> Vec<u8> {
+    // Deflate with a 32K window, default compression level.
+    let mut writer = BitWriter::new(vec![0x78, 0x9C]);
+    writer.put(1, 1);
+    writer.put(1, 2);
+
+    let hash = |at: usize| {
+        let value =
+            u32::from(data[at]) << 16 | u32::from(data[at + 1]) << 8 | u32::from(data[at + 2]);
+        (value.wrapping_mul(0x9E37_79B1) >> (32 - HASH_BITS)) as usize
+    };
+    // The latest position for each hash, and for each position the one before it.
+    let mut head = vec![usize::MAX; 1 << HASH_BITS];
+    let mut previous = vec![usize::MAX; WINDOW];
+    let insert = |at: usize, head: &mut [usize], previous: &mut [usize]| {
+        if at + MIN_MATCH <= data.len() {
+            let key = hash(at);
+            previous[at % WINDOW] = head[key];
+            head[key] = at;
+        }
+    };
+
+    let mut position = 0;
+    while position < data.len() {
+        let (mut length, mut distance) = (0, 0);
+        if position + MIN_MATCH <= data.len() {
+            let limit = MAX_MATCH.min(data.len() - position);
+            let mut candidate = head[hash(position)];
+            for _ in 0..MAX_CHAIN {
+                if candidate == usize::MAX || position - candidate > WINDOW {
+                    break;
+                }
+                let matched = data[candidate..]
+                    .iter()
+                    .zip(&data[position..position + limit])
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                if matched > length {
+                    length = matched;
+                    distance = position - candidate;
+                    if matched == limit {
+                        break;
+                    }
+                }
+                let next = previous[candidate % WINDOW];
+                if next == usize::MAX || next >= candidate {
+                    break;
+                }
+                candidate = next;
+            }
+        }
+
+        if length >= MIN_MATCH {
+            writer.put_length(length);
+            writer.put_distance(distance);
+            for at in position..position + length {
+                insert(at, &mut head, &mut previous);
+            }
+            position += length;
+        } else {
+            writer.put_literal(u16::from(data[position]));
+            insert(position, &mut head, &mut previous);
+            position += 1;
+        }
+    }
+    writer.put_literal(256);
+
+    let mut output = writer.finish();
+    output.extend(adler32(data).to_be_bytes());
+    output
+}
+
+fn inflate(bits: &mut Bits) -> Result<Vec<u8>, &'static str> {
+    let mut output = Vec::new();
+
+    loop {
+        let last = bits.take(1)? == 1;
+        match bits.take(2)? {
+            0 => stored(bits, &mut output)?,
+            1 => {
+                let (literals, distances) = fixed_codes();
+                codes(bits, &mut output, &literals, &distances)?
+            }
+            2 => {
+                let (literals, distances) = dynamic_codes(bits)?;
+                codes(bits, &mut output, &literals, &distances)?
+            }
+            _ => return Err("bad DEFLATE block type"),
+        }
+        if last {
+            return Ok(output);
+        }
+    }
+}
+
+fn stored(bits: &mut Bits, output: &mut Vec<u8>) -> Result<(), &'static str> {
+    bits.align();
+    let length = bits.take(16)?;
+    let complement = bits.take(16)?;
+    if length != !complement & 0xFFFF {
+        return Err("stored block length does not match its complement");
+    }
+    for _ in 0..length {
+        output.push(bits.take(8)? as u8);
+    }
+    Ok(())
+}
+
+fn codes(
+    bits: &mut Bits,
+    output: &mut Vec<u8>,
+    literals: &Huffman,
+    distances: &Huffman,
+) -> Result<(), &'static str> {
+    loop {
+        let symbol = literals.decode(bits)? as usize;
+        match symbol {
+            0..=255 => output.push(symbol as u8),
+            256 => return Ok(()),
+            _ => {
+                let index = symbol - 257;
+                if index >= LENGTH_BASE.len() {
+                    return Err("bad length code");
+                }
+                let length = LENGTH_BASE[index] as usize
+                    + bits.take(u32::from(LENGTH_EXTRA[index]))? as usize;
+
+                let index = distances.decode(bits)? as usize;
+                if index >= DISTANCE_BASE.len() {
+                    return Err("bad distance code");
+                }
+                let distance = DISTANCE_BASE[index] as usize
+                    + bits.take(u32::from(DISTANCE_EXTRA[index]))? as usize;
+                if distance > output.len() {
+                    return Err("distance reaches before the start of the data");
+                }
+
+                let start = output.len() - distance;
+                for offset in 0..length {
+                    output.push(output[start + offset]);
+                }
+            }
+        }
+    }
+}
+
+fn fixed_codes() -> (Huffman, Huffman) {
+    let mut lengths = [0u8; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+
+    (
+        Huffman::new(&lengths).expect("fixed literal code"),
+        Huffman::new(&[5; 30]).expect("fixed distance code"),
+    )
+}
+
+fn dynamic_codes(bits: &mut Bits) -> Result<(Huffman, Huffman), &'static str> {
+    let literal_count = bits.take(5)? as usize + 257;
+    let distance_count = bits.take(5)? as usize + 1;
+    let code_length_count = bits.take(4)? as usize + 4;
+    if literal_count > 286 || distance_count > 30 {
+        return Err("too many codes in dynamic block");
+    }
+
+    let mut code_lengths = [0u8; 19];
+    for &index in &CODE_LENGTH_ORDER[..code_length_count] {
+        code_lengths[index] = bits.take(3)? as u8;
+    }
+    let code_length_code = Huffman::new(&code_lengths)?;
+
+    let mut lengths = Vec::with_capacity(literal_count + distance_count);
+    while lengths.len() < literal_count + distance_count {
+        let (value, repeat) = match code_length_code.decode(bits)? {
+            symbol @ 0..=15 => (symbol as u8, 1),
+            16 => {
+                let previous = *lengths.last().ok_or("repeat with no previous length")?;
+                (previous, 3 + bits.take(2)?)
+            }
+            17 => (0, 3 + bits.take(3)?),
+            _ => (0, 11 + bits.take(7)?),
+        };
+        if lengths.len() + repeat as usize > literal_count + distance_count {
+            return Err("code lengths overrun the block header");
+        }
+        lengths.extend(std::iter::repeat_n(value, repeat as usize));
+    }
+    if lengths[256] == 0 {
+        return Err("dynamic block has no end-of-block code");
+    }
+
+    Ok((
+        Huffman::new(&lengths[..literal_count])?,
+        Huffman::new(&lengths[literal_count..])?,
+    ))
+}
+
+/// A canonical Huffman code: how many codes there are of each length, and the symbols
+/// in code order.
+struct Huffman {
+    counts: [u16; MAX_BITS + 1],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    /// Builds the code for symbols with these code lengths, zero meaning unused. Codes
+    /// that claim more than the bit space holds are rejected; incomplete ones are allowed,
+    /// as a block may use a single distance code.
+    fn new(lengths: &[u8]) -> Result<Self, &'static str> {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for &length in lengths {
+            counts[length as usize] += 1;
+        }
+        counts[0] = 0;
+
+        let mut left = 1i32;
+        for &count in &counts[1..] {
+            left = (left << 1) - i32::from(count);
+            if left < 0 {
+                return Err("Huffman code is over-subscribed");
+            }
+        }
+
+        let mut offsets = [0u16; MAX_BITS + 2];
+        for length in 1..=MAX_BITS {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+        let mut symbols = vec![0; offsets[MAX_BITS + 1] as usize];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                symbols[offsets[length as usize] as usize] = symbol as u16;
+                offsets[length as usize] += 1;
+            }
+        }
+
+        Ok(Self { counts, symbols })
+    }
+
+    fn decode(&self, bits: &mut Bits) -> Result<u16, &'static str> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+
+        for &count in &self.counts[1..] {
+            code |= bits.take(1)? as i32;
+            let count = i32::from(count);
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+
+        Err("bad Huffman code")
+    }
+}
+
+/// Reads bits least significant first, as DEFLATE packs them.
+struct Bits<'a> {
+    data: &'a [u8],
+    position: usize,
+    buffer: u32,
+    count: u32,
+}
+
+impl<'a> Bits<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            position: 0,
+            buffer: 0,
+            count: 0,
+        }
+    }
+
+    fn take(&mut self, wanted: u32) -> Result<u32, &'static str> {
+        while self.count < wanted {
+            let byte = *self
+                .data
+                .get(self.position)
+                .ok_or("compressed data ends early")?;
+            self.position += 1;
+            self.buffer |= u32::from(byte) << self.count;
+            self.count += 8;
+        }
+
+        let value = self.buffer & ((1u64 << wanted) - 1) as u32;
+        self.buffer >>= wanted;
+        self.count -= wanted;
+        Ok(value)
+    }
+
+    /// Drops the bits left in the current byte.
+    fn align(&mut self) {
+        self.buffer >>= self.count % 8;
+        self.count -= self.count % 8;
+    }
+
+    /// The bytes after the current one, once the rest of it is dropped.
+    fn aligned_tail(&mut self) -> &'a [u8] {
+        self.align();
+        let buffered = (self.count / 8) as usize;
+        &self.data[self.position - buffered..]
+    }
+}
+
+/// Writes bits least significant first, and Huffman codes most significant first.
+struct BitWriter {
+    output: Vec<u8>,
+    buffer: u32,
+    count: u32,
+}
+
+impl BitWriter {
+    fn new(output: Vec<u8>) -> Self {
+        Self {
+            output,
+            buffer: 0,
+            count: 0,
+        }
+    }
+
+    fn put(&mut self, value: u32, bits: u32) {
+        self.buffer |= value << self.count;
+        self.count += bits;
+        while self.count >= 8 {
+            self.output.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    fn put_code(&mut self, code: u32, bits: u32) {
+        self.put(code.reverse_bits() >> (32 - bits), bits);
+    }
+
+    /// A literal byte or the end-of-block symbol, in the fixed literal/length code.
+    fn put_literal(&mut self, symbol: u16) {
+        let symbol = u32::from(symbol);
+        match symbol {
+            0..=143 => self.put_code(0x30 + symbol, 8),
+            144..=255 => self.put_code(0x190 + symbol - 144, 9),
+            256..=279 => self.put_code(symbol - 256, 7),
+            _ => self.put_code(0xC0 + symbol - 280, 8),
+        }
+    }
+
+    fn put_length(&mut self, length: usize) {
+        let index = LENGTH_BASE
+            .iter()
+            .rposition(|&base| usize::from(base) <= length)
+            .expect("match length of at least 3");
+        self.put_literal(257 + index as u16);
+        self.put(
+            (length - usize::from(LENGTH_BASE[index])) as u32,
+            u32::from(LENGTH_EXTRA[index]),
+        );
+    }
+
+    fn put_distance(&mut self, distance: usize) {
+        let index = DISTANCE_BASE
+            .iter()
+            .rposition(|&base| usize::from(base) <= distance)
+            .expect("distance of at least 1");
+        self.put_code(index as u32, 5);
+        self.put(
+            (distance - usize::from(DISTANCE_BASE[index])) as u32,
+            u32::from(DISTANCE_EXTRA[index]),
+        );
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.output.push(self.buffer as u8);
+        }
+        self.output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// "Stop the Bus! " three times, compressed by zlib with fixed codes and a back
+    /// reference.
+    const FIXED: [u8; 25] = [
+        0x78, 0xda, 0x0b, 0x2e, 0xc9, 0x2f, 0x50, 0x28, 0xc9, 0x48, 0x55, 0x70, 0x2a, 0x2d, 0x56,
+        0x54, 0x08, 0xc6, 0xcd, 0x03, 0x00, 0x22, 0x9c, 0x0d, 0x97,
+    ];
+
+    #[test]
+    fn inflates_fixed_and_stored_blocks() {
+        assert_eq!(
+            inflate_zlib(&FIXED).unwrap(),
+            b"Stop the Bus! Stop the Bus! Stop the Bus!"
+        );
+
+        // One final stored block holding "abc".
+        let mut stored = vec![0x78, 0x01, 0x01, 3, 0, 0xFC, 0xFF];
+        stored.extend_from_slice(b"abc");
+        stored.extend_from_slice(&adler32(b"abc").to_be_bytes());
+        assert_eq!(inflate_zlib(&stored).unwrap(), b"abc");
+    }
+
+    #[test]
+    fn deflates_what_it_inflates() {
+        let samples: [Vec<u8>; 4] = [
+            Vec::new(),
+            b"Stop the Bus! Stop the Bus! Stop the Bus!".to_vec(),
+            (0..70_000u32)
+                .map(|i| (i.wrapping_mul(i) / 7 % 251) as u8)
+                .collect(),
+            vec![0x80; 1000],
+        ];
+
+        for sample in &samples {
+            let compressed = deflate_zlib(sample);
+            assert_eq!(&inflate_zlib(&compressed).unwrap(), sample);
+            assert_eq!(deflate_zlib(sample), compressed);
+        }
+        // A run shrinks to a literal and a handful of back references.
+        assert!(deflate_zlib(&samples[3]).len() < 20);
+    }
+
+    #[test]
+    fn reports_damaged_streams() {
+        let mut bad_checksum = FIXED;
+        bad_checksum[24] ^= 1;
+        assert_eq!(
+            inflate_zlib(&bad_checksum),
+            Err("zlib checksum does not match")
+        );
+        assert_eq!(
+            inflate_zlib(&FIXED[..12]),
+            Err("compressed data ends early")
+        );
+        assert_eq!(inflate_zlib(&[0x78, 0x00]), Err("bad zlib header"));
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+}
@@ -0,0 +1,118 @@
+//! Trains the learned Stop the Bus policy by self-play and writes the weights file.
+//!
+//! Usage: stopbus-train [--games N] [--seed N] [--epsilon F] [--final-epsilon F]
+//!                      [--resume PATH] [--eval N] [--out PATH]
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use stopbus_core::learned::{PolicyTable, LEARNED_POLICY_FILE};
+use stopbus_core::{ClassicStrategy, LearnedStrategy};
+use stopbus_lab::{evaluate_seat0, train, TrainingConfig};
+
+const DEFAULT_EVAL_GAMES: u64 = 2_000;
+
+struct Options {
+    config: TrainingConfig,
+    resume: Option<PathBuf>,
+    output: PathBuf,
+    eval_games: u64,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        config: TrainingConfig::default(),
+        resume: None,
+        output: PathBuf::from(LEARNED_POLICY_FILE),
+        eval_games: DEFAULT_EVAL_GAMES,
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--games" => options.config.games = parse(&value()?)?,
+            "--seed" => options.config.seed = parse(&value()?)?,
+            "--epsilon" => options.config.epsilon = parse(&value()?)?,
+            "--final-epsilon" => options.config.final_epsilon = parse(&value()?)?,
+            "--resume" => options.resume = Some(PathBuf::from(value()?)),
+            "--eval" => options.eval_games = parse(&value()?)?,
+            "--out" => options.output = PathBuf::from(value()?),
+            "-h" | "--help" => {
+                return Err("usage: stopbus-train [--games N] [--seed N] [--epsilon F] \
+                     [--final-epsilon F] [--resume PATH] [--eval N] [--out PATH]"
+                    .to_string())
+            }
+            other => return Err(format!("unknown argument '{}'", other)),
+        }
+    }
+
+    Ok(options)
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{}'", value))
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let table = match &options.resume {
+        Some(path) => match PolicyTable::load(path) {
+            Ok(table) => table,
+            Err(err) => {
+                eprintln!("{}: {}", path.display(), err);
+                return ExitCode::FAILURE;
+            }
+        },
+        None => PolicyTable::new(),
+    };
+
+    let games = options.config.games;
+    let report_every = (games / 20).max(1);
+    let table = train(&options.config, table, |done, table| {
+        if done % report_every == 0 || done == games {
+            println!(
+                "trained {}/{} games, {} states visited",
+                done,
+                games,
+                table.visited_states()
+            );
+        }
+    });
+
+    if let Err(err) = table.save(&options.output) {
+        eprintln!("{}: {}", options.output.display(), err);
+        return ExitCode::FAILURE;
+    }
+    println!("wrote {}", options.output.display());
+
+    if options.eval_games > 0 {
+        let seeds = 0..options.eval_games;
+        let table = Arc::new(table);
+        let learned = evaluate_seat0(seeds.clone(), || {
+            Box::new(LearnedStrategy::new(Arc::clone(&table)))
+        });
+        let classic = evaluate_seat0(seeds, || Box::new(ClassicStrategy::default()));
+
+        println!(
+            "seat 0 wins over {} games: learned {} ({:.1}%), classic {} ({:.1}%)",
+            learned.games,
+            learned.wins,
+            learned.win_rate() * 100.0,
+            classic.wins,
+            classic.win_rate() * 100.0
+        );
+    }
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,96 @@
+//! Evolves the classic AI thresholds by simulated play and writes difficulty presets.
+//!
+//! Usage: stopbus-tune [--population N] [--generations N] [--games N] [--seed N]
+//!                     [--easy-target F] [--top N] [--out PATH]
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use stopbus_core::params::PRESETS_FILE;
+use stopbus_core::AiParams;
+use stopbus_lab::tune::{tune, TuningConfig};
+
+const DEFAULT_TOP: usize = 5;
+
+struct Options {
+    config: TuningConfig,
+    output: PathBuf,
+    top: usize,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        config: TuningConfig::default(),
+        output: PathBuf::from(PRESETS_FILE),
+        top: DEFAULT_TOP,
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--population" => options.config.population = parse(&value()?)?,
+            "--generations" => options.config.generations = parse(&value()?)?,
+            "--games" => options.config.games = parse(&value()?)?,
+            "--seed" => options.config.seed = parse(&value()?)?,
+            "--easy-target" => options.config.easy_target = parse(&value()?)?,
+            "--top" => options.top = parse(&value()?)?,
+            "--out" => options.output = PathBuf::from(value()?),
+            "-h" | "--help" => {
+                return Err("usage: stopbus-tune [--population N] [--generations N] \
+                     [--games N] [--seed N] [--easy-target F] [--top N] [--out PATH]"
+                    .to_string())
+            }
+            other => return Err(format!("unknown argument '{}'", other)),
+        }
+    }
+
+    Ok(options)
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{}'", value))
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let result = tune(&options.config, |generation, best| {
+        println!(
+            "generation {}: best {:?} won {}/{}",
+            generation, best.params, best.evaluation.wins, best.evaluation.games
+        );
+    });
+
+    println!(
+        "classic {:?} won {}/{}",
+        AiParams::CLASSIC,
+        result.classic.wins,
+        result.classic.games
+    );
+    for (rank, scored) in result.ranked.iter().take(options.top).enumerate() {
+        println!(
+            "#{} {:?} won {:.1}%",
+            rank + 1,
+            scored.params,
+            scored.evaluation.win_rate() * 100.0
+        );
+    }
+
+    let presets = result.presets(options.config.easy_target);
+    if let Err(err) = presets.save(&options.output) {
+        eprintln!("{}: {}", options.output.display(), err);
+        return ExitCode::FAILURE;
+    }
+    println!("wrote {}", options.output.display());
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,261 @@
+//! Offline experiments with the Stop the Bus AI: self-play training of the learned policy,
+//! tuning of the classic thresholds and head-to-head evaluation against the classic heuristic.
+
+pub mod tune;
+
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use stopbus_core::learned::{PolicyAction, PolicyState, PolicyTable};
+use stopbus_core::sim::{self, MAX_GAME_STEPS};
+use stopbus_core::{AutoMove, ClassicStrategy, GameState, SeatView, Strategy, PLAYERS};
+
+/// Return credited to every decision a seat made in a round where it lost a life.
+pub const LIFE_LOST_RETURN: f32 = -1.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainingConfig {
+    pub games: usize,
+    pub seed: u64,
+    /// Probability of a random legal action, decayed linearly to `final_epsilon`.
+    pub epsilon: f32,
+    pub final_epsilon: f32,
+}
+
+impl Default for TrainingConfig {
+    fn default() -> Self {
+        Self {
+            games: 1_000_000,
+            seed: 1,
+            epsilon: 0.3,
+            final_epsilon: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Decision {
+    player: usize,
+    state: PolicyState,
+    action: PolicyAction,
+}
+
+#[derive(Debug)]
+struct SelfPlay {
+    table: PolicyTable,
+    rng: StdRng,
+    epsilon: f32,
+    decisions: Vec<Decision>,
+}
+
+/// Seat that explores around the shared table and logs what it chose.
+#[derive(Debug, Clone)]
+struct TrainingStrategy {
+    player: usize,
+    shared: Arc<Mutex<SelfPlay>>,
+}
+
+impl Strategy for TrainingStrategy {
+    fn choose_move(&mut self, view: &SeatView<'_>) -> AutoMove {
+        let state = PolicyState::from_view(view);
+        let legal = PolicyState::legal_actions(view);
+        let mut shared = self.shared.lock().unwrap();
+
+        let explore = shared.rng.gen::<f32>() < shared.epsilon;
+        let action = match shared.table.best_action(&state, &legal) {
+            Some(action) if !explore => action,
+            _ => legal[shared.rng.gen_range(0..legal.len())],
+        };
+
+        shared.decisions.push(Decision {
+            player: self.player,
+            state,
+            action,
+        });
+        action.to_move(view)
+    }
+}
+
+/// Trains a policy by self-play, continuing from `table`. `progress` is called after every
+/// game with the number of games completed.
+pub fn train(
+    config: &TrainingConfig,
+    table: PolicyTable,
+    mut progress: impl FnMut(usize, &PolicyTable),
+) -> PolicyTable {
+    let shared = Arc::new(Mutex::new(SelfPlay {
+        table,
+        rng: StdRng::seed_from_u64(config.seed),
+        epsilon: config.epsilon,
+        decisions: Vec::new(),
+    }));
+
+    for game_index in 0..config.games {
+        let fraction = game_index as f32 / config.games.max(1) as f32;
+        let game_seed = {
+            let mut shared = shared.lock().unwrap();
+            shared.epsilon = config.epsilon + (config.final_epsilon - config.epsilon) * fraction;
+            shared.decisions.clear();
+            shared.rng.gen::<u64>()
+        };
+
+        let mut game = GameState::new(Some(game_seed));
+        for player in 0..PLAYERS {
+            game.set_strategy(
+                player,
+                Box::new(TrainingStrategy {
+                    player,
+                    shared: Arc::clone(&shared),
+                }),
+            );
+        }
+
+        play_training_game(&mut game, &shared);
+        progress(game_index + 1, &shared.lock().unwrap().table);
+    }
+
+    let shared = Arc::try_unwrap(shared).expect("training seats outlived their games");
+    shared.into_inner().unwrap().table
+}
+
+fn play_training_game(game: &mut GameState, shared: &Arc<Mutex<SelfPlay>>) {
+    let mut report = game.start_fresh();
+    let mut lives = *game.lives();
+    let mut steps = 1;
+
+    while !report.game_over() && steps < MAX_GAME_STEPS {
+        report = sim::next_report(game, &report);
+        steps += 1;
+
+        // Every finished round costs somebody a life, so a change marks the round boundary.
+        let after = *game.lives();
+        if after != lives {
+            credit_round(&mut shared.lock().unwrap(), &lives, &after);
+            lives = after;
+        }
+    }
+
+    // Decisions from a stalled round taught nothing.
+    shared.lock().unwrap().decisions.clear();
+}
+
+fn credit_round(shared: &mut SelfPlay, before: &[u8; PLAYERS], after: &[u8; PLAYERS]) {
+    let decisions = std::mem::take(&mut shared.decisions);
+    for decision in decisions {
+        let reward = if after[decision.player] < before[decision.player] {
+            LIFE_LOST_RETURN
+        } else {
+            0.0
+        };
+        shared
+            .table
+            .record(&decision.state, decision.action, reward);
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Evaluation {
+    pub games: usize,
+    /// Games won by seat 0.
+    pub wins: usize,
+    pub draws: usize,
+    pub stalled: usize,
+}
+
+impl Evaluation {
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            self.wins as f64 / self.games as f64
+        }
+    }
+}
+
+/// Plays one game per seed with `seat0` in seat 0 and the classic AI everywhere else.
+pub fn evaluate_seat0(seeds: Range<u64>, seat0: impl Fn() -> Box<dyn Strategy>) -> Evaluation {
+    let mut evaluation = Evaluation::default();
+
+    for seed in seeds {
+        let mut strategies: [Box<dyn Strategy>; PLAYERS] =
+            std::array::from_fn(|_| Box::new(ClassicStrategy::default()) as Box<dyn Strategy>);
+        strategies[0] = seat0();
+
+        let outcome = sim::play_game(seed, strategies);
+        evaluation.games += 1;
+        if outcome.winner == Some(0) {
+            evaluation.wins += 1;
+        }
+        if outcome.draw {
+            evaluation.draws += 1;
+        }
+        if outcome.stalled {
+            evaluation.stalled += 1;
+        }
+    }
+
+    evaluation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use stopbus_core::learned::LEARNED_POLICY_FILE;
+    use stopbus_core::LearnedStrategy;
+
+    #[test]
+    fn training_is_deterministic_for_a_seed() {
+        let config = TrainingConfig {
+            games: 50,
+            ..TrainingConfig::default()
+        };
+
+        let first = train(&config, PolicyTable::new(), |_, _| {});
+        let second = train(&config, PolicyTable::new(), |_, _| {});
+
+        assert_eq!(first, second);
+        assert!(first.visited_states() > 0);
+    }
+
+    #[test]
+    fn progress_reports_every_game() {
+        let config = TrainingConfig {
+            games: 5,
+            ..TrainingConfig::default()
+        };
+        let mut seen = Vec::new();
+
+        train(&config, PolicyTable::new(), |games, _| seen.push(games));
+        assert_eq!(seen, vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn classic_evaluation_counts_every_seed() {
+        let evaluation = evaluate_seat0(0..20, || Box::new(ClassicStrategy::default()));
+
+        assert_eq!(evaluation.games, 20);
+        assert!(evaluation.wins <= 20);
+    }
+
+    #[test]
+    fn shipped_policy_beats_classic_in_seat_zero() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../..")
+            .join(LEARNED_POLICY_FILE);
+        let learned = LearnedStrategy::load(&path).expect("shipped policy should load");
+
+        let learned = evaluate_seat0(0..1000, || Box::new(learned.clone()));
+        let classic = evaluate_seat0(0..1000, || Box::new(ClassicStrategy::default()));
+
+        assert!(
+            learned.wins > classic.wins,
+            "learned won {} games, classic won {}",
+            learned.wins,
+            classic.wins
+        );
+    }
+}
@@ -0,0 +1,287 @@
+//! Genetic search over [`AiParams`], scored by seat-0 wins against classic opponents.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use stopbus_core::params::MAX_THRESHOLD;
+use stopbus_core::{AiParams, AiPresets, ClassicStrategy};
+
+use crate::{evaluate_seat0, Evaluation};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TuningConfig {
+    pub population: usize,
+    pub generations: usize,
+    /// Games played by each candidate per evaluation.
+    pub games: u64,
+    pub seed: u64,
+    /// Candidates copied unchanged into the next generation.
+    pub elite: usize,
+    pub mutation_rate: f64,
+    /// Seat-0 win rate the `easy` preset should come closest to.
+    pub easy_target: f64,
+}
+
+impl Default for TuningConfig {
+    fn default() -> Self {
+        Self {
+            population: 24,
+            generations: 15,
+            games: 1_000,
+            seed: 1,
+            elite: 4,
+            mutation_rate: 0.3,
+            easy_target: 0.15,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scored {
+    pub params: AiParams,
+    pub evaluation: Evaluation,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TuningResult {
+    /// Every distinct candidate evaluated, best first.
+    pub ranked: Vec<Scored>,
+    /// The classic thresholds scored on the same seeds.
+    pub classic: Evaluation,
+}
+
+impl TuningResult {
+    pub fn best(&self) -> Scored {
+        self.ranked[0]
+    }
+
+    /// `easy` is the candidate closest to the target win rate, `normal` the original
+    /// thresholds and `hard` the strongest candidate found.
+    pub fn presets(&self, easy_target: f64) -> AiPresets {
+        let easy = self
+            .ranked
+            .iter()
+            .min_by(|a, b| {
+                let distance = |scored: &Scored| (scored.evaluation.win_rate() - easy_target).abs();
+                distance(a).total_cmp(&distance(b))
+            })
+            .map(|scored| scored.params)
+            .unwrap_or(AiParams::CLASSIC);
+
+        let mut presets = AiPresets::new();
+        presets.insert("easy", easy);
+        presets.insert("normal", AiParams::CLASSIC);
+        presets.insert("hard", self.best().params);
+        presets
+    }
+}
+
+/// Evolves a population of parameter sets. `progress` receives the generation number and
+/// its best candidate.
+pub fn tune(config: &TuningConfig, mut progress: impl FnMut(usize, &Scored)) -> TuningResult {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let seeds = seeds(config);
+    let mut cache: HashMap<AiParams, Evaluation> = HashMap::new();
+
+    let mut population = vec![AiParams::CLASSIC];
+    while population.len() < config.population.max(2) {
+        population.push(random_params(&mut rng));
+    }
+
+    for generation in 1..=config.generations.max(1) {
+        let mut scored: Vec<Scored> = population
+            .iter()
+            .map(|&params| score(params, &seeds, &mut cache))
+            .collect();
+        rank(&mut scored);
+        progress(generation, &scored[0]);
+
+        if generation == config.generations.max(1) {
+            break;
+        }
+
+        let elite = config.elite.clamp(1, scored.len());
+        let mut next: Vec<AiParams> = scored[..elite].iter().map(|s| s.params).collect();
+        while next.len() < population.len() {
+            let first = tournament(&scored, &mut rng);
+            let second = tournament(&scored, &mut rng);
+            let child = mutate(
+                crossover(first, second, &mut rng),
+                config.mutation_rate,
+                &mut rng,
+            );
+            next.push(child);
+        }
+        population = next;
+    }
+
+    let mut ranked: Vec<Scored> = cache
+        .iter()
+        .map(|(&params, &evaluation)| Scored { params, evaluation })
+        .collect();
+    rank(&mut ranked);
+
+    TuningResult {
+        ranked,
+        classic: cache[&AiParams::CLASSIC],
+    }
+}
+
+/// The games every candidate plays: a block per tuning seed, moved down when it would run
+/// past `u64::MAX`.
+fn seeds(config: &TuningConfig) -> Range<u64> {
+    let first = config
+        .seed
+        .wrapping_mul(1_000_000)
+        .min(u64::MAX - config.games);
+    first..first + config.games
+}
+
+fn score(
+    params: AiParams,
+    seeds: &Range<u64>,
+    cache: &mut HashMap<AiParams, Evaluation>,
+) -> Scored {
+    let evaluation = *cache.entry(params).or_insert_with(|| {
+        evaluate_seat0(seeds.clone(), || Box::new(ClassicStrategy::new(params)))
+    });
+    Scored { params, evaluation }
+}
+
+/// Sorts by wins, breaking ties on the parameters so the order is reproducible.
+fn rank(scored: &mut [Scored]) {
+    scored.sort_by(|a, b| {
+        b.evaluation.wins.cmp(&a.evaluation.wins).then_with(|| {
+            let key = |p: &AiParams| (p.stick_above, p.first_swap_above, p.draw_swap_above);
+            key(&a.params).cmp(&key(&b.params))
+        })
+    });
+}
+
+fn random_params(rng: &mut StdRng) -> AiParams {
+    AiParams {
+        stick_above: rng.gen_range(15..=MAX_THRESHOLD),
+        first_swap_above: rng.gen_range(0..=20),
+        draw_swap_above: rng.gen_range(0..=20),
+    }
+}
+
+fn tournament(scored: &[Scored], rng: &mut StdRng) -> AiParams {
+    let first = &scored[rng.gen_range(0..scored.len())];
+    let second = &scored[rng.gen_range(0..scored.len())];
+    if first.evaluation.wins >= second.evaluation.wins {
+        first.params
+    } else {
+        second.params
+    }
+}
+
+fn crossover(first: AiParams, second: AiParams, rng: &mut StdRng) -> AiParams {
+    let mut pick = |a: u8, b: u8| if rng.gen_bool(0.5) { a } else { b };
+    AiParams {
+        stick_above: pick(first.stick_above, second.stick_above),
+        first_swap_above: pick(first.first_swap_above, second.first_swap_above),
+        draw_swap_above: pick(first.draw_swap_above, second.draw_swap_above),
+    }
+}
+
+fn mutate(params: AiParams, rate: f64, rng: &mut StdRng) -> AiParams {
+    let mut nudge = |value: u8| {
+        if rng.gen_bool(rate.clamp(0.0, 1.0)) {
+            (value as i16 + rng.gen_range(-3..=3)).clamp(0, MAX_THRESHOLD as i16) as u8
+        } else {
+            value
+        }
+    };
+    AiParams {
+        stick_above: nudge(params.stick_above),
+        first_swap_above: nudge(params.first_swap_above),
+        draw_swap_above: nudge(params.draw_swap_above),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn small_config() -> TuningConfig {
+        TuningConfig {
+            population: 6,
+            generations: 3,
+            games: 30,
+            elite: 2,
+            ..TuningConfig::default()
+        }
+    }
+
+    #[test]
+    fn tuning_is_deterministic_for_a_seed() {
+        let first = tune(&small_config(), |_, _| {});
+        let second = tune(&small_config(), |_, _| {});
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn best_candidate_is_never_worse_than_classic() {
+        let mut generations = Vec::new();
+        let result = tune(&small_config(), |generation, _| {
+            generations.push(generation)
+        });
+
+        assert_eq!(generations, vec![1, 2, 3]);
+        assert!(result.best().evaluation.wins >= result.classic.wins);
+        assert!(result
+            .ranked
+            .windows(2)
+            .all(|pair| pair[0].evaluation.wins >= pair[1].evaluation.wins));
+    }
+
+    #[test]
+    fn large_seeds_still_play_every_game() {
+        for (seed, games) in [(0, 1_000), (u64::MAX, 1_000), (1, u64::MAX - 10)] {
+            let config = TuningConfig {
+                seed,
+                games,
+                ..TuningConfig::default()
+            };
+            let seeds = seeds(&config);
+            assert_eq!(seeds.end - seeds.start, games, "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn presets_load_back_from_text() {
+        let result = tune(&small_config(), |_, _| {});
+        let presets = result.presets(0.15);
+
+        let loaded = AiPresets::from_text(&presets.to_text()).unwrap();
+        assert_eq!(loaded.get("normal"), Some(AiParams::CLASSIC));
+        assert_eq!(loaded.get("hard"), Some(result.best().params));
+        assert!(loaded.get("easy").is_some());
+    }
+
+    #[test]
+    fn shipped_presets_load_and_hard_beats_normal() {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../..")
+            .join(stopbus_core::params::PRESETS_FILE);
+        let presets = AiPresets::load(&path).expect("shipped presets should load");
+
+        assert!(presets.get("easy").is_some());
+        assert_eq!(presets.get("normal"), Some(AiParams::CLASSIC));
+
+        let hard = presets.get("hard").unwrap();
+        let hard = evaluate_seat0(0..1000, || Box::new(ClassicStrategy::new(hard)));
+        let normal = evaluate_seat0(0..1000, || Box::new(ClassicStrategy::default()));
+        assert!(
+            hard.wins > normal.wins,
+            "hard won {} games, normal won {}",
+            hard.wins,
+            normal.wins
+        );
+    }
+}
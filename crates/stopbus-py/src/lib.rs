@@ -0,0 +1,330 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use stopbus_core::{
+    self as core, CardId, DriveReport, GameEvent, GameState, MessageKind, RoundSummary, Suit,
+    HAND_SIZE, PLAYERS,
+};
+
+#[pyclass(name = "GameEvent", module = "stopbus", frozen)]
+#[derive(Clone)]
+struct PyGameEvent {
+    #[pyo3(get)]
+    kind: &'static str,
+    #[pyo3(get)]
+    text: String,
+}
+
+impl From<&GameEvent> for PyGameEvent {
+    fn from(event: &GameEvent) -> Self {
+        let kind = match event.kind {
+            MessageKind::Info => "info",
+            MessageKind::Alert => "alert",
+        };
+
+        Self {
+            kind,
+            text: event.text.clone(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyGameEvent {
+    fn __repr__(&self) -> String {
+        format!("GameEvent(kind={:?}, text={:?})", self.kind, self.text)
+    }
+}
+
+#[pyclass(name = "RoundSummary", module = "stopbus", frozen)]
+#[derive(Clone)]
+struct PyRoundSummary {
+    #[pyo3(get)]
+    hands: Vec<Option<Vec<Option<CardId>>>>,
+    #[pyo3(get)]
+    round_scores: Vec<u32>,
+    #[pyo3(get)]
+    stick_player: Option<usize>,
+    #[pyo3(get)]
+    stop_player: Option<usize>,
+    #[pyo3(get)]
+    lives_before: Vec<u32>,
+    #[pyo3(get)]
+    lives_after: Vec<u32>,
+}
+
+impl From<&RoundSummary> for PyRoundSummary {
+    fn from(summary: &RoundSummary) -> Self {
+        Self {
+            hands: summary
+                .hands
+                .iter()
+                .map(|hand| hand.map(|cards| cards.to_vec()))
+                .collect(),
+            round_scores: widen(&summary.round_scores),
+            stick_player: summary.stick_player,
+            stop_player: summary.stop_player,
+            lives_before: widen(&summary.lives_before),
+            lives_after: widen(&summary.lives_after),
+        }
+    }
+}
+
+#[pymethods]
+impl PyRoundSummary {
+    fn __repr__(&self) -> String {
+        format!(
+            "RoundSummary(round_scores={:?}, stick_player={:?}, stop_player={:?}, lives_after={:?})",
+            self.round_scores, self.stick_player, self.stop_player, self.lives_after
+        )
+    }
+}
+
+#[pyclass(name = "DriveReport", module = "stopbus", frozen)]
+struct PyDriveReport {
+    #[pyo3(get)]
+    events: Vec<PyGameEvent>,
+    #[pyo3(get)]
+    awaiting_human: bool,
+    #[pyo3(get)]
+    winner: Option<usize>,
+    #[pyo3(get)]
+    draw: bool,
+    #[pyo3(get)]
+    turn_sequence: Vec<usize>,
+    #[pyo3(get)]
+    round_summary: Option<PyRoundSummary>,
+}
+
+impl From<DriveReport> for PyDriveReport {
+    fn from(report: DriveReport) -> Self {
+        Self {
+            events: report.events.iter().map(PyGameEvent::from).collect(),
+            awaiting_human: report.awaiting_human,
+            winner: report.winner,
+            draw: report.draw,
+            turn_sequence: report.turn_sequence,
+            round_summary: report.round_summary.as_ref().map(PyRoundSummary::from),
+        }
+    }
+}
+
+#[pymethods]
+impl PyDriveReport {
+    fn game_over(&self) -> bool {
+        self.winner.is_some() || self.draw
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "DriveReport(awaiting_human={}, winner={:?}, draw={}, turn_sequence={:?}, events={})",
+            self.awaiting_human,
+            self.winner,
+            self.draw,
+            self.turn_sequence,
+            self.events.len()
+        )
+    }
+}
+
+#[pyclass(name = "GameState", module = "stopbus")]
+struct PyGameState {
+    inner: GameState,
+}
+
+#[pymethods]
+impl PyGameState {
+    #[new]
+    #[pyo3(signature = (seed=None))]
+    fn new(seed: Option<u64>) -> Self {
+        Self {
+            inner: GameState::new(seed),
+        }
+    }
+
+    fn start_game(&mut self) {
+        self.inner.start_game();
+    }
+
+    fn start_fresh(&mut self) -> PyDriveReport {
+        self.inner.start_fresh().into()
+    }
+
+    fn start_new_round(&mut self) -> PyDriveReport {
+        self.inner.start_new_round().into()
+    }
+
+    fn advance_after_human_turn(&mut self) -> PyDriveReport {
+        self.inner.advance_after_human_turn().into()
+    }
+
+    fn continue_automation(&mut self) -> PyDriveReport {
+        self.inner.continue_automation().into()
+    }
+
+    fn auto_play_human_turn(&mut self) -> Option<PyDriveReport> {
+        self.inner.auto_play_human_turn().map(Into::into)
+    }
+
+    fn apply_stick(&mut self, player: usize) -> PyResult<bool> {
+        check_player(player)?;
+        Ok(self.inner.apply_stick(player))
+    }
+
+    fn human_stick(&mut self) -> Option<PyDriveReport> {
+        self.inner.human_stick().map(Into::into)
+    }
+
+    fn human_swap_with_stack(&mut self, slot: usize) -> Option<PyDriveReport> {
+        self.inner.human_swap_with_stack(slot).map(Into::into)
+    }
+
+    fn human_draw_next_card(&mut self) -> Option<PyDriveReport> {
+        self.inner.human_draw_next_card().map(Into::into)
+    }
+
+    fn set_lives(&mut self, lives: [u8; PLAYERS]) {
+        self.inner.set_lives(lives);
+    }
+
+    fn update_round_scores(&mut self) {
+        self.inner.update_round_scores();
+    }
+
+    fn lowest_alive_score(&self) -> Option<u8> {
+        self.inner.lowest_alive_score()
+    }
+
+    fn player_has_stop_the_bus(&self, player: usize) -> bool {
+        self.inner.player_has_stop_the_bus(player)
+    }
+
+    #[getter]
+    fn lives(&self) -> Vec<u32> {
+        // u8 sequences convert to `bytes` in pyo3, so widen to get a plain list.
+        widen(self.inner.lives())
+    }
+
+    #[getter]
+    fn hands(&self) -> Vec<Vec<Option<CardId>>> {
+        self.inner.hands.iter().map(|hand| hand.to_vec()).collect()
+    }
+
+    #[getter]
+    fn deck(&self) -> Vec<u32> {
+        widen(&self.inner.deck)
+    }
+
+    #[getter]
+    fn round_scores(&self) -> Vec<u32> {
+        widen(&self.inner.round_scores)
+    }
+
+    #[getter]
+    fn awaiting_human(&self) -> bool {
+        self.inner.awaiting_human()
+    }
+
+    #[getter]
+    fn human_can_stick(&self) -> bool {
+        self.inner.human_can_stick()
+    }
+
+    #[getter]
+    fn current_player(&self) -> usize {
+        self.inner.current_player()
+    }
+
+    #[getter]
+    fn round_start_player(&self) -> usize {
+        self.inner.round_start_player()
+    }
+
+    #[getter]
+    fn stick_player(&self) -> Option<usize> {
+        self.inner.stick_player()
+    }
+
+    #[getter]
+    fn stack_index(&self) -> usize {
+        self.inner.stack_index()
+    }
+
+    #[getter]
+    fn stack_top_card(&self) -> Option<CardId> {
+        self.inner.stack_top_card()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "GameState(lives={:?}, current_player={}, stack_top_card={:?})",
+            self.inner.lives(),
+            self.inner.current_player(),
+            self.inner.stack_top_card()
+        )
+    }
+}
+
+fn widen(values: &[u8]) -> Vec<u32> {
+    values.iter().map(|&value| u32::from(value)).collect()
+}
+
+fn check_player(player: usize) -> PyResult<()> {
+    if player < PLAYERS {
+        Ok(())
+    } else {
+        Err(PyValueError::new_err(format!(
+            "player must be in 0..{}, got {}",
+            PLAYERS, player
+        )))
+    }
+}
+
+#[pyfunction]
+fn card_rank(card: CardId) -> Option<u8> {
+    core::card_rank(card)
+}
+
+#[pyfunction]
+fn card_suit(card: CardId) -> Option<&'static str> {
+    core::card_suit(card).map(|suit| match suit {
+        Suit::Clubs => "clubs",
+        Suit::Diamonds => "diamonds",
+        Suit::Hearts => "hearts",
+        Suit::Spades => "spades",
+    })
+}
+
+#[pyfunction]
+fn card_points(card: CardId) -> u8 {
+    core::card_points(card)
+}
+
+#[pyfunction]
+fn hand_max_score(cards: Vec<Option<CardId>>) -> PyResult<u8> {
+    let hand: [Option<CardId>; HAND_SIZE] = cards.try_into().map_err(|cards: Vec<_>| {
+        PyValueError::new_err(format!(
+            "hand must have {} slots, got {}",
+            HAND_SIZE,
+            cards.len()
+        ))
+    })?;
+
+    Ok(core::hand_max_score(&hand))
+}
+
+#[pymodule]
+fn stopbus(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyGameState>()?;
+    module.add_class::<PyDriveReport>()?;
+    module.add_class::<PyGameEvent>()?;
+    module.add_class::<PyRoundSummary>()?;
+    module.add_function(wrap_pyfunction!(card_rank, module)?)?;
+    module.add_function(wrap_pyfunction!(card_suit, module)?)?;
+    module.add_function(wrap_pyfunction!(card_points, module)?)?;
+    module.add_function(wrap_pyfunction!(hand_max_score, module)?)?;
+    module.add("PLAYERS", PLAYERS)?;
+    module.add("HAND_SIZE", HAND_SIZE)?;
+    module.add("DECK_SIZE", core::DECK_SIZE)?;
+    Ok(())
+}
@@ -0,0 +1,109 @@
+"""Seeded game checks for the ``stopbus`` extension module.
+
+Build the module with ``maturin develop`` from ``crates/stopbus-py`` and run
+``pytest``. The expected outcomes mirror the Rust tests in
+``crates/stopbus-core/src/lib.rs`` so both layers agree on the same seeds.
+"""
+
+import stopbus
+
+
+def play_seeded_autoplay_game(seed):
+    game = stopbus.GameState(seed)
+    report = game.start_fresh()
+    steps = 0
+
+    while not report.game_over() and steps < 10_000:
+        if report.awaiting_human:
+            report = game.auto_play_human_turn()
+            assert report is not None, "human seat should be on turn"
+        else:
+            report = game.continue_automation()
+        steps += 1
+
+    return game, report, steps
+
+
+def test_card_helpers_match_core():
+    assert stopbus.card_rank(1) == 1
+    assert stopbus.card_rank(14) == 1
+    assert stopbus.card_rank(53) is None
+    assert stopbus.card_suit(1) == "clubs"
+    assert stopbus.card_suit(14) == "diamonds"
+    assert stopbus.card_suit(27) == "hearts"
+    assert stopbus.card_suit(40) == "spades"
+    assert stopbus.card_points(1) == 11
+    assert stopbus.card_points(12) == 10
+    assert stopbus.card_points(8) == 8
+
+
+def test_hand_scoring_matches_core():
+    assert stopbus.hand_max_score([1, 14, 27]) == 11
+    assert stopbus.hand_max_score([1, 2, 3]) == 16
+    assert stopbus.hand_max_score([27, 36, 39]) == 31
+    assert stopbus.hand_max_score([None, 2, 3]) == 5
+
+
+def test_hand_scoring_rejects_wrong_length():
+    try:
+        stopbus.hand_max_score([1, 2])
+    except ValueError:
+        pass
+    else:
+        raise AssertionError("expected ValueError for a two-card hand")
+
+
+def test_new_game_state():
+    game = stopbus.GameState(42)
+    assert game.lives == [3, 3, 3, 3]
+    assert len(game.deck) == stopbus.DECK_SIZE
+    assert sorted(game.deck) == list(range(1, 53))
+    assert not game.awaiting_human
+
+
+def test_human_actions_require_turn():
+    game = stopbus.GameState(7)
+    game.start_game()
+    assert game.human_stick() is None
+    assert game.human_swap_with_stack(0) is None
+    assert game.human_draw_next_card() is None
+    assert game.auto_play_human_turn() is None
+
+
+def test_seeded_autoplay_game_matches_rust():
+    game, report, steps = play_seeded_autoplay_game(2024)
+
+    # Same expectations as test_seeded_autoplay_game_is_reproducible in stopbus-core.
+    assert report.game_over()
+    assert report.winner == 2
+    assert game.lives == [0, 0, 1, 0]
+    assert steps == 68
+
+
+def test_seeded_games_are_reproducible():
+    first, first_report, first_steps = play_seeded_autoplay_game(99)
+    second, second_report, second_steps = play_seeded_autoplay_game(99)
+
+    assert first_report.winner == second_report.winner
+    assert first.lives == second.lives
+    assert first_steps == second_steps
+
+
+def test_round_summaries_reveal_every_live_hand():
+    game = stopbus.GameState(2024)
+    report = game.start_fresh()
+    summaries = []
+
+    while not report.game_over():
+        if report.awaiting_human:
+            report = game.auto_play_human_turn()
+        else:
+            report = game.continue_automation()
+        if report.round_summary is not None:
+            summaries.append(report.round_summary)
+
+    assert len(summaries) > 1
+    for summary in summaries:
+        for player, hand in enumerate(summary.hands):
+            assert (hand is not None) == (summary.lives_before[player] > 0)
+    assert summaries[-1].lives_after == game.lives
@@ -0,0 +1,337 @@
+//! Draws the table without Windows GDI, for bug reports, documentation and tests.
+//!
+//! [`Scene::table`] lists what `WindowState::paint` in the Win32 front end draws for a
+//! [`ViewModel`]: the back of the deck, the top card of the stack, the human's hand, the
+//! lives table with the start player, turn pointer and stick marker, or the showdown in
+//! their place. [`raster`] draws a scene into an [`Image`](stopbus_image::Image) for PNG
+//! files and [`svg`] writes the same pixels as an SVG document, both with the card art
+//! of a [`Theme`](stopbus_theme::Theme). Text uses the bitmap [`font`], so neither
+//! depends on the fonts installed.
+
+use stopbus_core::narration::describe_round_result;
+use stopbus_core::{GameState, PLAYERS};
+use stopbus_frontend::layout::BASE_CLIENT_SIZE;
+use stopbus_frontend::{Effect, Key, Layout, Presenter, Rect, Target, ViewModel};
+use stopbus_theme::{Theme, BACK, CLASSIC_TABLE_COLOR, CROSS};
+
+pub mod font;
+pub mod raster;
+pub mod record;
+pub mod svg;
+
+/// The keyboard focus frame, as the front end's `FOCUS_COLOR`.
+pub const FOCUS_COLOR: [u8; 3] = [0xFF, 0xFF, 0x00];
+
+pub const FOCUS_FRAME_WIDTH: i32 = 3;
+
+pub const TEXT_COLOR: [u8; 3] = [0x00, 0x00, 0x00];
+
+/// One thing drawn on the table, in design pixels.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Shape {
+    /// Card art for a theme slot, stretched to `rect`, with its corners rounded off.
+    Card { slot: u8, rect: Rect },
+    /// A frame `width` pixels thick just outside `rect`.
+    Frame {
+        rect: Rect,
+        width: i32,
+        color: [u8; 3],
+    },
+    /// A line of text with the top left of its line at `(x, y)`.
+    Text { x: i32, y: i32, text: String },
+}
+
+/// Everything drawn on the table, back to front.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Scene {
+    pub width: i32,
+    pub height: i32,
+    pub table_color: [u8; 3],
+    /// Line height of the text, which sets the size it is drawn at.
+    pub font_height: i32,
+    pub shapes: Vec<Shape>,
+}
+
+impl Scene {
+    /// [`Scene::table`] at the design size, on the theme's table colour.
+    pub fn new(view: &ViewModel, theme: &Theme) -> Self {
+        let color = theme.table_color.unwrap_or(CLASSIC_TABLE_COLOR);
+        Self::table(view, &Self::design_layout(), color)
+    }
+
+    /// The table at the original 600x400 window's client size.
+    pub fn design_layout() -> Layout {
+        Layout::new(BASE_CLIENT_SIZE.0, BASE_CLIENT_SIZE.1, 1.0, PLAYERS)
+    }
+
+    /// What `WindowState::paint` draws for `view`.
+    pub fn table(view: &ViewModel, layout: &Layout, table_color: [u8; 3]) -> Self {
+        let mut shapes = Vec::new();
+        let card = |card: Option<u8>| card.unwrap_or(CROSS);
+
+        if view.showdown.is_none() {
+            shapes.push(Shape::Card {
+                slot: BACK,
+                rect: layout.stack,
+            });
+            for (slot, rect) in layout.hand.iter().enumerate() {
+                shapes.push(Shape::Card {
+                    slot: card(view.hand[slot]),
+                    rect: *rect,
+                });
+            }
+            if let Some(rect) = view.focused_slot.and_then(|slot| layout.hand.get(slot)) {
+                shapes.push(Shape::Frame {
+                    rect: *rect,
+                    width: FOCUS_FRAME_WIDTH,
+                    color: FOCUS_COLOR,
+                });
+            }
+            if let Some(slot) = view.stack_top {
+                shapes.push(Shape::Card {
+                    slot,
+                    rect: layout.top_card,
+                });
+            }
+            if let Some(drag) = view.drag {
+                let (rect, slot) = match drag.source {
+                    Target::HandCard(slot) => (layout.hand[slot], view.hand[slot]),
+                    Target::TopCard => (layout.top_card, view.stack_top),
+                    Target::Stack => (layout.stack, Some(BACK)),
+                };
+                if let Some(slot) = slot {
+                    let (dx, dy) = drag.offset();
+                    shapes.push(Shape::Card {
+                        slot,
+                        rect: Rect::new(rect.x + dx, rect.y + dy, rect.width, rect.height),
+                    });
+                }
+            }
+        }
+
+        match &view.showdown {
+            Some(summary) => {
+                for (player, row) in layout.showdown.iter().enumerate() {
+                    let name = if player == 0 {
+                        "You".to_string()
+                    } else {
+                        format!("Player {}", player + 1)
+                    };
+                    text(&mut shapes, row.name, name);
+
+                    let Some(hand) = summary.hands[player] else {
+                        text(&mut shapes, row.detail, "out");
+                        continue;
+                    };
+                    for (slot, rect) in hand.iter().zip(row.cards) {
+                        shapes.push(Shape::Card {
+                            slot: card(*slot),
+                            rect,
+                        });
+                    }
+                    text(
+                        &mut shapes,
+                        row.detail,
+                        describe_round_result(summary, player),
+                    );
+                }
+            }
+            None => {
+                text(&mut shapes, layout.deck_label, "Deck:");
+                text(&mut shapes, layout.hand_label, "Your hand:");
+            }
+        }
+
+        text(&mut shapes, layout.lives_heading, "Remaining Lives:");
+        for (index, (row, lives)) in layout.seats.iter().zip(view.lives.iter()).enumerate() {
+            text(&mut shapes, row.label, format!("Player {} -", index + 1));
+            text(&mut shapes, row.lives, lives.to_string());
+        }
+        text(
+            &mut shapes,
+            layout.start_player,
+            format!("Player {} to start", view.round_start_player + 1),
+        );
+        if let Some(row) = layout.seats.get(view.pointer_player) {
+            text(&mut shapes, row.pointer, " ->");
+        }
+        if let Some(row) = view
+            .stick_player
+            .and_then(|player| layout.seats.get(player))
+        {
+            text(&mut shapes, row.stick_marker, "***");
+        }
+
+        Self {
+            width: layout.client.width,
+            height: layout.client.height,
+            table_color,
+            font_height: layout.font_height,
+            shapes,
+        }
+    }
+
+    /// Size of one pixel of the [`font`]: whole multiples of the stock 16-pixel line.
+    pub fn text_scale(&self) -> i32 {
+        (self.font_height / stopbus_frontend::layout::FONT_HEIGHT).max(1)
+    }
+}
+
+fn text(shapes: &mut Vec<Shape>, (x, y): (i32, i32), text: impl Into<String>) {
+    shapes.push(Shape::Text {
+        x,
+        y,
+        text: text.into(),
+    });
+}
+
+/// The pixels GDI's card drawing paints over with the table colour to round a card's
+/// corners: three at each corner.
+pub fn card_corners(rect: Rect) -> Vec<(i32, i32)> {
+    if rect.width <= 1 || rect.height <= 1 {
+        return vec![(rect.x, rect.y)];
+    }
+    let (right, bottom) = (rect.right() - 1, rect.bottom() - 1);
+    vec![
+        (rect.x, rect.y),
+        (rect.x + 1, rect.y),
+        (rect.x, rect.y + 1),
+        (right, rect.y),
+        (right - 1, rect.y),
+        (right, rect.y + 1),
+        (rect.x, bottom),
+        (rect.x + 1, bottom),
+        (rect.x, bottom - 1),
+        (right, bottom),
+        (right - 1, bottom),
+        (right, bottom - 1),
+    ]
+}
+
+/// Keys as typed in the terminal front end, with `.` for Enter: `.2s.` dismisses a
+/// showdown, selects the second card, swaps it with the stack and ends the turn.
+pub fn parse_keys(text: &str) -> Option<Vec<Key>> {
+    text.chars()
+        .map(|c| match c {
+            '.' => Some(Key::Enter),
+            c if c.is_ascii_alphanumeric() => Some(Key::Char(c.to_ascii_lowercase())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A seeded game after dealing and pressing `keys`, with the AI turns played out as the
+/// turn timer would play them.
+pub fn play(seed: u64, keys: &[Key]) -> Presenter {
+    let mut presenter = Presenter::new(GameState::new(Some(seed)));
+    for key in std::iter::once(Key::Char('n')).chain(keys.iter().copied()) {
+        let effects = presenter.key_press(key);
+        settle(&mut presenter, effects, |_| {});
+    }
+    presenter
+}
+
+/// Applies `effects` and any that follow until the presenter waits for input, calling
+/// `repaint` wherever the window would repaint.
+fn settle(presenter: &mut Presenter, effects: Vec<Effect>, mut repaint: impl FnMut(&Presenter)) {
+    let mut queue = effects;
+    loop {
+        if queue.contains(&Effect::Repaint) {
+            repaint(presenter);
+        }
+        let mut next = Vec::new();
+        for effect in queue {
+            if effect == Effect::ContinueAutomation {
+                next.extend(presenter.continue_automation());
+            }
+        }
+        if next.is_empty() && presenter.turn_timer_active() {
+            next = presenter.timer_tick();
+        }
+        if next.is_empty() {
+            return;
+        }
+        queue = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use stopbus_image::png;
+    use stopbus_theme::CLASSIC_CARDS_DIR;
+
+    fn root() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../..")
+    }
+
+    /// Compares the rendering of a seeded position with the files in `golden/`, which
+    /// `cargo run -p stopbus-render -- --seed SEED --keys KEYS golden/NAME.png
+    /// golden/NAME.svg` writes again from the repository root.
+    fn assert_golden(name: &str, seed: u64, keys: &str) {
+        let theme = Theme::classic(&root().join(CLASSIC_CARDS_DIR)).unwrap();
+        let presenter = play(seed, &parse_keys(keys).unwrap());
+        let scene = Scene::new(&presenter.view(), &theme);
+        let golden = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("golden");
+        let regenerate = format!(
+            "cargo run -p stopbus-render -- --seed {} --keys '{}' \
+             crates/stopbus-render/golden/{name}.png crates/stopbus-render/golden/{name}.svg",
+            seed, keys
+        );
+
+        let expected =
+            png::decode(&std::fs::read(golden.join(format!("{}.png", name))).unwrap()).unwrap();
+        let image = raster::draw(&scene, &theme);
+        assert!(image == expected, "{}.png differs; {}", name, regenerate);
+
+        let expected = std::fs::read_to_string(golden.join(format!("{}.svg", name))).unwrap();
+        assert!(
+            svg::write(&scene, &theme) == expected,
+            "{}.svg differs; {}",
+            name,
+            regenerate
+        );
+    }
+
+    #[test]
+    fn matches_the_golden_table() {
+        assert_golden("table", 2, "2s.2s.3");
+    }
+
+    #[test]
+    fn matches_the_golden_showdown() {
+        assert_golden("showdown", 2024, "");
+    }
+
+    #[test]
+    fn draws_the_lives_table_markers() {
+        let presenter = play(2, &parse_keys("2s.2s.3").unwrap());
+        let view = presenter.view();
+        let layout = Scene::design_layout();
+        let scene = Scene::table(&view, &layout, CLASSIC_TABLE_COLOR);
+
+        let text = |x: i32, y: i32, text: &str| Shape::Text {
+            x,
+            y,
+            text: text.to_string(),
+        };
+        let (pointer, stick) = (layout.seats[0].pointer, layout.seats[3].stick_marker);
+        assert_eq!(view.stick_player, Some(3));
+        assert!(scene.shapes.contains(&text(pointer.0, pointer.1, " ->")));
+        assert!(scene.shapes.contains(&text(stick.0, stick.1, "***")));
+        assert!(scene.shapes.contains(&Shape::Frame {
+            rect: layout.hand[2],
+            width: FOCUS_FRAME_WIDTH,
+            color: FOCUS_COLOR,
+        }));
+        assert_eq!(
+            scene.shapes[0],
+            Shape::Card {
+                slot: BACK,
+                rect: layout.stack
+            }
+        );
+        assert_eq!(parse_keys("a!"), None);
+    }
+}
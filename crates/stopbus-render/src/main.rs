@@ -0,0 +1,158 @@
+//! Renders a position from a seeded game to SVG or PNG, for bug reports and docs, or
+//! records the whole game.
+//!
+//! Usage: stopbus-render --seed N [--keys KEYS] [--theme DIR] [--record] OUTPUT...
+//!
+//! The game is dealt and KEYS pressed as in the terminal front end, with `.` for Enter;
+//! the AI turns in between are played out. Each OUTPUT ending in `.svg` or `.png` gets
+//! the table as the Win32 window would paint it. With `--record` the AI then plays the
+//! human's seat to the end of the game: a `.gif` OUTPUT gets the animation, and `.svg`
+//! or `.png` ones are numbered, `game.png` becoming `game-0001.png` and on. The classic
+//! cards are read from `crates/stopbus-ui/resources/cards` under the current directory
+//! unless a theme folder is given.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use stopbus_frontend::Key;
+use stopbus_image::png;
+use stopbus_render::record::{self, Frame};
+use stopbus_render::{parse_keys, play, raster, svg, Scene};
+use stopbus_theme::{Theme, CLASSIC_CARDS_DIR};
+
+const USAGE: &str =
+    "usage: stopbus-render --seed N [--keys KEYS] [--theme DIR] [--record] OUTPUT...";
+
+struct Options {
+    seed: u64,
+    keys: Vec<Key>,
+    theme: Option<PathBuf>,
+    record: bool,
+    outputs: Vec<PathBuf>,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut seed = None;
+    let mut keys = Vec::new();
+    let mut theme = None;
+    let mut record = false;
+    let mut outputs = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--seed" => {
+                let text = value()?;
+                seed = Some(
+                    text.parse()
+                        .map_err(|_| format!("invalid seed '{}'", text))?,
+                );
+            }
+            "--keys" => {
+                let text = value()?;
+                keys = parse_keys(&text).ok_or_else(|| format!("invalid keys '{}'", text))?;
+            }
+            "--theme" => theme = Some(PathBuf::from(value()?)),
+            "--record" => record = true,
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            other if other.starts_with('-') => return Err(format!("unknown argument '{}'", other)),
+            output => outputs.push(PathBuf::from(output)),
+        }
+    }
+
+    match seed {
+        Some(seed) if !outputs.is_empty() => Ok(Options {
+            seed,
+            keys,
+            theme,
+            record,
+            outputs,
+        }),
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn run(options: Options) -> Result<(), String> {
+    let theme = match &options.theme {
+        Some(dir) => Theme::load(dir),
+        None => Theme::classic(Path::new(CLASSIC_CARDS_DIR)),
+    }
+    .map_err(|err| err.to_string())?;
+
+    if options.record {
+        let frames = record::record(options.seed, &options.keys, &theme);
+        for output in &options.outputs {
+            write_recording(output, &frames, &theme)?;
+        }
+        return Ok(());
+    }
+
+    let presenter = play(options.seed, &options.keys);
+    let scene = Scene::new(&presenter.view(), &theme);
+    for output in &options.outputs {
+        write(output, &scene, &theme)?;
+    }
+    Ok(())
+}
+
+fn write(output: &Path, scene: &Scene, theme: &Theme) -> Result<(), String> {
+    let data = match extension(output) {
+        Some("svg") => svg::write(scene, theme).into_bytes(),
+        Some("png") => png::encode(&raster::draw(scene, theme)),
+        Some("gif") => return Err(format!("{}: a GIF needs --record", output.display())),
+        _ => {
+            return Err(format!(
+                "{}: expected a .svg or .png file",
+                output.display()
+            ))
+        }
+    };
+    fs::write(output, data).map_err(|err| format!("{}: {}", output.display(), err))
+}
+
+fn write_recording(output: &Path, frames: &[Frame], theme: &Theme) -> Result<(), String> {
+    match extension(output) {
+        Some("gif") => {
+            let data = record::gif(frames, theme).map_err(|err| err.to_string())?;
+            fs::write(output, data).map_err(|err| format!("{}: {}", output.display(), err))
+        }
+        Some("svg" | "png") => frames.iter().enumerate().try_for_each(|(index, frame)| {
+            write(&numbered(output, index + 1), &frame.scene, theme)
+        }),
+        _ => Err(format!(
+            "{}: expected a .gif, .svg or .png file",
+            output.display()
+        )),
+    }
+}
+
+fn extension(path: &Path) -> Option<&str> {
+    path.extension().and_then(|extension| extension.to_str())
+}
+
+/// `game.png` as the `number`th frame, `game-0001.png`.
+fn numbered(output: &Path, number: usize) -> PathBuf {
+    let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = extension(output).unwrap_or_default();
+    output.with_file_name(format!("{}-{:04}.{}", stem, number, extension))
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("stopbus-render: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
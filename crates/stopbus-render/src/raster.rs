@@ -0,0 +1,154 @@
+//! A [`Scene`] drawn into an image, pixel for pixel what the Win32 front end paints at
+//! the same size, apart from the font.
+
+use stopbus_frontend::Rect;
+use stopbus_image::Image;
+use stopbus_theme::Theme;
+
+use crate::{card_corners, font, Scene, Shape, TEXT_COLOR};
+
+/// Draws `scene` with `theme`'s card art. Cards are stretched to their rectangles by
+/// taking the nearest pixel, and art with transparency is blended over the table.
+pub fn draw(scene: &Scene, theme: &Theme) -> Image {
+    let mut canvas = Canvas::new(scene.width, scene.height, scene.table_color);
+
+    for shape in &scene.shapes {
+        match shape {
+            Shape::Card { slot, rect } => {
+                if let Some(art) = theme.image(*slot) {
+                    canvas.blit(art, *rect);
+                }
+                for (x, y) in card_corners(*rect) {
+                    canvas.plot(x, y, scene.table_color);
+                }
+            }
+            Shape::Frame { rect, width, color } => {
+                for inset in 1..=*width {
+                    canvas.frame(
+                        Rect::new(
+                            rect.x - inset,
+                            rect.y - inset,
+                            rect.width + 2 * inset,
+                            rect.height + 2 * inset,
+                        ),
+                        *color,
+                    );
+                }
+            }
+            Shape::Text { x, y, text } => {
+                let scale = scene.text_scale();
+                let top = y + text_top(scene.font_height, scale);
+                font::for_each_pixel(text, *x, top, scale, |x, y| {
+                    canvas.fill(Rect::new(x, y, scale, scale), TEXT_COLOR);
+                });
+            }
+        }
+    }
+
+    canvas.image
+}
+
+/// Offset from the top of a text line to the top of its glyphs, centring them.
+pub fn text_top(font_height: i32, scale: i32) -> i32 {
+    ((font_height - (font::GLYPH_SIZE.1 + 1) * scale) / 2).max(0)
+}
+
+struct Canvas {
+    image: Image,
+}
+
+impl Canvas {
+    fn new(width: i32, height: i32, color: [u8; 3]) -> Self {
+        let mut image = Image::new(width.max(0) as u32, height.max(0) as u32);
+        for pixel in image.rgba.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&[color[0], color[1], color[2], 0xFF]);
+        }
+        Self { image }
+    }
+
+    fn contains(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && (x as u32) < self.image.width && (y as u32) < self.image.height
+    }
+
+    fn plot(&mut self, x: i32, y: i32, color: [u8; 3]) {
+        if self.contains(x, y) {
+            self.image
+                .set_pixel(x as u32, y as u32, [color[0], color[1], color[2], 0xFF]);
+        }
+    }
+
+    fn fill(&mut self, rect: Rect, color: [u8; 3]) {
+        for y in rect.y..rect.bottom() {
+            for x in rect.x..rect.right() {
+                self.plot(x, y, color);
+            }
+        }
+    }
+
+    /// A one-pixel outline just inside `rect`, as GDI's `FrameRect` draws.
+    fn frame(&mut self, rect: Rect, color: [u8; 3]) {
+        self.fill(Rect::new(rect.x, rect.y, rect.width, 1), color);
+        self.fill(Rect::new(rect.x, rect.bottom() - 1, rect.width, 1), color);
+        self.fill(Rect::new(rect.x, rect.y, 1, rect.height), color);
+        self.fill(Rect::new(rect.right() - 1, rect.y, 1, rect.height), color);
+    }
+
+    fn blit(&mut self, art: &Image, rect: Rect) {
+        if rect.width <= 0 || rect.height <= 0 {
+            return;
+        }
+        for y in 0..rect.height {
+            for x in 0..rect.width {
+                if !self.contains(rect.x + x, rect.y + y) {
+                    continue;
+                }
+                let source_x = (x as i64 * art.width as i64 / rect.width as i64) as u32;
+                let source_y = (y as i64 * art.height as i64 / rect.height as i64) as u32;
+                let [red, green, blue, alpha] = art.pixel(source_x, source_y);
+
+                let (x, y) = ((rect.x + x) as u32, (rect.y + y) as u32);
+                let under = self.image.pixel(x, y);
+                let blend = |over: u8, under: u8| {
+                    let alpha = u32::from(alpha);
+                    ((u32::from(over) * alpha + u32::from(under) * (255 - alpha) + 127) / 255) as u8
+                };
+                self.image.set_pixel(
+                    x,
+                    y,
+                    [
+                        blend(red, under[0]),
+                        blend(green, under[1]),
+                        blend(blue, under[2]),
+                        0xFF,
+                    ],
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stretches_frames_and_clips() {
+        let mut art = Image::new(2, 1);
+        art.set_pixel(0, 0, [10, 20, 30, 0xFF]);
+        art.set_pixel(1, 0, [200, 200, 200, 0]);
+
+        let mut canvas = Canvas::new(6, 4, [0, 0x80, 0]);
+        canvas.blit(&art, Rect::new(3, 1, 4, 2));
+        canvas.frame(Rect::new(0, 0, 3, 3), [0xFF, 0xFF, 0]);
+        let image = canvas.image;
+
+        assert_eq!(image.pixel(3, 1), [10, 20, 30, 0xFF]);
+        assert_eq!(image.pixel(4, 2), [10, 20, 30, 0xFF]);
+        assert_eq!(image.pixel(5, 1), [0, 0x80, 0, 0xFF], "transparent art");
+        assert_eq!(image.pixel(4, 3), [0, 0x80, 0, 0xFF], "below the card");
+        assert_eq!(image.pixel(0, 2), [0xFF, 0xFF, 0, 0xFF]);
+        assert_eq!(image.pixel(1, 1), [0, 0x80, 0, 0xFF], "inside the frame");
+        assert_eq!(text_top(16, 1), 4);
+        assert_eq!(text_top(8, 2), 0);
+    }
+}
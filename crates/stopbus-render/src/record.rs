@@ -0,0 +1,165 @@
+//! A whole game as a run of frames, for sharing odd games and for checking by eye.
+//!
+//! [`record`] deals a seeded game, presses the given keys and then lets the AI play the
+//! human's seat until the game is over. A frame is taken wherever the window would
+//! repaint: after each human action, at each step of the turn animation, which points at
+//! every player of a report's `turn_sequence` in turn, and for each round's showdown.
+//! Frames stay up for the turn timer's interval, the showdown for longer, as a reader
+//! would take a moment before pressing OK.
+
+use stopbus_core::GameState;
+use stopbus_frontend::{Key, Presenter, TURN_TIMER_INTERVAL_MS};
+use stopbus_image::gif::{Encoder, GifError};
+use stopbus_theme::Theme;
+
+use crate::{raster, settle, Scene};
+
+pub const SHOWDOWN_HOLD_MS: u32 = 4 * TURN_TIMER_INTERVAL_MS;
+
+/// Human moves played before giving up on a game that never ends.
+const MAX_STEPS: usize = 10_000;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    pub scene: Scene,
+    /// How long the frame stays up before the next.
+    pub delay_ms: u32,
+}
+
+pub fn record(seed: u64, keys: &[Key], theme: &Theme) -> Vec<Frame> {
+    let mut presenter = Presenter::new(GameState::new(Some(seed)));
+    let mut keys = std::iter::once(Key::Char('n')).chain(keys.iter().copied());
+    let mut frames = Vec::new();
+
+    for _ in 0..MAX_STEPS {
+        let effects = if let Some(key) = keys.next() {
+            presenter.key_press(key)
+        } else if presenter.showdown().is_some() && !game_over(&presenter) {
+            presenter.key_press(Key::Enter)
+        } else if let Some(report) = presenter.game_mut().auto_play_human_turn() {
+            presenter.process_report(report)
+        } else {
+            break;
+        };
+        settle(&mut presenter, effects, |presenter| {
+            let view = presenter.view();
+            let delay_ms = if view.showdown.is_some() {
+                SHOWDOWN_HOLD_MS
+            } else {
+                TURN_TIMER_INTERVAL_MS
+            };
+            push(&mut frames, Scene::new(&view, theme), delay_ms);
+        });
+    }
+    frames
+}
+
+/// The frames as an animated GIF at the scenes' size.
+pub fn gif(frames: &[Frame], theme: &Theme) -> Result<Vec<u8>, GifError> {
+    let (width, height) = frames
+        .first()
+        .map_or((0, 0), |frame| (frame.scene.width, frame.scene.height));
+    let mut encoder = Encoder::new(width.max(0) as u32, height.max(0) as u32)?;
+    for frame in frames {
+        encoder.add_frame(&raster::draw(&frame.scene, theme), frame.delay_ms)?;
+    }
+    Ok(encoder.finish())
+}
+
+/// Adds a frame, or keeps the last one up for longer when nothing visible changed.
+fn push(frames: &mut Vec<Frame>, scene: Scene, delay_ms: u32) {
+    match frames.last_mut() {
+        Some(last) if last.scene == scene => last.delay_ms += delay_ms,
+        _ => frames.push(Frame { scene, delay_ms }),
+    }
+}
+
+fn game_over(presenter: &Presenter) -> bool {
+    presenter
+        .game()
+        .lives()
+        .iter()
+        .filter(|&&lives| lives > 0)
+        .count()
+        <= 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Shape;
+    use std::path::PathBuf;
+    use stopbus_theme::CLASSIC_CARDS_DIR;
+
+    fn texts(scene: &Scene) -> Vec<&str> {
+        scene
+            .shapes
+            .iter()
+            .filter_map(|shape| match shape {
+                Shape::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn records_a_whole_game() {
+        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../..");
+        let theme = Theme::classic(&root.join(CLASSIC_CARDS_DIR)).unwrap();
+        let frames = record(2024, &[], &theme);
+
+        assert!(frames.windows(2).all(|pair| pair[0].scene != pair[1].scene));
+        assert!(frames
+            .iter()
+            .all(|frame| frame.delay_ms.is_multiple_of(TURN_TIMER_INTERVAL_MS)));
+
+        let showdowns: Vec<&Frame> = frames
+            .iter()
+            .filter(|frame| texts(&frame.scene).contains(&"You"))
+            .collect();
+        assert!(showdowns.len() > 1);
+        assert!(showdowns
+            .iter()
+            .all(|frame| frame.delay_ms >= SHOWDOWN_HOLD_MS));
+        assert_eq!(
+            frames.last().map(|frame| &frame.scene),
+            showdowns.last().map(|frame| &frame.scene),
+            "the game ends on its last showdown"
+        );
+
+        let last = &frames.last().unwrap().scene;
+        let out = texts(last).iter().filter(|&&text| text == "0").count();
+        assert_eq!(out, 3, "one player is left with lives");
+
+        assert_eq!(record(2024, &[], &theme), frames);
+    }
+
+    #[test]
+    fn merges_repaints_that_change_nothing() {
+        let scene = |width| Scene {
+            width,
+            height: 1,
+            table_color: [0, 0, 0],
+            font_height: 16,
+            shapes: Vec::new(),
+        };
+        let mut frames = Vec::new();
+        push(&mut frames, scene(1), 500);
+        push(&mut frames, scene(1), 500);
+        push(&mut frames, scene(2), 2000);
+
+        assert_eq!(
+            frames,
+            vec![
+                Frame {
+                    scene: scene(1),
+                    delay_ms: 1000
+                },
+                Frame {
+                    scene: scene(2),
+                    delay_ms: 2000
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,175 @@
+//! A [`Scene`] as a self-contained SVG document.
+//!
+//! Each card's art is embedded once as a PNG and placed with `<use>`, and text is drawn
+//! with the bitmap [`font`] as one path per line, so the document shows the same pixels
+//! as [`raster::draw`](crate::raster::draw) in any viewer. Each text path carries its
+//! words in `aria-label`.
+
+use stopbus_image::png;
+use stopbus_theme::Theme;
+
+use crate::raster::text_top;
+use crate::{card_corners, font, Scene, Shape, TEXT_COLOR};
+
+pub fn write(scene: &Scene, theme: &Theme) -> String {
+    let (width, height) = (scene.width, scene.height);
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+         viewBox=\"0 0 {} {}\" shape-rendering=\"crispEdges\">\n",
+        width, height, width, height
+    );
+    svg.push_str(&format!(
+        "<rect width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+        width,
+        height,
+        hex(scene.table_color)
+    ));
+
+    let mut slots: Vec<u8> = Vec::new();
+    for shape in &scene.shapes {
+        if let Shape::Card { slot, .. } = shape {
+            if !slots.contains(slot) && theme.image(*slot).is_some() {
+                slots.push(*slot);
+            }
+        }
+    }
+    if !slots.is_empty() {
+        svg.push_str("<defs>\n");
+        for &slot in &slots {
+            let art = theme.image(slot).expect("slots with art only");
+            svg.push_str(&format!(
+                "<image id=\"{}\" width=\"{}\" height=\"{}\" image-rendering=\"pixelated\" \
+                 href=\"data:image/png;base64,{}\"/>\n",
+                card_id(slot),
+                art.width,
+                art.height,
+                base64(&png::encode(art))
+            ));
+        }
+        svg.push_str("</defs>\n");
+    }
+
+    for shape in &scene.shapes {
+        match shape {
+            Shape::Card { slot, rect } => {
+                if let Some(art) = theme.image(*slot) {
+                    let size = (art.width as i32, art.height as i32);
+                    let place = if size == (rect.width, rect.height) {
+                        format!("x=\"{}\" y=\"{}\"", rect.x, rect.y)
+                    } else {
+                        format!(
+                            "transform=\"translate({} {}) scale({} {})\"",
+                            rect.x,
+                            rect.y,
+                            f64::from(rect.width) / f64::from(size.0),
+                            f64::from(rect.height) / f64::from(size.1)
+                        )
+                    };
+                    svg.push_str(&format!("<use href=\"#{}\" {}/>\n", card_id(*slot), place));
+                }
+                let corners: String = card_corners(*rect)
+                    .into_iter()
+                    .map(|(x, y)| square(x, y, 1))
+                    .collect();
+                svg.push_str(&format!(
+                    "<path fill=\"{}\" d=\"{}\"/>\n",
+                    hex(scene.table_color),
+                    corners
+                ));
+            }
+            Shape::Frame { rect, width, color } => {
+                let half = f64::from(*width) / 2.0;
+                svg.push_str(&format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" \
+                     stroke=\"{}\" stroke-width=\"{}\"/>\n",
+                    f64::from(rect.x) - half,
+                    f64::from(rect.y) - half,
+                    f64::from(rect.width) + 2.0 * half,
+                    f64::from(rect.height) + 2.0 * half,
+                    hex(*color),
+                    width
+                ));
+            }
+            Shape::Text { x, y, text } => {
+                let scale = scene.text_scale();
+                let mut path = String::new();
+                let top = y + text_top(scene.font_height, scale);
+                font::for_each_pixel(text, *x, top, scale, |x, y| {
+                    path.push_str(&square(x, y, scale))
+                });
+                if path.is_empty() {
+                    continue;
+                }
+                svg.push_str(&format!(
+                    "<path fill=\"{}\" aria-label=\"{}\" d=\"{}\"/>\n",
+                    hex(TEXT_COLOR),
+                    escape(text),
+                    path
+                ));
+            }
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn card_id(slot: u8) -> String {
+    format!("card{:02}", slot)
+}
+
+/// A filled square as path data.
+fn square(x: i32, y: i32, size: i32) -> String {
+    format!("M{} {}h{}v{}h-{}z", x, y, size, size, size)
+}
+
+fn hex([red, green, blue]: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", red, green, blue)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut text = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let bits = u32::from(bytes[0]) << 16 | u32::from(bytes[1]) << 8 | u32::from(bytes[2]);
+        for index in 0..4 {
+            if index <= chunk.len() {
+                text.push(char::from(
+                    ALPHABET[(bits >> (18 - 6 * index)) as usize & 0x3F],
+                ));
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_base64_and_escapes_labels() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+        assert_eq!(escape("<a & \"b\">"), "&lt;a &amp; &quot;b&quot;&gt;");
+        assert_eq!(hex([0, 0x80, 0xFF]), "#0080ff");
+        assert_eq!(square(3, 4, 2), "M3 4h2v2h-2z");
+    }
+}
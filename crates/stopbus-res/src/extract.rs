@@ -0,0 +1,249 @@
+//! Turns resources back into the files they were compiled from.
+//!
+//! Bitmaps get their `BITMAPFILEHEADER` back and icon groups are reassembled into `.ico`
+//! files from the `RT_ICON` images they list. Everything else is written as stored.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{read_u16, read_u32, ResError, ResFile, ResId, Resource};
+use crate::{RT_BITMAP, RT_GROUP_ICON, RT_ICON};
+
+const BITMAP_FILE_HEADER_SIZE: u32 = 14;
+const CORE_HEADER_SIZE: u32 = 12;
+const INFO_HEADER_SIZE: u32 = 40;
+const BI_BITFIELDS: u32 = 3;
+
+/// `NEWHEADER` in front of an icon group, and each `RESDIR` entry after it.
+const GROUP_HEADER_SIZE: usize = 6;
+const GROUP_ENTRY_SIZE: usize = 14;
+
+/// `ICONDIR` and each `ICONDIRENTRY` in an `.ico` file.
+const ICO_HEADER_SIZE: usize = 6;
+const ICO_ENTRY_SIZE: usize = 16;
+
+/// A `.bmp` file for a bitmap resource, with the pixel offset pointing past the palette.
+pub fn bitmap_file(resource: &Resource) -> Result<Vec<u8>, ResError> {
+    let dib = &resource.data;
+    let malformed = |reason: &str| malformed(resource, reason);
+
+    let header_size = read_u32(dib, 0).ok_or_else(|| malformed("no bitmap header"))?;
+    let (bit_count, entry_size, colors_used, masks) = match header_size {
+        CORE_HEADER_SIZE => (read_u16(dib, 10), 3, 0, 0),
+        size if size >= INFO_HEADER_SIZE => {
+            let compression = read_u32(dib, 16).unwrap_or_default();
+            let masks: u32 = if size == INFO_HEADER_SIZE && compression == BI_BITFIELDS {
+                12
+            } else {
+                0
+            };
+            (
+                read_u16(dib, 14),
+                4,
+                read_u32(dib, 32).unwrap_or_default(),
+                masks,
+            )
+        }
+        other => return Err(malformed(&format!("unknown header size {}", other))),
+    };
+    let bit_count = bit_count.ok_or_else(|| malformed("bitmap header is cut short"))?;
+
+    let palette_entries = match (colors_used, bit_count) {
+        (0, 1..=8) => 1 << bit_count,
+        (0, _) => 0,
+        (used, _) => used,
+    };
+    let palette_end =
+        u64::from(header_size) + u64::from(masks) + u64::from(palette_entries) * entry_size;
+    if palette_end > dib.len() as u64 {
+        return Err(malformed("palette runs past the end of the data"));
+    }
+    let bits_offset = BITMAP_FILE_HEADER_SIZE + palette_end as u32;
+
+    let mut file = Vec::with_capacity(BITMAP_FILE_HEADER_SIZE as usize + dib.len());
+    file.extend_from_slice(b"BM");
+    file.extend_from_slice(&(BITMAP_FILE_HEADER_SIZE + dib.len() as u32).to_le_bytes());
+    file.extend_from_slice(&[0; 4]);
+    file.extend_from_slice(&bits_offset.to_le_bytes());
+    file.extend_from_slice(dib);
+    Ok(file)
+}
+
+/// An `.ico` file holding every image an icon group lists.
+///
+/// The Windows 3.x resource compiler stored the planes and bit count in each group entry
+/// the wrong way round, so they are taken from the image's own header where it has one.
+pub fn icon_file(file: &ResFile, group: &Resource) -> Result<Vec<u8>, ResError> {
+    let data = &group.data;
+    let count = read_u16(data, 4).ok_or_else(|| malformed(group, "no icon group header"))?;
+
+    let mut entries = Vec::with_capacity(count as usize);
+    for index in 0..count as usize {
+        let start = GROUP_HEADER_SIZE + index * GROUP_ENTRY_SIZE;
+        let entry = data
+            .get(start..start + GROUP_ENTRY_SIZE)
+            .ok_or_else(|| malformed(group, "icon group is cut short"))?;
+        let id = u16::from_le_bytes([entry[12], entry[13]]);
+        let image = file.find(RT_ICON, id).ok_or_else(|| {
+            malformed(
+                group,
+                &format!("lists icon {} which is not in the file", id),
+            )
+        })?;
+        entries.push((entry, &image.data));
+    }
+
+    let mut ico = Vec::new();
+    ico.extend_from_slice(&[0, 0, 1, 0]);
+    ico.extend_from_slice(&count.to_le_bytes());
+
+    let mut offset = ICO_HEADER_SIZE + entries.len() * ICO_ENTRY_SIZE;
+    for (entry, image) in &entries {
+        let (planes, bit_count) = match read_u32(image, 0) {
+            Some(INFO_HEADER_SIZE) => (read_u16(image, 12), read_u16(image, 14)),
+            _ => (None, None),
+        };
+        ico.extend_from_slice(&entry[..4]);
+        ico.extend_from_slice(&planes.map_or([entry[4], entry[5]], u16::to_le_bytes));
+        ico.extend_from_slice(&bit_count.map_or([entry[6], entry[7]], u16::to_le_bytes));
+        ico.extend_from_slice(&(image.len() as u32).to_le_bytes());
+        ico.extend_from_slice(&(offset as u32).to_le_bytes());
+        offset += image.len();
+    }
+    for (_, image) in &entries {
+        ico.extend_from_slice(image);
+    }
+
+    Ok(ico)
+}
+
+/// `bitmap_1.bmp`, `group_icon_ICON_1.ico`, `dialog_OPTIONS.bin` and so on.
+pub fn file_name(resource: &Resource) -> String {
+    let extension = match resource.kind {
+        ResId::Number(RT_BITMAP) => "bmp",
+        ResId::Number(RT_GROUP_ICON) => "ico",
+        _ => "bin",
+    };
+    let clean = |text: String| -> String {
+        text.chars()
+            .map(|c| match c {
+                'A'..='Z' | 'a'..='z' | '0'..='9' | '-' => c,
+                _ => '_',
+            })
+            .collect()
+    };
+
+    format!(
+        "{}_{}.{}",
+        clean(resource.kind_name().to_ascii_lowercase()),
+        clean(resource.name.to_string()),
+        extension
+    )
+}
+
+/// Writes each resource into `dir` under its [`file_name`], returning the paths.
+/// Icon images are written as part of their groups rather than on their own.
+pub fn extract_all(file: &ResFile, dir: &Path) -> Result<Vec<PathBuf>, ResError> {
+    fs::create_dir_all(dir).map_err(ResError::Io)?;
+
+    let mut written = Vec::new();
+    for resource in &file.resources {
+        let data = match resource.kind {
+            ResId::Number(RT_ICON) => continue,
+            ResId::Number(RT_BITMAP) => bitmap_file(resource)?,
+            ResId::Number(RT_GROUP_ICON) => icon_file(file, resource)?,
+            _ => resource.data.clone(),
+        };
+        let path = dir.join(file_name(resource));
+        fs::write(&path, data).map_err(ResError::Io)?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+fn malformed(resource: &Resource, reason: &str) -> ResError {
+    ResError::Malformed {
+        resource: format!("{} {}", resource.kind_name(), resource.name),
+        reason: reason.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checked_in;
+    use stopbus_image::bmp;
+
+    #[test]
+    fn bitmaps_become_files_that_decode_alike() {
+        let file = checked_in();
+
+        for resource in file.of_kind(RT_BITMAP) {
+            let bmp_file = bitmap_file(resource).unwrap();
+            assert_eq!(&bmp_file[..2], b"BM");
+            assert_eq!(read_u32(&bmp_file, 2), Some(bmp_file.len() as u32));
+            assert_eq!(
+                bmp::decode_file(&bmp_file).unwrap(),
+                bmp::decode_dib(&resource.data).unwrap(),
+            );
+        }
+
+        // Card 1 is a monochrome core bitmap; card 53 has the 40-byte header and 16 colours.
+        let offset =
+            |id: u16| read_u32(&bitmap_file(file.find(RT_BITMAP, id).unwrap()).unwrap(), 10);
+        assert_eq!(offset(1), Some(14 + 12 + 2 * 3));
+        assert_eq!(offset(53), Some(14 + 40 + 16 * 4));
+    }
+
+    #[test]
+    fn icon_groups_become_ico_files() {
+        let file = checked_in();
+        let group = file.find(RT_GROUP_ICON, "ICON_2").unwrap();
+        let ico = icon_file(&file, group).unwrap();
+
+        assert_eq!(&ico[..6], [0, 0, 1, 0, 1, 0]);
+        // 32x32, 16 colours, one plane at 4bpp, 744 bytes straight after the directory.
+        assert_eq!(
+            &ico[6..22],
+            [32, 32, 16, 0, 1, 0, 4, 0, 0xe8, 2, 0, 0, 22, 0, 0, 0]
+        );
+        assert_eq!(&ico[22..], file.find(RT_ICON, 2).unwrap().data.as_slice());
+
+        let mut broken = group.clone();
+        broken.data[18] = 9;
+        assert_eq!(
+            icon_file(&file, &broken).unwrap_err().to_string(),
+            "GROUP_ICON ICON_2: lists icon 9 which is not in the file"
+        );
+    }
+
+    #[test]
+    fn extracts_everything_to_a_directory() {
+        let dir = std::env::temp_dir().join(format!("stopbus-res-extract-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let written = extract_all(&checked_in(), &dir).unwrap();
+        let mut names: Vec<String> = written
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+
+        assert_eq!(names.len(), 54 + 6);
+        assert!(names.contains(&"bitmap_54.bmp".to_string()));
+        for name in [
+            "dialog_ABOUTBOX.bin",
+            "dialog_OPTIONS.bin",
+            "group_icon_ICON_1.ico",
+            "group_icon_ICON_2.ico",
+            "menu_MENU.bin",
+            "versioninfo_1.bin",
+        ] {
+            assert!(names.contains(&name.to_string()), "{}", name);
+        }
+        assert_eq!(fs::read(dir.join("dialog_OPTIONS.bin")).unwrap().len(), 262);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,418 @@
+//! Win16 `.RES` files, as the 16-bit resource compiler wrote `STOPBUS.RES`.
+//!
+//! The file is a plain run of resources with no header. Each one is its type, its name,
+//! 16-bit memory flags and a 32-bit size, followed by that many bytes of data; nothing is
+//! padded. A type or name is either `0xFF` and a 16-bit number, or a NUL-terminated
+//! string.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub mod extract;
+pub mod rc;
+pub mod template;
+
+pub const RT_CURSOR: u16 = 1;
+pub const RT_BITMAP: u16 = 2;
+pub const RT_ICON: u16 = 3;
+pub const RT_MENU: u16 = 4;
+pub const RT_DIALOG: u16 = 5;
+pub const RT_STRING: u16 = 6;
+pub const RT_FONTDIR: u16 = 7;
+pub const RT_FONT: u16 = 8;
+pub const RT_ACCELERATOR: u16 = 9;
+pub const RT_RCDATA: u16 = 10;
+pub const RT_GROUP_CURSOR: u16 = 12;
+pub const RT_GROUP_ICON: u16 = 14;
+pub const RT_VERSION: u16 = 16;
+
+/// The name the resource compiler gives a numbered type, such as `BITMAP` for 2.
+pub fn type_name(kind: u16) -> Option<&'static str> {
+    Some(match kind {
+        RT_CURSOR => "CURSOR",
+        RT_BITMAP => "BITMAP",
+        RT_ICON => "ICON",
+        RT_MENU => "MENU",
+        RT_DIALOG => "DIALOG",
+        RT_STRING => "STRING",
+        RT_FONTDIR => "FONTDIR",
+        RT_FONT => "FONT",
+        RT_ACCELERATOR => "ACCELERATORS",
+        RT_RCDATA => "RCDATA",
+        RT_GROUP_CURSOR => "GROUP_CURSOR",
+        RT_GROUP_ICON => "GROUP_ICON",
+        RT_VERSION => "VERSIONINFO",
+        _ => return None,
+    })
+}
+
+/// A resource type or name.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ResId {
+    Number(u16),
+    Name(String),
+}
+
+impl ResId {
+    /// Whether this is `name`, compared the way `FindResource` does: numbers exactly,
+    /// strings ignoring ASCII case.
+    pub fn matches(&self, name: &ResId) -> bool {
+        match (self, name) {
+            (ResId::Number(a), ResId::Number(b)) => a == b,
+            (ResId::Name(a), ResId::Name(b)) => a.eq_ignore_ascii_case(b),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for ResId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResId::Number(number) => write!(f, "{}", number),
+            ResId::Name(name) => f.write_str(name),
+        }
+    }
+}
+
+impl From<u16> for ResId {
+    fn from(number: u16) -> Self {
+        ResId::Number(number)
+    }
+}
+
+impl From<&str> for ResId {
+    fn from(name: &str) -> Self {
+        ResId::Name(name.to_string())
+    }
+}
+
+/// How Windows 3.x kept a resource in memory once loaded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryFlags(pub u16);
+
+impl MemoryFlags {
+    pub const MOVEABLE: u16 = 0x0010;
+    pub const PURE: u16 = 0x0020;
+    pub const PRELOAD: u16 = 0x0040;
+    pub const DISCARDABLE: u16 = 0x1000;
+
+    const NAMES: [(u16, &'static str); 4] = [
+        (Self::MOVEABLE, "MOVEABLE"),
+        (Self::PURE, "PURE"),
+        (Self::PRELOAD, "PRELOAD"),
+        (Self::DISCARDABLE, "DISCARDABLE"),
+    ];
+
+    pub fn contains(self, flag: u16) -> bool {
+        self.0 & flag == flag
+    }
+}
+
+/// The flag names the resource compiler accepts, then any other bits in hex.
+impl fmt::Display for MemoryFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut words: Vec<String> = Self::NAMES
+            .iter()
+            .filter(|(flag, _)| self.contains(*flag))
+            .map(|(_, name)| name.to_string())
+            .collect();
+
+        let known = Self::NAMES.iter().fold(0, |bits, (flag, _)| bits | flag);
+        if self.0 & !known != 0 {
+            words.push(format!("0x{:04x}", self.0 & !known));
+        }
+        if words.is_empty() {
+            words.push("FIXED".to_string());
+        }
+
+        f.write_str(&words.join(" "))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Resource {
+    pub kind: ResId,
+    pub name: ResId,
+    pub flags: MemoryFlags,
+    /// Where the resource header starts in the file.
+    pub offset: usize,
+    pub data: Vec<u8>,
+}
+
+impl Resource {
+    /// `BITMAP`, `DIALOG` and so on for standard types, otherwise the type as stored.
+    pub fn kind_name(&self) -> String {
+        match &self.kind {
+            ResId::Number(number) => type_name(*number)
+                .map(str::to_string)
+                .unwrap_or_else(|| number.to_string()),
+            ResId::Name(name) => name.clone(),
+        }
+    }
+}
+
+/// Every resource in a `.RES` file, in file order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ResFile {
+    pub resources: Vec<Resource>,
+}
+
+impl ResFile {
+    pub fn read(path: &Path) -> Result<Self, ResError> {
+        Self::parse(&fs::read(path).map_err(ResError::Io)?)
+    }
+
+    /// Reads resources until the data runs out. A zero byte where a type would start also
+    /// ends the file, for tools that pad it.
+    pub fn parse(data: &[u8]) -> Result<Self, ResError> {
+        let mut resources = Vec::new();
+        let mut offset = 0;
+
+        while offset < data.len() && data[offset] != 0 {
+            let start = offset;
+            let kind = read_id(data, &mut offset)?;
+            let name = read_id(data, &mut offset)?;
+
+            let flags = read_u16(data, offset).ok_or(ResError::Truncated { offset })?;
+            let size = read_u32(data, offset + 2).ok_or(ResError::Truncated { offset })?;
+            offset += 6;
+
+            let available = data.len() - offset;
+            if size as usize > available {
+                return Err(ResError::TruncatedData {
+                    offset: start,
+                    size,
+                    available,
+                });
+            }
+
+            resources.push(Resource {
+                kind,
+                name,
+                flags: MemoryFlags(flags),
+                offset: start,
+                data: data[offset..offset + size as usize].to_vec(),
+            });
+            offset += size as usize;
+        }
+
+        Ok(Self { resources })
+    }
+
+    pub fn find(&self, kind: impl Into<ResId>, name: impl Into<ResId>) -> Option<&Resource> {
+        let (kind, name) = (kind.into(), name.into());
+        self.resources
+            .iter()
+            .find(|resource| resource.kind.matches(&kind) && resource.name.matches(&name))
+    }
+
+    pub fn of_kind(&self, kind: impl Into<ResId>) -> impl Iterator<Item = &Resource> {
+        let kind = kind.into();
+        self.resources
+            .iter()
+            .filter(move |resource| resource.kind.matches(&kind))
+    }
+
+    /// The DIB stored for bitmap `id`, as `FindResource(.., RT_BITMAP)` would return it.
+    pub fn bitmap(&self, id: u16) -> Option<&[u8]> {
+        self.find(RT_BITMAP, id)
+            .map(|resource| resource.data.as_slice())
+    }
+}
+
+/// Names are ANSI text; bytes past ASCII are read as Latin-1.
+fn read_id(data: &[u8], offset: &mut usize) -> Result<ResId, ResError> {
+    let start = *offset;
+
+    if data.get(start) == Some(&0xFF) {
+        let number = read_u16(data, start + 1).ok_or(ResError::Truncated { offset: start })?;
+        *offset += 3;
+        return Ok(ResId::Number(number));
+    }
+
+    let length = data[start.min(data.len())..]
+        .iter()
+        .position(|&byte| byte == 0)
+        .ok_or(ResError::UnterminatedName { offset: start })?;
+    *offset += length + 1;
+
+    Ok(ResId::Name(
+        data[start..start + length]
+            .iter()
+            .map(|&byte| char::from(byte))
+            .collect(),
+    ))
+}
+
+pub(crate) fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset.checked_add(2)?)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+pub(crate) fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[derive(Debug)]
+pub enum ResError {
+    Io(io::Error),
+    /// The header field starting at `offset` runs past the end of the file.
+    Truncated {
+        offset: usize,
+    },
+    UnterminatedName {
+        offset: usize,
+    },
+    TruncatedData {
+        offset: usize,
+        size: u32,
+        available: usize,
+    },
+    /// A resource whose data cannot be turned back into a file.
+    Malformed {
+        resource: String,
+        reason: String,
+    },
+}
+
+impl fmt::Display for ResError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResError::Io(err) => write!(f, "{}", err),
+            ResError::Truncated { offset } => {
+                write!(f, "resource header is cut short at byte {}", offset)
+            }
+            ResError::UnterminatedName { offset } => {
+                write!(f, "name at byte {} has no terminating NUL", offset)
+            }
+            ResError::TruncatedData {
+                offset,
+                size,
+                available,
+            } => write!(
+                f,
+                "resource at byte {} claims {} bytes but only {} remain",
+                offset, size, available
+            ),
+            ResError::Malformed { resource, reason } => write!(f, "{}: {}", resource, reason),
+        }
+    }
+}
+
+impl std::error::Error for ResError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ResError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn checked_in() -> ResFile {
+    let path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../STOPBUS.RES");
+    ResFile::read(&path).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_the_checked_in_resources() {
+        let file = checked_in();
+
+        let bitmaps: Vec<&ResId> = file.of_kind(RT_BITMAP).map(|r| &r.name).collect();
+        let expected: Vec<ResId> = (1..=54).map(ResId::Number).collect();
+        assert_eq!(bitmaps, expected.iter().collect::<Vec<_>>());
+
+        let others: Vec<(String, String, u16, usize)> = file
+            .resources
+            .iter()
+            .filter(|r| r.kind != ResId::Number(RT_BITMAP))
+            .map(|r| (r.kind_name(), r.name.to_string(), r.flags.0, r.data.len()))
+            .collect();
+        let others: Vec<(&str, &str, u16, usize)> = others
+            .iter()
+            .map(|(kind, name, flags, size)| (kind.as_str(), name.as_str(), *flags, *size))
+            .collect();
+        assert_eq!(
+            others,
+            [
+                ("ICON", "1", 0x1c10, 744),
+                ("ICON", "2", 0x1030, 744),
+                ("MENU", "MENU", 0x1c30, 110),
+                ("DIALOG", "ABOUTBOX", 0x1030, 492),
+                ("DIALOG", "OPTIONS", 0x1030, 262),
+                ("GROUP_ICON", "ICON_1", 0x1c30, 20),
+                ("GROUP_ICON", "ICON_2", 0x1030, 20),
+                ("VERSIONINFO", "1", 0x30, 404),
+            ]
+        );
+
+        assert_eq!(file.bitmap(1).unwrap().len(), 1170);
+        assert_eq!(file.find(RT_BITMAP, 53).unwrap().offset, 90216);
+        assert!(file.find(RT_DIALOG, "options").is_some());
+        assert!(file.find(RT_DIALOG, 5).is_none());
+    }
+
+    #[test]
+    fn bitmaps_match_the_converted_cards() {
+        let file = checked_in();
+        let cards = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../stopbus-ui/resources/cards");
+
+        for id in 1..=54 {
+            let legacy = stopbus_image::bmp::decode_dib(file.bitmap(id).unwrap()).unwrap();
+            let converted = fs::read(cards.join(format!("card{:02}.bmp", id))).unwrap();
+            let converted = stopbus_image::bmp::decode_file(&converted).unwrap();
+            assert_eq!(legacy, converted, "bitmap {}", id);
+        }
+    }
+
+    #[test]
+    fn reads_named_types_and_flags() {
+        let mut data = b"\xFF\x0a\x00CONFIG\x00\x40\x00\x03\x00\x00\x00abc".to_vec();
+        data.extend_from_slice(b"THEME\x00\xFF\x07\x00\x30\x1c\x00\x00\x00\x00\x00\x00");
+        let file = ResFile::parse(&data).unwrap();
+
+        assert_eq!(file.resources.len(), 2);
+        assert_eq!(file.resources[0].kind_name(), "RCDATA");
+        assert_eq!(file.resources[0].name, ResId::from("CONFIG"));
+        assert_eq!(file.resources[0].data, b"abc");
+        assert_eq!(file.resources[0].flags.to_string(), "PRELOAD");
+        assert_eq!(file.resources[1].kind, ResId::from("THEME"));
+        assert_eq!(file.resources[1].name, ResId::Number(7));
+        assert_eq!(file.resources[1].offset, 19);
+        assert_eq!(
+            file.resources[1].flags.to_string(),
+            "MOVEABLE PURE DISCARDABLE 0x0c00"
+        );
+        assert_eq!(MemoryFlags(0).to_string(), "FIXED");
+    }
+
+    #[test]
+    fn reports_damaged_files() {
+        let error = |data: &[u8]| ResFile::parse(data).unwrap_err().to_string();
+
+        assert_eq!(
+            error(b"\xFF\x02\x00\xFF\x01\x00\x30\x00\x10\x00\x00\x00abc"),
+            "resource at byte 0 claims 16 bytes but only 3 remain"
+        );
+        assert_eq!(
+            error(b"\xFF\x02\x00\xFF\x01"),
+            "resource header is cut short at byte 3"
+        );
+        assert_eq!(
+            error(b"\xFF\x05\x00ABOUT"),
+            "name at byte 3 has no terminating NUL"
+        );
+        assert_eq!(
+            error(b"\xFF\x02\x00\xFF\x01\x00\x30\x00\x00\x00"),
+            "resource header is cut short at byte 6"
+        );
+    }
+}
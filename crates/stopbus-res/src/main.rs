@@ -0,0 +1,162 @@
+//! Lists or unpacks a Win16 `.RES` file, or describes the dialogs and menus in it or in
+//! a resource script.
+//!
+//! Usage: stopbus-res list FILE
+//!        stopbus-res extract FILE DIR
+//!        stopbus-res describe FILE
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use stopbus_res::extract::extract_all;
+use stopbus_res::rc::Script;
+use stopbus_res::template::{self, Dialog, Menu, MenuItem};
+use stopbus_res::{ResFile, ResId, RT_DIALOG, RT_MENU};
+
+const USAGE: &str = "usage: stopbus-res list FILE\n       stopbus-res extract FILE DIR\n       \
+                     stopbus-res describe FILE";
+
+enum Command {
+    List(PathBuf),
+    Extract(PathBuf, PathBuf),
+    Describe(PathBuf),
+}
+
+fn parse_args() -> Result<Command, String> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match args.as_slice() {
+        ["list", file] => Ok(Command::List(file.into())),
+        ["extract", file, dir] => Ok(Command::Extract(file.into(), dir.into())),
+        ["describe", file] => Ok(Command::Describe(file.into())),
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn run(command: Command) -> Result<(), String> {
+    let read =
+        |path: &PathBuf| ResFile::read(path).map_err(|err| format!("{}: {}", path.display(), err));
+
+    match command {
+        Command::List(path) => {
+            for resource in read(&path)?.resources {
+                println!(
+                    "{:<12} {:<10} {:>6}  {}",
+                    resource.kind_name(),
+                    resource.name.to_string(),
+                    resource.data.len(),
+                    resource.flags
+                );
+            }
+        }
+        Command::Extract(path, dir) => {
+            let written = extract_all(&read(&path)?, &dir).map_err(|err| err.to_string())?;
+            println!("wrote {} files to {}", written.len(), dir.display());
+        }
+        Command::Describe(path) => {
+            let script = if path
+                .extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("rc"))
+            {
+                let data = std::fs::read(&path).map_err(|err| err.to_string())?;
+                Script::parse(&data).map_err(|err| format!("{}: {}", path.display(), err))?
+            } else {
+                compiled_templates(&read(&path)?)?
+            };
+
+            for (name, dialog) in &script.dialogs {
+                print_dialog(name, dialog);
+            }
+            for (name, menu) in &script.menus {
+                println!("MENU {}", name);
+                print_menu(menu, 1);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn compiled_templates(file: &ResFile) -> Result<Script, String> {
+    let mut script = Script::default();
+
+    for resource in &file.resources {
+        let failed = |err: template::TemplateError| {
+            format!("{} {}: {}", resource.kind_name(), resource.name, err)
+        };
+        match resource.kind {
+            ResId::Number(RT_DIALOG) => script.dialogs.push((
+                resource.name.clone(),
+                template::parse_dialog(&resource.data).map_err(failed)?,
+            )),
+            ResId::Number(RT_MENU) => script.menus.push((
+                resource.name.clone(),
+                template::parse_menu(&resource.data).map_err(failed)?,
+            )),
+            _ => {}
+        }
+    }
+
+    Ok(script)
+}
+
+fn print_dialog(name: &ResId, dialog: &Dialog) {
+    println!(
+        "DIALOG {} {:?} at {},{} size {}x{} style 0x{:08x}",
+        name, dialog.caption, dialog.x, dialog.y, dialog.width, dialog.height, dialog.style
+    );
+    if let Some(font) = &dialog.font {
+        println!("  font {}pt {:?}", font.point_size, font.face);
+    }
+
+    for control in &dialog.controls {
+        let id = match control.id {
+            template::ID_STATIC => "-1".to_string(),
+            id => id.to_string(),
+        };
+        println!(
+            "  {:>5}  {:<36} {:?} at {},{} size {}x{}",
+            id,
+            format!("{:?}", control.kind),
+            control.text,
+            control.x,
+            control.y,
+            control.width,
+            control.height
+        );
+    }
+}
+
+fn print_menu(menu: &Menu, depth: usize) {
+    let indent = "  ".repeat(depth);
+
+    for item in &menu.items {
+        match item {
+            MenuItem::Command { id, text, .. } => println!("{}{:>5}  {:?}", indent, id, text),
+            MenuItem::Popup { text, menu, .. } => {
+                println!("{}{:?}", indent, text);
+                print_menu(menu, depth + 1);
+            }
+            MenuItem::Separator => println!("{}-----", indent),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let command = match parse_args() {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("stopbus-res: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,905 @@
+//! `DIALOG` and `MENU` statements in resource scripts such as `ABOUT.RC`.
+//!
+//! Statements are read the way the resource compiler reads them, down to the default
+//! styles each control statement implies, so a script gives the same [`Dialog`] as the
+//! template compiled from it. `#define`s of numbers are honoured and other preprocessor
+//! lines are skipped; the window style names the scripts use are built in. Every other
+//! statement, such as `BITMAP` or `VERSIONINFO`, is passed over.
+
+use crate::template::{
+    Control, ControlKind, Dialog, Font, Menu, MenuItem, TemplateError, DS_SETFONT, WS_CHILD,
+    WS_VISIBLE,
+};
+use crate::ResId;
+
+const WS_POPUP: u32 = 0x8000_0000;
+const WS_CAPTION: u32 = 0x00C0_0000;
+const WS_BORDER: u32 = 0x0080_0000;
+const WS_SYSMENU: u32 = 0x0008_0000;
+const WS_GROUP: u32 = 0x0002_0000;
+const WS_TABSTOP: u32 = 0x0001_0000;
+
+/// Names from `windows.h` that dialog scripts use.
+const SYMBOLS: &[(&str, i64)] = &[
+    ("IDOK", 1),
+    ("IDCANCEL", 2),
+    ("IDABORT", 3),
+    ("IDRETRY", 4),
+    ("IDIGNORE", 5),
+    ("IDYES", 6),
+    ("IDNO", 7),
+    ("IDC_STATIC", -1),
+    ("WS_OVERLAPPED", 0),
+    ("WS_POPUP", WS_POPUP as i64),
+    ("WS_CHILD", WS_CHILD as i64),
+    ("WS_MINIMIZE", 0x2000_0000),
+    ("WS_VISIBLE", WS_VISIBLE as i64),
+    ("WS_DISABLED", 0x0800_0000),
+    ("WS_CLIPSIBLINGS", 0x0400_0000),
+    ("WS_CLIPCHILDREN", 0x0200_0000),
+    ("WS_MAXIMIZE", 0x0100_0000),
+    ("WS_CAPTION", WS_CAPTION as i64),
+    ("WS_BORDER", WS_BORDER as i64),
+    ("WS_DLGFRAME", 0x0040_0000),
+    ("WS_VSCROLL", 0x0020_0000),
+    ("WS_HSCROLL", 0x0010_0000),
+    ("WS_SYSMENU", WS_SYSMENU as i64),
+    ("WS_THICKFRAME", 0x0004_0000),
+    ("WS_GROUP", WS_GROUP as i64),
+    ("WS_TABSTOP", WS_TABSTOP as i64),
+    ("WS_MINIMIZEBOX", 0x0002_0000),
+    ("WS_MAXIMIZEBOX", 0x0001_0000),
+    ("DS_ABSALIGN", 0x0001),
+    ("DS_SYSMODAL", 0x0002),
+    ("DS_LOCALEDIT", 0x0020),
+    ("DS_SETFONT", DS_SETFONT as i64),
+    ("DS_MODALFRAME", 0x0080),
+    ("DS_NOIDLEMSG", 0x0100),
+    ("DS_CENTER", 0x0800),
+    ("BS_PUSHBUTTON", 0),
+    ("BS_DEFPUSHBUTTON", 1),
+    ("BS_CHECKBOX", 2),
+    ("BS_AUTOCHECKBOX", 3),
+    ("BS_RADIOBUTTON", 4),
+    ("BS_3STATE", 5),
+    ("BS_AUTO3STATE", 6),
+    ("BS_GROUPBOX", 7),
+    ("BS_AUTORADIOBUTTON", 9),
+    ("BS_LEFTTEXT", 0x0020),
+    ("SS_LEFT", 0),
+    ("SS_CENTER", 1),
+    ("SS_RIGHT", 2),
+    ("SS_ICON", 3),
+    ("SS_NOPREFIX", 0x0080),
+    ("ES_LEFT", 0),
+    ("ES_CENTER", 1),
+    ("ES_RIGHT", 2),
+    ("ES_MULTILINE", 0x0004),
+    ("ES_AUTOVSCROLL", 0x0040),
+    ("ES_AUTOHSCROLL", 0x0080),
+    ("ES_READONLY", 0x0800),
+    ("LBS_NOTIFY", 0x0001),
+    ("LBS_SORT", 0x0002),
+    ("LBS_NOINTEGRALHEIGHT", 0x0100),
+    ("CBS_SIMPLE", 1),
+    ("CBS_DROPDOWN", 2),
+    ("CBS_DROPDOWNLIST", 3),
+    ("SBS_HORZ", 0),
+    ("SBS_VERT", 1),
+];
+
+/// Class and style each control statement implies before its own style is applied.
+fn control_defaults(keyword: &str) -> Option<(&'static str, u32)> {
+    Some(match keyword {
+        "LTEXT" => ("STATIC", WS_GROUP),
+        "CTEXT" => ("STATIC", 1 | WS_GROUP),
+        "RTEXT" => ("STATIC", 2 | WS_GROUP),
+        "ICON" => ("STATIC", 3),
+        "PUSHBUTTON" => ("BUTTON", WS_TABSTOP),
+        "DEFPUSHBUTTON" => ("BUTTON", 1 | WS_TABSTOP),
+        "CHECKBOX" => ("BUTTON", 2 | WS_TABSTOP),
+        "AUTOCHECKBOX" => ("BUTTON", 3 | WS_TABSTOP),
+        "RADIOBUTTON" => ("BUTTON", 4),
+        "STATE3" => ("BUTTON", 5 | WS_TABSTOP),
+        "AUTO3STATE" => ("BUTTON", 6 | WS_TABSTOP),
+        "GROUPBOX" => ("BUTTON", 7),
+        "AUTORADIOBUTTON" => ("BUTTON", 9),
+        "EDITTEXT" => ("EDIT", WS_BORDER | WS_TABSTOP),
+        "LISTBOX" => ("LISTBOX", 1 | WS_BORDER),
+        "COMBOBOX" => ("COMBOBOX", 1 | WS_TABSTOP),
+        "SCROLLBAR" => ("SCROLLBAR", 0),
+        _ => return None,
+    })
+}
+
+/// Load and memory options that may follow a resource type.
+const MEMORY_OPTIONS: &[&str] = &[
+    "PRELOAD",
+    "LOADONCALL",
+    "FIXED",
+    "MOVEABLE",
+    "DISCARDABLE",
+    "PURE",
+    "IMPURE",
+];
+
+/// The dialogs and menus a script defines, in the order it defines them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Script {
+    pub dialogs: Vec<(ResId, Dialog)>,
+    pub menus: Vec<(ResId, Menu)>,
+}
+
+impl Script {
+    /// Reads a script; bytes past ASCII are taken as Latin-1, the ANSI code page the
+    /// scripts were written in.
+    pub fn parse(data: &[u8]) -> Result<Self, TemplateError> {
+        let text: String = data.iter().map(|&byte| char::from(byte)).collect();
+        Parser {
+            tokens: tokenize(&text)?,
+            position: 0,
+            symbols: defines(&text),
+        }
+        .script()
+    }
+
+    pub fn dialog(&self, name: impl Into<ResId>) -> Option<&Dialog> {
+        let name = name.into();
+        self.dialogs
+            .iter()
+            .find(|(found, _)| found.matches(&name))
+            .map(|(_, dialog)| dialog)
+    }
+
+    pub fn menu(&self, name: impl Into<ResId>) -> Option<&Menu> {
+        let name = name.into();
+        self.menus
+            .iter()
+            .find(|(found, _)| found.matches(&name))
+            .map(|(_, menu)| menu)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Number(i64),
+    Text(String),
+    Comma,
+    Pipe,
+    Plus,
+    Minus,
+    Open,
+    Close,
+    Begin,
+    End,
+}
+
+/// `#define NAME number` lines, later ones winning.
+fn defines(text: &str) -> Vec<(String, i64)> {
+    text.lines()
+        .filter_map(|line| {
+            let mut words = line.trim().strip_prefix('#')?.split_whitespace();
+            if words.next()? != "define" {
+                return None;
+            }
+            let name = words.next()?;
+            let value = parse_number(words.next()?)?;
+            Some((name.to_string(), value))
+        })
+        .rev()
+        .collect()
+}
+
+fn parse_number(text: &str) -> Option<i64> {
+    let digits = text.trim_end_matches(['L', 'l', 'U', 'u']);
+    match digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        Some(hex) => i64::from_str_radix(hex, 16).ok(),
+        None => digits.parse().ok(),
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<(Token, usize)>, TemplateError> {
+    let mut tokens = Vec::new();
+
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+    let mut line_start = true;
+
+    while let Some(c) = chars.next() {
+        if c == '\n' {
+            line += 1;
+            line_start = true;
+            continue;
+        }
+        if c.is_whitespace() {
+            continue;
+        }
+        if c == '#' && line_start {
+            while chars.peek().is_some_and(|&next| next != '\n') {
+                chars.next();
+            }
+            continue;
+        }
+        line_start = false;
+
+        let token = match c {
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.peek().is_some_and(|&next| next != '\n') {
+                    chars.next();
+                }
+                continue;
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                loop {
+                    match chars.next() {
+                        None => return Err(syntax(line, "unterminated comment")),
+                        Some('/') if previous == '*' => break,
+                        Some(next) => {
+                            if next == '\n' {
+                                line += 1;
+                            }
+                            previous = next;
+                        }
+                    }
+                }
+                continue;
+            }
+            ',' => Token::Comma,
+            '|' => Token::Pipe,
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '{' => Token::Begin,
+            '}' => Token::End,
+            '"' => Token::Text(read_string(&mut chars).map_err(|reason| syntax(line, &reason))?),
+            c if c.is_ascii_digit() => {
+                let mut word = c.to_string();
+                while let Some(&next) = chars.peek().filter(|next| next.is_ascii_alphanumeric()) {
+                    word.push(next);
+                    chars.next();
+                }
+                Token::Number(
+                    parse_number(&word)
+                        .ok_or_else(|| syntax(line, &format!("bad number '{}'", word)))?,
+                )
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some(&next) = chars
+                    .peek()
+                    .filter(|next| next.is_alphanumeric() || **next == '_')
+                {
+                    word.push(next);
+                    chars.next();
+                }
+                match word.to_ascii_uppercase().as_str() {
+                    "BEGIN" => Token::Begin,
+                    "END" => Token::End,
+                    _ => Token::Word(word),
+                }
+            }
+            other => return Err(syntax(line, &format!("unexpected '{}'", other))),
+        };
+        tokens.push((token, line));
+    }
+
+    Ok(tokens)
+}
+
+fn syntax(line: usize, reason: &str) -> TemplateError {
+    TemplateError::Syntax {
+        line,
+        reason: reason.to_string(),
+    }
+}
+
+/// Reads a string after its opening quote: `""` is a quote, and backslash escapes
+/// include octal and hex character codes.
+fn read_string(chars: &mut std::iter::Peekable<std::str::Chars>) -> Result<String, String> {
+    let mut text = String::new();
+
+    loop {
+        match chars.next() {
+            None | Some('\n') => return Err("unterminated string".to_string()),
+            Some('"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                text.push('"');
+            }
+            Some('"') => return Ok(text),
+            Some('\\') => match chars.next() {
+                Some('n') => text.push('\n'),
+                Some('t') => text.push('\t'),
+                Some('r') => text.push('\r'),
+                Some('a') => text.push('\u{8}'),
+                Some('x') => {
+                    let mut code = 0;
+                    for _ in 0..2 {
+                        match chars.peek().and_then(|next| next.to_digit(16)) {
+                            Some(digit) => code = code * 16 + digit,
+                            None => break,
+                        }
+                        chars.next();
+                    }
+                    text.push(char::from(code as u8));
+                }
+                Some(first @ '0'..='7') => {
+                    let mut code = first.to_digit(8).unwrap_or_default();
+                    for _ in 0..2 {
+                        match chars.peek().and_then(|next| next.to_digit(8)) {
+                            Some(digit) => code = code * 8 + digit,
+                            None => break,
+                        }
+                        chars.next();
+                    }
+                    text.push(char::from(code as u8));
+                }
+                Some(other) => text.push(other),
+                None => return Err("unterminated string".to_string()),
+            },
+            Some(c) => text.push(c),
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    symbols: Vec<(String, i64)>,
+}
+
+impl Parser {
+    fn script(&mut self) -> Result<Script, TemplateError> {
+        let mut script = Script::default();
+
+        while self.position < self.tokens.len() {
+            let keyword = match self.tokens.get(self.position + 1) {
+                Some((Token::Word(word), _)) => word.to_ascii_uppercase(),
+                _ => String::new(),
+            };
+            // A statement starts a line with its name, then its type.
+            let starts_line = self.position == 0
+                || self.tokens[self.position - 1].1 != self.tokens[self.position].1;
+            let is_name = starts_line
+                && matches!(
+                    self.peek(),
+                    Some(Token::Word(_) | Token::Number(_) | Token::Text(_))
+                );
+
+            match keyword.as_str() {
+                "DIALOG" | "DIALOGEX" if is_name => {
+                    let name = self.name()?;
+                    self.position += 1;
+                    script.dialogs.push((name, self.dialog()?));
+                }
+                "MENU" if is_name => {
+                    let name = self.name()?;
+                    self.position += 1;
+                    self.memory_options();
+                    self.expect(Token::Begin, "BEGIN")?;
+                    script.menus.push((name, self.menu_items()?));
+                }
+                _ => self.position += 1,
+            }
+        }
+
+        Ok(script)
+    }
+
+    fn dialog(&mut self) -> Result<Dialog, TemplateError> {
+        self.memory_options();
+        let x = self.number()? as i16;
+        self.expect(Token::Comma, "','")?;
+        let [y, width, height] = self.numbers()?;
+        if self.peek() == Some(&Token::Comma) {
+            self.position += 1;
+            self.number()?;
+        }
+
+        let mut style = None;
+        let mut caption = String::new();
+        let mut font = None;
+        let mut menu = None;
+
+        loop {
+            let option = match self.advance() {
+                Some(Token::Begin) => break,
+                Some(Token::Word(word)) => word.to_ascii_uppercase(),
+                _ => return Err(self.syntax("expected a dialog option or BEGIN")),
+            };
+            match option.as_str() {
+                "STYLE" => {
+                    let (set, cleared) = self.style()?;
+                    style = Some(set & !cleared);
+                }
+                "CAPTION" => caption = self.text()?,
+                "FONT" => {
+                    let point_size = self.number()? as u16;
+                    self.expect(Token::Comma, "','")?;
+                    let face = self.text()?;
+                    while self.peek() == Some(&Token::Comma) {
+                        self.position += 1;
+                        self.number()?;
+                    }
+                    font = Some(Font { point_size, face });
+                }
+                "MENU" => menu = Some(self.name()?),
+                "CLASS" => {
+                    self.name()?;
+                }
+                "EXSTYLE" => {
+                    self.style()?;
+                }
+                "CHARACTERISTICS" | "VERSION" => {
+                    self.number()?;
+                }
+                "LANGUAGE" => {
+                    self.number()?;
+                    self.expect(Token::Comma, "','")?;
+                    self.number()?;
+                }
+                other => return Err(self.syntax(&format!("unknown dialog option '{}'", other))),
+            }
+        }
+
+        let mut style = style.unwrap_or(WS_POPUP | WS_BORDER | WS_SYSMENU);
+        if !caption.is_empty() {
+            style |= WS_CAPTION;
+        }
+        if font.is_some() {
+            style |= DS_SETFONT;
+        }
+
+        let mut controls = Vec::new();
+        loop {
+            let keyword = match self.advance() {
+                Some(Token::End) => break,
+                Some(Token::Word(word)) => word.to_ascii_uppercase(),
+                _ => return Err(self.syntax("expected a control or END")),
+            };
+            controls.push(self.control(&keyword)?);
+        }
+
+        Ok(Dialog {
+            x,
+            y,
+            width,
+            height,
+            style,
+            caption,
+            font,
+            menu,
+            controls,
+        })
+    }
+
+    fn control(&mut self, keyword: &str) -> Result<Control, TemplateError> {
+        let (text, id, class, mut style, rect) = if keyword == "CONTROL" {
+            let text = self.control_text()?;
+            self.expect(Token::Comma, "','")?;
+            let id = self.number()? as u16;
+            self.expect(Token::Comma, "','")?;
+            let class = self.name()?.to_string();
+            self.expect(Token::Comma, "','")?;
+            let (set, cleared) = self.style()?;
+            self.expect(Token::Comma, "','")?;
+            let x = self.number()? as i16;
+            self.expect(Token::Comma, "','")?;
+            let [y, width, height] = self.numbers()?;
+            (text, id, class, set & !cleared, [x, y, width, height])
+        } else {
+            let (class, default_style) = control_defaults(keyword)
+                .ok_or_else(|| self.syntax(&format!("unknown control '{}'", keyword)))?;
+            let text = match class {
+                "EDIT" | "LISTBOX" | "COMBOBOX" | "SCROLLBAR" => String::new(),
+                _ => {
+                    let text = self.control_text()?;
+                    self.expect(Token::Comma, "','")?;
+                    text
+                }
+            };
+            let id = self.number()? as u16;
+            self.expect(Token::Comma, "','")?;
+            let x = self.number()? as i16;
+            self.expect(Token::Comma, "','")?;
+            let y = self.number()? as i16;
+
+            // Icons take their size from the image, so theirs may be left out.
+            let (width, height) = if keyword == "ICON" && self.peek() != Some(&Token::Comma) {
+                (0, 0)
+            } else {
+                self.expect(Token::Comma, "','")?;
+                let width = self.number()? as i16;
+                self.expect(Token::Comma, "','")?;
+                (width, self.number()? as i16)
+            };
+
+            let mut style = default_style;
+            if self.peek() == Some(&Token::Comma) {
+                self.position += 1;
+                let (set, cleared) = self.style()?;
+                style = (style | set) & !cleared;
+            }
+            (text, id, class.to_string(), style, [x, y, width, height])
+        };
+
+        if self.peek() == Some(&Token::Comma) {
+            self.position += 1;
+            self.style()?;
+        }
+        style |= WS_CHILD | WS_VISIBLE;
+
+        let [x, y, width, height] = rect;
+        Ok(Control {
+            id,
+            kind: ControlKind::classify(&class, style),
+            class,
+            text,
+            x,
+            y,
+            width,
+            height,
+            style,
+        })
+    }
+
+    /// Items up to the `END` matching a `BEGIN` already read.
+    fn menu_items(&mut self) -> Result<Menu, TemplateError> {
+        let mut menu = Menu::default();
+
+        loop {
+            let keyword = match self.advance() {
+                Some(Token::End) => return Ok(menu),
+                Some(Token::Word(word)) => word.to_ascii_uppercase(),
+                _ => return Err(self.syntax("expected MENUITEM, POPUP or END")),
+            };
+
+            match keyword.as_str() {
+                "MENUITEM" => {
+                    if let Some(Token::Word(word)) = self.peek() {
+                        if word.eq_ignore_ascii_case("SEPARATOR") {
+                            self.position += 1;
+                            menu.items.push(MenuItem::Separator);
+                            continue;
+                        }
+                    }
+                    let text = self.text()?;
+                    self.expect(Token::Comma, "','")?;
+                    let id = self.number()? as u16;
+                    let flags = self.menu_options()?;
+                    menu.items.push(MenuItem::Command { id, text, flags });
+                }
+                "POPUP" => {
+                    let text = self.text()?;
+                    let flags = self.menu_options()?;
+                    self.expect(Token::Begin, "BEGIN")?;
+                    menu.items.push(MenuItem::Popup {
+                        text,
+                        flags,
+                        menu: self.menu_items()?,
+                    });
+                }
+                other => return Err(self.syntax(&format!("unknown menu item '{}'", other))),
+            }
+        }
+    }
+
+    fn menu_options(&mut self) -> Result<u16, TemplateError> {
+        let mut flags = 0;
+
+        loop {
+            if self.peek() == Some(&Token::Comma) {
+                self.position += 1;
+            }
+            let Some(Token::Word(word)) = self.peek() else {
+                return Ok(flags);
+            };
+            flags |= match word.to_ascii_uppercase().as_str() {
+                "GRAYED" => 0x0001,
+                "INACTIVE" => 0x0002,
+                "CHECKED" => 0x0008,
+                "MENUBARBREAK" => 0x0020,
+                "MENUBREAK" => 0x0040,
+                "HELP" => 0x4000,
+                _ => return Ok(flags),
+            };
+            self.position += 1;
+        }
+    }
+
+    fn memory_options(&mut self) {
+        while let Some(Token::Word(word)) = self.peek() {
+            if !MEMORY_OPTIONS.contains(&word.to_ascii_uppercase().as_str()) {
+                break;
+            }
+            self.position += 1;
+        }
+    }
+
+    /// Style bits to set and, from `NOT` terms, to clear.
+    fn style(&mut self) -> Result<(u32, u32), TemplateError> {
+        let (mut set, mut cleared) = (0, 0);
+
+        loop {
+            let negated =
+                matches!(self.peek(), Some(Token::Word(word)) if word.eq_ignore_ascii_case("NOT"));
+            if negated {
+                self.position += 1;
+            }
+            let bits = self.number()? as u32;
+            if negated {
+                cleared |= bits;
+                set &= !bits;
+            } else {
+                set |= bits;
+            }
+
+            match self.peek() {
+                Some(Token::Pipe | Token::Plus) => self.position += 1,
+                _ => return Ok((set, cleared)),
+            }
+        }
+    }
+
+    /// A number: a literal, a defined or built-in name, a negation or a parenthesised
+    /// `|` expression.
+    fn number(&mut self) -> Result<i64, TemplateError> {
+        match self.advance() {
+            Some(Token::Number(number)) => Ok(number),
+            Some(Token::Minus) => Ok(-self.number()?),
+            Some(Token::Open) => {
+                let mut value = self.number()?;
+                while let Some(Token::Pipe | Token::Plus) = self.peek() {
+                    self.position += 1;
+                    value |= self.number()?;
+                }
+                self.expect(Token::Close, "')'")?;
+                Ok(value)
+            }
+            Some(Token::Word(word)) => self
+                .symbol(&word)
+                .ok_or_else(|| self.syntax(&format!("unknown name '{}'", word))),
+            _ => Err(self.syntax("expected a number")),
+        }
+    }
+
+    /// Three numbers, each after a comma.
+    fn numbers(&mut self) -> Result<[i16; 3], TemplateError> {
+        let mut values = [0; 3];
+        for (index, value) in values.iter_mut().enumerate() {
+            if index > 0 {
+                self.expect(Token::Comma, "','")?;
+            }
+            *value = self.number()? as i16;
+        }
+        Ok(values)
+    }
+
+    fn symbol(&self, word: &str) -> Option<i64> {
+        self.symbols
+            .iter()
+            .find(|(name, _)| name == word)
+            .map(|(_, value)| *value)
+            .or_else(|| {
+                SYMBOLS
+                    .iter()
+                    .find(|(name, _)| *name == word)
+                    .map(|(_, value)| *value)
+            })
+    }
+
+    fn text(&mut self) -> Result<String, TemplateError> {
+        match self.advance() {
+            Some(Token::Text(text)) => Ok(text),
+            _ => Err(self.syntax("expected a string")),
+        }
+    }
+
+    /// A caption, or the icon a control shows, which may be a name or a number.
+    fn control_text(&mut self) -> Result<String, TemplateError> {
+        Ok(match self.name()? {
+            ResId::Number(number) => format!("#{}", number),
+            ResId::Name(text) => text,
+        })
+    }
+
+    /// A resource name: a string, a number, or a word standing for either.
+    fn name(&mut self) -> Result<ResId, TemplateError> {
+        match self.advance() {
+            Some(Token::Text(text)) => Ok(ResId::Name(text)),
+            Some(Token::Number(number)) => Ok(ResId::Number(number as u16)),
+            Some(Token::Word(word)) => Ok(match self.symbol(&word) {
+                Some(number) => ResId::Number(number as u16),
+                None => ResId::Name(word),
+            }),
+            _ => Err(self.syntax("expected a name")),
+        }
+    }
+
+    fn expect(&mut self, token: Token, what: &str) -> Result<(), TemplateError> {
+        if self.advance() == Some(token) {
+            Ok(())
+        } else {
+            Err(self.syntax(&format!("expected {}", what)))
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.peek().cloned();
+        self.position += 1;
+        token
+    }
+
+    /// An error on the line of the token just read.
+    fn syntax(&self, reason: &str) -> TemplateError {
+        let index = self
+            .position
+            .saturating_sub(1)
+            .min(self.tokens.len().saturating_sub(1));
+        TemplateError::Syntax {
+            line: self.tokens.get(index).map_or(1, |(_, line)| *line),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template::{parse_dialog, Align, ID_STATIC};
+    use crate::{checked_in, RT_DIALOG};
+    use std::path::PathBuf;
+
+    fn script(path: &str) -> Script {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../..")
+            .join(path);
+        Script::parse(&std::fs::read(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn reads_about_rc() {
+        let script = script("ABOUT.RC");
+        let about = script.dialog("ABOUTBOX").unwrap();
+
+        assert_eq!(about.caption, "About Nonogram Solver");
+        assert_eq!(
+            (about.x, about.y, about.width, about.height),
+            (28, 22, 157, 119)
+        );
+        assert_eq!(about.style, 0x90c8_00c0);
+        assert_eq!(about.font.as_ref().unwrap().face, "System");
+
+        let copyright = about.control(551).unwrap();
+        assert_eq!(copyright.text, "Copyright \u{a9} M G Davidson - 1994");
+        assert_eq!(copyright.kind, ControlKind::Text(Align::Left));
+        assert_eq!(copyright.style, 0x5002_0000);
+        assert_eq!(
+            (copyright.x, copyright.y, copyright.width, copyright.height),
+            (34, 24, 109, 9)
+        );
+
+        let ids: Vec<u16> = about.controls.iter().map(|control| control.id).collect();
+        assert_eq!(
+            ids,
+            [551, 554, 555, ID_STATIC, ID_STATIC, 552, 1, 550, ID_STATIC, 553]
+        );
+        assert_eq!(about.controls[3].kind, ControlKind::Icon);
+        assert_eq!(about.controls[3].text, "ICON_1");
+        assert_eq!(about.controls[8].kind, ControlKind::GroupBox);
+        assert_eq!(about.controls[8].style, 0x5000_0007);
+    }
+
+    /// The options and about dialogs the executable embeds keep every control the 1993
+    /// ones had, with the same kind of control behind each ID.
+    #[test]
+    fn modern_dialogs_keep_the_legacy_controls() {
+        let modern = script("crates/stopbus-ui/resources/stopbus.rc");
+        let legacy = checked_in();
+
+        for (name, ids) in [("OPTIONS", 500..=503), ("ABOUTBOX", 550..=556)] {
+            let old = parse_dialog(&legacy.find(RT_DIALOG, name).unwrap().data).unwrap();
+            let new = modern.dialog(name).unwrap();
+            for id in ids {
+                // Labels may be aligned differently; they only need to stay labels.
+                let kind = |dialog: &Dialog| {
+                    dialog.control(id).map(|control| match control.kind {
+                        ControlKind::Text(_) => ControlKind::Text(Align::Left),
+                        kind => kind,
+                    })
+                };
+                assert!(kind(&old).is_some(), "{} {}", name, id);
+                assert_eq!(kind(new), kind(&old), "{} {}", name, id);
+            }
+        }
+
+        let options = modern.dialog("OPTIONS").unwrap();
+        assert_eq!(
+            options.control(1).unwrap().kind,
+            ControlKind::PushButton { default: true }
+        );
+        assert_eq!(options.control(505).unwrap().text, "&Pop up game messages");
+
+        // `ICON_1` is #defined, so the icon refers to it by number.
+        let about = modern.dialog("ABOUTBOX").unwrap();
+        assert_eq!(about.controls[0].text, "#100");
+        assert_eq!(
+            (about.controls[0].width, about.controls[0].height),
+            (16, 16)
+        );
+    }
+
+    #[test]
+    fn reads_menus_and_skips_other_statements() {
+        let script = Script::parse(
+            b"#define CM_DEAL 100\n\
+              /* the game menu */\n\
+              ICON_1 ICON \"MD.ICO\"\n\
+              MENU MENU DISCARDABLE\n\
+              {\n\
+                POPUP \"&Game\"\n\
+                BEGIN\n\
+                  MENUITEM \"&Deal\\tF2\", CM_DEAL\n\
+                  MENUITEM \"&Options...\", 101, GRAYED\n\
+                  MENUITEM SEPARATOR\n\
+                END\n\
+                MENUITEM \"&Help\", 998, HELP\n\
+              }\n",
+        )
+        .unwrap();
+
+        assert!(script.dialogs.is_empty());
+        let menu = script.menu("menu").unwrap();
+        assert_eq!(
+            menu.command(100),
+            Some(&MenuItem::Command {
+                id: 100,
+                text: "&Deal\tF2".to_string(),
+                flags: 0
+            })
+        );
+        assert_eq!(
+            menu.command(101),
+            Some(&MenuItem::Command {
+                id: 101,
+                text: "&Options...".to_string(),
+                flags: 0x0001
+            })
+        );
+        assert!(
+            matches!(&menu.items[0], MenuItem::Popup { menu, .. } if menu.items[2] == MenuItem::Separator)
+        );
+        assert!(matches!(
+            &menu.items[1],
+            MenuItem::Command { flags: 0x4000, .. }
+        ));
+    }
+
+    #[test]
+    fn reports_the_line_of_bad_statements() {
+        let error = |text: &str| Script::parse(text.as_bytes()).unwrap_err().to_string();
+
+        assert_eq!(
+            error("X DIALOG 0, 0, 10, 10\nBEGIN\n  LTEXT \"a\", ID_MISSING, 0, 0, 5, 5\nEND\n"),
+            "line 3: unknown name 'ID_MISSING'"
+        );
+        assert_eq!(
+            error("X DIALOG 0, 0, 10, 10\nSTYLE WS_POPUP\nBEGIN\n  SLIDER 1, 0, 0, 5, 5\nEND\n"),
+            "line 4: unknown control 'SLIDER'"
+        );
+        assert_eq!(
+            error("X DIALOG 0, 0, 10, 10\nCAPTION \"open\n"),
+            "line 2: unterminated string"
+        );
+    }
+}
@@ -0,0 +1,517 @@
+//! Dialog and menu templates, decoded into a description any front end can lay out.
+//!
+//! Coordinates stay in dialog units, a quarter of the font's average character width
+//! across and an eighth of its height down, as the templates give them. Styles are kept
+//! as the raw window style bits next to the kind of control they make.
+
+use std::fmt;
+
+use crate::{read_u16, read_u32, ResId};
+
+/// The ID the resource compiler stores for `-1`, used by labels nothing refers to.
+pub const ID_STATIC: u16 = 0xFFFF;
+
+pub const WS_CHILD: u32 = 0x4000_0000;
+pub const WS_VISIBLE: u32 = 0x1000_0000;
+pub const WS_DISABLED: u32 = 0x0800_0000;
+pub const WS_GROUP: u32 = 0x0002_0000;
+pub const WS_TABSTOP: u32 = 0x0001_0000;
+
+pub const DS_SETFONT: u32 = 0x0040;
+
+pub const MF_GRAYED: u16 = 0x0001;
+pub const MF_CHECKED: u16 = 0x0008;
+pub const MF_POPUP: u16 = 0x0010;
+pub const MF_END: u16 = 0x0080;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dialog {
+    pub x: i16,
+    pub y: i16,
+    pub width: i16,
+    pub height: i16,
+    pub style: u32,
+    pub caption: String,
+    pub font: Option<Font>,
+    pub menu: Option<ResId>,
+    pub controls: Vec<Control>,
+}
+
+impl Dialog {
+    /// The first control with `id`.
+    pub fn control(&self, id: u16) -> Option<&Control> {
+        self.controls.iter().find(|control| control.id == id)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Font {
+    pub point_size: u16,
+    pub face: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Control {
+    pub id: u16,
+    pub kind: ControlKind,
+    /// `BUTTON`, `STATIC` and so on, or the registered class name of a custom control.
+    pub class: String,
+    /// The caption, or for an icon the resource it shows: a name, or `#` and a number.
+    pub text: String,
+    pub x: i16,
+    pub y: i16,
+    pub width: i16,
+    pub height: i16,
+    pub style: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ControlKind {
+    PushButton {
+        default: bool,
+    },
+    CheckBox {
+        auto: bool,
+    },
+    RadioButton {
+        auto: bool,
+    },
+    GroupBox,
+    Text(Align),
+    Icon,
+    Edit,
+    ListBox,
+    ComboBox,
+    ScrollBar,
+    /// A class or style this description has no name for.
+    Other,
+}
+
+impl ControlKind {
+    /// What a control of `class` with `style` looks like; class names ignore case.
+    pub fn classify(class: &str, style: u32) -> Self {
+        match class.to_ascii_uppercase().as_str() {
+            "BUTTON" => match style & 0x0F {
+                0 => ControlKind::PushButton { default: false },
+                1 => ControlKind::PushButton { default: true },
+                2 | 5 => ControlKind::CheckBox { auto: false },
+                3 | 6 => ControlKind::CheckBox { auto: true },
+                4 => ControlKind::RadioButton { auto: false },
+                9 => ControlKind::RadioButton { auto: true },
+                7 => ControlKind::GroupBox,
+                _ => ControlKind::Other,
+            },
+            "STATIC" => match style & 0x0F {
+                0 => ControlKind::Text(Align::Left),
+                1 => ControlKind::Text(Align::Center),
+                2 => ControlKind::Text(Align::Right),
+                3 => ControlKind::Icon,
+                _ => ControlKind::Other,
+            },
+            "EDIT" => ControlKind::Edit,
+            "LISTBOX" => ControlKind::ListBox,
+            "COMBOBOX" => ControlKind::ComboBox,
+            "SCROLLBAR" => ControlKind::ScrollBar,
+            _ => ControlKind::Other,
+        }
+    }
+}
+
+/// The items of a menu bar, or of one of its popups.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Menu {
+    pub items: Vec<MenuItem>,
+}
+
+impl Menu {
+    /// The command with `id`, looking inside popups.
+    pub fn command(&self, id: u16) -> Option<&MenuItem> {
+        self.items.iter().find_map(|item| match item {
+            MenuItem::Command { id: found, .. } if *found == id => Some(item),
+            MenuItem::Popup { menu, .. } => menu.command(id),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MenuItem {
+    /// `flags` holds the remaining `MF_` bits, such as [`MF_GRAYED`] and [`MF_CHECKED`].
+    Command {
+        id: u16,
+        text: String,
+        flags: u16,
+    },
+    Popup {
+        text: String,
+        flags: u16,
+        menu: Menu,
+    },
+    Separator,
+}
+
+/// Decodes a Win16 `DIALOG` resource.
+///
+/// The header is the style, a control count, the position and size, then the menu, the
+/// window class and the caption, and the font when `DS_SETFONT` is set. Each control is
+/// its position, size, ID and style, a class byte (`0x80` to `0x85` for the predefined
+/// classes) or name, its text and a count of extra bytes to skip.
+pub fn parse_dialog(data: &[u8]) -> Result<Dialog, TemplateError> {
+    let mut reader = Reader { data, offset: 0 };
+
+    let style = reader.u32()?;
+    let count = reader.u8()?;
+    let [x, y, width, height] = reader.rect()?;
+    let menu = reader.id()?;
+    let _class = reader.id()?;
+    let caption = reader.string()?;
+    let font = if style & DS_SETFONT != 0 {
+        Some(Font {
+            point_size: reader.u16()?,
+            face: reader.string()?,
+        })
+    } else {
+        None
+    };
+
+    let mut controls = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let [x, y, width, height] = reader.rect()?;
+        let id = reader.u16()?;
+        let style = reader.u32()?;
+        let class = match reader.u8()? {
+            0x80 => "BUTTON".to_string(),
+            0x81 => "EDIT".to_string(),
+            0x82 => "STATIC".to_string(),
+            0x83 => "LISTBOX".to_string(),
+            0x84 => "SCROLLBAR".to_string(),
+            0x85 => "COMBOBOX".to_string(),
+            _ => {
+                reader.offset -= 1;
+                reader.string()?
+            }
+        };
+        let text = match reader.id()? {
+            Some(ResId::Number(number)) => format!("#{}", number),
+            Some(ResId::Name(text)) => text,
+            None => String::new(),
+        };
+        let extra = reader.u8()?;
+        reader.skip(extra as usize)?;
+
+        controls.push(Control {
+            id,
+            kind: ControlKind::classify(&class, style),
+            class,
+            text,
+            x,
+            y,
+            width,
+            height,
+            style,
+        });
+    }
+
+    Ok(Dialog {
+        x,
+        y,
+        width,
+        height,
+        style,
+        caption,
+        font,
+        menu,
+        controls,
+    })
+}
+
+/// Decodes a Win16 `MENU` resource: a four-byte header, then items in order. Each is
+/// its flags, an ID unless it opens a popup, and its text. A popup's items follow it
+/// directly, and `MF_END` marks the last item at each level.
+pub fn parse_menu(data: &[u8]) -> Result<Menu, TemplateError> {
+    if data.len() < 4 {
+        return Err(TemplateError::Truncated { offset: 0 });
+    }
+
+    read_items(&mut Reader { data, offset: 4 })
+}
+
+fn read_items(reader: &mut Reader) -> Result<Menu, TemplateError> {
+    let mut menu = Menu::default();
+
+    loop {
+        let flags = reader.u16()?;
+        let rest = flags & !(MF_POPUP | MF_END);
+
+        if flags & MF_POPUP != 0 {
+            let text = reader.string()?;
+            menu.items.push(MenuItem::Popup {
+                text,
+                flags: rest,
+                menu: read_items(reader)?,
+            });
+        } else {
+            let id = reader.u16()?;
+            let text = reader.string()?;
+            menu.items.push(if id == 0 && text.is_empty() && rest == 0 {
+                MenuItem::Separator
+            } else {
+                MenuItem::Command {
+                    id,
+                    text,
+                    flags: rest,
+                }
+            });
+        }
+
+        if flags & MF_END != 0 {
+            return Ok(menu);
+        }
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl Reader<'_> {
+    fn truncated(&self) -> TemplateError {
+        TemplateError::Truncated {
+            offset: self.offset,
+        }
+    }
+
+    fn skip(&mut self, count: usize) -> Result<(), TemplateError> {
+        if self.data.len() - self.offset < count {
+            return Err(self.truncated());
+        }
+        self.offset += count;
+        Ok(())
+    }
+
+    fn u8(&mut self) -> Result<u8, TemplateError> {
+        let value = *self.data.get(self.offset).ok_or_else(|| self.truncated())?;
+        self.offset += 1;
+        Ok(value)
+    }
+
+    fn u16(&mut self) -> Result<u16, TemplateError> {
+        let value = read_u16(self.data, self.offset).ok_or_else(|| self.truncated())?;
+        self.offset += 2;
+        Ok(value)
+    }
+
+    fn u32(&mut self) -> Result<u32, TemplateError> {
+        let value = read_u32(self.data, self.offset).ok_or_else(|| self.truncated())?;
+        self.offset += 4;
+        Ok(value)
+    }
+
+    fn rect(&mut self) -> Result<[i16; 4], TemplateError> {
+        let mut rect = [0; 4];
+        for value in &mut rect {
+            *value = self.u16()? as i16;
+        }
+        Ok(rect)
+    }
+
+    /// NUL-terminated ANSI text, read as Latin-1.
+    fn string(&mut self) -> Result<String, TemplateError> {
+        let rest = &self.data[self.offset.min(self.data.len())..];
+        let length = rest
+            .iter()
+            .position(|&byte| byte == 0)
+            .ok_or_else(|| self.truncated())?;
+        self.offset += length + 1;
+        Ok(rest[..length]
+            .iter()
+            .map(|&byte| char::from(byte))
+            .collect())
+    }
+
+    /// A string, `0xFF` and a number, or nothing when the string is empty.
+    fn id(&mut self) -> Result<Option<ResId>, TemplateError> {
+        if self.data.get(self.offset) == Some(&0xFF) {
+            self.offset += 1;
+            return Ok(Some(ResId::Number(self.u16()?)));
+        }
+        let text = self.string()?;
+        Ok((!text.is_empty()).then_some(ResId::Name(text)))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TemplateError {
+    /// The template ends in the middle of the field at `offset`.
+    Truncated { offset: usize },
+    /// A resource script line that could not be read.
+    Syntax { line: usize, reason: String },
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::Truncated { offset } => {
+                write!(f, "template is cut short at byte {}", offset)
+            }
+            TemplateError::Syntax { line, reason } => write!(f, "line {}: {}", line, reason),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{checked_in, RT_DIALOG, RT_MENU};
+
+    fn dialog(name: &str) -> Dialog {
+        parse_dialog(&checked_in().find(RT_DIALOG, name).unwrap().data).unwrap()
+    }
+
+    /// ID, kind and text of each control, in template order.
+    fn summary(dialog: &Dialog) -> Vec<(u16, ControlKind, &str)> {
+        dialog
+            .controls
+            .iter()
+            .map(|control| (control.id, control.kind, control.text.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn reads_the_options_dialog() {
+        let options = dialog("OPTIONS");
+
+        assert_eq!(options.caption, "Options");
+        assert_eq!(options.font, None);
+        assert_eq!(
+            (options.x, options.y, options.width, options.height),
+            (18, 19, 130, 77)
+        );
+
+        let check = ControlKind::CheckBox { auto: true };
+        assert_eq!(
+            summary(&options),
+            [
+                (1, ControlKind::PushButton { default: false }, "OK"),
+                (2, ControlKind::PushButton { default: false }, "Cancel"),
+                (998, ControlKind::PushButton { default: false }, "&Help"),
+                (500, check, "&Card window"),
+                (501, check, "&Stack window"),
+                (502, check, "S&core window"),
+                (503, check, "Save settings on e&xit"),
+                (ID_STATIC, ControlKind::GroupBox, "Cheat options"),
+                (ID_STATIC, ControlKind::GroupBox, ""),
+            ]
+        );
+
+        let save = options.control(503).unwrap();
+        assert_eq!((save.x, save.y, save.width, save.height), (8, 60, 81, 12));
+        assert_eq!(
+            save.style & (WS_CHILD | WS_VISIBLE | WS_TABSTOP),
+            WS_CHILD | WS_VISIBLE | WS_TABSTOP
+        );
+    }
+
+    #[test]
+    fn reads_the_about_dialog() {
+        let about = dialog("ABOUTBOX");
+
+        assert_eq!(about.caption, "About <Program Name>");
+        assert_eq!(
+            about.font,
+            Some(Font {
+                point_size: 10,
+                face: "System".to_string()
+            })
+        );
+        for id in 550..=556 {
+            assert!(
+                matches!(about.control(id).unwrap().kind, ControlKind::Text(_)),
+                "{}",
+                id
+            );
+        }
+        assert_eq!(
+            about.control(551).unwrap().text,
+            "Copyright \u{a9} Martin Davidson - 1994"
+        );
+        assert!(about
+            .control(556)
+            .unwrap()
+            .text
+            .contains("Hertford College"));
+
+        let icons: Vec<&str> = about
+            .controls
+            .iter()
+            .filter(|control| control.kind == ControlKind::Icon)
+            .map(|control| control.text.as_str())
+            .collect();
+        assert_eq!(icons, ["ICON_1", "ICON_2"]);
+    }
+
+    #[test]
+    fn reads_the_menu() {
+        let menu = parse_menu(&checked_in().find(RT_MENU, "MENU").unwrap().data).unwrap();
+
+        let command = |id: u16, text: &str| MenuItem::Command {
+            id,
+            text: text.to_string(),
+            flags: 0,
+        };
+        assert_eq!(
+            menu.items,
+            [
+                MenuItem::Popup {
+                    text: "&Game".to_string(),
+                    flags: 0,
+                    menu: Menu {
+                        items: vec![
+                            command(100, "&Deal"),
+                            command(101, "&Options..."),
+                            MenuItem::Separator,
+                            command(102, "E&xit"),
+                        ]
+                    },
+                },
+                MenuItem::Popup {
+                    text: "&Help".to_string(),
+                    flags: 0,
+                    menu: Menu {
+                        items: vec![
+                            command(900, "&Contents"),
+                            command(901, "&Using help"),
+                            MenuItem::Separator,
+                            command(999, "&About..."),
+                        ]
+                    },
+                },
+            ]
+        );
+        assert_eq!(menu.command(999), Some(&command(999, "&About...")));
+    }
+
+    #[test]
+    fn reports_cut_short_templates() {
+        let file = checked_in();
+        let data = &file.find(RT_DIALOG, "OPTIONS").unwrap().data;
+        assert_eq!(
+            parse_dialog(&data[..40]),
+            Err(TemplateError::Truncated { offset: 38 })
+        );
+        assert_eq!(
+            parse_menu(b"\0\0\0\0\x10\0&Game\0"),
+            Err(TemplateError::Truncated { offset: 12 })
+        );
+    }
+}
@@ -0,0 +1,246 @@
+//! Typed names for every stored setting.
+//!
+//! Each key has a TOML section and name, and the registry value the Win32 build has always
+//! used, so both backends store the same things under names their users already know.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::Value;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValueKind {
+    Bool,
+    Int,
+    Text,
+}
+
+/// The untyped description of a key, for backends that walk every key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyInfo {
+    pub section: &'static str,
+    pub name: &'static str,
+    /// Value name under the registry key; a DWORD for flags and numbers.
+    pub registry_name: &'static str,
+    pub kind: ValueKind,
+}
+
+impl KeyInfo {
+    /// "options.save_on_exit"
+    pub fn path(&self) -> String {
+        format!("{}.{}", self.section, self.name)
+    }
+}
+
+/// Types a key can hold.
+pub trait SettingValue: Sized {
+    const KIND: ValueKind;
+
+    fn into_value(self) -> Value;
+
+    fn from_value(value: &Value) -> Option<Self>;
+}
+
+impl SettingValue for bool {
+    const KIND: ValueKind = ValueKind::Bool;
+
+    fn into_value(self) -> Value {
+        Value::Bool(self)
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Bool(flag) => Some(*flag),
+            _ => None,
+        }
+    }
+}
+
+impl SettingValue for i32 {
+    const KIND: ValueKind = ValueKind::Int;
+
+    fn into_value(self) -> Value {
+        Value::Int(self.into())
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Int(number) => i32::try_from(*number).ok(),
+            _ => None,
+        }
+    }
+}
+
+impl SettingValue for String {
+    const KIND: ValueKind = ValueKind::Text;
+
+    fn into_value(self) -> Value {
+        Value::Text(self)
+    }
+
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Text(text) => Some(text.clone()),
+            _ => None,
+        }
+    }
+}
+
+pub struct Key<T> {
+    info: KeyInfo,
+    value: PhantomData<fn() -> T>,
+}
+
+impl<T: SettingValue> Key<T> {
+    const fn new(section: &'static str, name: &'static str, registry_name: &'static str) -> Self {
+        Self {
+            info: KeyInfo {
+                section,
+                name,
+                registry_name,
+                kind: T::KIND,
+            },
+            value: PhantomData,
+        }
+    }
+}
+
+impl<T> Key<T> {
+    pub fn info(&self) -> &KeyInfo {
+        &self.info
+    }
+}
+
+impl<T> Clone for Key<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Key<T> {}
+
+impl<T> fmt::Debug for Key<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Key({})", self.info.path())
+    }
+}
+
+/// A screen position stored as two numbers.
+#[derive(Clone, Copy, Debug)]
+pub struct PointKey {
+    pub x: Key<i32>,
+    pub y: Key<i32>,
+}
+
+pub const CHEAT_CARDS_VISIBLE: Key<bool> =
+    Key::new("options", "cheat_cards_visible", "CheatCardsVisible");
+
+pub const CHEAT_STACK_VISIBLE: Key<bool> =
+    Key::new("options", "cheat_stack_visible", "CheatStackVisible");
+
+pub const CHEAT_SCORES_VISIBLE: Key<bool> =
+    Key::new("options", "cheat_scores_visible", "CheatScoresVisible");
+
+pub const TABLE_READ_VISIBLE: Key<bool> =
+    Key::new("options", "table_read_visible", "TableReadVisible");
+
+pub const SAVE_ON_EXIT: Key<bool> = Key::new("options", "save_on_exit", "SaveOnExit");
+
+pub const MODAL_EVENTS: Key<bool> = Key::new("options", "modal_events", "ModalEvents");
+
+/// Whether alerts pop up over the table while game messages go to the log instead.
+pub const ALERT_TOASTS: Key<bool> = Key::new("options", "alert_toasts", "AlertToasts");
+
+/// Folder name of the card theme under the themes directory; unset means the built-in cards.
+pub const THEME: Key<String> = Key::new("appearance", "theme", "Theme");
+
+pub const MAIN_WINDOW_POS: PointKey = PointKey {
+    x: Key::new("windows", "main_window_x", "MainWindowPosX"),
+    y: Key::new("windows", "main_window_y", "MainWindowPosY"),
+};
+
+pub const CHEAT_CARDS_POS: PointKey = PointKey {
+    x: Key::new("windows", "cheat_cards_x", "CheatCardsPosX"),
+    y: Key::new("windows", "cheat_cards_y", "CheatCardsPosY"),
+};
+
+pub const CHEAT_STACK_POS: PointKey = PointKey {
+    x: Key::new("windows", "cheat_stack_x", "CheatStackPosX"),
+    y: Key::new("windows", "cheat_stack_y", "CheatStackPosY"),
+};
+
+pub const CHEAT_SCORES_POS: PointKey = PointKey {
+    x: Key::new("windows", "cheat_scores_x", "CheatScoresPosX"),
+    y: Key::new("windows", "cheat_scores_y", "CheatScoresPosY"),
+};
+
+pub const TABLE_READ_POS: PointKey = PointKey {
+    x: Key::new("windows", "table_read_x", "TableReadPosX"),
+    y: Key::new("windows", "table_read_y", "TableReadPosY"),
+};
+
+/// Every key, in the order the options dialog and window list present them.
+pub const ALL_KEYS: [KeyInfo; 18] = [
+    CHEAT_CARDS_VISIBLE.info,
+    CHEAT_STACK_VISIBLE.info,
+    CHEAT_SCORES_VISIBLE.info,
+    TABLE_READ_VISIBLE.info,
+    SAVE_ON_EXIT.info,
+    MODAL_EVENTS.info,
+    ALERT_TOASTS.info,
+    THEME.info,
+    MAIN_WINDOW_POS.x.info,
+    MAIN_WINDOW_POS.y.info,
+    CHEAT_CARDS_POS.x.info,
+    CHEAT_CARDS_POS.y.info,
+    CHEAT_STACK_POS.x.info,
+    CHEAT_STACK_POS.y.info,
+    CHEAT_SCORES_POS.x.info,
+    CHEAT_SCORES_POS.y.info,
+    TABLE_READ_POS.x.info,
+    TABLE_READ_POS.y.info,
+];
+
+pub fn find(path: &str) -> Option<&'static KeyInfo> {
+    ALL_KEYS.iter().find(|info| info.path() == path)
+}
+
+pub fn find_registry_name(name: &str) -> Option<&'static KeyInfo> {
+    ALL_KEYS
+        .iter()
+        .find(|info| info.registry_name.eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_are_unique_in_both_backends() {
+        for (index, key) in ALL_KEYS.iter().enumerate() {
+            for other in &ALL_KEYS[index + 1..] {
+                assert_ne!(key.path(), other.path());
+                assert!(!key.registry_name.eq_ignore_ascii_case(other.registry_name));
+            }
+        }
+
+        assert_eq!(find("options.save_on_exit"), Some(SAVE_ON_EXIT.info()));
+        assert_eq!(
+            find_registry_name("mainwindowposy"),
+            Some(MAIN_WINDOW_POS.y.info())
+        );
+        assert_eq!(find("options.missing"), None);
+    }
+
+    #[test]
+    fn values_convert_only_to_their_own_type() {
+        assert_eq!(bool::from_value(&Value::Bool(true)), Some(true));
+        assert_eq!(bool::from_value(&Value::Int(1)), None);
+        assert_eq!(i32::from_value(&Value::Int(-40)), Some(-40));
+        assert_eq!(i32::from_value(&Value::Int(1 << 40)), None);
+        assert_eq!(
+            String::from_value(&Value::Text("green".into())),
+            Some("green".to_string())
+        );
+    }
+}
@@ -0,0 +1,265 @@
+//! Persistent user settings: typed keys over interchangeable storage backends.
+//!
+//! Settings live in `settings.toml` under the platform config directory. On Windows the
+//! registry key the game has always used is a backend too, so existing installs keep
+//! their options and window positions.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::ffi::OsString;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+pub mod keys;
+pub mod migrate;
+#[cfg(windows)]
+pub mod registry;
+pub mod toml;
+
+pub use keys::{Key, KeyInfo, PointKey, SettingValue, ValueKind};
+#[cfg(windows)]
+pub use registry::Registry;
+pub use toml::TomlFile;
+
+/// Version written with every save. Bump it, and teach [`upgrade`] the step, whenever a
+/// key is renamed or changes meaning.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Value {
+    Bool(bool),
+    Int(i64),
+    Text(String),
+}
+
+impl Value {
+    pub fn kind(&self) -> ValueKind {
+        match self {
+            Value::Bool(_) => ValueKind::Bool,
+            Value::Int(_) => ValueKind::Int,
+            Value::Text(_) => ValueKind::Text,
+        }
+    }
+}
+
+/// Stored values keyed by `section.name`. Values a backend found but no key describes are
+/// kept, so saving with an older build does not lose them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Settings {
+    values: BTreeMap<String, Value>,
+}
+
+impl Settings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The stored value, or `None` when it is missing or holds another type.
+    pub fn get<T: SettingValue>(&self, key: Key<T>) -> Option<T> {
+        self.values.get(&key.info().path()).and_then(T::from_value)
+    }
+
+    pub fn set<T: SettingValue>(&mut self, key: Key<T>, value: T) {
+        self.values.insert(key.info().path(), value.into_value());
+    }
+
+    pub fn remove<T>(&mut self, key: Key<T>) {
+        self.values.remove(&key.info().path());
+    }
+
+    /// Both coordinates, or `None` unless both are stored.
+    pub fn point(&self, key: PointKey) -> Option<(i32, i32)> {
+        Some((self.get(key.x)?, self.get(key.y)?))
+    }
+
+    pub fn set_point(&mut self, key: PointKey, (x, y): (i32, i32)) {
+        self.set(key.x, x);
+        self.set(key.y, y);
+    }
+
+    pub fn get_raw(&self, path: &str) -> Option<&Value> {
+        self.values.get(path)
+    }
+
+    pub fn set_raw(&mut self, path: impl Into<String>, value: Value) {
+        self.values.insert(path.into(), value);
+    }
+
+    /// Every stored `(path, value)`, sorted by path.
+    pub fn values(&self) -> impl Iterator<Item = (&str, &Value)> + '_ {
+        self.values
+            .iter()
+            .map(|(path, value)| (path.as_str(), value))
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+/// Where settings are kept.
+pub trait Backend {
+    /// Reads every stored value. A store that does not exist yet loads as empty.
+    fn load(&self) -> Result<Settings, SettingsError>;
+
+    fn save(&self, settings: &Settings) -> Result<(), SettingsError>;
+
+    /// A path or registry key for messages.
+    fn location(&self) -> String;
+}
+
+/// Brings settings stored by an older schema up to [`SCHEMA_VERSION`].
+///
+/// Version 0 is the registry layout written before versioning; its value names are the
+/// ones the keys still use, so it needs no changes.
+pub fn upgrade(settings: Settings, version: u32) -> Result<Settings, SettingsError> {
+    match version {
+        0 | SCHEMA_VERSION => Ok(settings),
+        _ => Err(SettingsError::UnsupportedVersion(version)),
+    }
+}
+
+/// The per-user directory settings go in: `%APPDATA%\StopBus` on Windows,
+/// `~/Library/Application Support/StopBus` on macOS and `$XDG_CONFIG_HOME/stopbus`
+/// (or `~/.config/stopbus`) elsewhere.
+pub fn config_dir() -> Option<PathBuf> {
+    config_dir_from(|name| env::var_os(name))
+}
+
+fn config_dir_from(var: impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    let non_empty = |name: &str| {
+        var(name)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    };
+
+    if cfg!(windows) {
+        non_empty("APPDATA").map(|dir| dir.join("StopBus"))
+    } else if cfg!(target_os = "macos") {
+        non_empty("HOME").map(|home| home.join("Library/Application Support/StopBus"))
+    } else {
+        non_empty("XDG_CONFIG_HOME")
+            .or_else(|| non_empty("HOME").map(|home| home.join(".config")))
+            .map(|dir| dir.join("stopbus"))
+    }
+}
+
+/// The registry on Windows, `settings.toml` in [`config_dir`] elsewhere; `None` when no
+/// config directory can be found.
+pub fn default_backend() -> Option<Box<dyn Backend>> {
+    #[cfg(windows)]
+    {
+        Some(Box::new(Registry::default()))
+    }
+
+    #[cfg(not(windows))]
+    {
+        TomlFile::in_config_dir().map(|file| Box::new(file) as Box<dyn Backend>)
+    }
+}
+
+#[derive(Debug)]
+pub enum SettingsError {
+    Io(io::Error),
+    MissingVersion,
+    UnsupportedVersion(u32),
+    InvalidLine {
+        line: usize,
+        reason: String,
+    },
+    /// A registry call failed with this Win32 error code.
+    Registry(u32),
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::Io(err) => write!(f, "failed to access settings file: {}", err),
+            SettingsError::MissingVersion => write!(f, "missing 'schema = <version>' line"),
+            SettingsError::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported settings schema {} (expected {})",
+                version, SCHEMA_VERSION
+            ),
+            SettingsError::InvalidLine { line, reason } => {
+                write!(f, "line {}: {}", line, reason)
+            }
+            SettingsError::Registry(code) => write!(f, "registry error {}", code),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SettingsError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typed_access_and_points() {
+        let mut settings = Settings::new();
+        assert_eq!(settings.get(keys::SAVE_ON_EXIT), None);
+
+        settings.set(keys::SAVE_ON_EXIT, true);
+        settings.set_point(keys::CHEAT_STACK_POS, (-1200, 40));
+        assert_eq!(settings.get(keys::SAVE_ON_EXIT), Some(true));
+        assert_eq!(settings.point(keys::CHEAT_STACK_POS), Some((-1200, 40)));
+
+        settings.remove(keys::CHEAT_STACK_POS.y);
+        assert_eq!(settings.point(keys::CHEAT_STACK_POS), None);
+
+        settings.set_raw("options.save_on_exit", Value::Int(1));
+        assert_eq!(settings.get(keys::SAVE_ON_EXIT), None);
+
+        let paths: Vec<&str> = settings.values().map(|(path, _)| path).collect();
+        assert_eq!(paths, ["options.save_on_exit", "windows.cheat_stack_x"]);
+    }
+
+    #[test]
+    fn upgrade_accepts_known_versions() {
+        let mut settings = Settings::new();
+        settings.set(keys::MODAL_EVENTS, false);
+
+        assert_eq!(upgrade(settings.clone(), 0).unwrap(), settings);
+        assert_eq!(upgrade(settings.clone(), SCHEMA_VERSION).unwrap(), settings);
+        assert!(matches!(
+            upgrade(settings, SCHEMA_VERSION + 1),
+            Err(SettingsError::UnsupportedVersion(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(all(unix, not(target_os = "macos")))]
+    fn config_dir_follows_xdg() {
+        let env = |pairs: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                pairs
+                    .iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| OsString::from(value))
+            }
+        };
+
+        assert_eq!(
+            config_dir_from(env(&[("XDG_CONFIG_HOME", "/xdg"), ("HOME", "/home/a")])),
+            Some(PathBuf::from("/xdg/stopbus"))
+        );
+        assert_eq!(
+            config_dir_from(env(&[("XDG_CONFIG_HOME", ""), ("HOME", "/home/a")])),
+            Some(PathBuf::from("/home/a/.config/stopbus"))
+        );
+        assert_eq!(config_dir_from(env(&[])), None);
+    }
+}
@@ -0,0 +1,482 @@
+//! Imports settings left by older builds.
+//!
+//! The 1994 Pascal build kept its options in `STOPBUS.INI` (`INIFILENAME` in
+//! `STOPBUS.PAS`), written with `WritePrivateProfileString`:
+//!
+//! ```text
+//! [Cheat Cards]
+//! Exists=1
+//! X position=0
+//! Y position=280
+//!
+//! [Main Window]
+//! Save on exit=1
+//! ```
+//!
+//! The Rust build before this crate wrote DWORDs under its registry key, which users can
+//! carry over as a `.reg` export. Both map onto the modern keys; every line that does not
+//! is listed in [`Import::unrecognised`] rather than dropped silently.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::keys::{self, Key, KeyInfo, PointKey, ValueKind};
+use crate::{Settings, SettingsError, Value, SCHEMA_VERSION};
+
+/// The legacy file name, `INIFILENAME` in `STOPBUS.PAS`.
+pub const INI_FILE_NAME: &str = "STOPBUS.INI";
+
+/// The registry key the Rust build wrote, as it appears in a `.reg` export.
+pub const REG_KEY_PATH: &str = r"HKEY_CURRENT_USER\Software\StopBus\Modernization";
+
+const REG_HEADERS: [&str; 2] = ["Windows Registry Editor Version 5.00", "REGEDIT4"];
+
+/// Registry value holding the schema version; known, but not a setting.
+const REG_VERSION_VALUE: &str = "SchemaVersion";
+
+/// A source line that did not map onto any setting.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Unrecognised {
+    pub line: usize,
+    pub text: String,
+    pub reason: String,
+}
+
+impl fmt::Display for Unrecognised {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {} ({})", self.line, self.text, self.reason)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Import {
+    pub settings: Settings,
+    pub unrecognised: Vec<Unrecognised>,
+}
+
+impl Import {
+    /// Reads `STOPBUS.INI`. Names are matched without regard to case and numbers the way
+    /// `GetPrivateProfileInt` reads them, so any file the old build accepted imports.
+    pub fn from_ini(text: &str) -> Self {
+        let mut import = Import::default();
+        let mut section: Option<String> = None;
+
+        for (index, raw) in text.lines().enumerate() {
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with(';') {
+                continue;
+            }
+            let mut skip = |reason: &str| import.skip(index + 1, line, reason);
+
+            if let Some(rest) = line.strip_prefix('[') {
+                match rest.split_once(']') {
+                    Some((name, _)) => section = Some(name.trim().to_string()),
+                    None => skip("expected '[section]'"),
+                }
+                continue;
+            }
+
+            let Some((name, value)) = line.split_once('=') else {
+                skip("expected 'name=value'");
+                continue;
+            };
+            let Some(section) = &section else {
+                skip("outside a section");
+                continue;
+            };
+
+            let Some(key) = ini_key(section, name.trim()) else {
+                skip("unknown setting");
+                continue;
+            };
+            let Some(number) = profile_int(value) else {
+                skip("not a number");
+                continue;
+            };
+
+            let path = key.path();
+            if import.settings.get_raw(&path).is_some() {
+                import.skip(index + 1, line, "duplicate; the first one is used");
+                continue;
+            }
+
+            let value = match key.kind {
+                ValueKind::Bool => Value::Bool(number != 0),
+                _ => Value::Int(number.into()),
+            };
+            import.settings.set_raw(path, value);
+        }
+
+        import
+    }
+
+    /// Reads a `.reg` export of the registry key. Values under other keys, and values of
+    /// the wrong type, are reported.
+    pub fn from_reg(text: &str) -> Result<Self, SettingsError> {
+        let mut import = Import::default();
+        let mut lines = logical_lines(text).into_iter();
+
+        let header = lines.by_ref().find(|(_, line)| !line.is_empty());
+        if !header
+            .as_ref()
+            .is_some_and(|(_, line)| REG_HEADERS.contains(&line.as_str()))
+        {
+            return Err(SettingsError::InvalidLine {
+                line: header.map_or(1, |(line, _)| line),
+                reason: format!("expected '{}'", REG_HEADERS[0]),
+            });
+        }
+
+        let mut in_key = false;
+
+        for (line_number, line) in lines {
+            if line.is_empty() || line.starts_with(';') {
+                continue;
+            }
+
+            if let Some(rest) = line.strip_prefix('[') {
+                let path = rest.trim_end_matches(']');
+                in_key = path.eq_ignore_ascii_case(REG_KEY_PATH);
+                if !in_key {
+                    import.skip(line_number, &line, "not the StopBus key");
+                }
+                continue;
+            }
+
+            if !in_key {
+                import.skip(line_number, &line, "not the StopBus key");
+                continue;
+            }
+
+            let Some((name, data)) = parse_reg_value(&line) else {
+                import.skip(line_number, &line, "expected '\"Name\"=value'");
+                continue;
+            };
+
+            if name.eq_ignore_ascii_case(REG_VERSION_VALUE) {
+                match data {
+                    RegData::Dword(version) if version <= SCHEMA_VERSION => {}
+                    _ => import.skip(line_number, &line, "unsupported schema version"),
+                }
+                continue;
+            }
+
+            let Some(key) = keys::find_registry_name(&name) else {
+                import.skip(line_number, &line, "unknown setting");
+                continue;
+            };
+
+            let value = match (key.kind, data) {
+                (ValueKind::Bool, RegData::Dword(value)) => Value::Bool(value != 0),
+                (ValueKind::Int, RegData::Dword(value)) => Value::Int((value as i32).into()),
+                (ValueKind::Text, RegData::String(text)) => Value::Text(text),
+                (ValueKind::Text, _) => {
+                    import.skip(line_number, &line, "expected a string");
+                    continue;
+                }
+                (_, RegData::Other) | (_, RegData::String(_)) => {
+                    import.skip(line_number, &line, "expected a dword");
+                    continue;
+                }
+            };
+            import.settings.set_raw(key.path(), value);
+        }
+
+        Ok(import)
+    }
+
+    /// Reads a `.reg` file or `STOPBUS.INI`, telling them apart by extension.
+    pub fn from_file(path: &Path) -> Result<Self, SettingsError> {
+        let bytes = fs::read(path).map_err(SettingsError::Io)?;
+        let text = decode_text(&bytes);
+
+        let is_reg = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("reg"));
+        if is_reg {
+            Self::from_reg(&text)
+        } else {
+            Ok(Self::from_ini(&text))
+        }
+    }
+
+    /// Copies the imported values over `settings`, keeping anything the import lacks.
+    pub fn apply_to(&self, settings: &mut Settings) {
+        for (path, value) in self.settings.values() {
+            settings.set_raw(path, value.clone());
+        }
+    }
+
+    fn skip(&mut self, line: usize, text: &str, reason: &str) {
+        self.unrecognised.push(Unrecognised {
+            line,
+            text: text.to_string(),
+            reason: reason.to_string(),
+        });
+    }
+}
+
+/// Text from an export: regedit writes UTF-16 with a byte order mark, `REGEDIT4` files and
+/// INI files are 8-bit. Bytes that are not UTF-8 are read as Latin-1.
+pub fn decode_text(bytes: &[u8]) -> String {
+    if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        let units: Vec<u16> = rest
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        return String::from_utf16_lossy(&units);
+    }
+
+    let bytes = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => bytes.iter().map(|&byte| char::from(byte)).collect(),
+    }
+}
+
+fn ini_key(section: &str, name: &str) -> Option<KeyInfo> {
+    let section = section.to_ascii_lowercase();
+    let name = name.to_ascii_lowercase();
+
+    let (visible, position): (Key<bool>, PointKey) = match section.as_str() {
+        "cheat cards" => (keys::CHEAT_CARDS_VISIBLE, keys::CHEAT_CARDS_POS),
+        "cheat stack" => (keys::CHEAT_STACK_VISIBLE, keys::CHEAT_STACK_POS),
+        "cheat score" => (keys::CHEAT_SCORES_VISIBLE, keys::CHEAT_SCORES_POS),
+        "main window" => {
+            return match name.as_str() {
+                "x position" => Some(*keys::MAIN_WINDOW_POS.x.info()),
+                "y position" => Some(*keys::MAIN_WINDOW_POS.y.info()),
+                "save on exit" => Some(*keys::SAVE_ON_EXIT.info()),
+                _ => None,
+            };
+        }
+        _ => return None,
+    };
+
+    match name.as_str() {
+        "exists" => Some(*visible.info()),
+        "x position" => Some(*position.x.info()),
+        "y position" => Some(*position.y.info()),
+        _ => None,
+    }
+}
+
+/// A leading, optionally signed, decimal number, as `GetPrivateProfileInt` reads it.
+fn profile_int(value: &str) -> Option<i32> {
+    let value = value.trim();
+    let (sign, digits) = match value.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, value),
+    };
+    let end = digits
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(digits.len());
+
+    digits[..end]
+        .parse::<i64>()
+        .ok()
+        .and_then(|number| i32::try_from(sign * number).ok())
+}
+
+/// Joins hex data continued with a trailing backslash, keeping the first line's number.
+fn logical_lines(text: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    let mut continuing = false;
+
+    for (index, raw) in text.lines().enumerate() {
+        let line = raw.trim();
+        let (content, continues) = match line.strip_suffix('\\') {
+            Some(rest) => (rest, true),
+            None => (line, false),
+        };
+
+        match lines.last_mut() {
+            Some((_, joined)) if continuing => joined.push_str(content),
+            _ => lines.push((index + 1, content.to_string())),
+        }
+        continuing = continues;
+    }
+
+    lines
+}
+
+enum RegData {
+    Dword(u32),
+    String(String),
+    /// Binary, multi-string, deletion and other values no setting uses.
+    Other,
+}
+
+fn parse_reg_value(line: &str) -> Option<(String, RegData)> {
+    let (name, rest) = parse_reg_string(line.strip_prefix('"')?)?;
+    let data = rest.trim_start().strip_prefix('=')?.trim();
+
+    let data = if let Some(hex) = data.strip_prefix("dword:") {
+        RegData::Dword(u32::from_str_radix(hex.trim(), 16).ok()?)
+    } else if let Some(text) = data.strip_prefix('"') {
+        let (text, rest) = parse_reg_string(text)?;
+        if !rest.trim().is_empty() {
+            return None;
+        }
+        RegData::String(text)
+    } else if data == "-" || data.starts_with("hex") {
+        RegData::Other
+    } else {
+        return None;
+    };
+
+    Some((name, data))
+}
+
+/// Reads a quoted string after its opening quote; returns it and the text after it.
+fn parse_reg_string(text: &str) -> Option<(String, &str)> {
+    let mut value = String::new();
+    let mut chars = text.char_indices();
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => return Some((value, &text[index + 1..])),
+            '\\' => value.push(chars.next()?.1),
+            c => value.push(c),
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEGACY_INI: &str = "\
+[Cheat Cards]
+Exists=1
+X position=12
+Y position=280
+
+[cheat stack]
+exists=0
+x position=280
+Y Position=370 ; moved
+
+[Cheat Score]
+Exists=1
+X position=-490
+Y position=370
+Height=90
+
+[Main Window]
+X position=40
+Y position=oops
+Save on exit=1
+X position=99
+
+[Sound]
+Enabled=1
+";
+
+    #[test]
+    fn ini_maps_every_legacy_entry() {
+        let import = Import::from_ini(LEGACY_INI);
+        let settings = &import.settings;
+
+        assert_eq!(settings.get(keys::CHEAT_CARDS_VISIBLE), Some(true));
+        assert_eq!(settings.get(keys::CHEAT_STACK_VISIBLE), Some(false));
+        assert_eq!(settings.get(keys::CHEAT_SCORES_VISIBLE), Some(true));
+        assert_eq!(settings.point(keys::CHEAT_CARDS_POS), Some((12, 280)));
+        assert_eq!(settings.point(keys::CHEAT_STACK_POS), Some((280, 370)));
+        assert_eq!(settings.point(keys::CHEAT_SCORES_POS), Some((-490, 370)));
+        assert_eq!(settings.get(keys::SAVE_ON_EXIT), Some(true));
+        assert_eq!(settings.get(keys::MAIN_WINDOW_POS.x), Some(40));
+        assert_eq!(settings.get(keys::MAIN_WINDOW_POS.y), None);
+
+        let reported: Vec<(usize, &str)> = import
+            .unrecognised
+            .iter()
+            .map(|entry| (entry.line, entry.reason.as_str()))
+            .collect();
+        assert_eq!(
+            reported,
+            [
+                (15, "unknown setting"),
+                (19, "not a number"),
+                (21, "duplicate; the first one is used"),
+                (24, "unknown setting"),
+            ]
+        );
+        assert_eq!(
+            import.unrecognised[0].to_string(),
+            "line 15: Height=90 (unknown setting)"
+        );
+    }
+
+    #[test]
+    fn reg_export_maps_the_rust_build_values() {
+        let text = "Windows Registry Editor Version 5.00\r\n\r\n\
+            [HKEY_CURRENT_USER\\Software\\StopBus\\Modernization]\r\n\
+            \"SchemaVersion\"=dword:00000001\r\n\
+            \"CheatCardsVisible\"=dword:00000001\r\n\
+            \"tablereadvisible\"=dword:00000000\r\n\
+            \"MainWindowPosX\"=dword:fffff830\r\n\
+            \"MainWindowPosY\"=dword:00000040\r\n\
+            \"SaveOnExit\"=\"yes\"\r\n\
+            \"Unused\"=hex:01,02,\\\r\n  03,04\r\n\
+            \r\n\
+            [HKEY_CURRENT_USER\\Software\\Other]\r\n\
+            \"CheatStackVisible\"=dword:00000001\r\n";
+
+        let import = Import::from_reg(text).unwrap();
+        let settings = &import.settings;
+
+        assert_eq!(settings.get(keys::CHEAT_CARDS_VISIBLE), Some(true));
+        assert_eq!(settings.get(keys::TABLE_READ_VISIBLE), Some(false));
+        assert_eq!(settings.point(keys::MAIN_WINDOW_POS), Some((-2000, 64)));
+        assert_eq!(settings.get(keys::CHEAT_STACK_VISIBLE), None);
+        assert_eq!(settings.len(), 4);
+
+        let reported: Vec<(usize, &str)> = import
+            .unrecognised
+            .iter()
+            .map(|entry| (entry.line, entry.reason.as_str()))
+            .collect();
+        assert_eq!(
+            reported,
+            [
+                (9, "expected a dword"),
+                (10, "unknown setting"),
+                (13, "not the StopBus key"),
+                (14, "not the StopBus key"),
+            ]
+        );
+        assert_eq!(import.unrecognised[1].text, "\"Unused\"=hex:01,02,03,04");
+    }
+
+    #[test]
+    fn reg_export_needs_a_header() {
+        assert!(matches!(
+            Import::from_reg("\n[HKEY_CURRENT_USER\\Software]\n"),
+            Err(SettingsError::InvalidLine { line: 2, .. })
+        ));
+        assert!(Import::from_reg("REGEDIT4\n").unwrap().settings.is_empty());
+    }
+
+    #[test]
+    fn decodes_utf16_exports_and_applies_over_settings() {
+        let text = "REGEDIT4\n[HKEY_CURRENT_USER\\Software\\StopBus\\Modernization]\n\
+                    \"ModalEvents\"=dword:00000001\n";
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+        assert_eq!(decode_text(&bytes), text);
+        assert_eq!(decode_text(b"X position=\xe9"), "X position=\u{e9}");
+
+        let import = Import::from_reg(&decode_text(&bytes)).unwrap();
+        let mut settings = Settings::new();
+        settings.set(keys::MODAL_EVENTS, false);
+        settings.set(keys::SAVE_ON_EXIT, true);
+        import.apply_to(&mut settings);
+
+        assert_eq!(settings.get(keys::MODAL_EVENTS), Some(true));
+        assert_eq!(settings.get(keys::SAVE_ON_EXIT), Some(true));
+    }
+}
@@ -0,0 +1,284 @@
+//! The registry key the Win32 build has always used, `HKCU\Software\StopBus\Modernization`.
+//!
+//! Flags and numbers are DWORDs, negative coordinates stored as their two's complement
+//! bits, and text is `REG_SZ`. The schema version goes in a `SchemaVersion` DWORD; a key
+//! without one was written before versioning and loads as version 0.
+
+use std::ffi::c_void;
+
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{ERROR_FILE_NOT_FOUND, ERROR_SUCCESS, WIN32_ERROR};
+use windows::Win32::System::Registry::{
+    RegDeleteKeyValueW, RegGetValueW, RegSetKeyValueW, HKEY_CURRENT_USER, REG_DWORD, REG_SZ,
+    RRF_RT_REG_DWORD, RRF_RT_REG_SZ,
+};
+
+use crate::keys::{self, KeyInfo, ValueKind};
+use crate::{upgrade, Backend, Settings, SettingsError, Value, SCHEMA_VERSION};
+
+pub const DEFAULT_SUBKEY: &str = r"Software\StopBus\Modernization";
+
+const VERSION_VALUE: &str = "SchemaVersion";
+
+/// A key under `HKEY_CURRENT_USER`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Registry {
+    subkey: String,
+}
+
+impl Registry {
+    pub fn new(subkey: impl Into<String>) -> Self {
+        Self {
+            subkey: subkey.into(),
+        }
+    }
+
+    fn read_dword(&self, name: &str) -> Result<Option<u32>, SettingsError> {
+        let subkey = wide(&self.subkey);
+        let name = wide(name);
+        let mut data: u32 = 0;
+        let mut size = size_of::<u32>() as u32;
+
+        let status = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                PCWSTR(subkey.as_ptr()),
+                PCWSTR(name.as_ptr()),
+                RRF_RT_REG_DWORD,
+                None,
+                Some(std::ptr::addr_of_mut!(data) as *mut c_void),
+                Some(&mut size),
+            )
+        };
+
+        found(status).map(|found| found.then_some(data))
+    }
+
+    fn read_string(&self, name: &str) -> Result<Option<String>, SettingsError> {
+        let subkey = wide(&self.subkey);
+        let name = wide(name);
+        let mut size = 0u32;
+
+        let status = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                PCWSTR(subkey.as_ptr()),
+                PCWSTR(name.as_ptr()),
+                RRF_RT_REG_SZ,
+                None,
+                None,
+                Some(&mut size),
+            )
+        };
+        if !found(status)? {
+            return Ok(None);
+        }
+
+        let mut buffer = vec![0u16; (size as usize).div_ceil(2)];
+        let status = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                PCWSTR(subkey.as_ptr()),
+                PCWSTR(name.as_ptr()),
+                RRF_RT_REG_SZ,
+                None,
+                Some(buffer.as_mut_ptr() as *mut c_void),
+                Some(&mut size),
+            )
+        };
+        if !found(status)? {
+            return Ok(None);
+        }
+
+        let text = &buffer[..(size as usize / 2).min(buffer.len())];
+        let end = text.iter().position(|&c| c == 0).unwrap_or(text.len());
+        Ok(Some(String::from_utf16_lossy(&text[..end])))
+    }
+
+    fn write(&self, name: &str, value: &Value) -> Result<(), SettingsError> {
+        match value {
+            Value::Bool(flag) => self.write_dword(name, *flag as u32),
+            Value::Int(number) => self.write_dword(name, *number as i32 as u32),
+            Value::Text(text) => self.write_string(name, text),
+        }
+    }
+
+    fn write_dword(&self, name: &str, data: u32) -> Result<(), SettingsError> {
+        let subkey = wide(&self.subkey);
+        let name = wide(name);
+
+        let status = unsafe {
+            RegSetKeyValueW(
+                HKEY_CURRENT_USER,
+                PCWSTR(subkey.as_ptr()),
+                PCWSTR(name.as_ptr()),
+                REG_DWORD.0,
+                Some(std::ptr::addr_of!(data) as *const c_void),
+                size_of::<u32>() as u32,
+            )
+        };
+
+        succeeded(status)
+    }
+
+    fn write_string(&self, name: &str, text: &str) -> Result<(), SettingsError> {
+        let subkey = wide(&self.subkey);
+        let name = wide(name);
+        let data = wide(text);
+
+        let status = unsafe {
+            RegSetKeyValueW(
+                HKEY_CURRENT_USER,
+                PCWSTR(subkey.as_ptr()),
+                PCWSTR(name.as_ptr()),
+                REG_SZ.0,
+                Some(data.as_ptr() as *const c_void),
+                (data.len() * 2) as u32,
+            )
+        };
+
+        succeeded(status)
+    }
+
+    /// Removes a value; one that is already gone is not an error.
+    fn delete(&self, name: &str) -> Result<(), SettingsError> {
+        let subkey = wide(&self.subkey);
+        let name = wide(name);
+
+        let status = unsafe {
+            RegDeleteKeyValueW(
+                HKEY_CURRENT_USER,
+                PCWSTR(subkey.as_ptr()),
+                PCWSTR(name.as_ptr()),
+            )
+        };
+
+        found(status).map(|_| ())
+    }
+
+    fn read_key(&self, info: &KeyInfo) -> Result<Option<Value>, SettingsError> {
+        Ok(match info.kind {
+            ValueKind::Bool => self
+                .read_dword(info.registry_name)?
+                .map(|v| Value::Bool(v != 0)),
+            ValueKind::Int => self
+                .read_dword(info.registry_name)?
+                .map(|v| Value::Int((v as i32).into())),
+            ValueKind::Text => self.read_string(info.registry_name)?.map(Value::Text),
+        })
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new(DEFAULT_SUBKEY)
+    }
+}
+
+impl Backend for Registry {
+    fn load(&self) -> Result<Settings, SettingsError> {
+        let version = self.read_dword(VERSION_VALUE)?.unwrap_or(0);
+        if version > SCHEMA_VERSION {
+            return Err(SettingsError::UnsupportedVersion(version));
+        }
+
+        let mut settings = Settings::new();
+        for info in &keys::ALL_KEYS {
+            if let Some(value) = self.read_key(info)? {
+                settings.set_raw(info.path(), value);
+            }
+        }
+
+        upgrade(settings, version)
+    }
+
+    /// Writes every value a key describes and deletes the described values `settings`
+    /// no longer has, so a removed key reads as unset next time. Anything else has no
+    /// registry name and is left out.
+    fn save(&self, settings: &Settings) -> Result<(), SettingsError> {
+        self.write_dword(VERSION_VALUE, SCHEMA_VERSION)?;
+
+        for info in &keys::ALL_KEYS {
+            match settings.get_raw(&info.path()) {
+                Some(value) if info.kind == value.kind() => {
+                    self.write(info.registry_name, value)?
+                }
+                _ => self.delete(info.registry_name)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn location(&self) -> String {
+        format!(r"HKEY_CURRENT_USER\{}", self.subkey)
+    }
+}
+
+fn wide(text: &str) -> Vec<u16> {
+    text.encode_utf16().chain(Some(0)).collect()
+}
+
+fn succeeded(status: WIN32_ERROR) -> Result<(), SettingsError> {
+    if status == ERROR_SUCCESS {
+        Ok(())
+    } else {
+        Err(SettingsError::Registry(status.0))
+    }
+}
+
+/// `Ok(false)` for a missing key or value, which just means nothing was saved yet.
+fn found(status: WIN32_ERROR) -> Result<bool, SettingsError> {
+    if status == ERROR_SUCCESS {
+        Ok(true)
+    } else if status == ERROR_FILE_NOT_FOUND {
+        Ok(false)
+    } else {
+        Err(SettingsError::Registry(status.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use windows::Win32::System::Registry::RegDeleteTreeW;
+
+    /// A scratch key under `HKEY_CURRENT_USER`, deleted when dropped.
+    struct TempKey(Registry);
+
+    impl TempKey {
+        fn new(name: &str) -> Self {
+            Self(Registry::new(format!(
+                r"Software\StopBus\Tests\{}-{}",
+                name,
+                std::process::id()
+            )))
+        }
+    }
+
+    impl Drop for TempKey {
+        fn drop(&mut self) {
+            let subkey = wide(&self.0.subkey);
+            unsafe {
+                let _ = RegDeleteTreeW(HKEY_CURRENT_USER, PCWSTR(subkey.as_ptr()));
+            }
+        }
+    }
+
+    #[test]
+    fn removed_keys_are_deleted_on_save() {
+        let key = TempKey::new("remove");
+        let mut settings = Settings::new();
+        settings.set(keys::THEME, "Felt".to_string());
+        settings.set(keys::MODAL_EVENTS, true);
+        key.0.save(&settings).unwrap();
+        assert_eq!(key.0.load().unwrap(), settings);
+
+        settings.remove(keys::THEME);
+        key.0.save(&settings).unwrap();
+
+        let loaded = key.0.load().unwrap();
+        assert_eq!(loaded.get(keys::THEME), None);
+        assert_eq!(loaded, settings);
+    }
+}
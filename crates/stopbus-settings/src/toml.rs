@@ -0,0 +1,380 @@
+//! The settings file: a small subset of TOML.
+//!
+//! ```text
+//! # Stop the Bus settings
+//! schema = 1
+//!
+//! [options]
+//! save_on_exit = true
+//!
+//! [windows]
+//! main_window_x = 120
+//! ```
+//!
+//! Only sections, booleans, integers and basic strings are read; that is all the keys use.
+//! The writer sorts sections and keys, so saving the same settings gives the same bytes.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::{config_dir, upgrade, Backend, Settings, SettingsError, Value, SCHEMA_VERSION};
+
+pub const FILE_NAME: &str = "settings.toml";
+
+const VERSION_KEY: &str = "schema";
+
+pub fn to_text(settings: &Settings) -> String {
+    let mut text = format!(
+        "# Stop the Bus settings\n{} = {}\n",
+        VERSION_KEY, SCHEMA_VERSION
+    );
+    let mut current = None;
+
+    for (path, value) in settings.values() {
+        let (section, name) = path.split_once('.').unwrap_or(("", path));
+        if current != Some(section) {
+            text.push_str(&format!("\n[{}]\n", section));
+            current = Some(section);
+        }
+        text.push_str(&format!("{} = {}\n", name, format_value(value)));
+    }
+
+    text
+}
+
+pub fn from_text(text: &str) -> Result<Settings, SettingsError> {
+    let (version, settings) = parse_document(text)?;
+
+    let version = version.ok_or(SettingsError::MissingVersion)?;
+    if version == 0 || version > SCHEMA_VERSION {
+        return Err(SettingsError::UnsupportedVersion(version));
+    }
+    upgrade(settings, version)
+}
+
+/// Reads any file in this format, returning its `schema` line, if it has one, and its
+/// values without checking either against the settings keys. Theme manifests use it too.
+pub fn parse_document(text: &str) -> Result<(Option<u32>, Settings), SettingsError> {
+    let mut settings = Settings::new();
+    let mut version = None;
+    let mut section: Option<String> = None;
+
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let invalid = |reason: &str| SettingsError::InvalidLine {
+            line: line_number,
+            reason: reason.to_string(),
+        };
+
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix('[') {
+            let name = strip_comment(rest)
+                .strip_suffix(']')
+                .map(str::trim)
+                .filter(|name| is_bare_key(name))
+                .ok_or_else(|| invalid("expected '[section]'"))?;
+            section = Some(name.to_string());
+            continue;
+        }
+
+        let (name, value) = line
+            .split_once('=')
+            .ok_or_else(|| invalid("expected 'name = value'"))?;
+        let name = name.trim();
+        if !is_bare_key(name) {
+            return Err(invalid(&format!("bad key name '{}'", name)));
+        }
+        let value = parse_value(value.trim()).map_err(|reason| invalid(&reason))?;
+
+        let Some(section) = &section else {
+            if name != VERSION_KEY {
+                return Err(invalid(&format!("'{}' is outside a section", name)));
+            }
+            match value {
+                Value::Int(number) => {
+                    version =
+                        Some(u32::try_from(number).map_err(|_| invalid("bad schema version"))?)
+                }
+                _ => return Err(invalid("bad schema version")),
+            }
+            continue;
+        };
+
+        let path = format!("{}.{}", section, name);
+        if settings.get_raw(&path).is_some() {
+            return Err(invalid(&format!("duplicate key '{}'", path)));
+        }
+        settings.set_raw(path, value);
+    }
+
+    Ok((version, settings))
+}
+
+fn is_bare_key(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Drops a trailing `# comment` from text that holds no string.
+fn strip_comment(text: &str) -> &str {
+    text.split_once('#')
+        .map_or(text, |(before, _)| before)
+        .trim()
+}
+
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::Bool(flag) => flag.to_string(),
+        Value::Int(number) => number.to_string(),
+        Value::Text(text) => {
+            let mut quoted = String::from("\"");
+            for c in text.chars() {
+                match c {
+                    '"' => quoted.push_str("\\\""),
+                    '\\' => quoted.push_str("\\\\"),
+                    '\n' => quoted.push_str("\\n"),
+                    '\r' => quoted.push_str("\\r"),
+                    '\t' => quoted.push_str("\\t"),
+                    c if c.is_control() => quoted.push_str(&format!("\\u{:04X}", c as u32)),
+                    c => quoted.push(c),
+                }
+            }
+            quoted.push('"');
+            quoted
+        }
+    }
+}
+
+fn parse_value(text: &str) -> Result<Value, String> {
+    if let Some(rest) = text.strip_prefix('"') {
+        return parse_string(rest).map(Value::Text);
+    }
+
+    match strip_comment(text) {
+        "true" => Ok(Value::Bool(true)),
+        "false" => Ok(Value::Bool(false)),
+        number => number
+            .replace('_', "")
+            .parse::<i64>()
+            .map(Value::Int)
+            .map_err(|_| format!("bad value '{}'", number)),
+    }
+}
+
+/// Reads a basic string after its opening quote, allowing only a comment after it.
+fn parse_string(text: &str) -> Result<String, String> {
+    let mut value = String::new();
+    let mut chars = text.chars();
+
+    loop {
+        match chars.next() {
+            None => return Err("unterminated string".to_string()),
+            Some('"') => break,
+            Some('\\') => {
+                let escaped = match chars.next() {
+                    Some('"') => '"',
+                    Some('\\') => '\\',
+                    Some('n') => '\n',
+                    Some('r') => '\r',
+                    Some('t') => '\t',
+                    Some('u') => {
+                        let digits: String = chars.by_ref().take(4).collect();
+                        u32::from_str_radix(&digits, 16)
+                            .ok()
+                            .filter(|_| digits.len() == 4)
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| format!("bad escape '\\u{}'", digits))?
+                    }
+                    other => {
+                        return Err(format!(
+                            "bad escape '\\{}'",
+                            other.map(String::from).unwrap_or_default()
+                        ))
+                    }
+                };
+                value.push(escaped);
+            }
+            Some(c) => value.push(c),
+        }
+    }
+
+    let rest = chars.as_str().trim();
+    if !rest.is_empty() && !rest.starts_with('#') {
+        return Err(format!("unexpected '{}' after string", rest));
+    }
+    Ok(value)
+}
+
+/// A `settings.toml` file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TomlFile {
+    path: PathBuf,
+}
+
+impl TomlFile {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// `settings.toml` in [`config_dir`].
+    pub fn in_config_dir() -> Option<Self> {
+        config_dir().map(|dir| Self::new(dir.join(FILE_NAME)))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Backend for TomlFile {
+    fn load(&self) -> Result<Settings, SettingsError> {
+        match fs::read_to_string(&self.path) {
+            Ok(text) => from_text(&text),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Settings::new()),
+            Err(err) => Err(SettingsError::Io(err)),
+        }
+    }
+
+    /// Writes a sibling file and renames it over the old one, so a crash mid-save leaves
+    /// the previous settings intact.
+    fn save(&self, settings: &Settings) -> Result<(), SettingsError> {
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(SettingsError::Io)?;
+        }
+
+        let mut partial = self.path.clone().into_os_string();
+        partial.push(".partial");
+        fs::write(&partial, to_text(settings)).map_err(SettingsError::Io)?;
+        fs::rename(&partial, &self.path).map_err(SettingsError::Io)
+    }
+
+    fn location(&self) -> String {
+        self.path.display().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys;
+
+    /// A fresh directory under the system temp dir, removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!(
+                "stopbus-settings-{}-{}",
+                name,
+                std::process::id()
+            ));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn sample() -> Settings {
+        let mut settings = Settings::new();
+        settings.set(keys::CHEAT_CARDS_VISIBLE, true);
+        settings.set(keys::SAVE_ON_EXIT, false);
+        settings.set_point(keys::MAIN_WINDOW_POS, (-1800, 64));
+        settings.set_raw("theme.name", Value::Text("Felt \"green\"\n".into()));
+        settings
+    }
+
+    #[test]
+    fn text_round_trips_sorted() {
+        let text = to_text(&sample());
+
+        assert_eq!(
+            text,
+            "# Stop the Bus settings\nschema = 1\n\n\
+             [options]\ncheat_cards_visible = true\nsave_on_exit = false\n\n\
+             [theme]\nname = \"Felt \\\"green\\\"\\n\"\n\n\
+             [windows]\nmain_window_x = -1800\nmain_window_y = 64\n"
+        );
+        assert_eq!(from_text(&text).unwrap(), sample());
+    }
+
+    #[test]
+    fn reads_comments_and_reports_bad_lines() {
+        let settings = from_text(
+            "schema = 1 # current\n[ options ] # flags\nmodal_events = true # on\n\
+             [windows]\ntable_read_x = 1_024\n",
+        )
+        .unwrap();
+        assert_eq!(settings.get(keys::MODAL_EVENTS), Some(true));
+        assert_eq!(settings.get(keys::TABLE_READ_POS.x), Some(1024));
+
+        let error = |text: &str| from_text(text).unwrap_err().to_string();
+        assert_eq!(
+            error("schema = 1\nsave_on_exit = true\n"),
+            "line 2: 'save_on_exit' is outside a section"
+        );
+        assert_eq!(
+            error("schema = 1\n[options]\nmodal_events = yes\n"),
+            "line 3: bad value 'yes'"
+        );
+        assert_eq!(
+            error("schema = 1\n[a]\nb = 1\nb = 2\n"),
+            "line 4: duplicate key 'a.b'"
+        );
+        assert_eq!(
+            error("schema = 1\n[a]\nb = \"open\n"),
+            "line 3: unterminated string"
+        );
+    }
+
+    #[test]
+    fn schema_version_is_required_and_checked() {
+        assert!(matches!(
+            from_text("[options]\nsave_on_exit = true\n"),
+            Err(SettingsError::MissingVersion)
+        ));
+        assert!(matches!(
+            from_text("schema = 2\n"),
+            Err(SettingsError::UnsupportedVersion(2))
+        ));
+        assert!(matches!(
+            from_text("schema = 0\n"),
+            Err(SettingsError::UnsupportedVersion(0))
+        ));
+    }
+
+    #[test]
+    fn file_backend_saves_and_loads() {
+        let dir = TempDir::new("file");
+        let file = TomlFile::new(dir.0.join("nested").join(FILE_NAME));
+
+        assert_eq!(file.load().unwrap(), Settings::new());
+
+        file.save(&sample()).unwrap();
+        assert_eq!(file.load().unwrap(), sample());
+        assert!(!dir.0.join("nested").join("settings.toml.partial").exists());
+
+        let mut changed = sample();
+        changed.set(keys::SAVE_ON_EXIT, true);
+        file.save(&changed).unwrap();
+        assert_eq!(file.load().unwrap().get(keys::SAVE_ON_EXIT), Some(true));
+
+        fs::write(file.path(), "schema = 9\n").unwrap();
+        assert!(matches!(
+            file.load(),
+            Err(SettingsError::UnsupportedVersion(9))
+        ));
+    }
+}
@@ -0,0 +1,359 @@
+//! Plain-text front end: reads commands line by line and narrates the table.
+//!
+//! Every output line is a complete sentence, so the session works with screen readers,
+//! braille displays and scripted play alike. The help file is read through [`Pager`].
+
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+use stopbus_core::narration::{card_name, describe_lives, describe_table};
+use stopbus_core::{GameState, MessageKind};
+use stopbus_frontend::{Effect, Key, LogFilter, Presenter};
+use stopbus_help::Browser;
+
+pub mod pager;
+
+pub use pager::Pager;
+
+pub const HELP: &str = "\
+Keys: 1-3 select a card, s swaps it with the stack, d draws the next card, k sticks,
+an empty line ends your turn or the showdown, and n deals a new game.
+Commands: look, lives, log (or log alerts, log all), help, keys, quit.";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    Key(Key),
+    Look,
+    Lives,
+    /// Print the game log with timestamps.
+    Log(LogFilter),
+    /// Read the help file, or list the keys when there is none.
+    Help,
+    Keys,
+    Quit,
+}
+
+/// Parses one input line. Lines that are not a command are read as a sequence of keys,
+/// so `2s` selects the second card and swaps it.
+pub fn parse_line(line: &str) -> Option<Vec<Command>> {
+    let line = line.trim();
+
+    let command = match line.to_ascii_lowercase().as_str() {
+        "" => Command::Key(Key::Enter),
+        "look" | "l" => Command::Look,
+        "lives" => Command::Lives,
+        "log" => Command::Log(LogFilter::EVENTS),
+        "log alerts" => Command::Log(LogFilter::ALERTS),
+        "log all" => Command::Log(LogFilter::ALL),
+        "help" | "h" => Command::Help,
+        "keys" | "?" => Command::Keys,
+        "quit" | "q" | "exit" => Command::Quit,
+        _ => {
+            return line
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .map(|c| {
+                    c.is_ascii_alphanumeric()
+                        .then_some(Command::Key(Key::Char(c)))
+                })
+                .collect();
+        }
+    };
+
+    Some(vec![command])
+}
+
+pub struct Session {
+    presenter: Presenter,
+    /// Pause between animated AI turns; zero plays them back instantly.
+    turn_delay: Duration,
+    help: Option<Pager>,
+    /// Input goes to the help pager until the reader leaves it.
+    reading_help: bool,
+}
+
+impl Session {
+    pub fn new(game: GameState, turn_delay: Duration) -> Self {
+        let mut presenter = Presenter::new(game);
+        presenter.set_narration(true);
+
+        Self {
+            presenter,
+            turn_delay,
+            help: None,
+            reading_help: false,
+        }
+    }
+
+    /// Makes the help file available to the help command.
+    pub fn set_help(&mut self, browser: Browser) {
+        self.help = Some(Pager::new(browser));
+    }
+
+    pub fn reading_help(&self) -> bool {
+        self.reading_help
+    }
+
+    pub fn presenter(&self) -> &Presenter {
+        &self.presenter
+    }
+
+    pub fn start(&mut self, out: &mut impl Write) -> io::Result<()> {
+        self.press(Key::Char('n'), out)
+    }
+
+    /// Handles one input line. Returns `false` when the user asked to quit.
+    pub fn handle_line(&mut self, line: &str, out: &mut impl Write) -> io::Result<bool> {
+        if let Some(pager) = self.help.as_mut() {
+            if self.reading_help {
+                if !pager.handle_line(line, out)? {
+                    self.reading_help = false;
+                    writeln!(out, "Back to the game.")?;
+                }
+                return Ok(true);
+            }
+        }
+
+        let Some(commands) = parse_line(line) else {
+            writeln!(out, "Unknown command. Type ? for the keys.")?;
+            return Ok(true);
+        };
+
+        for command in commands {
+            match command {
+                Command::Key(key) => self.press(key, out)?,
+                Command::Look => writeln!(out, "{}", describe_table(self.presenter.game()))?,
+                Command::Lives => writeln!(out, "{}", describe_lives(self.presenter.game()))?,
+                Command::Log(filter) => self.print_log(filter, out)?,
+                Command::Help => match self.help.as_mut() {
+                    Some(pager) => {
+                        self.reading_help = true;
+                        pager.open_contents(out)?;
+                    }
+                    None => writeln!(out, "{}", HELP)?,
+                },
+                Command::Keys => writeln!(out, "{}", HELP)?,
+                Command::Quit => {
+                    let effects = self.presenter.shutdown();
+                    self.apply(effects, out)?;
+                    return Ok(false);
+                }
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn print_log(&self, filter: LogFilter, out: &mut impl Write) -> io::Result<()> {
+        let mut entries = self.presenter.log().filtered(filter).peekable();
+        if entries.peek().is_none() {
+            writeln!(out, "The log is empty.")?;
+        }
+        for entry in entries {
+            writeln!(out, "{}", entry.line())?;
+        }
+        Ok(())
+    }
+
+    fn press(&mut self, key: Key, out: &mut impl Write) -> io::Result<()> {
+        let focused = self.presenter.focused_slot();
+        let effects = self.presenter.key_press(key);
+
+        if effects.is_empty() {
+            writeln!(out, "That key does nothing right now.")?;
+            return Ok(());
+        }
+
+        if let Some(slot) = self.presenter.focused_slot() {
+            if Some(slot) != focused {
+                let card = self.presenter.game().hands[0][slot]
+                    .map(card_name)
+                    .unwrap_or_else(|| "no card".to_string());
+                writeln!(out, "Card {} selected: {}.", slot + 1, card)?;
+            }
+        }
+
+        self.apply(effects, out)
+    }
+
+    fn apply(&mut self, effects: Vec<Effect>, out: &mut impl Write) -> io::Result<()> {
+        let mut queue = effects;
+
+        loop {
+            let mut next = Vec::new();
+
+            for effect in queue {
+                match effect {
+                    Effect::ShowEvent(event) => {
+                        for line in event.text.lines() {
+                            match event.kind {
+                                MessageKind::Info => writeln!(out, "{}", line)?,
+                                MessageKind::Alert => writeln!(out, "! {}", line)?,
+                            }
+                        }
+                    }
+                    Effect::Announce(text) => writeln!(out, "{}", text)?,
+                    Effect::ContinueAutomation => next.extend(self.presenter.continue_automation()),
+                    Effect::Repaint
+                    | Effect::RefreshPanels
+                    | Effect::StartTurnTimer { .. }
+                    | Effect::StopTurnTimer => {}
+                }
+            }
+
+            if next.is_empty() && self.presenter.turn_timer_active() {
+                if !self.turn_delay.is_zero() {
+                    out.flush()?;
+                    thread::sleep(self.turn_delay);
+                }
+                next = self.presenter.timer_tick();
+            }
+
+            if next.is_empty() {
+                if self.presenter.showdown().is_some() && self.presenter.automation_pending() {
+                    writeln!(out, "Press Enter for the next round.")?;
+                }
+                return Ok(());
+            }
+            queue = next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A session on the human's first turn, past any showdown before it.
+    fn session(seed: u64) -> (Session, String) {
+        let mut session = Session::new(GameState::new(Some(seed)), Duration::ZERO);
+        let mut out = Vec::new();
+        session.start(&mut out).unwrap();
+        while session.presenter().showdown().is_some() {
+            session.handle_line("", &mut out).unwrap();
+        }
+        (session, String::from_utf8(out).unwrap())
+    }
+
+    fn run(session: &mut Session, line: &str) -> (bool, String) {
+        let mut out = Vec::new();
+        let keep_going = session.handle_line(line, &mut out).unwrap();
+        (keep_going, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn parses_commands_and_key_sequences() {
+        assert_eq!(parse_line(""), Some(vec![Command::Key(Key::Enter)]));
+        assert_eq!(parse_line(" LOOK "), Some(vec![Command::Look]));
+        assert_eq!(
+            parse_line("2s"),
+            Some(vec![
+                Command::Key(Key::Char('2')),
+                Command::Key(Key::Char('s'))
+            ])
+        );
+        assert_eq!(parse_line("2-"), None);
+        assert_eq!(
+            parse_line("Log Alerts"),
+            Some(vec![Command::Log(LogFilter::ALERTS)])
+        );
+    }
+
+    #[test]
+    fn opening_deal_narrates_the_humans_turn() {
+        let (session, output) = session(2024);
+
+        assert!(session.presenter().awaiting_human());
+        let last = output.lines().last().unwrap();
+        assert!(last.starts_with("Your turn. Your hand: "), "{}", output);
+    }
+
+    #[test]
+    fn selecting_and_swapping_reads_out_the_card() {
+        let (mut session, _) = session(2024);
+        let card = session.presenter().game().hands[0][1].unwrap();
+        let stack = session.presenter().game().stack_top_card().unwrap();
+
+        let (keep_going, output) = run(&mut session, "2s");
+
+        assert!(keep_going);
+        assert!(output.starts_with(&format!("Card 2 selected: {}.", card_name(card))));
+        assert!(output.contains(&format!(
+            "You take {} and leave {}.",
+            card_name(stack),
+            card_name(card)
+        )));
+    }
+
+    #[test]
+    fn showdown_waits_for_enter() {
+        let mut session = Session::new(GameState::new(Some(2024)), Duration::ZERO);
+        let mut out = Vec::new();
+        session.start(&mut out).unwrap();
+        let output = String::from_utf8(out).unwrap();
+
+        assert!(output.contains("\nShowdown.\n"), "{}", output);
+        assert!(output.ends_with("Press Enter for the next round.\n"));
+        assert!(!session.presenter().awaiting_human());
+
+        let (_, output) = run(&mut session, "");
+        assert!(session.presenter().showdown().is_none());
+        assert!(output.contains("Your turn."));
+    }
+
+    #[test]
+    fn ignored_keys_and_unknown_input_explain_themselves() {
+        let (mut session, _) = session(2024);
+
+        assert_eq!(
+            run(&mut session, "s").1,
+            "That key does nothing right now.\n"
+        );
+        assert_eq!(
+            run(&mut session, "2-").1,
+            "Unknown command. Type ? for the keys.\n"
+        );
+    }
+
+    #[test]
+    fn help_opens_the_pager_until_the_reader_leaves() {
+        let (mut session, _) = session(2024);
+        assert_eq!(run(&mut session, "help").1, format!("{}\n", HELP));
+
+        let root = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../..");
+        session.set_help(Browser::load(&root).unwrap());
+        let (keep_going, output) = run(&mut session, "help");
+        assert!(keep_going);
+        assert!(session.reading_help());
+        assert!(output.starts_with("Contents\n"), "{}", output);
+
+        // Keys and commands belong to the pager while it is open.
+        assert_eq!(run(&mut session, "?").1, format!("{}\n", pager::PAGER_HELP));
+        let (keep_going, output) = run(&mut session, "q");
+        assert!(keep_going);
+        assert_eq!(output, "Back to the game.\n");
+        assert!(!session.reading_help());
+        assert_eq!(run(&mut session, "?").1, format!("{}\n", HELP));
+    }
+
+    #[test]
+    fn ending_turns_plays_the_game_forward() {
+        let (mut session, _) = session(2024);
+
+        for _ in 0..200 {
+            if session.presenter().game().lives()[0] == 0 {
+                break;
+            }
+            run(&mut session, "");
+        }
+
+        let (_, output) = run(&mut session, "log");
+        assert!(output.starts_with("[00:00] "), "{}", output);
+        assert!(!output.contains("Your turn."));
+
+        let (_, output) = run(&mut session, "lives");
+        assert!(output.starts_with("Lives: you "));
+        assert!(!run(&mut session, "quit").0);
+    }
+}
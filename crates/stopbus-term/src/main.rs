@@ -0,0 +1,165 @@
+//! Plays Stop the Bus in a terminal.
+//!
+//! Usage: stopbus-term [--seed N] [--delay-ms N] [--ai classic|learned|PRESET]
+//!
+//! The help command reads `HELP/STOPBUS.HPJ` from the working directory or from beside
+//! the executable; without it, help lists the keys. `--ai learned` has the AI seats play
+//! the self-play policy in `assets/ai/learned-policy.txt`, found the same way, and any
+//! other name picks a difficulty from `assets/ai/presets.txt`, such as `easy` or `hard`.
+
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use stopbus_core::learned::LEARNED_POLICY_FILE;
+use stopbus_core::params::PRESETS_FILE;
+use stopbus_core::{AiPresets, ClassicStrategy, GameState, LearnedStrategy, Strategy, PLAYERS};
+use stopbus_frontend::TURN_TIMER_INTERVAL_MS;
+use stopbus_help::{Browser, HELP_DIR, PROJECT_FILE};
+use stopbus_term::{Session, HELP};
+
+struct Options {
+    seed: Option<u64>,
+    delay_ms: u64,
+    ai: String,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        seed: None,
+        delay_ms: u64::from(TURN_TIMER_INTERVAL_MS),
+        ai: "classic".to_string(),
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--seed" => options.seed = Some(parse(&value()?)?),
+            "--delay-ms" => options.delay_ms = parse(&value()?)?,
+            "--ai" => options.ai = value()?,
+            "-h" | "--help" => {
+                return Err(
+                    "usage: stopbus-term [--seed N] [--delay-ms N] [--ai classic|learned|PRESET]"
+                        .to_string(),
+                )
+            }
+            other => return Err(format!("unknown argument '{}'", other)),
+        }
+    }
+
+    Ok(options)
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{}'", value))
+}
+
+/// The first of the working directory and the executable's folder that holds `file`.
+fn find_root(file: &Path) -> Option<PathBuf> {
+    let mut candidates = vec![PathBuf::from(".")];
+
+    if let Ok(exe_path) = std::env::current_exe() {
+        if let Some(dir) = exe_path.parent() {
+            candidates.push(dir.to_path_buf());
+        }
+    }
+
+    candidates
+        .into_iter()
+        .find(|candidate| candidate.join(file).exists())
+}
+
+/// The help file from the first folder that has one. A help file that is present but
+/// unreadable is reported and left out.
+fn load_help() -> Option<Browser> {
+    let root = find_root(&Path::new(HELP_DIR).join(PROJECT_FILE))?;
+
+    match Browser::load(&root) {
+        Ok(browser) => Some(browser),
+        Err(err) => {
+            eprintln!("stopbus-term: help is not available: {}", err);
+            None
+        }
+    }
+}
+
+fn find_asset(file: &str) -> Result<PathBuf, String> {
+    find_root(Path::new(file))
+        .map(|root| root.join(file))
+        .ok_or_else(|| format!("{} was not found", file))
+}
+
+/// Gives every AI seat the strategy named by `--ai`.
+fn set_ai(game: &mut GameState, name: &str) -> Result<(), String> {
+    let strategy: Box<dyn Fn() -> Box<dyn Strategy>> = match name {
+        "classic" => return Ok(()),
+        "learned" => {
+            let path = find_asset(LEARNED_POLICY_FILE)?;
+            let learned = LearnedStrategy::load(&path)
+                .map_err(|err| format!("{}: {}", path.display(), err))?;
+            Box::new(move || Box::new(learned.clone()))
+        }
+        preset => {
+            let path = find_asset(PRESETS_FILE)?;
+            let presets =
+                AiPresets::load(&path).map_err(|err| format!("{}: {}", path.display(), err))?;
+            let params = presets
+                .get(preset)
+                .ok_or_else(|| format!("unknown AI '{}'", preset))?;
+            Box::new(move || Box::new(ClassicStrategy::new(params)))
+        }
+    };
+
+    for player in 1..PLAYERS {
+        game.set_strategy(player, strategy());
+    }
+    Ok(())
+}
+
+fn run(options: Options) -> io::Result<()> {
+    let stdin = io::stdin();
+    let mut out = io::stdout().lock();
+
+    let mut game = GameState::new(options.seed);
+    set_ai(&mut game, &options.ai).map_err(io::Error::other)?;
+
+    let mut session = Session::new(game, Duration::from_millis(options.delay_ms));
+    if let Some(browser) = load_help() {
+        session.set_help(browser);
+    }
+
+    writeln!(out, "Stop the Bus.")?;
+    writeln!(out, "{}", HELP)?;
+    session.start(&mut out)?;
+
+    for line in stdin.lock().lines() {
+        if !session.handle_line(&line?, &mut out)? {
+            break;
+        }
+        out.flush()?;
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("stopbus-term: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
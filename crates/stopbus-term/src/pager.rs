@@ -0,0 +1,456 @@
+//! The help file as a pager: topics are printed a screen at a time as plain lines, with
+//! each jump and pop-up numbered so it can be followed by typing its number.
+
+use std::io::{self, Write};
+
+use stopbus_help::{Block, Browser, Span, Topic};
+
+pub const PAGER_HELP: &str = "\
+Help keys: an empty line shows more, a number follows that link, b goes back, f goes
+forward, c shows the contents, p and n read the previous and next topics, s and a word
+searches the keywords, and q returns to the game.";
+
+/// Lines shown before the pager waits for Enter.
+pub const PAGE_LINES: usize = 20;
+/// Column text is wrapped at.
+pub const PAGE_WIDTH: usize = 72;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HelpCommand {
+    More,
+    /// Follow the numbered link or search result.
+    Follow(usize),
+    Back,
+    Forward,
+    Contents,
+    Previous,
+    Next,
+    Search(String),
+    Keys,
+    Leave,
+}
+
+pub fn parse_help_line(line: &str) -> Option<HelpCommand> {
+    let line = line.trim();
+    if let Ok(number) = line.parse() {
+        return Some(HelpCommand::Follow(number));
+    }
+    let (word, rest) = line.split_once(' ').unwrap_or((line, ""));
+
+    let command = match word.to_ascii_lowercase().as_str() {
+        "" => HelpCommand::More,
+        "b" | "back" => HelpCommand::Back,
+        "f" | "forward" => HelpCommand::Forward,
+        "c" | "contents" => HelpCommand::Contents,
+        "p" | "previous" => HelpCommand::Previous,
+        "n" | "next" => HelpCommand::Next,
+        "s" | "search" => HelpCommand::Search(rest.trim().to_string()),
+        "?" | "keys" => HelpCommand::Keys,
+        "q" | "quit" | "exit" => HelpCommand::Leave,
+        _ => return None,
+    };
+
+    (rest.is_empty() || matches!(command, HelpCommand::Search(_))).then_some(command)
+}
+
+pub struct Pager {
+    browser: Browser,
+    /// The current topic, wrapped, and how many of its lines have been printed.
+    lines: Vec<String>,
+    shown: usize,
+    /// The topics typed numbers open: the current topic's links or the last search.
+    choices: Vec<String>,
+}
+
+impl Pager {
+    pub fn new(browser: Browser) -> Self {
+        Self {
+            browser,
+            lines: Vec::new(),
+            shown: 0,
+            choices: Vec::new(),
+        }
+    }
+
+    pub fn browser(&self) -> &Browser {
+        &self.browser
+    }
+
+    /// Shows the contents topic, as the Help menu's Contents command did.
+    pub fn open_contents(&mut self, out: &mut impl Write) -> io::Result<()> {
+        self.browser.contents();
+        self.show_current(out)
+    }
+
+    /// Shows the topic a dialog's Help button asks for by context number.
+    pub fn open_context(&mut self, number: u32, out: &mut impl Write) -> io::Result<()> {
+        match self.browser.open_context(number) {
+            Some(topic) if topic.popup => {
+                let topic = topic.clone();
+                self.show_popup(&topic, out)
+            }
+            Some(_) => self.show_current(out),
+            None => writeln!(out, "There is no help for context number {}.", number),
+        }
+    }
+
+    /// Handles one input line. Returns `false` when the reader goes back to the game.
+    pub fn handle_line(&mut self, line: &str, out: &mut impl Write) -> io::Result<bool> {
+        let Some(command) = parse_help_line(line) else {
+            writeln!(out, "Unknown help command. Type ? for the help keys.")?;
+            return Ok(true);
+        };
+
+        match command {
+            HelpCommand::More if self.shown < self.lines.len() => self.show_page(out)?,
+            HelpCommand::More => writeln!(out, "That is the end of the topic.")?,
+            HelpCommand::Follow(number) => self.follow(number, out)?,
+            HelpCommand::Back => match self.browser.back() {
+                Some(_) => self.show_current(out)?,
+                None => writeln!(out, "There is no earlier topic to go back to.")?,
+            },
+            HelpCommand::Forward => match self.browser.forward() {
+                Some(_) => self.show_current(out)?,
+                None => writeln!(out, "There is no later topic to go forward to.")?,
+            },
+            HelpCommand::Contents => self.open_contents(out)?,
+            HelpCommand::Previous => match self.browser.browse_previous() {
+                Some(_) => self.show_current(out)?,
+                None => writeln!(out, "This is the first topic in its sequence.")?,
+            },
+            HelpCommand::Next => match self.browser.browse_next() {
+                Some(_) => self.show_current(out)?,
+                None => writeln!(out, "This is the last topic in its sequence.")?,
+            },
+            HelpCommand::Search(text) => self.search(&text, out)?,
+            HelpCommand::Keys => writeln!(out, "{}", PAGER_HELP)?,
+            HelpCommand::Leave => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    fn follow(&mut self, number: usize, out: &mut impl Write) -> io::Result<()> {
+        let Some(target) = number
+            .checked_sub(1)
+            .and_then(|index| self.choices.get(index))
+            .cloned()
+        else {
+            return writeln!(out, "There is no link numbered {}.", number);
+        };
+
+        match self.browser.open(&target) {
+            Some(topic) if topic.popup => {
+                let topic = topic.clone();
+                self.show_popup(&topic, out)
+            }
+            _ => self.show_current(out),
+        }
+    }
+
+    fn search(&mut self, text: &str, out: &mut impl Write) -> io::Result<()> {
+        let found = self.browser.search(text);
+        if found.is_empty() {
+            return writeln!(out, "No keyword matches '{}'.", text);
+        }
+
+        let mut choices = Vec::new();
+        writeln!(out, "Keywords matching '{}':", text)?;
+        for keyword in found {
+            for topic in keyword.topics {
+                choices.push(topic.context.clone());
+                writeln!(
+                    out,
+                    "[{}] {}: {}",
+                    choices.len(),
+                    keyword.keyword,
+                    topic.title
+                )?;
+            }
+        }
+        writeln!(out, "Type a number to read that topic.")?;
+        self.choices = choices;
+        Ok(())
+    }
+
+    fn show_current(&mut self, out: &mut impl Write) -> io::Result<()> {
+        let mut choices = Vec::new();
+        self.lines = render(&self.browser, self.browser.current(), &mut choices);
+        self.choices = choices;
+        self.shown = 0;
+        self.show_page(out)
+    }
+
+    /// Prints a pop-up whole, leaving the current topic and its link numbers alone.
+    fn show_popup(&mut self, topic: &Topic, out: &mut impl Write) -> io::Result<()> {
+        for line in render(&self.browser, topic, &mut Vec::new()) {
+            writeln!(out, "{}", line)?;
+        }
+        Ok(())
+    }
+
+    fn show_page(&mut self, out: &mut impl Write) -> io::Result<()> {
+        let end = (self.shown + PAGE_LINES).min(self.lines.len());
+        for line in &self.lines[self.shown..end] {
+            writeln!(out, "{}", line)?;
+        }
+        self.shown = end;
+
+        if self.shown < self.lines.len() {
+            writeln!(out, "Press Enter for more.")
+        } else {
+            writeln!(
+                out,
+                "End of topic. Type a link number, ? for the help keys or q to return to the game."
+            )
+        }
+    }
+}
+
+/// A topic as wrapped lines. The targets of its links are added to `links`, and each link
+/// is followed by its number in brackets.
+fn render(browser: &Browser, topic: &Topic, links: &mut Vec<String>) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut pictures = Vec::new();
+
+    let title = inline(&topic.heading, links, &mut pictures);
+    let title = if title.trim().is_empty() {
+        topic.title.clone()
+    } else {
+        title
+    };
+    lines.extend(wrap(&title, "", ""));
+
+    for block in &topic.blocks {
+        lines.push(String::new());
+        let mut text = |spans: &[Span], first: &str, rest: &str| {
+            let text = inline(spans, links, &mut pictures);
+            wrap(&text, first, rest)
+        };
+        match block {
+            Block::Heading(spans) | Block::Paragraph(spans) => lines.extend(text(spans, "", "")),
+            Block::Lines(items) => {
+                for line in items {
+                    lines.extend(text(line, "  ", "  "));
+                }
+            }
+            Block::Bullets(items) => {
+                for item in items {
+                    lines.extend(text(item, "- ", "  "));
+                }
+            }
+            Block::Terms(terms) => {
+                for (term, body) in terms {
+                    lines.extend(text(term, "", ""));
+                    lines.extend(text(body, "    ", "    "));
+                }
+            }
+        }
+
+        // Hotspots have no text of their own, so they are listed after the picture.
+        for picture in pictures.drain(..) {
+            let hotspots: Vec<_> = browser.hotspots(&picture).collect();
+            if hotspots.is_empty() {
+                continue;
+            }
+            lines.push("The picture links to:".to_string());
+            for hotspot in hotspots {
+                let Some(target) = browser.help().topic(&hotspot.target) else {
+                    continue;
+                };
+                links.push(target.context.clone());
+                lines.extend(wrap(
+                    &format!("{} [{}]", target.title, links.len()),
+                    "- ",
+                    "  ",
+                ));
+            }
+        }
+    }
+
+    lines
+}
+
+/// The text of `spans` on one line. Pictures are left out and added to `pictures`.
+fn inline(spans: &[Span], links: &mut Vec<String>, pictures: &mut Vec<String>) -> String {
+    let mut text = String::new();
+    for span in spans {
+        match span {
+            Span::Text { text: run, .. } => text.push_str(run),
+            Span::Link {
+                text: label,
+                target,
+                ..
+            } => {
+                links.push(target.clone());
+                text.push_str(&format!("{} [{}]", label.trim(), links.len()));
+            }
+            Span::Picture(name) => pictures.push(name.clone()),
+        }
+    }
+    text
+}
+
+/// Breaks `text` into lines of at most [`PAGE_WIDTH`] columns, where words allow.
+fn wrap(text: &str, first: &str, rest: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = first.to_string();
+    let mut empty = true;
+
+    for word in text.split_whitespace() {
+        let width = line.chars().count();
+        if !empty && width + 1 + word.chars().count() > PAGE_WIDTH {
+            lines.push(std::mem::replace(&mut line, rest.to_string()));
+            empty = true;
+        }
+        if !empty {
+            line.push(' ');
+        }
+        line.push_str(word);
+        empty = false;
+    }
+    if !empty {
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use stopbus_help::HC_OPTIONS;
+
+    use super::*;
+
+    fn pager() -> Pager {
+        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../..");
+        Pager::new(Browser::load(&root).unwrap())
+    }
+
+    fn run(pager: &mut Pager, line: &str) -> (bool, String) {
+        let mut out = Vec::new();
+        let keep_going = pager.handle_line(line, &mut out).unwrap();
+        (keep_going, String::from_utf8(out).unwrap())
+    }
+
+    fn context(pager: &Pager) -> &str {
+        &pager.browser().current().context
+    }
+
+    #[test]
+    fn parses_help_commands() {
+        assert_eq!(parse_help_line(""), Some(HelpCommand::More));
+        assert_eq!(parse_help_line(" 12 "), Some(HelpCommand::Follow(12)));
+        assert_eq!(parse_help_line("B"), Some(HelpCommand::Back));
+        assert_eq!(
+            parse_help_line("s  Rules of"),
+            Some(HelpCommand::Search("Rules of".to_string()))
+        );
+        assert_eq!(parse_help_line("back two"), None);
+        assert_eq!(parse_help_line("x"), None);
+    }
+
+    #[test]
+    fn wraps_words_within_the_page_width() {
+        let text = "word ".repeat(40);
+        let lines = wrap(&text, "- ", "  ");
+        assert!(lines.iter().all(|line| line.len() <= PAGE_WIDTH));
+        assert!(lines[0].starts_with("- word"));
+        assert!(lines[1].starts_with("  word"));
+        assert_eq!(
+            lines
+                .iter()
+                .map(|line| line.split_whitespace().count())
+                .sum::<usize>(),
+            40 + 1
+        );
+        assert!(wrap("  ", "", "").is_empty());
+    }
+
+    #[test]
+    fn pages_through_the_contents_and_follows_links() {
+        let mut pager = pager();
+        let mut out = Vec::new();
+        pager.open_contents(&mut out).unwrap();
+        let output = String::from_utf8(out).unwrap();
+
+        assert!(output.starts_with("Contents\n\nStop the Bus is a card game"));
+        assert!(output.contains("- About Stop the Bus [1]\n"), "{}", output);
+        assert!(output.ends_with("Press Enter for more.\n"));
+        assert_eq!(output.lines().count(), PAGE_LINES + 1);
+
+        let (_, output) = run(&mut pager, "");
+        assert!(output.contains("- Play the game [8]\n"), "{}", output);
+        assert!(output.ends_with("q to return to the game.\n"));
+        assert_eq!(run(&mut pager, "").1, "That is the end of the topic.\n");
+
+        let (_, output) = run(&mut pager, "8");
+        assert_eq!(context(&pager), "play");
+        assert!(output.starts_with("Playing the game\n"));
+        assert_eq!(run(&mut pager, "99").1, "There is no link numbered 99.\n");
+
+        run(&mut pager, "n");
+        assert_eq!(context(&pager), "options");
+        run(&mut pager, "b");
+        run(&mut pager, "b");
+        assert_eq!(context(&pager), "main_contents");
+        assert_eq!(
+            run(&mut pager, "b").1,
+            "There is no earlier topic to go back to.\n"
+        );
+        run(&mut pager, "f");
+        assert_eq!(context(&pager), "play");
+        assert!(!run(&mut pager, "q").0);
+    }
+
+    #[test]
+    fn the_options_dialog_opens_its_topic_and_pop_ups_stay_put() {
+        let mut pager = pager();
+        let mut out = Vec::new();
+        pager.open_context(HC_OPTIONS, &mut out).unwrap();
+        let output = String::from_utf8(out).unwrap();
+
+        assert_eq!(context(&pager), "options");
+        assert!(output.starts_with("Setting game options\n"));
+        assert!(
+            output.contains("The picture links to:\n- Cheat options [1]\n"),
+            "{}",
+            output
+        );
+
+        let (_, output) = run(&mut pager, "1");
+        assert_eq!(context(&pager), "options");
+        assert!(output.starts_with("Cheat options\n"), "{}", output);
+
+        let mut out = Vec::new();
+        pager.open_context(99, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "There is no help for context number 99.\n"
+        );
+    }
+
+    #[test]
+    fn searches_keywords_and_opens_results() {
+        let mut pager = pager();
+
+        let (_, output) = run(&mut pager, "s scor");
+        assert!(
+            output.starts_with("Keywords matching 'scor':\n[1] "),
+            "{}",
+            output
+        );
+        run(&mut pager, "1");
+        assert_eq!(context(&pager), "scores");
+
+        assert_eq!(
+            run(&mut pager, "search zebra").1,
+            "No keyword matches 'zebra'.\n"
+        );
+        assert_eq!(
+            run(&mut pager, "jump").1,
+            "Unknown help command. Type ? for the help keys.\n"
+        );
+    }
+}
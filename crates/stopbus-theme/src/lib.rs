@@ -0,0 +1,673 @@
+//! Card themes: a folder of card art described by a `theme.toml` manifest.
+//!
+//! ```text
+//! schema = 1
+//!
+//! [theme]
+//! name = "Blue baize"
+//! table_color = "#1b3f8b"
+//!
+//! [cards]
+//! pattern = "card{id}.png"
+//! back = "back.bmp"
+//! 1 = "aces/clubs.png"
+//! ```
+//!
+//! Slots 1 to 52 are the [`CardId`]s, 53 is the back of the deck and 54 the cross shown
+//! for an empty slot; `back` and `cross` name the last two. A slot without its own entry
+//! uses `pattern`, with `{id}` replaced by its two-digit number. Images may be PNG or BMP
+//! files inside the theme folder, and all must share one size that is a whole multiple
+//! of the 71x96 card. The manifest is read with the settings file parser, so the same
+//! subset of TOML applies.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use stopbus_core::narration::card_name;
+use stopbus_core::{CardId, DECK_SIZE};
+use stopbus_frontend::layout::CARD_SIZE;
+use stopbus_image::{Image, ImageError};
+use stopbus_settings::{config_dir, toml, SettingsError, Value};
+
+pub const MANIFEST_FILE: &str = "theme.toml";
+
+/// Manifest version this build reads.
+pub const MANIFEST_SCHEMA: u32 = 1;
+
+/// Slot of the card back, after the 52 cards.
+pub const BACK: u8 = 53;
+
+/// Slot of the cross drawn where a hand has no card.
+pub const CROSS: u8 = 54;
+
+pub const SLOT_COUNT: u8 = CROSS;
+
+/// The 1994 card art `stopbus-assets` writes, relative to the repository root.
+pub const CLASSIC_CARDS_DIR: &str = "crates/stopbus-ui/resources/cards";
+
+/// Red, green and blue of the 1994 table.
+pub const CLASSIC_TABLE_COLOR: [u8; 3] = [0x00, 0x80, 0x00];
+
+/// The parsed `theme.toml`, before any image is read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Manifest {
+    pub name: Option<String>,
+    /// Red, green and blue of the table, replacing the classic green.
+    pub table_color: Option<[u8; 3]>,
+    pub pattern: Option<String>,
+    /// Files listed for single slots, indexed by slot - 1.
+    files: Vec<Option<String>>,
+}
+
+impl Manifest {
+    pub fn parse(text: &str) -> Result<Self, ThemeError> {
+        let (version, values) = toml::parse_document(text).map_err(ThemeError::Manifest)?;
+        match version {
+            None => return Err(ThemeError::Manifest(SettingsError::MissingVersion)),
+            Some(MANIFEST_SCHEMA) => {}
+            Some(version) => {
+                return Err(ThemeError::Manifest(SettingsError::UnsupportedVersion(
+                    version,
+                )))
+            }
+        }
+
+        let mut manifest = Self {
+            name: None,
+            table_color: None,
+            pattern: None,
+            files: vec![None; SLOT_COUNT as usize],
+        };
+
+        for (path, value) in values.values() {
+            let invalid = |reason: &str| ThemeError::InvalidEntry {
+                key: path.to_string(),
+                reason: reason.to_string(),
+            };
+            let Value::Text(text) = value else {
+                return Err(invalid("expected a string"));
+            };
+
+            match path.split_once('.') {
+                Some(("theme", "name")) => manifest.name = Some(text.clone()),
+                Some(("theme", "table_color")) => {
+                    manifest.table_color =
+                        Some(parse_color(text).ok_or_else(|| invalid("expected \"#rrggbb\""))?)
+                }
+                Some(("cards", "pattern")) => {
+                    if !text.contains("{id}") {
+                        return Err(invalid("pattern has no '{id}'"));
+                    }
+                    manifest.pattern = Some(text.clone());
+                }
+                Some(("cards", slot)) => {
+                    let slot = parse_slot(slot).ok_or_else(|| {
+                        invalid("expected a card number from 1 to 54, 'back' or 'cross'")
+                    })?;
+                    if manifest.files[slot as usize - 1].is_some() {
+                        return Err(invalid("slot is listed twice"));
+                    }
+                    manifest.files[slot as usize - 1] = Some(text.clone());
+                }
+                _ => return Err(invalid("unknown key")),
+            }
+        }
+
+        if manifest.pattern.is_none() {
+            if let Some(slot) = (1..=SLOT_COUNT).find(|&slot| manifest.file(slot).is_none()) {
+                return Err(ThemeError::InvalidEntry {
+                    key: format!("cards.{}", slot),
+                    reason: "no file and no pattern to make one".to_string(),
+                });
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    /// The file for a slot, relative to the theme folder.
+    pub fn file(&self, slot: u8) -> Option<String> {
+        let listed = self.files.get(usize::from(slot).checked_sub(1)?)?;
+        listed.clone().or_else(|| {
+            self.pattern
+                .as_ref()
+                .map(|pattern| pattern.replace("{id}", &format!("{:02}", slot)))
+        })
+    }
+}
+
+/// "#rrggbb" as red, green and blue.
+fn parse_color(text: &str) -> Option<[u8; 3]> {
+    let hex = text.strip_prefix('#').filter(|hex| hex.len() == 6)?;
+    let channel = |index: usize| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+fn parse_slot(name: &str) -> Option<u8> {
+    match name {
+        "back" => Some(BACK),
+        "cross" => Some(CROSS),
+        number => number
+            .parse()
+            .ok()
+            .filter(|slot| (1..=SLOT_COUNT).contains(slot)),
+    }
+}
+
+/// "Ace of clubs", "back" or "cross", for messages.
+pub fn slot_name(slot: u8) -> String {
+    match slot {
+        BACK => "back".to_string(),
+        CROSS => "cross".to_string(),
+        card => card_name(card),
+    }
+}
+
+/// A theme whose images have all been read and checked.
+#[derive(Clone, Debug)]
+pub struct Theme {
+    pub name: String,
+    pub table_color: Option<[u8; 3]>,
+    /// Width and height shared by every image.
+    pub card_size: (u32, u32),
+    /// Indexed by slot - 1.
+    images: Vec<Image>,
+}
+
+impl Theme {
+    /// Reads the manifest in `dir` and every image it names. Image problems are gathered
+    /// rather than stopping at the first, so one error lists everything to fix.
+    pub fn load(dir: &Path) -> Result<Self, ThemeError> {
+        let text = fs::read_to_string(dir.join(MANIFEST_FILE)).map_err(ThemeError::Io)?;
+        let manifest = Manifest::parse(&text)?;
+        Self::read(dir, manifest)
+    }
+
+    /// The classic cards, `card01.bmp` to `card54.bmp`, from a folder with no manifest
+    /// such as [`CLASSIC_CARDS_DIR`].
+    pub fn classic(dir: &Path) -> Result<Self, ThemeError> {
+        let manifest = Manifest {
+            name: Some("Classic".to_string()),
+            table_color: Some(CLASSIC_TABLE_COLOR),
+            pattern: Some("card{id}.bmp".to_string()),
+            files: vec![None; SLOT_COUNT as usize],
+        };
+        Self::read(dir, manifest)
+    }
+
+    fn read(dir: &Path, manifest: Manifest) -> Result<Self, ThemeError> {
+        let mut images = Vec::with_capacity(SLOT_COUNT as usize);
+        let mut problems = Vec::new();
+        for slot in 1..=SLOT_COUNT {
+            let file = manifest.file(slot).unwrap_or_default();
+            match read_image(dir, &file) {
+                Ok(image) => images.push((slot, file, image)),
+                Err(kind) => problems.push(ImageProblem { slot, file, kind }),
+            }
+        }
+
+        // The size most images share is the one the odd ones out are measured against.
+        let mut sizes: Vec<((u32, u32), usize)> = Vec::new();
+        for (_, _, image) in &images {
+            let size = (image.width, image.height);
+            match sizes.iter_mut().find(|(known, _)| *known == size) {
+                Some((_, count)) => *count += 1,
+                None => sizes.push((size, 1)),
+            }
+        }
+        let card_size = sizes
+            .iter()
+            .rev()
+            .max_by_key(|(_, count)| *count)
+            .map_or((0, 0), |(size, _)| *size);
+
+        let mut shape_reported = false;
+        for (slot, file, image) in &images {
+            let size = (image.width, image.height);
+            let kind = if size != card_size {
+                ProblemKind::WrongSize {
+                    size,
+                    expected: card_size,
+                }
+            } else if !is_card_shaped(size) && !shape_reported {
+                shape_reported = true;
+                ProblemKind::NotCardShaped { size }
+            } else {
+                continue;
+            };
+            problems.push(ImageProblem {
+                slot: *slot,
+                file: file.clone(),
+                kind,
+            });
+        }
+
+        if !problems.is_empty() {
+            problems.sort_by_key(|problem| problem.slot);
+            return Err(ThemeError::Images(problems));
+        }
+
+        let folder = dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned());
+        Ok(Self {
+            name: manifest.name.or(folder).unwrap_or_default(),
+            table_color: manifest.table_color,
+            card_size,
+            images: images.into_iter().map(|(_, _, image)| image).collect(),
+        })
+    }
+
+    /// The theme folder named `folder` under [`themes_dir`].
+    pub fn load_installed(folder: &str) -> Result<Self, ThemeError> {
+        let dir = themes_dir()
+            .filter(|_| is_plain_name(folder))
+            .map(|dir| dir.join(folder))
+            .ok_or_else(|| ThemeError::NotInstalled(folder.to_string()))?;
+        Self::load(&dir)
+    }
+
+    /// The image for slot 1 to 54.
+    pub fn image(&self, slot: u8) -> Option<&Image> {
+        self.images.get(usize::from(slot).checked_sub(1)?)
+    }
+
+    pub fn card(&self, card: CardId) -> Option<&Image> {
+        if !(1..=DECK_SIZE as u8).contains(&card) {
+            return None;
+        }
+        self.image(card)
+    }
+
+    pub fn back(&self) -> &Image {
+        &self.images[BACK as usize - 1]
+    }
+
+    pub fn cross(&self) -> &Image {
+        &self.images[CROSS as usize - 1]
+    }
+}
+
+fn read_image(dir: &Path, file: &str) -> Result<Image, ProblemKind> {
+    if !is_inside(Path::new(file)) {
+        return Err(ProblemKind::OutsideTheme);
+    }
+    let data = fs::read(dir.join(file)).map_err(|err| match err.kind() {
+        io::ErrorKind::NotFound => ProblemKind::Missing,
+        _ => ProblemKind::Unreadable(err.to_string()),
+    })?;
+    stopbus_image::decode(&data).map_err(ProblemKind::Undecodable)
+}
+
+/// True for a relative path that never climbs out of the folder it starts in.
+fn is_inside(path: &Path) -> bool {
+    path.components().next().is_some()
+        && path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+fn is_plain_name(folder: &str) -> bool {
+    let mut components = Path::new(folder).components();
+    matches!(components.next(), Some(Component::Normal(_))) && components.next().is_none()
+}
+
+/// The classic card size or a whole multiple of it, for art drawn at higher resolution.
+fn is_card_shaped((width, height): (u32, u32)) -> bool {
+    let (card_width, card_height) = (CARD_SIZE.0 as u32, CARD_SIZE.1 as u32);
+    width > 0
+        && width % card_width == 0
+        && height % card_height == 0
+        && width / card_width == height / card_height
+}
+
+/// `themes` in the settings directory, one folder per theme.
+pub fn themes_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("themes"))
+}
+
+/// A folder under the themes directory that has a manifest.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InstalledTheme {
+    /// What settings store to select it.
+    pub folder: String,
+    /// The manifest's name, or the folder's when it has none or cannot be read.
+    pub name: String,
+}
+
+/// Theme folders in `dir`, sorted by name. Their images are not checked until loaded.
+pub fn installed(dir: &Path) -> Vec<InstalledTheme> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut themes: Vec<InstalledTheme> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().join(MANIFEST_FILE).is_file())
+        .filter_map(|entry| {
+            let folder = entry.file_name().into_string().ok()?;
+            let name = fs::read_to_string(entry.path().join(MANIFEST_FILE))
+                .ok()
+                .and_then(|text| Manifest::parse(&text).ok())
+                .and_then(|manifest| manifest.name)
+                .unwrap_or_else(|| folder.clone());
+            Some(InstalledTheme { folder, name })
+        })
+        .collect();
+    themes.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.folder.cmp(&b.folder)));
+    themes
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImageProblem {
+    pub slot: u8,
+    /// The file the manifest names for the slot.
+    pub file: String,
+    pub kind: ProblemKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProblemKind {
+    Missing,
+    /// The path is absolute or climbs out of the theme folder.
+    OutsideTheme,
+    Unreadable(String),
+    Undecodable(ImageError),
+    /// Not a whole multiple of 71x96.
+    NotCardShaped {
+        size: (u32, u32),
+    },
+    /// Differs from the size most of the theme's images share.
+    WrongSize {
+        size: (u32, u32),
+        expected: (u32, u32),
+    },
+}
+
+impl fmt::Display for ImageProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}): ", slot_name(self.slot), self.file)?;
+        match &self.kind {
+            ProblemKind::Missing => write!(f, "file not found"),
+            ProblemKind::OutsideTheme => write!(f, "path leaves the theme folder"),
+            ProblemKind::Unreadable(reason) => write!(f, "{}", reason),
+            ProblemKind::Undecodable(err) => write!(f, "{}", err),
+            ProblemKind::NotCardShaped { size } => write!(
+                f,
+                "{}x{}, the size of most images, is not a multiple of the {}x{} card",
+                size.0, size.1, CARD_SIZE.0, CARD_SIZE.1
+            ),
+            ProblemKind::WrongSize { size, expected } => write!(
+                f,
+                "{}x{} differs from the other cards' {}x{}",
+                size.0, size.1, expected.0, expected.1
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ThemeError {
+    Io(io::Error),
+    /// The manifest is not valid TOML or has the wrong schema.
+    Manifest(SettingsError),
+    InvalidEntry {
+        key: String,
+        reason: String,
+    },
+    /// Settings name a theme folder that is not under the themes directory.
+    NotInstalled(String),
+    Images(Vec<ImageProblem>),
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThemeError::Io(err) => write!(f, "failed to read {}: {}", MANIFEST_FILE, err),
+            ThemeError::Manifest(err) => write!(f, "{}: {}", MANIFEST_FILE, err),
+            ThemeError::InvalidEntry { key, reason } => {
+                write!(f, "{}: '{}': {}", MANIFEST_FILE, key, reason)
+            }
+            ThemeError::NotInstalled(folder) => write!(f, "theme '{}' is not installed", folder),
+            ThemeError::Images(problems) => {
+                write!(f, "{} theme image(s) cannot be used:", problems.len())?;
+                for problem in problems {
+                    write!(f, "\n  {}", problem)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ThemeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ThemeError::Io(err) => Some(err),
+            ThemeError::Manifest(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh directory under the system temp dir, removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("stopbus-theme-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// A solid 24-bit BMP file.
+    fn bmp(width: u32, height: u32, bgr: [u8; 3]) -> Vec<u8> {
+        let stride = (width * 3).div_ceil(4) * 4;
+        let mut data = Vec::new();
+        data.extend_from_slice(b"BM");
+        data.extend((54 + stride * height).to_le_bytes());
+        data.extend([0; 4]);
+        data.extend(54u32.to_le_bytes());
+        data.extend(40u32.to_le_bytes());
+        data.extend(width.to_le_bytes());
+        data.extend(height.to_le_bytes());
+        data.extend(1u16.to_le_bytes());
+        data.extend(24u16.to_le_bytes());
+        data.extend([0; 24]);
+        for _ in 0..height {
+            for _ in 0..width {
+                data.extend(bgr);
+            }
+            data.resize(data.len() + (stride - width * 3) as usize, 0);
+        }
+        data
+    }
+
+    /// A theme folder with a card for every slot drawn by `pattern`.
+    fn write_theme(dir: &Path, manifest: &str) {
+        fs::write(dir.join(MANIFEST_FILE), manifest).unwrap();
+        for slot in 1..=SLOT_COUNT {
+            fs::write(
+                dir.join(format!("card{:02}.bmp", slot)),
+                bmp(71, 96, [0, 0, slot]),
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn manifest_maps_slots_through_pattern_and_overrides() {
+        let manifest = Manifest::parse(
+            "schema = 1\n[theme]\nname = \"Blue\"\ntable_color = \"#1B3f8b\"\n\
+             [cards]\npattern = \"card{id}.png\"\nback = \"back.bmp\"\n1 = \"aces/clubs.png\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(manifest.name.as_deref(), Some("Blue"));
+        assert_eq!(manifest.table_color, Some([0x1B, 0x3F, 0x8B]));
+        assert_eq!(manifest.file(1).as_deref(), Some("aces/clubs.png"));
+        assert_eq!(manifest.file(7).as_deref(), Some("card07.png"));
+        assert_eq!(manifest.file(BACK).as_deref(), Some("back.bmp"));
+        assert_eq!(manifest.file(CROSS).as_deref(), Some("card54.png"));
+        assert_eq!(manifest.file(55), None);
+    }
+
+    #[test]
+    fn manifest_errors_name_the_entry() {
+        let error = |text: &str| Manifest::parse(text).unwrap_err().to_string();
+
+        assert_eq!(
+            error("[cards]\npattern = \"{id}.png\"\n"),
+            "theme.toml: missing 'schema = <version>' line"
+        );
+        assert_eq!(
+            error("schema = 1\n[theme]\ntable_color = \"green\"\n"),
+            "theme.toml: 'theme.table_color': expected \"#rrggbb\""
+        );
+        assert_eq!(
+            error("schema = 1\n[cards]\npattern = \"{id}.png\"\n55 = \"x.png\"\n"),
+            "theme.toml: 'cards.55': expected a card number from 1 to 54, 'back' or 'cross'"
+        );
+        assert_eq!(
+            error("schema = 1\n[cards]\n53 = \"a.png\"\nback = \"b.png\"\n"),
+            "theme.toml: 'cards.back': slot is listed twice"
+        );
+        assert_eq!(
+            error("schema = 1\n[cards]\n1 = \"a.png\"\n"),
+            "theme.toml: 'cards.2': no file and no pattern to make one"
+        );
+        assert_eq!(
+            error("schema = 1\n[theme]\nname = true\n"),
+            "theme.toml: 'theme.name': expected a string"
+        );
+        assert_eq!(
+            error("schema = 1\n[a\n"),
+            "theme.toml: line 2: expected '[section]'"
+        );
+    }
+
+    #[test]
+    fn loads_a_complete_theme() {
+        let dir = TempDir::new("complete");
+        write_theme(
+            &dir.0,
+            "schema = 1\n[theme]\ntable_color = \"#102030\"\n[cards]\npattern = \"card{id}.bmp\"\n",
+        );
+
+        let theme = Theme::load(&dir.0).unwrap();
+        assert_eq!(theme.name, dir.0.file_name().unwrap().to_string_lossy());
+        assert_eq!(theme.table_color, Some([0x10, 0x20, 0x30]));
+        assert_eq!(theme.card_size, (71, 96));
+        assert_eq!(theme.card(13).unwrap().pixel(0, 0), [13, 0, 0, 0xFF]);
+        assert_eq!(theme.back().pixel(0, 0), [BACK, 0, 0, 0xFF]);
+        assert_eq!(theme.cross().pixel(70, 95), [CROSS, 0, 0, 0xFF]);
+        assert!(theme.card(BACK).is_none());
+    }
+
+    #[test]
+    fn loads_the_classic_cards() {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../..")
+            .join(CLASSIC_CARDS_DIR);
+
+        let theme = Theme::classic(&dir).unwrap();
+        assert_eq!(theme.name, "Classic");
+        assert_eq!(theme.table_color, Some(CLASSIC_TABLE_COLOR));
+        assert_eq!(theme.card_size, (71, 96));
+    }
+
+    #[test]
+    fn validation_reports_every_bad_image() {
+        let dir = TempDir::new("invalid");
+        write_theme(
+            &dir.0,
+            "schema = 1\n[cards]\npattern = \"card{id}.bmp\"\n\
+             2 = \"missing.png\"\n3 = \"../card03.bmp\"\n4 = \"notes.txt\"\n",
+        );
+        fs::write(dir.0.join("notes.txt"), "not an image").unwrap();
+        fs::write(dir.0.join("card05.bmp"), bmp(72, 96, [0, 0, 5])).unwrap();
+
+        let Err(ThemeError::Images(problems)) = Theme::load(&dir.0) else {
+            panic!("theme with bad images loaded");
+        };
+        let messages: Vec<String> = problems.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            [
+                "2 of clubs (missing.png): file not found",
+                "3 of clubs (../card03.bmp): path leaves the theme folder",
+                "4 of clubs (notes.txt): not a BMP or PNG image",
+                "5 of clubs (card05.bmp): 72x96 differs from the other cards' 71x96",
+            ]
+        );
+    }
+
+    #[test]
+    fn cards_must_be_a_multiple_of_the_classic_size() {
+        let dir = TempDir::new("shape");
+        write_theme(&dir.0, "schema = 1\n[cards]\npattern = \"card{id}.bmp\"\n");
+        for slot in 1..=SLOT_COUNT {
+            let card = bmp(142, 96, [0, 0, slot]);
+            fs::write(dir.0.join(format!("card{:02}.bmp", slot)), card).unwrap();
+        }
+
+        let Err(ThemeError::Images(problems)) = Theme::load(&dir.0) else {
+            panic!("theme with stretched cards loaded");
+        };
+        assert_eq!(problems.len(), 1);
+        assert_eq!(
+            problems[0].to_string(),
+            "Ace of clubs (card01.bmp): 142x96, the size of most images, is not a multiple \
+             of the 71x96 card"
+        );
+
+        assert!(is_card_shaped((142, 192)));
+        assert!(!is_card_shaped((0, 0)));
+    }
+
+    #[test]
+    fn installed_lists_theme_folders_by_name() {
+        let dir = TempDir::new("installed");
+        for (folder, manifest) in [
+            (
+                "zeta",
+                "schema = 1\n[theme]\nname = \"Alpine\"\n[cards]\npattern = \"{id}.png\"\n",
+            ),
+            ("beta", "broken"),
+        ] {
+            fs::create_dir(dir.0.join(folder)).unwrap();
+            fs::write(dir.0.join(folder).join(MANIFEST_FILE), manifest).unwrap();
+        }
+        fs::create_dir(dir.0.join("not-a-theme")).unwrap();
+
+        let themes = installed(&dir.0);
+        let names: Vec<(&str, &str)> = themes
+            .iter()
+            .map(|theme| (theme.folder.as_str(), theme.name.as_str()))
+            .collect();
+        assert_eq!(names, [("zeta", "Alpine"), ("beta", "beta")]);
+
+        assert!(is_plain_name("zeta"));
+        assert!(!is_plain_name("../zeta"));
+        assert!(!is_plain_name("a/b"));
+        assert!(matches!(
+            Theme::load_installed(".."),
+            Err(ThemeError::NotInstalled(_))
+        ));
+    }
+}
@@ -1,8 +1,9 @@
 use std::env;
-use std::fs::{self, File};
-use std::io::Read;
+use std::fs;
 use std::path::Path;
 
+use stopbus_image::bmp;
+
 fn main() {
     if env::var("CARGO_CFG_TARGET_OS").unwrap_or_default() != "windows" {
         return;
@@ -17,14 +18,16 @@ fn main() {
 
     println!("cargo:rerun-if-changed={}", rc_path.display());
     let cards_dir = rc_path.parent().unwrap().join("cards");
-    for entry in fs::read_dir(&cards_dir).expect("missing cards directory") {
+    let entries = fs::read_dir(&cards_dir)
+        .expect("missing cards directory; run `cargo run -p stopbus-assets` to regenerate it");
+    for entry in entries {
         let path = entry.expect("read_dir error").path();
         if path
             .extension()
             .map(|ext| ext.eq_ignore_ascii_case("bmp"))
             .unwrap_or(false)
         {
-            assert_modern_bitmap(&path);
+            assert_decodable_bitmap(&path);
             println!("cargo:rerun-if-changed={}", path.display());
         }
     }
@@ -38,22 +41,12 @@ fn main() {
     );
 }
 
-fn assert_modern_bitmap(path: &Path) {
-    let mut header = [0u8; 18];
-    let mut file =
-        File::open(path).unwrap_or_else(|err| panic!("failed to open {}: {}", path.display(), err));
-    file.read_exact(&mut header)
-        .unwrap_or_else(|err| panic!("failed to read header from {}: {}", path.display(), err));
-    if &header[0..2] != b"BM" {
-        panic!("{} is not a BMP file", path.display());
-    }
-    let dib_size = u32::from_le_bytes([header[14], header[15], header[16], header[17]]);
-    if dib_size < 40 {
-        panic!(
-            "{} still uses an OS/2 BMP header ({} bytes). Run python tools/extract_cards.py {} before building.",
-            path.display(),
-            dib_size,
-            path.display()
-        );
+/// Rejects card art the game could not draw. Both OS/2 and Windows headers are fine, so
+/// the bitmaps under `assets/cards-os2` can be used as they are.
+fn assert_decodable_bitmap(path: &Path) {
+    let data =
+        fs::read(path).unwrap_or_else(|err| panic!("failed to read {}: {}", path.display(), err));
+    if let Err(err) = bmp::decode_file(&data) {
+        panic!("{} is not a usable bitmap: {}", path.display(), err);
     }
 }
@@ -1,12 +1,8 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 use std::ffi::c_void;
 
-use std::collections::VecDeque;
-
 use std::iter;
 
-use std::os::windows::ffi::OsStrExt;
-
 use std::path::Path;
 
 use std::mem::size_of;
@@ -15,45 +11,81 @@ use std::ptr::copy_nonoverlapping;
 
 use std::slice;
 
-use stopbus_core::{DriveReport, GameEvent, GameState, MessageKind, DECK_SIZE, HAND_SIZE};
+use stopbus_core::narration::describe_round_result;
+
+use stopbus_core::{
+    GameEvent, GameState, MessageKind, OpponentRead, RoundSummary, Suit, DECK_SIZE, PLAYERS,
+};
+
+use stopbus_frontend::event_log::{single_line, TOAST_DURATION_MS};
+
+use stopbus_frontend::placement;
+
+use stopbus_frontend::layout::{Rect, EVENT_LOG_HEIGHT, FONT_HEIGHT};
+
+use stopbus_frontend::{Effect, Intent, Key, Layout, LogFilter, LogKind, Presenter, Target};
+
+use stopbus_image::{bmp, Image};
+
+use stopbus_res::ResFile;
+
+use stopbus_settings::migrate::{Import, INI_FILE_NAME};
+
+use stopbus_settings::{keys, Backend, Settings, SettingsError};
+
+use stopbus_theme::{InstalledTheme, Theme};
 
 use windows::core::{w, Error, Result, PCWSTR};
 
-use windows::Win32::Foundation::{
-    FreeLibrary, BOOL, COLORREF, ERROR_SUCCESS, HINSTANCE, HMODULE, HWND, LPARAM, LRESULT, RECT,
-    WPARAM,
-};
+use windows::Win32::Foundation::{BOOL, COLORREF, HINSTANCE, HWND, LPARAM, LRESULT, RECT, WPARAM};
 
 use windows::Win32::Graphics::Gdi::{
-    BeginPaint, BitBlt, CreateCompatibleDC, CreateDIBSection, CreateSolidBrush, DeleteDC,
-    DeleteObject, EndPaint, FillRect, GetDC, InvalidateRect, LoadBitmapW, ReleaseDC, SelectObject,
-    SetBkColor, SetPixelV, SetTextColor, StretchBlt, TextOutW, BITMAPINFO, BITMAPINFOHEADER,
-    BI_RGB, DIB_RGB_COLORS, HBITMAP, HBRUSH, HDC, PAINTSTRUCT, RGBQUAD, SRCCOPY,
+    BeginPaint, BitBlt, CreateCompatibleDC, CreateDIBSection, CreateFontW, CreateSolidBrush,
+    DeleteDC, DeleteObject, DrawTextW, EndPaint, EnumDisplayMonitors, FillRect, FrameRect, GetDC,
+    GetMonitorInfoW, GetStockObject, InvalidateRect, LoadBitmapW, ReleaseDC, SelectObject,
+    SetBkColor, SetBkMode, SetDCBrushColor, SetPixelV, SetStretchBltMode, SetTextColor, StretchBlt,
+    TextOutW, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, CLIP_DEFAULT_PRECIS, DC_BRUSH, DEFAULT_CHARSET,
+    DEFAULT_GUI_FONT, DEFAULT_PITCH, DEFAULT_QUALITY, DIB_RGB_COLORS, DT_END_ELLIPSIS, DT_NOPREFIX,
+    DT_SINGLELINE, DT_VCENTER, FF_SWISS, FW_BOLD, HALFTONE, HBITMAP, HBRUSH, HDC, HFONT, HGDIOBJ,
+    HMONITOR, MONITORINFO, OUT_DEFAULT_PRECIS, PAINTSTRUCT, RGBQUAD, SRCCOPY, TRANSPARENT,
 };
 
 use windows::Win32::System::LibraryLoader::{
-    FindResourceW, GetModuleHandleW, LoadLibraryExW, LoadResource, LockResource, SizeofResource,
-    LOAD_LIBRARY_AS_DATAFILE, LOAD_LIBRARY_AS_IMAGE_RESOURCE,
+    FindResourceW, GetModuleHandleW, LoadResource, LockResource, SizeofResource,
 };
 
-use windows::Win32::System::Registry::{
-    RegGetValueW, RegSetKeyValueW, HKEY_CURRENT_USER, REG_DWORD, RRF_RT_REG_DWORD,
+use windows::Win32::UI::Accessibility::NotifyWinEvent;
+
+use windows::Win32::UI::Controls::{DRAWITEMSTRUCT, ODT_LISTBOX};
+
+use windows::Win32::UI::HiDpi::{
+    AdjustWindowRectExForDpi, GetDpiForWindow, SetProcessDpiAwarenessContext,
+    DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2,
 };
 
-use windows::Win32::UI::Input::KeyboardAndMouse::EnableWindow;
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    EnableWindow, ReleaseCapture, SetCapture, VIRTUAL_KEY, VK_ESCAPE, VK_LEFT, VK_RETURN, VK_RIGHT,
+};
 
 use windows::Win32::UI::WindowsAndMessaging::{
-    AppendMenuW, CreateMenu, CreatePopupMenu, CreateWindowExW, DefWindowProcW, DestroyWindow,
-    DialogBoxParamW, DispatchMessageW, EndDialog, GetClientRect, GetMessageW, GetWindowLongPtrW,
-    GetWindowRect, IsWindowVisible, KillTimer, LoadCursorW, LoadIconW, MessageBoxW, PostMessageW,
-    PostQuitMessage, RegisterClassExW, SendDlgItemMessageW, SetDlgItemTextW, SetMenu, SetTimer,
-    SetWindowLongPtrW, SetWindowPos, ShowWindow, TranslateMessage, BM_GETCHECK, BM_SETCHECK,
-    BS_DEFPUSHBUTTON, BS_PUSHBUTTON, CREATESTRUCTW, CS_HREDRAW, CS_VREDRAW, CW_USEDEFAULT,
-    GWLP_USERDATA, HICON, HMENU, IDCANCEL, IDOK, MB_ICONEXCLAMATION, MB_ICONINFORMATION, MB_OK,
-    MF_POPUP, MF_STRING, MSG, RT_BITMAP, SWP_NOSIZE, SWP_NOZORDER, SW_SHOWDEFAULT, WINDOW_EX_STYLE,
-    WINDOW_STYLE, WM_COMMAND, WM_CREATE, WM_DESTROY, WM_INITDIALOG, WM_LBUTTONDOWN, WM_LBUTTONUP,
-    WM_MOVE, WM_NCDESTROY, WM_PAINT, WM_TIMER, WNDCLASSEXW, WNDPROC, WS_CAPTION, WS_CHILD,
-    WS_EX_TOOLWINDOW, WS_MINIMIZEBOX, WS_OVERLAPPED, WS_POPUP, WS_SYSMENU, WS_VISIBLE,
+    AppendMenuW, CreateMenu, CreatePopupMenu, CreateWindowExW, DefWindowProcW, DeleteMenu,
+    DestroyWindow, DialogBoxParamW, DispatchMessageW, EndDialog, GetClientRect, GetMenuItemCount,
+    GetMessageW, GetWindowLongPtrW, GetWindowRect, IsWindowVisible, KillTimer, LoadCursorW,
+    LoadIconW, MessageBoxW, MoveWindow, PostMessageW, PostQuitMessage, RegisterClassExW,
+    SendDlgItemMessageW, SendMessageW, SetDlgItemTextW, SetMenu, SetTimer, SetWindowLongPtrW,
+    SetWindowPos, SetWindowTextW, ShowWindow, TranslateMessage, BM_CLICK, BM_GETCHECK, BM_SETCHECK,
+    BS_DEFPUSHBUTTON, BS_PUSHBUTTON, CHILDID_SELF, CREATESTRUCTW, CS_HREDRAW, CS_VREDRAW,
+    CW_USEDEFAULT, EVENT_OBJECT_NAMECHANGE, GWLP_USERDATA, HICON, HMENU, IDCANCEL, IDOK,
+    LBS_HASSTRINGS, LBS_NOINTEGRALHEIGHT, LBS_NOSEL, LBS_OWNERDRAWFIXED, LB_ADDSTRING,
+    LB_RESETCONTENT, LB_SETITEMHEIGHT, LB_SETTOPINDEX, MB_ICONEXCLAMATION, MB_ICONINFORMATION,
+    MB_OK, MF_BYPOSITION, MF_CHECKED, MF_POPUP, MF_SEPARATOR, MF_STRING, MSG, OBJID_CLIENT,
+    RT_BITMAP, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE, SWP_NOZORDER, SW_HIDE, SW_SHOWDEFAULT,
+    SW_SHOWNOACTIVATE, USER_DEFAULT_SCREEN_DPI, WINDOW_EX_STYLE, WINDOW_STYLE, WM_CAPTURECHANGED,
+    WM_COMMAND, WM_CREATE, WM_CTLCOLORSTATIC, WM_DESTROY, WM_DPICHANGED, WM_DRAWITEM,
+    WM_INITDIALOG, WM_INITMENUPOPUP, WM_KEYDOWN, WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MOUSEMOVE,
+    WM_MOVE, WM_NCDESTROY, WM_PAINT, WM_SETFONT, WM_SIZE, WM_TIMER, WNDCLASSEXW, WNDPROC,
+    WS_CAPTION, WS_CHILD, WS_EX_CLIENTEDGE, WS_EX_TOOLWINDOW, WS_MAXIMIZEBOX, WS_MINIMIZEBOX,
+    WS_OVERLAPPED, WS_POPUP, WS_SYSMENU, WS_THICKFRAME, WS_VISIBLE, WS_VSCROLL,
 };
 
 const WINDOW_CLASS_NAME: PCWSTR = w!("StopBusMainWindow");
@@ -63,8 +95,8 @@ const WINDOW_TITLE: PCWSTR = w!("Stop The Bus");
 const WM_APP_START: u32 = windows::Win32::UI::WindowsAndMessaging::WM_APP + 1;
 
 const TURN_TIMER_ID: usize = 1;
-const TURN_TIMER_INTERVAL_MS: u32 = 500;
 
+const TOAST_TIMER_ID: usize = 2;
 const LEGACY_RES_PATH: &str = "STOPBUS.RES";
 
 const APP_ICON_ID: u16 = 102;
@@ -81,6 +113,12 @@ const ID_OPT_SCORES: i32 = 502;
 
 const ID_OPT_SAVE_EXIT: i32 = 503;
 
+const ID_OPT_TABLE_READ: i32 = 504;
+
+const ID_OPT_MODAL_EVENTS: i32 = 505;
+
+const ID_OPT_ALERT_TOASTS: i32 = 506;
+
 const ID_ABOUT_TITLE: i32 = 550;
 
 const ID_ABOUT_COPYRIGHT: i32 = 551;
@@ -95,6 +133,15 @@ const ID_ABOUT_RELEASE: i32 = 555;
 
 const ID_ABOUT_ADDRESS: i32 = 556;
 
+// The About text is rewritten by `stopbus-assets`; edit it there.
+const ABOUT_TITLE: &str = "Stop the Bus";
+
+const ABOUT_COPYRIGHT: &str = "Copyright (c) Martin Davidson - 1993-2025";
+
+const ABOUT_VERSION: &str = "Version 2.0.0";
+
+const ABOUT_RELEASE: &str = "Modernization build: 2025-09-22";
+
 const LICENSE_NAME: &str = "Rust modernization build.";
 
 const LICENSE_COMPANY: &str = "Maintained by the community.";
@@ -117,81 +164,41 @@ const CHEAT_STACK_SIZE: (i32, i32) = (260, 180);
 
 const CHEAT_SCORES_SIZE: (i32, i32) = (220, 160);
 
-const CHEAT_WINDOW_PADDING: i32 = 20;
-
-const CHEAT_STACK_PREVIEW: usize = 4;
-
-const REGISTRY_SUBKEY: PCWSTR = w!(r"Software\StopBus\Modernization");
-
-const REG_VALUE_CHEAT_CARDS_VISIBLE: PCWSTR = w!("CheatCardsVisible");
-
-const REG_VALUE_CHEAT_STACK_VISIBLE: PCWSTR = w!("CheatStackVisible");
-
-const REG_VALUE_CHEAT_SCORES_VISIBLE: PCWSTR = w!("CheatScoresVisible");
-
-const REG_VALUE_CHEAT_CARDS_POS_X: PCWSTR = w!("CheatCardsPosX");
+const TABLE_READ_CLASS: PCWSTR = w!("StopBusTableRead");
 
-const REG_VALUE_CHEAT_CARDS_POS_Y: PCWSTR = w!("CheatCardsPosY");
+const TABLE_READ_SIZE: (i32, i32) = (300, 130);
 
-const REG_VALUE_CHEAT_STACK_POS_X: PCWSTR = w!("CheatStackPosX");
-
-const REG_VALUE_CHEAT_STACK_POS_Y: PCWSTR = w!("CheatStackPosY");
-
-const REG_VALUE_CHEAT_SCORES_POS_X: PCWSTR = w!("CheatScoresPosX");
-
-const REG_VALUE_CHEAT_SCORES_POS_Y: PCWSTR = w!("CheatScoresPosY");
-
-const REG_VALUE_MAIN_WINDOW_POS_X: PCWSTR = w!("MainWindowPosX");
-
-const REG_VALUE_MAIN_WINDOW_POS_Y: PCWSTR = w!("MainWindowPosY");
-
-const REG_VALUE_SAVE_ON_EXIT: PCWSTR = w!("SaveOnExit");
+const CHEAT_WINDOW_PADDING: i32 = 20;
 
-const WINDOW_WIDTH: i32 = 600;
+const CHEAT_STACK_PREVIEW: usize = 4;
 
-const WINDOW_HEIGHT: i32 = 400;
+const MAIN_WINDOW_STYLE: WINDOW_STYLE = WINDOW_STYLE(
+    WS_OVERLAPPED.0
+        | WS_CAPTION.0
+        | WS_SYSMENU.0
+        | WS_THICKFRAME.0
+        | WS_MINIMIZEBOX.0
+        | WS_MAXIMIZEBOX.0,
+);
 
+/// The classic table green, used unless a theme sets its own colour.
 const BACKGROUND_COLOR: u32 = 0x0000_8000;
 
-const STACK_POSITION: (i32, i32) = (10, 40);
-
-const TOP_CARD_POSITION: (i32, i32) = (110, 40);
-
-const PLAYER1_CARD_POSITIONS: [(i32, i32); HAND_SIZE] = [(10, 200), (110, 200), (210, 200)];
+const FOCUS_COLOR: u32 = 0x0000_FFFF;
 
-const DECK_LABEL_POSITION: (i32, i32) = (20, 15);
+const LOG_BACKGROUND_COLOR: u32 = 0x00FF_FFFF;
 
-const HAND_LABEL_POSITION: (i32, i32) = (20, 170);
+const LOG_INFO_COLOR: u32 = 0x0000_0000;
 
-const LIVES_HEADING_POSITION: (i32, i32) = (400, 55);
+const LOG_ALERT_COLOR: u32 = 0x0000_00C0;
 
-const PLAYER_LABEL_X: i32 = 400;
+const LOG_NARRATION_COLOR: u32 = 0x0060_6060;
 
-const PLAYER_LABEL_BASE_Y: i32 = 75;
+const LOG_TEXT_MARGIN: i32 = 4;
 
-const PLAYER_LABEL_STEP: i32 = 15;
+const TOAST_COLOR: u32 = 0x0080_FFFF;
 
-const POINTER_BASE_Y: i32 = 60;
-
-const LIVES_VALUE_X: i32 = 470;
-
-const POINTER_X: i32 = 380;
-
-const STICK_MARKER_X: i32 = 360;
-
-const START_PLAYER_POSITION: (i32, i32) = (400, 150);
-
-const BUTTON_WIDTH: i32 = 50;
-
-const BUTTON_HEIGHT: i32 = 50;
-
-const BUTTON_STICK_POS: (i32, i32) = (400, 200);
-
-const BUTTON_DEAL_POS: (i32, i32) = (500, 200);
-
-const BUTTON_EXIT_POS: (i32, i32) = (500, 260);
-
-const BUTTON_OK_POS: (i32, i32) = (400, 260);
+const FOCUS_FRAME_WIDTH: i32 = 3;
 
 const CARD_WIDTH: i32 = 71;
 
@@ -207,6 +214,11 @@ const CM_GAME_OPTIONS: usize = 101;
 
 const CM_GAME_EXIT: usize = 102;
 
+/// The classic cards in the Theme menu; installed themes follow it.
+const CM_GAME_THEME_CLASSIC: usize = 110;
+
+const MAX_THEME_MENU_ITEMS: usize = 50;
+
 const CM_HELP_CONTENTS: usize = 900;
 
 const CM_HELP_ABOUT: usize = 999;
@@ -219,10 +231,22 @@ const ID_EXIT_BUTTON: usize = 1002;
 
 const ID_OK_BUTTON: usize = 1003;
 
+const ID_NARRATION_LABEL: usize = 1004;
+
+const ID_EVENT_LOG: usize = 1005;
+
+const ID_TOAST_LABEL: usize = 1006;
+
+const SS_CENTER: u32 = 0x0001;
+
+const SS_NOPREFIX: u32 = 0x0080;
+
+const SS_CENTERIMAGE: u32 = 0x0200;
+
 struct WindowState {
-    game: GameState,
+    presenter: Presenter,
 
-    legacy_module: Option<HMODULE>,
+    layout: Layout,
 
     module_instance: HINSTANCE,
 
@@ -236,26 +260,52 @@ struct WindowState {
 
     card_cross: HBITMAP,
 
+    /// Pixel size of the card bitmaps, larger than a card for high-resolution themes.
+    card_art_size: (i32, i32),
+
+    table_color: u32,
+
+    /// Themes listed in the Theme menu, in menu order after the classic cards.
+    themes: Vec<InstalledTheme>,
+
+    theme_menu: Option<HMENU>,
+
     options_show_cheat_cards: bool,
 
     options_show_cheat_stack: bool,
 
     options_show_cheat_scores: bool,
 
+    options_show_table_read: bool,
+
     options_save_on_exit: bool,
 
+    /// Show each game event in a message box, as the original did, instead of the log panel.
+    options_modal_events: bool,
+
+    /// Pop alerts up over the table while game events go to the log panel.
+    options_alert_toasts: bool,
+
+    settings: Settings,
+
+    settings_store: Option<Box<dyn Backend>>,
+
     cheat_cards_window: Option<HWND>,
 
     cheat_stack_window: Option<HWND>,
 
     cheat_scores_window: Option<HWND>,
 
+    table_read_window: Option<HWND>,
+
     cheat_cards_pos: (i32, i32),
 
     cheat_stack_pos: (i32, i32),
 
     cheat_scores_pos: (i32, i32),
 
+    table_read_pos: (i32, i32),
+
     stick_button: Option<HWND>,
 
     deal_button: Option<HWND>,
@@ -264,49 +314,39 @@ struct WindowState {
 
     ok_button: Option<HWND>,
 
-    awaiting_human: bool,
-
-    pending_card: Option<usize>,
-
-    stack_pressed: bool,
+    narration_label: Option<HWND>,
 
-    pending_turns: VecDeque<usize>,
+    event_log_panel: Option<HWND>,
 
-    turn_indicator_override: Option<usize>,
+    /// Kind and text of each row in the log panel, for owner drawing.
+    event_log_rows: Vec<(LogKind, String)>,
 
-    turn_timer_active: bool,
+    /// Newest log entry the panel was filled up to.
+    event_log_shown: Option<u64>,
 
-    automation_pending: bool,
+    toast_label: Option<HWND>,
 }
 
 impl WindowState {
     fn new(instance: HINSTANCE) -> Result<Self> {
-        let legacy_module = load_legacy_resource_module();
+        let mut cards = unsafe { load_classic_art(instance)? };
 
-        let resource_instance = legacy_module
-            .map(|module| HINSTANCE(module.0))
-            .unwrap_or(instance);
+        let card_cross = cards.pop().unwrap_or_default();
 
-        let mut cards = Vec::with_capacity(52);
+        let card_back = cards.pop().unwrap_or_default();
 
-        for id in 1..=52 {
-            cards.push(unsafe { load_bitmap(resource_instance, id as u16)? });
-        }
-
-        let card_back = unsafe { load_bitmap(resource_instance, 53)? };
-
-        let card_cross = unsafe { load_bitmap(resource_instance, 54)? };
-
-        let cheat_base_y = WINDOW_HEIGHT + CHEAT_WINDOW_PADDING;
+        let cheat_base_y = default_window_size(USER_DEFAULT_SCREEN_DPI).1 + CHEAT_WINDOW_PADDING;
 
         let cheat_stack_x = CHEAT_CARDS_SIZE.0 + CHEAT_WINDOW_PADDING;
 
         let cheat_scores_x = cheat_stack_x + CHEAT_STACK_SIZE.0 + CHEAT_WINDOW_PADDING;
 
+        let table_read_x = cheat_scores_x + CHEAT_SCORES_SIZE.0 + CHEAT_WINDOW_PADDING;
+
         let mut state = Self {
-            game: GameState::default(),
+            presenter: Presenter::new(GameState::default()),
 
-            legacy_module,
+            layout: Layout::new(0, 0, 1.0, PLAYERS),
 
             module_instance: instance,
 
@@ -320,26 +360,48 @@ impl WindowState {
 
             card_cross,
 
+            card_art_size: (CARD_WIDTH, CARD_HEIGHT),
+
+            table_color: BACKGROUND_COLOR,
+
+            themes: Vec::new(),
+
+            theme_menu: None,
+
             options_show_cheat_cards: false,
 
             options_show_cheat_stack: false,
 
             options_show_cheat_scores: false,
 
+            options_show_table_read: false,
+
             options_save_on_exit: false,
 
+            options_modal_events: false,
+
+            options_alert_toasts: true,
+
+            settings: Settings::new(),
+
+            settings_store: stopbus_settings::default_backend(),
+
             cheat_cards_window: None,
 
             cheat_stack_window: None,
 
             cheat_scores_window: None,
 
+            table_read_window: None,
+
             cheat_cards_pos: (0, cheat_base_y),
 
             cheat_stack_pos: (cheat_stack_x, cheat_base_y),
 
             cheat_scores_pos: (cheat_scores_x, cheat_base_y),
 
+            table_read_pos: (table_read_x, cheat_base_y),
+
             stick_button: None,
 
             deal_button: None,
@@ -348,23 +410,27 @@ impl WindowState {
 
             ok_button: None,
 
-            awaiting_human: false,
+            narration_label: None,
 
-            pending_card: None,
+            event_log_panel: None,
 
-            stack_pressed: false,
+            event_log_rows: Vec::new(),
 
-            pending_turns: VecDeque::new(),
+            event_log_shown: None,
 
-            turn_indicator_override: None,
-
-            turn_timer_active: false,
-
-            automation_pending: false,
+            toast_label: None,
         };
 
         state.load_persisted_cheat_settings();
 
+        if state.settings.get(keys::THEME).is_some() {
+            if let Err(message) = state.load_card_art() {
+                show_error(HWND::default(), &message);
+            }
+        }
+
+        state.recover_offscreen_windows();
+
         state.show_or_hide_cheat_windows()?;
 
         state.update_cheat_windows();
@@ -372,120 +438,182 @@ impl WindowState {
         Ok(state)
     }
 
-    fn process_report(&mut self, hwnd: HWND, report: DriveReport) {
-        for event in &report.events {
-            self.show_event(hwnd, event);
-        }
+    /// Applies `effects` and those of any automation they continue, in order, then speaks
+    /// their announcements together so a later one never gets talked over by an earlier.
+    fn apply_effects(&mut self, hwnd: HWND, effects: Vec<Effect>) {
+        let mut announcements = Vec::new();
+        let mut queue = effects;
 
-        self.update_button_states(&report);
+        while !queue.is_empty() {
+            let mut next = Vec::new();
 
-        self.automation_pending = !report.awaiting_human && !report.game_over();
+            for effect in queue {
+                match effect {
+                    Effect::ShowEvent(event) => self.show_event(hwnd, &event),
 
-        Self::request_repaint(hwnd);
+                    Effect::Announce(text) => announcements.push(text),
 
-        self.update_cheat_windows();
+                    Effect::Repaint => Self::request_repaint(hwnd),
+
+                    Effect::RefreshPanels => self.update_cheat_windows(),
 
-        if report.turn_sequence.is_empty() {
-            if self.automation_pending {
-                self.automation_pending = false;
-                let next_report = self.game.continue_automation();
-                self.process_report(hwnd, next_report);
+                    Effect::StartTurnTimer { interval_ms } => unsafe {
+                        let _ = SetTimer(hwnd, TURN_TIMER_ID, interval_ms, None);
+                    },
+
+                    Effect::StopTurnTimer => unsafe {
+                        let _ = KillTimer(hwnd, TURN_TIMER_ID);
+                    },
+
+                    Effect::ContinueAutomation => next.extend(self.presenter.continue_automation()),
+                }
             }
-            return;
-        }
 
-        self.enqueue_turn_animation(hwnd, report.turn_sequence.clone());
-    }
+            queue = next;
+        }
 
-    fn request_repaint(hwnd: HWND) {
-        unsafe {
-            let _ = InvalidateRect(hwnd, None, BOOL(1));
+        if !announcements.is_empty() {
+            self.announce(&announcements.join(" "));
         }
+
+        self.refresh_event_log();
+
+        self.update_button_states();
     }
 
-    fn enqueue_turn_animation(&mut self, hwnd: HWND, turns: Vec<usize>) {
-        if turns.is_empty() {
+    /// Refills the log panel when the presenter's log has new entries.
+    fn refresh_event_log(&mut self) {
+        let Some(panel) = self.event_log_panel else {
+            return;
+        };
+
+        let newest = self
+            .presenter
+            .log()
+            .entries()
+            .next_back()
+            .map(|entry| entry.sequence);
+
+        if newest == self.event_log_shown {
             return;
         }
 
-        self.pending_turns.extend(turns);
+        self.event_log_shown = newest;
+
+        self.event_log_rows = self
+            .presenter
+            .log()
+            .filtered(LogFilter::EVENTS)
+            .map(|entry| (entry.kind, entry.line()))
+            .collect();
+
+        unsafe {
+            SendMessageW(panel, LB_RESETCONTENT, WPARAM(0), LPARAM(0));
+
+            for (_, line) in &self.event_log_rows {
+                let wide = wide_string(line);
 
-        if !self.turn_timer_active {
-            self.start_turn_animation(hwnd);
+                SendMessageW(
+                    panel,
+                    LB_ADDSTRING,
+                    WPARAM(0),
+                    LPARAM(wide.as_ptr() as isize),
+                );
+            }
+
+            if let Some(last) = self.event_log_rows.len().checked_sub(1) {
+                SendMessageW(panel, LB_SETTOPINDEX, WPARAM(last), LPARAM(0));
+            }
         }
     }
 
-    fn start_turn_animation(&mut self, hwnd: HWND) {
-        if self.pending_turns.is_empty() {
+    /// Pops the alert over the table and hides it again after [`TOAST_DURATION_MS`].
+    fn show_toast(&self, hwnd: HWND, text: &str) {
+        let Some(toast) = self.toast_label else {
             return;
-        }
+        };
 
-        self.turn_timer_active = true;
+        let wide = wide_string(&single_line(text));
 
         unsafe {
-            let _ = SetTimer(hwnd, TURN_TIMER_ID, TURN_TIMER_INTERVAL_MS, None);
-        }
+            let _ = SetWindowTextW(toast, PCWSTR(wide.as_ptr()));
 
-        self.advance_turn_animation(hwnd);
-    }
+            let _ = ShowWindow(toast, SW_SHOWNOACTIVATE);
 
-    fn advance_turn_animation(&mut self, hwnd: HWND) {
-        if let Some(next_player) = self.pending_turns.pop_front() {
-            self.turn_indicator_override = Some(next_player);
-            Self::request_repaint(hwnd);
-        } else {
-            self.stop_turn_animation(hwnd);
+            let _ = SetTimer(hwnd, TOAST_TIMER_ID, TOAST_DURATION_MS, None);
         }
     }
 
-    fn stop_turn_animation(&mut self, hwnd: HWND) {
-        if self.turn_timer_active {
-            unsafe {
-                let _ = KillTimer(hwnd, TURN_TIMER_ID);
+    fn hide_toast(&self, hwnd: HWND) {
+        unsafe {
+            let _ = KillTimer(hwnd, TOAST_TIMER_ID);
+
+            if let Some(toast) = self.toast_label {
+                let _ = ShowWindow(toast, SW_HIDE);
             }
-            self.turn_timer_active = false;
         }
+    }
 
-        self.pending_turns.clear();
+    /// Shows the narration under the table and tells screen readers it changed.
+    fn announce(&self, text: &str) {
+        let Some(label) = self.narration_label else {
+            return;
+        };
 
-        if self.turn_indicator_override.take().is_some() {
-            Self::request_repaint(hwnd);
-        }
+        let wide = wide_string(text);
 
-        if self.automation_pending {
-            self.automation_pending = false;
-            let report = self.game.continue_automation();
-            self.process_report(hwnd, report);
+        unsafe {
+            let _ = SetWindowTextW(label, PCWSTR(wide.as_ptr()));
+
+            NotifyWinEvent(
+                EVENT_OBJECT_NAMECHANGE,
+                label,
+                OBJID_CLIENT.0,
+                CHILDID_SELF as i32,
+            );
         }
     }
 
-    fn update_button_states(&mut self, report: &DriveReport) {
-        let game_over = report.game_over();
+    fn handle_intent(&mut self, hwnd: HWND, intent: Intent) {
+        let effects = self.presenter.handle_intent(intent);
 
-        let human_alive = self.game.lives()[0] > 0;
-
-        self.awaiting_human = report.awaiting_human && human_alive && !game_over;
+        self.apply_effects(hwnd, effects);
+    }
 
-        Self::set_button_enabled(&self.ok_button, self.awaiting_human);
+    /// True for the Stick, Deal, Exit and OK buttons.
+    fn is_push_button(&self, handle: HWND) -> bool {
+        [
+            self.stick_button,
+            self.deal_button,
+            self.exit_button,
+            self.ok_button,
+        ]
+        .contains(&Some(handle))
+    }
 
-        let stick_enabled = self.awaiting_human && self.game.human_can_stick();
+    fn request_repaint(hwnd: HWND) {
+        unsafe {
+            let _ = InvalidateRect(hwnd, None, BOOL(1));
+        }
+    }
 
-        Self::set_button_enabled(&self.stick_button, stick_enabled);
+    fn update_button_states(&self) {
+        let view = self.presenter.view();
 
-        if !self.awaiting_human {
-            self.pending_card = None;
+        Self::set_button_enabled(&self.ok_button, view.ok_enabled);
 
-            self.stack_pressed = false;
-        }
+        Self::set_button_enabled(&self.stick_button, view.stick_enabled);
+    }
 
-        if game_over {
-            Self::set_button_enabled(&self.ok_button, false);
+    fn show_event(&self, hwnd: HWND, event: &GameEvent) {
+        if !self.options_modal_events {
+            if event.kind == MessageKind::Alert && self.options_alert_toasts {
+                self.show_toast(hwnd, &event.text);
+            }
 
-            Self::set_button_enabled(&self.stick_button, false);
+            return;
         }
-    }
 
-    fn show_event(&self, hwnd: HWND, event: &GameEvent) {
         let text = wide_string(&event.text);
 
         let title = wide_string("Stop the Bus");
@@ -515,6 +643,10 @@ impl WindowState {
     }
 
     fn paint(&mut self, hdc: HDC) {
+        let view = self.presenter.view();
+
+        let layout = &self.layout;
+
         let mem_dc = unsafe { CreateCompatibleDC(hdc) };
 
         if mem_dc.0.is_null() {
@@ -522,16 +654,16 @@ impl WindowState {
         }
 
         unsafe {
-            let brush = CreateSolidBrush(COLORREF(BACKGROUND_COLOR));
+            let brush = CreateSolidBrush(COLORREF(self.table_color));
 
             let rect = RECT {
-                left: 0,
+                left: layout.client.x,
 
-                top: 0,
+                top: layout.client.y,
 
-                right: WINDOW_WIDTH,
+                right: layout.client.right(),
 
-                bottom: WINDOW_HEIGHT,
+                bottom: layout.client.bottom(),
             };
 
             FillRect(hdc, &rect, brush);
@@ -539,101 +671,203 @@ impl WindowState {
             let _ = DeleteObject(brush);
         }
 
-        unsafe {
-            draw_bitmap(
-                hdc,
-                mem_dc,
-                self.card_back,
-                STACK_POSITION.0,
-                STACK_POSITION.1,
-            );
-        }
+        if view.showdown.is_none() {
+            unsafe {
+                self.draw_card(hdc, mem_dc, self.card_back, layout.stack);
+            }
 
-        for (slot, (x, y)) in PLAYER1_CARD_POSITIONS.iter().enumerate() {
-            let bitmap = match self.game.hands[0][slot] {
-                Some(card) => self.card_bitmap(card).unwrap_or(self.card_cross),
+            for (slot, rect) in layout.hand.iter().enumerate() {
+                let bitmap = match view.hand[slot] {
+                    Some(card) => self.card_bitmap(card).unwrap_or(self.card_cross),
 
-                None => self.card_cross,
-            };
+                    None => self.card_cross,
+                };
 
-            unsafe {
-                draw_bitmap(hdc, mem_dc, bitmap, *x, *y);
+                unsafe {
+                    self.draw_card(hdc, mem_dc, bitmap, *rect);
+                }
             }
-        }
 
-        if let Some(card) = self.game.stack_top_card() {
-            if let Some(bitmap) = self.card_bitmap(card) {
+            if let Some(rect) = view.focused_slot.and_then(|slot| layout.hand.get(slot)) {
                 unsafe {
-                    draw_bitmap(
-                        hdc,
-                        mem_dc,
-                        bitmap,
-                        TOP_CARD_POSITION.0,
-                        TOP_CARD_POSITION.1,
-                    );
+                    draw_focus_frame(hdc, *rect);
+                }
+            }
+
+            if let Some(card) = view.stack_top {
+                if let Some(bitmap) = self.card_bitmap(card) {
+                    unsafe {
+                        self.draw_card(hdc, mem_dc, bitmap, layout.top_card);
+                    }
+                }
+            }
+
+            if let Some(drag) = view.drag {
+                let (rect, bitmap) = match drag.source {
+                    Target::HandCard(slot) => (
+                        layout.hand[slot],
+                        view.hand[slot].and_then(|card| self.card_bitmap(card)),
+                    ),
+
+                    Target::TopCard => (
+                        layout.top_card,
+                        view.stack_top.and_then(|card| self.card_bitmap(card)),
+                    ),
+
+                    Target::Stack => (layout.stack, Some(self.card_back)),
+                };
+
+                if let Some(bitmap) = bitmap {
+                    let (dx, dy) = drag.offset();
+
+                    let moved = Rect::new(rect.x + dx, rect.y + dy, rect.width, rect.height);
+
+                    unsafe {
+                        self.draw_card(hdc, mem_dc, bitmap, moved);
+                    }
                 }
             }
         }
 
+        let font = unsafe { select_scaled_font(hdc, layout.font_height) };
+
         unsafe {
-            let _ = SetBkColor(hdc, COLORREF(BACKGROUND_COLOR));
+            let _ = SetBkColor(hdc, COLORREF(self.table_color));
 
             let _ = SetTextColor(hdc, COLORREF(0x0000_0000));
         }
 
-        draw_text(hdc, DECK_LABEL_POSITION.0, DECK_LABEL_POSITION.1, "Deck:");
+        match &view.showdown {
+            Some(summary) => self.paint_showdown(hdc, mem_dc, summary),
 
-        draw_text(
-            hdc,
-            HAND_LABEL_POSITION.0,
-            HAND_LABEL_POSITION.1,
-            "Your hand:",
-        );
+            None => {
+                draw_text(hdc, layout.deck_label.0, layout.deck_label.1, "Deck:");
+
+                draw_text(hdc, layout.hand_label.0, layout.hand_label.1, "Your hand:");
+            }
+        }
 
         draw_text(
             hdc,
-            LIVES_HEADING_POSITION.0,
-            LIVES_HEADING_POSITION.1,
+            layout.lives_heading.0,
+            layout.lives_heading.1,
             "Remaining Lives:",
         );
 
-        for (index, lives) in self.game.lives().iter().enumerate() {
-            let label_y = PLAYER_LABEL_BASE_Y + PLAYER_LABEL_STEP * index as i32;
-
+        for (index, (row, lives)) in layout.seats.iter().zip(view.lives.iter()).enumerate() {
             let label = format!("Player {} -", index + 1);
 
-            draw_text(hdc, PLAYER_LABEL_X, label_y, &label);
+            draw_text(hdc, row.label.0, row.label.1, &label);
 
             let life_text = format!("{}", lives);
 
-            draw_text(hdc, LIVES_VALUE_X, label_y, &life_text);
+            draw_text(hdc, row.lives.0, row.lives.1, &life_text);
         }
 
-        let start_label = format!("Player {} to start", self.game.round_start_player() + 1);
+        let start_label = format!("Player {} to start", view.round_start_player + 1);
 
         draw_text(
             hdc,
-            START_PLAYER_POSITION.0,
-            START_PLAYER_POSITION.1,
+            layout.start_player.0,
+            layout.start_player.1,
             &start_label,
         );
 
-        let pointer_player = self
-            .turn_indicator_override
-            .unwrap_or_else(|| self.game.current_player());
-        let arrow_y = POINTER_BASE_Y + PLAYER_LABEL_STEP * (pointer_player as i32 + 1);
+        if let Some(row) = layout.seats.get(view.pointer_player) {
+            draw_text(hdc, row.pointer.0, row.pointer.1, " ->");
+        }
+
+        if let Some(row) = view
+            .stick_player
+            .and_then(|player| layout.seats.get(player))
+        {
+            draw_text(hdc, row.stick_marker.0, row.stick_marker.1, "***");
+        }
+
+        unsafe {
+            restore_font(hdc, font);
+
+            let _ = DeleteDC(mem_dc);
+        }
+    }
+
+    /// Every hand from the round that just ended, with its score and what it cost.
+    fn paint_showdown(&self, hdc: HDC, mem_dc: HDC, summary: &RoundSummary) {
+        for (player, row) in self.layout.showdown.iter().enumerate() {
+            let name = if player == 0 {
+                "You".to_string()
+            } else {
+                format!("Player {}", player + 1)
+            };
+
+            draw_text(hdc, row.name.0, row.name.1, &name);
+
+            let Some(hand) = summary.hands[player] else {
+                draw_text(hdc, row.detail.0, row.detail.1, "out");
 
-        draw_text(hdc, POINTER_X, arrow_y, " ->");
+                continue;
+            };
+
+            for (card, rect) in hand.iter().zip(row.cards) {
+                let bitmap = card
+                    .and_then(|card| self.card_bitmap(card))
+                    .unwrap_or(self.card_cross);
 
-        if let Some(stick_player) = self.game.stick_player() {
-            let stick_y = POINTER_BASE_Y + PLAYER_LABEL_STEP * (stick_player as i32 + 1);
+                unsafe {
+                    self.draw_card(hdc, mem_dc, bitmap, rect);
+                }
+            }
 
-            draw_text(hdc, STICK_MARKER_X, stick_y, "***");
+            draw_text(
+                hdc,
+                row.detail.0,
+                row.detail.1,
+                &describe_round_result(summary, player),
+            );
         }
+    }
+
+    fn relayout(&mut self, hwnd: HWND) {
+        let mut client = RECT::default();
 
         unsafe {
-            let _ = DeleteDC(mem_dc);
+            let _ = GetClientRect(hwnd, &mut client);
         }
+
+        let dpi_scale = unsafe { GetDpiForWindow(hwnd) } as f32 / USER_DEFAULT_SCREEN_DPI as f32;
+
+        let client_width = client.right - client.left;
+
+        let (table_height, event_log) =
+            Layout::split_event_log(client_width, client.bottom - client.top, dpi_scale);
+
+        self.layout = Layout::new(client_width, table_height, dpi_scale, PLAYERS);
+
+        for (button, rect) in [
+            (self.stick_button, self.layout.stick_button),
+            (self.deal_button, self.layout.deal_button),
+            (self.exit_button, self.layout.exit_button),
+            (self.ok_button, self.layout.ok_button),
+            (self.narration_label, self.layout.narration),
+            (self.toast_label, self.layout.toast),
+            (self.event_log_panel, event_log),
+        ] {
+            if let Some(handle) = button {
+                unsafe {
+                    let _ = MoveWindow(handle, rect.x, rect.y, rect.width, rect.height, BOOL(1));
+                }
+            }
+        }
+
+        if let Some(panel) = self.event_log_panel {
+            let row_height = (FONT_HEIGHT as f32 * dpi_scale).round() as isize;
+
+            unsafe {
+                SendMessageW(panel, LB_SETITEMHEIGHT, WPARAM(0), LPARAM(row_height));
+            }
+        }
+
+        Self::request_repaint(hwnd);
     }
 
     fn card_bitmap(&self, card: u8) -> Option<HBITMAP> {
@@ -642,29 +876,145 @@ impl WindowState {
         self.cards.get(index).copied()
     }
 
-    fn hit_test_player_card(&self, x: i32, y: i32) -> Option<usize> {
-        for (slot, (card_x, card_y)) in PLAYER1_CARD_POSITIONS.iter().enumerate() {
-            if Self::point_in_rect(x, y, *card_x, *card_y, CARD_WIDTH, CARD_HEIGHT) {
-                return Some(slot);
+    unsafe fn draw_card(&self, hdc: HDC, mem_dc: HDC, bitmap: HBITMAP, rect: Rect) {
+        draw_bitmap(
+            hdc,
+            mem_dc,
+            bitmap,
+            self.card_art_size,
+            rect,
+            COLORREF(self.table_color),
+        );
+    }
+
+    /// Loads the theme settings name, or the classic cards when none is set, and swaps
+    /// it in. Whatever was showing stays when the theme cannot be used.
+    fn load_card_art(&mut self) -> std::result::Result<(), String> {
+        let (art, size, color) = match self.settings.get(keys::THEME) {
+            Some(folder) => {
+                let theme = Theme::load_installed(&folder).map_err(|err| {
+                    format!("The '{}' card theme cannot be used.\n\n{}", folder, err)
+                })?;
+
+                let color = theme.table_color.map_or(BACKGROUND_COLOR, |[r, g, b]| {
+                    u32::from(r) | u32::from(g) << 8 | u32::from(b) << 16
+                });
+
+                let art = unsafe { create_theme_art(&theme, color) }
+                    .map_err(|err| format!("Failed to create the theme's bitmaps: {}", err))?;
+
+                let (width, height) = theme.card_size;
+
+                (art, (width as i32, height as i32), color)
+            }
+
+            None => {
+                let art = unsafe { load_classic_art(self.module_instance) }
+                    .map_err(|err| format!("Failed to load the card bitmaps: {}", err))?;
+
+                (art, (CARD_WIDTH, CARD_HEIGHT), BACKGROUND_COLOR)
+            }
+        };
+
+        let mut art = art;
+
+        let cross = art.pop().unwrap_or_default();
+
+        let back = art.pop().unwrap_or_default();
+
+        unsafe {
+            for bitmap in &self.cards {
+                let _ = DeleteObject(*bitmap);
             }
+
+            let _ = DeleteObject(self.card_back);
+
+            let _ = DeleteObject(self.card_cross);
         }
 
-        None
+        self.cards = art;
+
+        self.card_back = back;
+
+        self.card_cross = cross;
+
+        self.card_art_size = size;
+
+        self.table_color = color;
+
+        Ok(())
     }
 
-    fn stack_contains(&self, x: i32, y: i32) -> bool {
-        Self::point_in_rect(
-            x,
-            y,
-            STACK_POSITION.0,
-            STACK_POSITION.1,
-            CARD_WIDTH,
-            CARD_HEIGHT,
-        )
+    /// Lists the classic cards and every installed theme, checking the one in use.
+    unsafe fn fill_theme_menu(&mut self, menu: HMENU) {
+        for _ in 0..GetMenuItemCount(menu).max(0) {
+            let _ = DeleteMenu(menu, 0, MF_BYPOSITION);
+        }
+
+        self.themes = stopbus_theme::themes_dir()
+            .map(|dir| stopbus_theme::installed(&dir))
+            .unwrap_or_default();
+
+        self.themes.truncate(MAX_THEME_MENU_ITEMS);
+
+        let selected = self.settings.get(keys::THEME);
+
+        let check = |chosen: bool| if chosen { MF_CHECKED } else { MF_STRING };
+
+        let _ = AppendMenuW(
+            menu,
+            check(selected.is_none()),
+            CM_GAME_THEME_CLASSIC,
+            w!("&Classic"),
+        );
+
+        if !self.themes.is_empty() {
+            let _ = AppendMenuW(menu, MF_SEPARATOR, 0, PCWSTR::null());
+        }
+
+        for (index, theme) in self.themes.iter().enumerate() {
+            let label = wide_string(&theme.name.replace('&', "&&"));
+
+            let _ = AppendMenuW(
+                menu,
+                check(selected.as_deref() == Some(theme.folder.as_str())),
+                CM_GAME_THEME_CLASSIC + 1 + index,
+                PCWSTR(label.as_ptr()),
+            );
+        }
     }
 
-    fn point_in_rect(x: i32, y: i32, left: i32, top: i32, width: i32, height: i32) -> bool {
-        x >= left && x < left + width && y >= top && y < top + height
+    /// Switches to the theme at `index` in the menu, zero being the classic cards, and
+    /// stores the choice. A theme that fails to load is reported and not stored.
+    fn select_theme(&mut self, hwnd: HWND, index: usize) {
+        let previous = self.settings.get(keys::THEME);
+
+        match index
+            .checked_sub(1)
+            .and_then(|index| self.themes.get(index))
+        {
+            Some(theme) => self.settings.set(keys::THEME, theme.folder.clone()),
+
+            None => self.settings.remove(keys::THEME),
+        }
+
+        if let Err(message) = self.load_card_art() {
+            match previous {
+                Some(folder) => self.settings.set(keys::THEME, folder),
+
+                None => self.settings.remove(keys::THEME),
+            }
+
+            show_error(hwnd, &message);
+
+            return;
+        }
+
+        self.persist_cheat_settings();
+
+        Self::request_repaint(hwnd);
+
+        self.update_cheat_windows();
     }
 
     fn show_or_hide_cheat_windows(&mut self) -> Result<()> {
@@ -709,6 +1059,19 @@ impl WindowState {
             }
         }
 
+        if self.options_show_table_read {
+            if self.table_read_window.is_none() && ready {
+                self.table_read_window = Some(create_table_read_window(self)?);
+            }
+        } else if let Some(hwnd) = self.table_read_window.take() {
+            if let Some(pos) = unsafe { window_screen_position(hwnd) } {
+                self.table_read_pos = pos;
+            }
+            unsafe {
+                let _ = DestroyWindow(hwnd);
+            }
+        }
+
         if ready {
             self.update_cheat_windows();
             self.capture_window_positions();
@@ -730,49 +1093,136 @@ impl WindowState {
             if let Some(hwnd) = self.cheat_scores_window {
                 repaint_cheat_window(self, hwnd, paint_cheat_scores);
             }
+
+            if let Some(hwnd) = self.table_read_window {
+                repaint_cheat_window(self, hwnd, paint_table_read);
+            }
         }
     }
 
     fn load_persisted_cheat_settings(&mut self) {
-        if let Some(value) = registry_read_bool(REG_VALUE_CHEAT_CARDS_VISIBLE) {
+        if let Some(store) = &self.settings_store {
+            match store.load() {
+                Ok(settings) => self.settings = settings,
+
+                // Keep the store as it is for the user to fix rather than save defaults over it.
+                Err(err) => {
+                    self.presenter.log_note(
+                        LogKind::Alert,
+                        format!(
+                            "Settings in {} could not be loaded, so changes will not be saved: {}",
+                            store.location(),
+                            err
+                        ),
+                    );
+
+                    self.settings_store = None;
+                }
+            }
+        }
+
+        if self.settings.is_empty() {
+            match import_legacy_ini() {
+                Some(Ok(import)) => {
+                    import.apply_to(&mut self.settings);
+
+                    for line in &import.unrecognised {
+                        self.presenter.log_note(
+                            LogKind::Info,
+                            format!("{} {} was not imported.", INI_FILE_NAME, line),
+                        );
+                    }
+                }
+
+                Some(Err(err)) => self.presenter.log_note(
+                    LogKind::Alert,
+                    format!("{} could not be imported: {}", INI_FILE_NAME, err),
+                ),
+
+                None => {}
+            }
+        }
+
+        let settings = &self.settings;
+
+        if let Some(value) = settings.get(keys::CHEAT_CARDS_VISIBLE) {
             self.options_show_cheat_cards = value;
         }
 
-        if let Some(value) = registry_read_bool(REG_VALUE_CHEAT_STACK_VISIBLE) {
+        if let Some(value) = settings.get(keys::CHEAT_STACK_VISIBLE) {
             self.options_show_cheat_stack = value;
         }
 
-        if let Some(value) = registry_read_bool(REG_VALUE_CHEAT_SCORES_VISIBLE) {
+        if let Some(value) = settings.get(keys::CHEAT_SCORES_VISIBLE) {
             self.options_show_cheat_scores = value;
         }
 
-        if let Some(value) = registry_read_bool(REG_VALUE_SAVE_ON_EXIT) {
-            self.options_save_on_exit = value;
+        if let Some(value) = settings.get(keys::TABLE_READ_VISIBLE) {
+            self.options_show_table_read = value;
+        }
+
+        if let Some(value) = settings.get(keys::SAVE_ON_EXIT) {
+            self.options_save_on_exit = value;
+        }
+
+        if let Some(value) = settings.get(keys::MODAL_EVENTS) {
+            self.options_modal_events = value;
+        }
+
+        if let Some(value) = settings.get(keys::ALERT_TOASTS) {
+            self.options_alert_toasts = value;
         }
 
-        if let Some(pos) =
-            registry_read_point(REG_VALUE_MAIN_WINDOW_POS_X, REG_VALUE_MAIN_WINDOW_POS_Y)
-        {
+        if let Some(pos) = settings.point(keys::MAIN_WINDOW_POS) {
             self.main_window_pos = Some(pos);
         }
 
-        if let Some(pos) =
-            registry_read_point(REG_VALUE_CHEAT_CARDS_POS_X, REG_VALUE_CHEAT_CARDS_POS_Y)
-        {
+        if let Some(pos) = settings.point(keys::CHEAT_CARDS_POS) {
             self.cheat_cards_pos = pos;
         }
 
-        if let Some(pos) =
-            registry_read_point(REG_VALUE_CHEAT_STACK_POS_X, REG_VALUE_CHEAT_STACK_POS_Y)
-        {
+        if let Some(pos) = settings.point(keys::CHEAT_STACK_POS) {
             self.cheat_stack_pos = pos;
         }
 
-        if let Some(pos) =
-            registry_read_point(REG_VALUE_CHEAT_SCORES_POS_X, REG_VALUE_CHEAT_SCORES_POS_Y)
-        {
+        if let Some(pos) = settings.point(keys::CHEAT_SCORES_POS) {
             self.cheat_scores_pos = pos;
         }
+
+        if let Some(pos) = settings.point(keys::TABLE_READ_POS) {
+            self.table_read_pos = pos;
+        }
+    }
+
+    /// Pulls saved positions back onto the current monitors, keeping the cheat windows
+    /// where they were relative to the main window when it has to move.
+    fn recover_offscreen_windows(&mut self) {
+        let (width, height) = default_window_size(USER_DEFAULT_SCREEN_DPI);
+
+        let main = self
+            .main_window_pos
+            .map(|(x, y)| Rect::new(x, y, width, height));
+
+        let windows = [
+            rect_at(self.cheat_cards_pos, CHEAT_CARDS_SIZE),
+            rect_at(self.cheat_stack_pos, CHEAT_STACK_SIZE),
+            rect_at(self.cheat_scores_pos, CHEAT_SCORES_SIZE),
+            rect_at(self.table_read_pos, TABLE_READ_SIZE),
+        ];
+
+        let placement = placement::recover(main, &windows, &monitor_work_areas());
+
+        self.main_window_pos = placement.main.map(|rect| (rect.x, rect.y));
+
+        if let [cards, stack, scores, table_read] = placement.windows[..] {
+            self.cheat_cards_pos = (cards.x, cards.y);
+
+            self.cheat_stack_pos = (stack.x, stack.y);
+
+            self.cheat_scores_pos = (scores.x, scores.y);
+
+            self.table_read_pos = (table_read.x, table_read.y);
+        }
     }
 
     fn capture_window_positions(&mut self) {
@@ -792,6 +1242,11 @@ impl WindowState {
                     self.cheat_scores_pos = pos;
                 }
             }
+            if let Some(hwnd) = self.table_read_window {
+                if let Some(pos) = window_screen_position(hwnd) {
+                    self.table_read_pos = pos;
+                }
+            }
         }
     }
 
@@ -818,6 +1273,7 @@ impl WindowState {
             dx,
             dy,
         );
+        Self::offset_single_cheat_window(self.table_read_window, &mut self.table_read_pos, dx, dy);
     }
 
     fn offset_single_cheat_window(window: Option<HWND>, pos: &mut (i32, i32), dx: i32, dy: i32) {
@@ -840,47 +1296,40 @@ impl WindowState {
         }
     }
 
-    fn persist_cheat_settings(&self) {
-        registry_write_bool(REG_VALUE_SAVE_ON_EXIT, self.options_save_on_exit);
+    fn persist_cheat_settings(&mut self) {
+        let settings = &mut self.settings;
 
-        if !self.options_save_on_exit {
-            return;
-        }
+        settings.set(keys::SAVE_ON_EXIT, self.options_save_on_exit);
 
-        registry_write_bool(REG_VALUE_CHEAT_CARDS_VISIBLE, self.options_show_cheat_cards);
+        if self.options_save_on_exit {
+            settings.set(keys::CHEAT_CARDS_VISIBLE, self.options_show_cheat_cards);
 
-        registry_write_bool(REG_VALUE_CHEAT_STACK_VISIBLE, self.options_show_cheat_stack);
+            settings.set(keys::CHEAT_STACK_VISIBLE, self.options_show_cheat_stack);
 
-        registry_write_bool(
-            REG_VALUE_CHEAT_SCORES_VISIBLE,
-            self.options_show_cheat_scores,
-        );
+            settings.set(keys::CHEAT_SCORES_VISIBLE, self.options_show_cheat_scores);
 
-        if let Some(pos) = self.main_window_pos {
-            registry_write_point(
-                REG_VALUE_MAIN_WINDOW_POS_X,
-                REG_VALUE_MAIN_WINDOW_POS_Y,
-                pos,
-            );
-        }
+            settings.set(keys::TABLE_READ_VISIBLE, self.options_show_table_read);
 
-        registry_write_point(
-            REG_VALUE_CHEAT_CARDS_POS_X,
-            REG_VALUE_CHEAT_CARDS_POS_Y,
-            self.cheat_cards_pos,
-        );
+            settings.set(keys::MODAL_EVENTS, self.options_modal_events);
 
-        registry_write_point(
-            REG_VALUE_CHEAT_STACK_POS_X,
-            REG_VALUE_CHEAT_STACK_POS_Y,
-            self.cheat_stack_pos,
-        );
+            settings.set(keys::ALERT_TOASTS, self.options_alert_toasts);
 
-        registry_write_point(
-            REG_VALUE_CHEAT_SCORES_POS_X,
-            REG_VALUE_CHEAT_SCORES_POS_Y,
-            self.cheat_scores_pos,
-        );
+            if let Some(pos) = self.main_window_pos {
+                settings.set_point(keys::MAIN_WINDOW_POS, pos);
+            }
+
+            settings.set_point(keys::CHEAT_CARDS_POS, self.cheat_cards_pos);
+
+            settings.set_point(keys::CHEAT_STACK_POS, self.cheat_stack_pos);
+
+            settings.set_point(keys::CHEAT_SCORES_POS, self.cheat_scores_pos);
+
+            settings.set_point(keys::TABLE_READ_POS, self.table_read_pos);
+        }
+
+        if let Some(store) = &self.settings_store {
+            let _ = store.save(&self.settings);
+        }
     }
 
     fn show_options_dialog(&mut self, hwnd: HWND) -> Result<()> {
@@ -900,6 +1349,10 @@ impl WindowState {
             self.show_or_hide_cheat_windows()?;
 
             self.update_cheat_windows();
+
+            if self.options_modal_events || !self.options_alert_toasts {
+                self.hide_toast(hwnd);
+            }
         }
 
         status
@@ -946,20 +1399,13 @@ impl Drop for WindowState {
                 let _ = DestroyWindow(hwnd);
             }
 
-            if let Some(module) = self.legacy_module.take() {
-                let _ = FreeLibrary(module);
+            if let Some(hwnd) = self.table_read_window.take() {
+                let _ = DestroyWindow(hwnd);
             }
         }
     }
 }
 
-fn wide_path(path: &Path) -> Vec<u16> {
-    path.as_os_str()
-        .encode_wide()
-        .chain(iter::once(0))
-        .collect()
-}
-
 fn wide_string(value: &str) -> Vec<u16> {
     value.encode_utf16().chain(iter::once(0)).collect()
 }
@@ -1015,7 +1461,7 @@ unsafe fn create_button(
 
     id: usize,
 
-    position: (i32, i32),
+    rect: Rect,
 
     default: bool,
 ) -> Result<HWND> {
@@ -1034,10 +1480,10 @@ unsafe fn create_button(
         w!("BUTTON"),
         PCWSTR(caption.as_ptr()),
         WINDOW_STYLE(style_bits),
-        position.0,
-        position.1,
-        BUTTON_WIDTH,
-        BUTTON_HEIGHT,
+        rect.x,
+        rect.y,
+        rect.width,
+        rect.height,
         parent,
         HMENU(id as isize as *mut c_void),
         instance,
@@ -1045,7 +1491,128 @@ unsafe fn create_button(
     )
 }
 
-fn load_legacy_resource_module() -> Option<HMODULE> {
+unsafe fn create_event_log_panel(parent: HWND, instance: HINSTANCE) -> Result<HWND> {
+    let style = LBS_OWNERDRAWFIXED | LBS_HASSTRINGS | LBS_NOINTEGRALHEIGHT | LBS_NOSEL;
+
+    let panel = CreateWindowExW(
+        WS_EX_CLIENTEDGE,
+        w!("LISTBOX"),
+        w!("Game log"),
+        WINDOW_STYLE(WS_CHILD.0 | WS_VISIBLE.0 | WS_VSCROLL.0 | style as u32),
+        0,
+        0,
+        0,
+        0,
+        parent,
+        HMENU(ID_EVENT_LOG as isize as *mut c_void),
+        instance,
+        None,
+    )?;
+
+    SendMessageW(
+        panel,
+        WM_SETFONT,
+        WPARAM(GetStockObject(DEFAULT_GUI_FONT).0 as usize),
+        LPARAM(0),
+    );
+
+    Ok(panel)
+}
+
+/// A hidden label that [`WindowState::show_toast`] pops up over the table.
+unsafe fn create_toast_label(parent: HWND, instance: HINSTANCE, rect: Rect) -> Result<HWND> {
+    let toast = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        w!("STATIC"),
+        w!(""),
+        WINDOW_STYLE(WS_CHILD.0 | SS_CENTER | SS_CENTERIMAGE | SS_NOPREFIX),
+        rect.x,
+        rect.y,
+        rect.width,
+        rect.height,
+        parent,
+        HMENU(ID_TOAST_LABEL as isize as *mut c_void),
+        instance,
+        None,
+    )?;
+
+    SendMessageW(
+        toast,
+        WM_SETFONT,
+        WPARAM(GetStockObject(DEFAULT_GUI_FONT).0 as usize),
+        LPARAM(0),
+    );
+
+    Ok(toast)
+}
+
+/// Draws one log row in the colour of its kind.
+unsafe fn draw_event_log_row(state: &WindowState, item: &DRAWITEMSTRUCT) {
+    let Some((kind, line)) = state.event_log_rows.get(item.itemID as usize) else {
+        return;
+    };
+
+    let hdc = item.hDC;
+
+    let _ = SetDCBrushColor(hdc, COLORREF(LOG_BACKGROUND_COLOR));
+
+    FillRect(hdc, &item.rcItem, HBRUSH(GetStockObject(DC_BRUSH).0));
+
+    let color = match kind {
+        LogKind::Info => LOG_INFO_COLOR,
+
+        LogKind::Alert => LOG_ALERT_COLOR,
+
+        LogKind::Narration => LOG_NARRATION_COLOR,
+    };
+
+    let _ = SetTextColor(hdc, COLORREF(color));
+
+    let _ = SetBkMode(hdc, TRANSPARENT);
+
+    let mut rect = item.rcItem;
+
+    rect.left += LOG_TEXT_MARGIN;
+
+    let mut text: Vec<u16> = line.encode_utf16().collect();
+
+    DrawTextW(
+        hdc,
+        &mut text,
+        &mut rect,
+        DT_SINGLELINE | DT_VCENTER | DT_END_ELLIPSIS | DT_NOPREFIX,
+    );
+}
+
+unsafe fn create_narration_label(parent: HWND, instance: HINSTANCE, rect: Rect) -> Result<HWND> {
+    let label = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        w!("STATIC"),
+        w!(""),
+        WINDOW_STYLE(WS_CHILD.0 | WS_VISIBLE.0 | SS_NOPREFIX),
+        rect.x,
+        rect.y,
+        rect.width,
+        rect.height,
+        parent,
+        HMENU(ID_NARRATION_LABEL as isize as *mut c_void),
+        instance,
+        None,
+    )?;
+
+    SendMessageW(
+        label,
+        WM_SETFONT,
+        WPARAM(GetStockObject(DEFAULT_GUI_FONT).0 as usize),
+        LPARAM(0),
+    );
+
+    Ok(label)
+}
+
+/// `STOPBUS.RES` from the working directory or beside the executable, when present and
+/// readable. It is a 16-bit resource file, which `LoadLibraryEx` cannot open.
+fn load_legacy_resources() -> Option<ResFile> {
     let mut candidates = Vec::new();
 
     candidates.push(Path::new(LEGACY_RES_PATH).to_path_buf());
@@ -1056,25 +1623,10 @@ fn load_legacy_resource_module() -> Option<HMODULE> {
         }
     }
 
-    for candidate in candidates {
-        if !candidate.exists() {
-            continue;
-        }
-
-        let wide = wide_path(&candidate);
-
-        if let Ok(module) = unsafe {
-            LoadLibraryExW(
-                PCWSTR(wide.as_ptr()),
-                None,
-                LOAD_LIBRARY_AS_DATAFILE | LOAD_LIBRARY_AS_IMAGE_RESOURCE,
-            )
-        } {
-            return Some(module);
-        }
-    }
-
-    None
+    candidates
+        .into_iter()
+        .filter(|candidate| candidate.exists())
+        .find_map(|candidate| ResFile::read(&candidate).ok())
 }
 
 fn register_window_class(instance: HINSTANCE) -> Result<()> {
@@ -1131,6 +1683,8 @@ fn register_window_class(instance: HINSTANCE) -> Result<()> {
 }
 
 fn create_main_window(instance: HINSTANCE, state: *mut WindowState) -> Result<HWND> {
+    let (width, height) = default_window_size(USER_DEFAULT_SCREEN_DPI);
+
     unsafe {
         let (x, y) = if state.is_null() {
             (CW_USEDEFAULT, CW_USEDEFAULT)
@@ -1146,11 +1700,11 @@ fn create_main_window(instance: HINSTANCE, state: *mut WindowState) -> Result<HW
             WINDOW_EX_STYLE(0),
             WINDOW_CLASS_NAME,
             WINDOW_TITLE,
-            WINDOW_STYLE(WS_OVERLAPPED.0 | WS_CAPTION.0 | WS_SYSMENU.0 | WS_MINIMIZEBOX.0),
+            MAIN_WINDOW_STYLE,
             x,
             y,
-            WINDOW_WIDTH,
-            WINDOW_HEIGHT,
+            width,
+            height,
             None,
             HMENU::default(),
             instance,
@@ -1159,6 +1713,52 @@ fn create_main_window(instance: HINSTANCE, state: *mut WindowState) -> Result<HW
     }
 }
 
+/// Outer size of a main window whose client area fits the table and game log at `dpi`.
+fn default_window_size(dpi: u32) -> (i32, i32) {
+    let dpi_scale = dpi as f32 / USER_DEFAULT_SCREEN_DPI as f32;
+
+    let (width, height) = Layout::preferred_client_size(dpi_scale, PLAYERS);
+
+    let mut rect = RECT {
+        left: 0,
+
+        top: 0,
+
+        right: width,
+
+        bottom: height + (EVENT_LOG_HEIGHT as f32 * dpi_scale).round() as i32,
+    };
+
+    unsafe {
+        let _ = AdjustWindowRectExForDpi(
+            &mut rect,
+            MAIN_WINDOW_STYLE,
+            BOOL(1),
+            WINDOW_EX_STYLE(0),
+            dpi,
+        );
+    }
+
+    (rect.right - rect.left, rect.bottom - rect.top)
+}
+
+/// Digits and letters map to their characters; the keyboard model decides which matter.
+fn key_from_virtual_key(key: VIRTUAL_KEY) -> Option<Key> {
+    match key {
+        VK_RETURN => Some(Key::Enter),
+
+        VK_ESCAPE => Some(Key::Escape),
+
+        VK_LEFT => Some(Key::Left),
+
+        VK_RIGHT => Some(Key::Right),
+
+        VIRTUAL_KEY(code @ (0x30..=0x39 | 0x41..=0x5A)) => Some(Key::Char(char::from(code as u8))),
+
+        _ => None,
+    }
+}
+
 unsafe fn window_state_mut(hwnd: HWND) -> Option<&'static mut WindowState> {
     let ptr = GetWindowLongPtrW(hwnd, GWLP_USERDATA) as *mut WindowState;
 
@@ -1169,12 +1769,11 @@ unsafe fn window_state_mut(hwnd: HWND) -> Option<&'static mut WindowState> {
     }
 }
 
-unsafe fn soften_card_corners(hdc: HDC, x: i32, y: i32, width: i32, height: i32) {
+unsafe fn soften_card_corners(hdc: HDC, x: i32, y: i32, width: i32, height: i32, color: COLORREF) {
     if width <= 0 || height <= 0 {
         return;
     }
 
-    let color = COLORREF(BACKGROUND_COLOR);
     let right = x + width - 1;
     let bottom = y + height - 1;
 
@@ -1203,22 +1802,125 @@ unsafe fn soften_card_corners(hdc: HDC, x: i32, y: i32, width: i32, height: i32)
     }
 }
 
-unsafe fn draw_bitmap(hdc: HDC, mem_dc: HDC, bitmap: HBITMAP, x: i32, y: i32) {
+/// Copies card art of `source` size into `rect`, stretching it when the sizes differ.
+unsafe fn draw_bitmap(
+    hdc: HDC,
+    mem_dc: HDC,
+    bitmap: HBITMAP,
+    source: (i32, i32),
+    rect: Rect,
+    background: COLORREF,
+) {
     let previous = SelectObject(mem_dc, bitmap);
 
     if !previous.0.is_null() {
-        let _ = BitBlt(hdc, x, y, CARD_WIDTH, CARD_HEIGHT, mem_dc, 0, 0, SRCCOPY);
+        if (rect.width, rect.height) == source {
+            let _ = BitBlt(
+                hdc,
+                rect.x,
+                rect.y,
+                rect.width,
+                rect.height,
+                mem_dc,
+                0,
+                0,
+                SRCCOPY,
+            );
+        } else {
+            let _ = SetStretchBltMode(hdc, HALFTONE);
+
+            let _ = StretchBlt(
+                hdc,
+                rect.x,
+                rect.y,
+                rect.width,
+                rect.height,
+                mem_dc,
+                0,
+                0,
+                source.0,
+                source.1,
+                SRCCOPY,
+            );
+        }
 
-        soften_card_corners(hdc, x, y, CARD_WIDTH, CARD_HEIGHT);
+        soften_card_corners(hdc, rect.x, rect.y, rect.width, rect.height, background);
 
         let _ = SelectObject(mem_dc, previous);
     }
 }
 
-unsafe fn draw_small_bitmap(hdc: HDC, mem_dc: HDC, bitmap: HBITMAP, x: i32, y: i32) {
+unsafe fn draw_focus_frame(hdc: HDC, rect: Rect) {
+    let brush = CreateSolidBrush(COLORREF(FOCUS_COLOR));
+
+    for inset in 1..=FOCUS_FRAME_WIDTH {
+        let frame = RECT {
+            left: rect.x - inset,
+
+            top: rect.y - inset,
+
+            right: rect.right() + inset,
+
+            bottom: rect.bottom() + inset,
+        };
+
+        FrameRect(hdc, &frame, brush);
+    }
+
+    let _ = DeleteObject(brush);
+}
+
+/// Selects a font matching the layout scale; the stock font is kept at 100%.
+unsafe fn select_scaled_font(hdc: HDC, height: i32) -> Option<(HFONT, HGDIOBJ)> {
+    if height == FONT_HEIGHT {
+        return None;
+    }
+
+    let font = CreateFontW(
+        height,
+        0,
+        0,
+        0,
+        FW_BOLD.0 as i32,
+        0,
+        0,
+        0,
+        DEFAULT_CHARSET.0 as u32,
+        OUT_DEFAULT_PRECIS.0 as u32,
+        CLIP_DEFAULT_PRECIS.0 as u32,
+        DEFAULT_QUALITY.0 as u32,
+        (DEFAULT_PITCH.0 | FF_SWISS.0) as u32,
+        w!("MS Sans Serif"),
+    );
+
+    if font.0.is_null() {
+        return None;
+    }
+
+    Some((font, SelectObject(hdc, font)))
+}
+
+unsafe fn restore_font(hdc: HDC, selected: Option<(HFONT, HGDIOBJ)>) {
+    if let Some((font, previous)) = selected {
+        let _ = SelectObject(hdc, previous);
+
+        let _ = DeleteObject(font);
+    }
+}
+
+unsafe fn draw_small_bitmap(
+    state: &WindowState,
+    hdc: HDC,
+    mem_dc: HDC,
+    bitmap: HBITMAP,
+    x: i32,
+    y: i32,
+) {
     let previous = SelectObject(mem_dc, bitmap);
 
     if !previous.0.is_null() {
+        let _ = SetStretchBltMode(hdc, HALFTONE);
+
         let _ = StretchBlt(
             hdc,
             x,
@@ -1228,12 +1930,19 @@ unsafe fn draw_small_bitmap(hdc: HDC, mem_dc: HDC, bitmap: HBITMAP, x: i32, y: i
             mem_dc,
             0,
             0,
-            CARD_WIDTH,
-            CARD_HEIGHT,
+            state.card_art_size.0,
+            state.card_art_size.1,
             SRCCOPY,
         );
 
-        soften_card_corners(hdc, x, y, SMALL_CARD_WIDTH, SMALL_CARD_HEIGHT);
+        soften_card_corners(
+            hdc,
+            x,
+            y,
+            SMALL_CARD_WIDTH,
+            SMALL_CARD_HEIGHT,
+            COLORREF(state.table_color),
+        );
 
         let _ = SelectObject(mem_dc, previous);
     }
@@ -1254,7 +1963,7 @@ unsafe extern "system" fn window_proc(
 
             let state_ptr = create.lpCreateParams as *mut WindowState;
 
-            let _ = create_menus(hwnd);
+            let theme_menu = create_menus(hwnd).ok();
 
             SetWindowLongPtrW(hwnd, GWLP_USERDATA, state_ptr as isize);
 
@@ -1263,6 +1972,10 @@ unsafe extern "system" fn window_proc(
 
                 state.main_hwnd = Some(hwnd);
 
+                state.theme_menu = theme_menu;
+
+                state.relayout(hwnd);
+
                 let instance = create.hInstance;
 
                 let stick = match create_button(
@@ -1270,7 +1983,7 @@ unsafe extern "system" fn window_proc(
                     instance,
                     "Stick",
                     ID_STICK_BUTTON,
-                    BUTTON_STICK_POS,
+                    state.layout.stick_button,
                     false,
                 ) {
                     Ok(handle) => handle,
@@ -1285,7 +1998,7 @@ unsafe extern "system" fn window_proc(
                     instance,
                     "Deal",
                     ID_DEAL_BUTTON,
-                    BUTTON_DEAL_POS,
+                    state.layout.deal_button,
                     false,
                 ) {
                     Ok(handle) => handle,
@@ -1300,7 +2013,7 @@ unsafe extern "system" fn window_proc(
                     instance,
                     "Exit",
                     ID_EXIT_BUTTON,
-                    BUTTON_EXIT_POS,
+                    state.layout.exit_button,
                     false,
                 ) {
                     Ok(handle) => handle,
@@ -1310,15 +2023,48 @@ unsafe extern "system" fn window_proc(
 
                 state.exit_button = Some(exit);
 
-                let ok =
-                    match create_button(hwnd, instance, "OK", ID_OK_BUTTON, BUTTON_OK_POS, true) {
-                        Ok(handle) => handle,
+                let ok = match create_button(
+                    hwnd,
+                    instance,
+                    "OK",
+                    ID_OK_BUTTON,
+                    state.layout.ok_button,
+                    true,
+                ) {
+                    Ok(handle) => handle,
 
-                        Err(_) => return DefWindowProcW(hwnd, message, wparam, lparam),
-                    };
+                    Err(_) => return DefWindowProcW(hwnd, message, wparam, lparam),
+                };
 
                 state.ok_button = Some(ok);
 
+                match create_narration_label(hwnd, instance, state.layout.narration) {
+                    Ok(label) => {
+                        state.narration_label = Some(label);
+
+                        state.presenter.set_narration(true);
+                    }
+
+                    Err(_) => return DefWindowProcW(hwnd, message, wparam, lparam),
+                }
+
+                match create_toast_label(hwnd, instance, state.layout.toast) {
+                    Ok(toast) => state.toast_label = Some(toast),
+
+                    Err(_) => return DefWindowProcW(hwnd, message, wparam, lparam),
+                }
+
+                match create_event_log_panel(hwnd, instance) {
+                    Ok(panel) => state.event_log_panel = Some(panel),
+
+                    Err(_) => return DefWindowProcW(hwnd, message, wparam, lparam),
+                }
+
+                // Shows anything logged while loading the settings.
+                state.refresh_event_log();
+
+                state.relayout(hwnd);
+
                 state.main_hwnd = Some(hwnd);
 
                 if let Some(pos) = unsafe { window_screen_position(hwnd) } {
@@ -1357,11 +2103,105 @@ unsafe extern "system" fn window_proc(
             LRESULT(0)
         }
 
+        WM_CTLCOLORSTATIC => {
+            let hdc = HDC(wparam.0 as *mut c_void);
+
+            let state = window_state_mut(hwnd);
+
+            let is_toast = state
+                .as_ref()
+                .and_then(|state| state.toast_label)
+                .is_some_and(|toast| toast.0 as isize == lparam.0);
+
+            let table = state.map_or(BACKGROUND_COLOR, |state| state.table_color);
+
+            let (background, text) = if is_toast {
+                (TOAST_COLOR, LOG_ALERT_COLOR)
+            } else {
+                (table, 0x0000_0000)
+            };
+
+            let _ = SetBkColor(hdc, COLORREF(background));
+
+            let _ = SetTextColor(hdc, COLORREF(text));
+
+            let _ = SetDCBrushColor(hdc, COLORREF(background));
+
+            LRESULT(GetStockObject(DC_BRUSH).0 as isize)
+        }
+
+        WM_DRAWITEM => {
+            let item = &*(lparam.0 as *const DRAWITEMSTRUCT);
+
+            if item.CtlType == ODT_LISTBOX && item.CtlID as usize == ID_EVENT_LOG {
+                if let Some(state) = window_state_mut(hwnd) {
+                    draw_event_log_row(state, item);
+                }
+
+                return LRESULT(1);
+            }
+
+            DefWindowProcW(hwnd, message, wparam, lparam)
+        }
+
+        WM_KEYDOWN => {
+            if let Some(key) = key_from_virtual_key(VIRTUAL_KEY(wparam.0 as u16)) {
+                if let Some(state) = window_state_mut(hwnd) {
+                    let effects = state.presenter.key_press(key);
+
+                    state.apply_effects(hwnd, effects);
+                }
+
+                return LRESULT(0);
+            }
+
+            DefWindowProcW(hwnd, message, wparam, lparam)
+        }
+
+        WM_SIZE => {
+            if let Some(state) = window_state_mut(hwnd) {
+                state.relayout(hwnd);
+            }
+
+            LRESULT(0)
+        }
+
+        WM_DPICHANGED => {
+            let suggested = &*(lparam.0 as *const RECT);
+
+            let _ = SetWindowPos(
+                hwnd,
+                None,
+                suggested.left,
+                suggested.top,
+                suggested.right - suggested.left,
+                suggested.bottom - suggested.top,
+                SWP_NOZORDER | SWP_NOACTIVATE,
+            );
+
+            // No WM_SIZE follows when the suggested size matches the old one.
+            if let Some(state) = window_state_mut(hwnd) {
+                state.relayout(hwnd);
+            }
+
+            LRESULT(0)
+        }
+
         WM_TIMER => {
             if wparam.0 == TURN_TIMER_ID {
                 if let Some(state) = window_state_mut(hwnd) {
-                    state.advance_turn_animation(hwnd);
+                    let effects = state.presenter.timer_tick();
+
+                    state.apply_effects(hwnd, effects);
+                }
+                return LRESULT(0);
+            }
+
+            if wparam.0 == TOAST_TIMER_ID {
+                if let Some(state) = window_state_mut(hwnd) {
+                    state.hide_toast(hwnd);
                 }
+
                 return LRESULT(0);
             }
 
@@ -1370,9 +2210,7 @@ unsafe extern "system" fn window_proc(
 
         WM_APP_START => {
             if let Some(state) = window_state_mut(hwnd) {
-                let report = state.game.start_fresh();
-
-                state.process_report(hwnd, report);
+                state.handle_intent(hwnd, Intent::Deal);
             }
 
             LRESULT(0)
@@ -1384,9 +2222,7 @@ unsafe extern "system" fn window_proc(
             match command {
                 CM_GAME_DEAL | ID_DEAL_BUTTON => {
                     if let Some(state) = window_state_mut(hwnd) {
-                        let report = state.game.start_fresh();
-
-                        state.process_report(hwnd, report);
+                        state.handle_intent(hwnd, Intent::Deal);
                     }
 
                     LRESULT(0)
@@ -1400,9 +2236,7 @@ unsafe extern "system" fn window_proc(
 
                 ID_STICK_BUTTON => {
                     if let Some(state) = window_state_mut(hwnd) {
-                        if let Some(report) = state.game.human_stick() {
-                            state.process_report(hwnd, report);
-                        }
+                        state.handle_intent(hwnd, Intent::Stick);
                     }
 
                     LRESULT(0)
@@ -1410,9 +2244,7 @@ unsafe extern "system" fn window_proc(
 
                 ID_OK_BUTTON => {
                     if let Some(state) = window_state_mut(hwnd) {
-                        let report = state.game.advance_after_human_turn();
-
-                        state.process_report(hwnd, report);
+                        state.handle_intent(hwnd, Intent::EndTurn);
                     }
 
                     LRESULT(0)
@@ -1428,6 +2260,17 @@ unsafe extern "system" fn window_proc(
                     LRESULT(0)
                 }
 
+                command
+                    if (CM_GAME_THEME_CLASSIC..=CM_GAME_THEME_CLASSIC + MAX_THEME_MENU_ITEMS)
+                        .contains(&command) =>
+                {
+                    if let Some(state) = window_state_mut(hwnd) {
+                        state.select_theme(hwnd, command - CM_GAME_THEME_CLASSIC);
+                    }
+
+                    LRESULT(0)
+                }
+
                 CM_HELP_ABOUT => {
                     if let Some(state) = window_state_mut(hwnd) {
                         if let Err(err) = state.show_about_dialog(hwnd) {
@@ -1447,31 +2290,43 @@ unsafe extern "system" fn window_proc(
                         "others use pip value. Hitting 31 lets you Stop the Bus immediately.\n\n",
                         "Stick to freeze the round. Each rival takes one final turn to beat you; ",
                         "afterward the lowest score loses a life. Lose three lives and you're out.\n\n",
+                        "Keyboard: 1-3 pick a card, S swaps it with the stack, D draws, K sticks, ",
+                        "Enter ends your turn and N deals.\n\n",
                         "Use Options to toggle cheat windows that reveal hands, the stack, or live ",
                         "scores while testing the modernization build.\n",
                     );
 
                     show_info(hwnd, summary);
 
-                    LRESULT(0)
+                    LRESULT(0)
+                }
+
+                _ => DefWindowProcW(hwnd, message, wparam, lparam),
+            }
+        }
+
+        WM_INITMENUPOPUP => {
+            if let Some(state) = window_state_mut(hwnd) {
+                let menu = HMENU(wparam.0 as *mut c_void);
+
+                if state.theme_menu == Some(menu) {
+                    state.fill_theme_menu(menu);
                 }
-
-                _ => DefWindowProcW(hwnd, message, wparam, lparam),
             }
+
+            DefWindowProcW(hwnd, message, wparam, lparam)
         }
 
         WM_LBUTTONDOWN => {
             let (x, y) = get_mouse_pos(lparam);
 
             if let Some(state) = window_state_mut(hwnd) {
-                if state.awaiting_human && state.game.awaiting_human() {
-                    state.pending_card = state.hit_test_player_card(x, y);
+                let target = state.layout.hit_test(x, y);
 
-                    state.stack_pressed = state.stack_contains(x, y);
-                } else {
-                    state.pending_card = None;
+                state.presenter.pointer_down(target, (x, y));
 
-                    state.stack_pressed = false;
+                if state.presenter.pressed().is_some() {
+                    SetCapture(hwnd);
                 }
             }
 
@@ -1482,39 +2337,35 @@ unsafe extern "system" fn window_proc(
             let (x, y) = get_mouse_pos(lparam);
 
             if let Some(state) = window_state_mut(hwnd) {
-                let mut handled = false;
-
-                if let Some(slot) = state.pending_card.take() {
-                    if state.awaiting_human
-                        && state.game.awaiting_human()
-                        && state.hit_test_player_card(x, y) == Some(slot)
-                    {
-                        if let Some(report) = state.game.human_swap_with_stack(slot) {
-                            state.process_report(hwnd, report);
-                        }
+                let target = state.layout.hit_test(x, y);
 
-                        handled = true;
-                    }
-                }
+                let effects = state.presenter.pointer_up(target, (x, y));
 
-                if state.stack_pressed {
-                    state.stack_pressed = false;
+                let _ = ReleaseCapture();
 
-                    if state.awaiting_human
-                        && state.game.awaiting_human()
-                        && state.stack_contains(x, y)
-                    {
-                        if let Some(report) = state.game.human_draw_next_card() {
-                            state.process_report(hwnd, report);
-                        }
+                state.apply_effects(hwnd, effects);
+            }
 
-                        handled = true;
-                    }
-                }
+            LRESULT(0)
+        }
 
-                if handled {
-                    return LRESULT(0);
-                }
+        WM_MOUSEMOVE => {
+            let (x, y) = get_mouse_pos(lparam);
+
+            if let Some(state) = window_state_mut(hwnd) {
+                let effects = state.presenter.pointer_move((x, y));
+
+                state.apply_effects(hwnd, effects);
+            }
+
+            LRESULT(0)
+        }
+
+        WM_CAPTURECHANGED => {
+            if let Some(state) = window_state_mut(hwnd) {
+                let effects = state.presenter.pointer_cancel();
+
+                state.apply_effects(hwnd, effects);
             }
 
             LRESULT(0)
@@ -1526,7 +2377,7 @@ unsafe extern "system" fn window_proc(
             let hdc = BeginPaint(hwnd, &mut ps);
 
             if let Some(state) = window_state_mut(hwnd) {
-                state.game.update_round_scores();
+                state.presenter.game_mut().update_round_scores();
 
                 state.paint(hdc);
             }
@@ -1538,7 +2389,9 @@ unsafe extern "system" fn window_proc(
 
         WM_DESTROY => {
             if let Some(state) = window_state_mut(hwnd) {
-                state.stop_turn_animation(hwnd);
+                let effects = state.presenter.shutdown();
+
+                state.apply_effects(hwnd, effects);
             }
 
             PostQuitMessage(0);
@@ -1611,7 +2464,13 @@ unsafe extern "system" fn options_dialog_proc(
 
                 set_checkbox(hwnd, ID_OPT_SCORES, state.options_show_cheat_scores);
 
+                set_checkbox(hwnd, ID_OPT_TABLE_READ, state.options_show_table_read);
+
                 set_checkbox(hwnd, ID_OPT_SAVE_EXIT, state.options_save_on_exit);
+
+                set_checkbox(hwnd, ID_OPT_MODAL_EVENTS, state.options_modal_events);
+
+                set_checkbox(hwnd, ID_OPT_ALERT_TOASTS, state.options_alert_toasts);
             }
 
             1
@@ -1631,7 +2490,13 @@ unsafe extern "system" fn options_dialog_proc(
 
                         state.options_show_cheat_scores = get_checkbox(hwnd, ID_OPT_SCORES);
 
+                        state.options_show_table_read = get_checkbox(hwnd, ID_OPT_TABLE_READ);
+
                         state.options_save_on_exit = get_checkbox(hwnd, ID_OPT_SAVE_EXIT);
+
+                        state.options_modal_events = get_checkbox(hwnd, ID_OPT_MODAL_EVENTS);
+
+                        state.options_alert_toasts = get_checkbox(hwnd, ID_OPT_ALERT_TOASTS);
                     }
 
                     let _ = EndDialog(hwnd, command_id as isize);
@@ -1667,25 +2532,25 @@ unsafe extern "system" fn about_dialog_proc(
             let _ = SetDlgItemTextW(
                 hwnd,
                 ID_ABOUT_TITLE,
-                PCWSTR(wide_string("Stop the Bus").as_ptr()),
+                PCWSTR(wide_string(ABOUT_TITLE).as_ptr()),
             );
 
             let _ = SetDlgItemTextW(
                 hwnd,
                 ID_ABOUT_COPYRIGHT,
-                PCWSTR(wide_string("Copyright (c) Martin Davidson - 1993-2025").as_ptr()),
+                PCWSTR(wide_string(ABOUT_COPYRIGHT).as_ptr()),
             );
 
             let _ = SetDlgItemTextW(
                 hwnd,
                 ID_ABOUT_VERSION,
-                PCWSTR(wide_string("Version 2.0.0").as_ptr()),
+                PCWSTR(wide_string(ABOUT_VERSION).as_ptr()),
             );
 
             let _ = SetDlgItemTextW(
                 hwnd,
                 ID_ABOUT_RELEASE,
-                PCWSTR(wide_string("Modernization build: 2025-09-22").as_ptr()),
+                PCWSTR(wide_string(ABOUT_RELEASE).as_ptr()),
             );
 
             let _ = SetDlgItemTextW(
@@ -1747,6 +2612,8 @@ fn register_cheat_window_classes(instance: HINSTANCE, icon: HICON) -> Result<()>
         Some(cheat_scores_wnd_proc),
     )?;
 
+    register_single_cheat_class(instance, TABLE_READ_CLASS, icon, Some(table_read_wnd_proc))?;
+
     Ok(())
 }
 
@@ -1824,6 +2691,16 @@ fn create_cheat_scores_window(state: &mut WindowState) -> Result<HWND> {
     )
 }
 
+fn create_table_read_window(state: &mut WindowState) -> Result<HWND> {
+    create_cheat_window(
+        state,
+        TABLE_READ_CLASS,
+        w!("Read the Table"),
+        state.table_read_pos,
+        TABLE_READ_SIZE,
+    )
+}
+
 fn create_cheat_window(
     state: &mut WindowState,
 
@@ -2041,6 +2918,66 @@ unsafe extern "system" fn cheat_scores_wnd_proc(
     }
 }
 
+unsafe extern "system" fn table_read_wnd_proc(
+    hwnd: HWND,
+
+    message: u32,
+
+    wparam: WPARAM,
+
+    lparam: LPARAM,
+) -> LRESULT {
+    match message {
+        WM_CREATE => {
+            let create = unsafe { &*(lparam.0 as *const CREATESTRUCTW) };
+
+            unsafe { SetWindowLongPtrW(hwnd, GWLP_USERDATA, create.lpCreateParams as isize) };
+
+            LRESULT(0)
+        }
+
+        WM_MOVE => {
+            if let Some(state) = unsafe { cheat_window_state_mut(hwnd) } {
+                if let Some(pos) = unsafe { window_screen_position(hwnd) } {
+                    state.table_read_pos = pos;
+
+                    state.persist_cheat_settings();
+                }
+            }
+
+            LRESULT(0)
+        }
+
+        WM_PAINT => {
+            let mut ps = PAINTSTRUCT::default();
+
+            let hdc = unsafe { BeginPaint(hwnd, &mut ps) };
+
+            if let Some(state) = unsafe { cheat_window_state_ref(hwnd) } {
+                unsafe { paint_table_read(state, hwnd, hdc) };
+            }
+
+            unsafe {
+                let _ = EndPaint(hwnd, &ps);
+            };
+
+            LRESULT(0)
+        }
+
+        WM_DESTROY => {
+            if let Some(state) = unsafe { cheat_window_state_mut(hwnd) } {
+                state.table_read_window = None;
+            }
+
+            unsafe { SetWindowLongPtrW(hwnd, GWLP_USERDATA, 0) };
+
+            LRESULT(0)
+        }
+
+        _ => unsafe { DefWindowProcW(hwnd, message, wparam, lparam) },
+    }
+}
+
 unsafe fn cheat_window_state_ref(hwnd: HWND) -> Option<&'static WindowState> {
     let ptr = unsafe { GetWindowLongPtrW(hwnd, GWLP_USERDATA) } as *const WindowState;
 
@@ -2053,63 +2990,62 @@ unsafe fn cheat_window_state_mut(hwnd: HWND) -> Option<&'static mut WindowState>
     unsafe { ptr.as_mut() }
 }
 
-fn registry_read_dword(value_name: PCWSTR) -> Option<u32> {
-    let mut data: u32 = 0;
+fn rect_at((x, y): (i32, i32), (width, height): (i32, i32)) -> Rect {
+    Rect::new(x, y, width, height)
+}
 
-    let mut data_size = size_of::<u32>() as u32;
+/// The work area of every monitor, so saved positions can be checked against them.
+fn monitor_work_areas() -> Vec<Rect> {
+    let mut areas: Vec<Rect> = Vec::new();
 
-    let status = unsafe {
-        RegGetValueW(
-            HKEY_CURRENT_USER,
-            REGISTRY_SUBKEY,
-            value_name,
-            RRF_RT_REG_DWORD,
+    unsafe {
+        let _ = EnumDisplayMonitors(
+            HDC::default(),
             None,
-            Some(std::ptr::addr_of_mut!(data) as *mut c_void),
-            Some(&mut data_size),
-        )
-    };
-
-    if status == ERROR_SUCCESS {
-        Some(data)
-    } else {
-        None
+            Some(collect_work_area),
+            LPARAM(std::ptr::addr_of_mut!(areas) as isize),
+        );
     }
-}
 
-fn registry_write_dword(value_name: PCWSTR, value: u32) {
-    let _ = unsafe {
-        RegSetKeyValueW(
-            HKEY_CURRENT_USER,
-            REGISTRY_SUBKEY,
-            value_name,
-            REG_DWORD.0,
-            Some(std::ptr::addr_of!(value) as *const c_void),
-            size_of::<u32>() as u32,
-        )
-    };
+    areas
 }
 
-fn registry_read_bool(value_name: PCWSTR) -> Option<bool> {
-    registry_read_dword(value_name).map(|value| value != 0)
-}
+unsafe extern "system" fn collect_work_area(
+    monitor: HMONITOR,
+    _hdc: HDC,
+    _clip: *mut RECT,
+    data: LPARAM,
+) -> BOOL {
+    let areas = unsafe { &mut *(data.0 as *mut Vec<Rect>) };
 
-fn registry_write_bool(value_name: PCWSTR, value: bool) {
-    registry_write_dword(value_name, value as u32);
-}
+    let mut info = MONITORINFO {
+        cbSize: size_of::<MONITORINFO>() as u32,
+
+        ..Default::default()
+    };
 
-fn registry_read_point(x_name: PCWSTR, y_name: PCWSTR) -> Option<(i32, i32)> {
-    match (registry_read_dword(x_name), registry_read_dword(y_name)) {
-        (Some(x), Some(y)) => Some((x as i32, y as i32)),
+    if unsafe { GetMonitorInfoW(monitor, &mut info) }.as_bool() {
+        let work = info.rcWork;
 
-        _ => None,
+        areas.push(Rect::new(
+            work.left,
+            work.top,
+            work.right - work.left,
+            work.bottom - work.top,
+        ));
     }
+
+    BOOL::from(true)
 }
 
-fn registry_write_point(x_name: PCWSTR, y_name: PCWSTR, value: (i32, i32)) {
-    registry_write_dword(x_name, value.0 as u32);
+/// The Win16 build's `STOPBUS.INI`, which lived in the Windows directory, or `None` when
+/// there is no such file.
+fn import_legacy_ini() -> Option<std::result::Result<Import, SettingsError>> {
+    let windows_dir = std::env::var_os("WINDIR")?;
+
+    let path = Path::new(&windows_dir).join(INI_FILE_NAME);
 
-    registry_write_dword(y_name, value.1 as u32);
+    path.exists().then(|| Import::from_file(&path))
 }
 
 unsafe fn window_screen_position(hwnd: HWND) -> Option<(i32, i32)> {
@@ -2155,7 +3091,7 @@ unsafe fn paint_cheat_cards(state: &WindowState, hwnd: HWND, hdc: HDC) {
 
     SetTextColor(hdc, COLORREF(0x00000000));
 
-    for (player_index, hand) in state.game.hands.iter().enumerate().skip(1) {
+    for (player_index, hand) in state.presenter.game().hands.iter().enumerate().skip(1) {
         let player_number = player_index + 1;
 
         for (slot, card) in hand.iter().enumerate() {
@@ -2167,7 +3103,7 @@ unsafe fn paint_cheat_cards(state: &WindowState, hwnd: HWND, hdc: HDC) {
 
             let y = 57 * (player_number as i32) - 108;
 
-            draw_small_bitmap(hdc, mem_dc, bitmap, x, y);
+            draw_small_bitmap(state, hdc, mem_dc, bitmap, x, y);
         }
 
         let label = format!("Player {}:", player_number);
@@ -2191,14 +3127,15 @@ unsafe fn paint_cheat_stack(state: &WindowState, hwnd: HWND, hdc: HDC) {
 
     SetTextColor(hdc, COLORREF(0x00000000));
 
-    let base_index = state.game.stack_index();
+    let base_index = state.presenter.game().stack_index();
 
     for i in 0..CHEAT_STACK_PREVIEW {
         let deck_index = base_index + i + 1;
 
         let bitmap = if deck_index < DECK_SIZE {
             state
-                .game
+                .presenter
+                .game()
                 .deck
                 .get(deck_index)
                 .copied()
@@ -2212,7 +3149,7 @@ unsafe fn paint_cheat_stack(state: &WindowState, hwnd: HWND, hdc: HDC) {
 
         let y = 30;
 
-        draw_small_bitmap(hdc, mem_dc, bitmap, x, y);
+        draw_small_bitmap(state, hdc, mem_dc, bitmap, x, y);
     }
 
     let label = format!("Stack pointer: {}", base_index + 1);
@@ -2229,7 +3166,7 @@ unsafe fn paint_cheat_scores(state: &WindowState, hwnd: HWND, hdc: HDC) {
 
     SetTextColor(hdc, COLORREF(0x00000000));
 
-    for (index, score) in state.game.round_scores.iter().enumerate() {
+    for (index, score) in state.presenter.game().round_scores.iter().enumerate() {
         let text = format!("Player {}: {}", index + 1, score);
 
         let y = 20 * (index as i32 + 1) - 16;
@@ -2238,217 +3175,190 @@ unsafe fn paint_cheat_scores(state: &WindowState, hwnd: HWND, hdc: HDC) {
     }
 }
 
-unsafe fn load_bitmap(instance: HINSTANCE, id: u16) -> Result<HBITMAP> {
-    match load_bitmap_from_resource(instance, id) {
-        Ok(bitmap) => Ok(bitmap),
-
-        Err(_) => {
-            let handle = LoadBitmapW(instance, make_int_resource(id));
-
-            if handle.0.is_null() {
-                Err(Error::from_win32())
-            } else {
-                Ok(handle)
-            }
-        }
-    }
-}
+unsafe fn paint_table_read(state: &WindowState, hwnd: HWND, hdc: HDC) {
+    fill_cheat_background(hwnd, hdc);
 
-unsafe fn load_bitmap_from_resource(instance: HINSTANCE, id: u16) -> Result<HBITMAP> {
-    let resource = FindResourceW(instance, make_int_resource(id), RT_BITMAP);
+    SetBkColor(hdc, COLORREF(0x00C0C0C0));
 
-    if resource.is_invalid() {
-        return Err(Error::from_win32());
-    }
+    SetTextColor(hdc, COLORREF(0x00000000));
 
-    let size = SizeofResource(instance, resource);
+    draw_text(hdc, 10, 5, "Visible swaps this round:");
 
-    if size == 0 {
-        return Err(Error::from_win32());
-    }
+    for player in 1..PLAYERS {
+        let read = state.presenter.game().history().read(player);
 
-    let handle = LoadResource(instance, resource)?;
+        let text = table_read_summary(&read);
 
-    let data_ptr = LockResource(handle) as *const u8;
+        let y = 25 * player as i32;
 
-    if data_ptr.is_null() {
-        return Err(Error::from_win32());
+        draw_text(hdc, 10, y, &text);
     }
-
-    let data = unsafe { slice::from_raw_parts(data_ptr, size as usize) };
-
-    create_dib_from_resource(data)
 }
 
-fn create_dib_from_resource(data: &[u8]) -> Result<HBITMAP> {
-    if data.len() < size_of::<BITMAPINFOHEADER>() {
-        return Err(Error::from_win32());
-    }
-
-    let mut header = BITMAPINFOHEADER::default();
-
-    unsafe {
-        copy_nonoverlapping(
-            data.as_ptr() as *const BITMAPINFOHEADER,
-            &mut header as *mut BITMAPINFOHEADER,
-            1,
-        );
-    }
+fn table_read_summary(read: &OpponentRead) -> String {
+    let name = format!("Player {}", read.player + 1);
 
-    if header.biCompression != BI_RGB.0 {
-        return Err(Error::from_win32());
+    if read.exchanges == 0 {
+        return format!("{}: no swaps yet", name);
     }
 
-    let width = header.biWidth;
-
-    let height = header.biHeight;
-
-    let abs_height = height.unsigned_abs() as usize;
+    let suit = match read.likely_suit {
+        Some(suit) => format!(
+            "{} {:.0}%",
+            suit_name(suit),
+            read.suit_probability(suit) * 100.0
+        ),
+        None => "no clear suit".to_string(),
+    };
 
-    let bit_count = header.biBitCount as usize;
+    format!(
+        "{}: {}, ~{:.0} points ({} swaps)",
+        name, suit, read.expected_score, read.exchanges
+    )
+}
 
-    if width == 0 || abs_height == 0 || bit_count == 0 {
-        return Err(Error::from_win32());
+fn suit_name(suit: Suit) -> &'static str {
+    match suit {
+        Suit::Clubs => "clubs",
+        Suit::Diamonds => "diamonds",
+        Suit::Hearts => "hearts",
+        Suit::Spades => "spades",
     }
+}
 
-    let palette_entries = if bit_count <= 8 {
-        let used = header.biClrUsed as usize;
-
-        if used != 0 {
-            used
-        } else {
-            1 << bit_count
-        }
-    } else {
-        0
-    };
-
-    let palette_bytes = palette_entries * size_of::<RGBQUAD>();
+/// Bitmaps 1 to 54, the cards then the back and the cross, from the legacy resource file
+/// when it has them and the executable otherwise.
+unsafe fn load_classic_art(instance: HINSTANCE) -> Result<Vec<HBITMAP>> {
+    let legacy_resources = load_legacy_resources();
 
-    let bits_offset = header.biSize as usize + palette_bytes;
+    let legacy = legacy_resources.as_ref();
 
-    if data.len() < bits_offset {
-        return Err(Error::from_win32());
-    }
+    let mut art = Vec::with_capacity(stopbus_theme::SLOT_COUNT as usize);
 
-    let stride = (width.unsigned_abs() as usize * bit_count).div_ceil(32) * 4;
+    for id in 1..=stopbus_theme::SLOT_COUNT {
+        match load_card_bitmap(legacy, instance, u16::from(id)) {
+            Ok(bitmap) => art.push(bitmap),
 
-    let bits = &data[bits_offset..];
+            Err(err) => {
+                for bitmap in art {
+                    let _ = DeleteObject(bitmap);
+                }
 
-    if bits.len() < stride * abs_height {
-        return Err(Error::from_win32());
+                return Err(err);
+            }
+        }
     }
 
-    let palette = if palette_entries > 0 {
-        &data[header.biSize as usize..header.biSize as usize + palette_bytes]
-    } else {
-        &[]
-    };
-
-    let width_usize = width.unsigned_abs() as usize;
+    Ok(art)
+}
 
-    let mut output = vec![0u8; abs_height * width_usize * 4];
+/// The theme's 54 images as bitmaps, with any transparency blended onto the table.
+unsafe fn create_theme_art(theme: &Theme, table_color: u32) -> Result<Vec<HBITMAP>> {
+    let table = [
+        table_color as u8,
+        (table_color >> 8) as u8,
+        (table_color >> 16) as u8,
+    ];
 
-    for row in 0..abs_height {
-        let src_row = if height > 0 {
-            abs_height - 1 - row
-        } else {
-            row
-        };
+    let mut art = Vec::with_capacity(stopbus_theme::SLOT_COUNT as usize);
 
-        let row_data = &bits[src_row * stride..(src_row + 1) * stride];
+    for slot in 1..=stopbus_theme::SLOT_COUNT {
+        let mut image = theme
+            .image(slot)
+            .cloned()
+            .unwrap_or_else(|| Image::new(1, 1));
 
-        for col in 0..width_usize {
-            let (b, g, r, a) = match bit_count {
-                1 => {
-                    let byte = row_data[col / 8];
+        image.flatten(table);
 
-                    let mask = 0x80 >> (col % 8);
+        match create_dib(&image) {
+            Ok(bitmap) => art.push(bitmap),
 
-                    let idx = if byte & mask == 0 { 0 } else { 1 };
+            Err(err) => {
+                for bitmap in art {
+                    let _ = DeleteObject(bitmap);
+                }
 
-                    let base = idx * 4;
+                return Err(err);
+            }
+        }
+    }
 
-                    (
-                        palette.get(base).copied().unwrap_or(0),
-                        palette.get(base + 1).copied().unwrap_or(0),
-                        palette.get(base + 2).copied().unwrap_or(0),
-                        0xFF,
-                    )
-                }
+    Ok(art)
+}
 
-                4 => {
-                    let byte = row_data[col / 2];
+/// Card art from the legacy resource file when it has the bitmap, otherwise the copy
+/// built into the executable.
+unsafe fn load_card_bitmap(
+    legacy: Option<&ResFile>,
+    instance: HINSTANCE,
+    id: u16,
+) -> Result<HBITMAP> {
+    match legacy.and_then(|resources| resources.bitmap(id)) {
+        Some(data) => create_dib_from_resource(data),
 
-                    let idx = if col % 2 == 0 {
-                        (byte >> 4) as usize
-                    } else {
-                        (byte & 0x0F) as usize
-                    };
+        None => load_bitmap(instance, id),
+    }
+}
 
-                    let base = idx * 4;
+unsafe fn load_bitmap(instance: HINSTANCE, id: u16) -> Result<HBITMAP> {
+    match load_bitmap_from_resource(instance, id) {
+        Ok(bitmap) => Ok(bitmap),
 
-                    (
-                        palette.get(base).copied().unwrap_or(0),
-                        palette.get(base + 1).copied().unwrap_or(0),
-                        palette.get(base + 2).copied().unwrap_or(0),
-                        0xFF,
-                    )
-                }
+        Err(_) => {
+            let handle = LoadBitmapW(instance, make_int_resource(id));
 
-                8 => {
-                    let idx = row_data[col] as usize;
+            if handle.0.is_null() {
+                Err(Error::from_win32())
+            } else {
+                Ok(handle)
+            }
+        }
+    }
+}
 
-                    let base = idx * 4;
+unsafe fn load_bitmap_from_resource(instance: HINSTANCE, id: u16) -> Result<HBITMAP> {
+    let resource = FindResourceW(instance, make_int_resource(id), RT_BITMAP);
 
-                    (
-                        palette.get(base).copied().unwrap_or(0),
-                        palette.get(base + 1).copied().unwrap_or(0),
-                        palette.get(base + 2).copied().unwrap_or(0),
-                        0xFF,
-                    )
-                }
+    if resource.is_invalid() {
+        return Err(Error::from_win32());
+    }
 
-                24 => {
-                    let base = col * 3;
+    let size = SizeofResource(instance, resource);
 
-                    (row_data[base], row_data[base + 1], row_data[base + 2], 0xFF)
-                }
+    if size == 0 {
+        return Err(Error::from_win32());
+    }
 
-                32 => {
-                    let base = col * 4;
+    let handle = LoadResource(instance, resource)?;
 
-                    (
-                        row_data[base],
-                        row_data[base + 1],
-                        row_data[base + 2],
-                        row_data[base + 3],
-                    )
-                }
+    let data_ptr = LockResource(handle) as *const u8;
 
-                _ => return Err(Error::from_win32()),
-            };
+    if data_ptr.is_null() {
+        return Err(Error::from_win32());
+    }
 
-            let dest_index = (row * width_usize + col) * 4;
+    let data = unsafe { slice::from_raw_parts(data_ptr, size as usize) };
 
-            output[dest_index] = b;
+    create_dib_from_resource(data)
+}
 
-            output[dest_index + 1] = g;
+fn create_dib_from_resource(data: &[u8]) -> Result<HBITMAP> {
+    let image = bmp::decode_dib(data).map_err(|_| Error::from_win32())?;
 
-            output[dest_index + 2] = r;
+    create_dib(&image)
+}
 
-            output[dest_index + 3] = a;
-        }
-    }
+fn create_dib(image: &Image) -> Result<HBITMAP> {
+    let pixels = image.to_bgra();
 
     let info = BITMAPINFOHEADER {
         biSize: size_of::<BITMAPINFOHEADER>() as u32,
-        biWidth: width,
-        biHeight: -(abs_height as i32),
+        biWidth: image.width as i32,
+        biHeight: -(image.height as i32),
         biPlanes: 1,
         biBitCount: 32,
         biCompression: BI_RGB.0,
-        biSizeImage: output.len() as u32,
+        biSizeImage: pixels.len() as u32,
         ..Default::default()
     };
 
@@ -2462,7 +3372,7 @@ fn create_dib_from_resource(data: &[u8]) -> Result<HBITMAP> {
     let bitmap = unsafe { CreateDIBSection(None, &bmi, DIB_RGB_COLORS, &mut bits_ptr, None, 0)? };
 
     unsafe {
-        copy_nonoverlapping(output.as_ptr(), bits_ptr as *mut u8, output.len());
+        copy_nonoverlapping(pixels.as_ptr(), bits_ptr as *mut u8, pixels.len());
     }
 
     Ok(bitmap)
@@ -2498,17 +3408,27 @@ fn show_error(hwnd: HWND, message: &str) {
     };
 }
 
-unsafe fn create_menus(hwnd: HWND) -> Result<()> {
+/// Builds the menu bar, returning the Theme submenu, which is filled each time it opens.
+unsafe fn create_menus(hwnd: HWND) -> Result<HMENU> {
     let main_menu = CreateMenu()?;
 
     let game_menu = CreatePopupMenu()?;
 
+    let theme_menu = CreatePopupMenu()?;
+
     let help_menu = CreatePopupMenu()?;
 
     AppendMenuW(game_menu, MF_STRING, CM_GAME_DEAL, w!("&Deal"))?;
 
     AppendMenuW(game_menu, MF_STRING, CM_GAME_OPTIONS, w!("&Options"))?;
 
+    AppendMenuW(
+        game_menu,
+        MF_POPUP | MF_STRING,
+        theme_menu.0 as usize,
+        w!("&Theme"),
+    )?;
+
     AppendMenuW(game_menu, MF_STRING, CM_GAME_EXIT, w!("E&xit"))?;
 
     AppendMenuW(help_menu, MF_STRING, CM_HELP_CONTENTS, w!("&Contents"))?;
@@ -2531,11 +3451,16 @@ unsafe fn create_menus(hwnd: HWND) -> Result<()> {
 
     SetMenu(hwnd, main_menu)?;
 
-    Ok(())
+    Ok(theme_menu)
 }
 
 fn main() -> Result<()> {
     unsafe {
+        // Before any window exists, so GetDpiForWindow reports the monitor's real DPI and
+        // WM_DPICHANGED arrives instead of Windows stretching a 96-DPI bitmap. Fails
+        // harmlessly when a manifest has already set the awareness.
+        let _ = SetProcessDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2);
+
         let module = GetModuleHandleW(None)?;
 
         let instance = HINSTANCE(module.0);
@@ -2556,11 +3481,48 @@ fn main() -> Result<()> {
             }
         };
 
+        // The window was sized for 96 DPI before it knew which monitor it would open on.
+        let dpi = GetDpiForWindow(hwnd);
+        if dpi != USER_DEFAULT_SCREEN_DPI {
+            let (width, height) = default_window_size(dpi);
+
+            let _ = SetWindowPos(
+                hwnd,
+                None,
+                0,
+                0,
+                width,
+                height,
+                SWP_NOMOVE | SWP_NOZORDER | SWP_NOACTIVATE,
+            );
+        }
+
         let _ = ShowWindow(hwnd, SW_SHOWDEFAULT);
 
         let mut message = MSG::default();
 
         while GetMessageW(&mut message, HWND::default(), 0, 0).into() {
+            // Buttons keep the keyboard focus after a click; send table keys to the main
+            // window, except Enter, which presses the button as it would in a dialog. Keys
+            // for other controls, such as the event log, stay with them.
+            if message.message == WM_KEYDOWN
+                && window_state_mut(hwnd).is_some_and(|state| state.is_push_button(message.hwnd))
+            {
+                let key = VIRTUAL_KEY(message.wParam.0 as u16);
+
+                if key == VK_RETURN {
+                    SendMessageW(message.hwnd, BM_CLICK, WPARAM(0), LPARAM(0));
+
+                    continue;
+                }
+
+                if key_from_virtual_key(key).is_some() {
+                    SendMessageW(hwnd, WM_KEYDOWN, message.wParam, message.lParam);
+
+                    continue;
+                }
+            }
+
             let _ = TranslateMessage(&message);
 
             DispatchMessageW(&message);